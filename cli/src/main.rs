@@ -0,0 +1,271 @@
+mod output;
+
+use anyhow::{Context, anyhow};
+use clap::{Parser, Subcommand, ValueEnum};
+use freemdu::device::{self, Device, DeviceSnapshot, PropertyKind, PropertySnapshot};
+use freemdu::serial::{self, Port, Rs485Config, SerialConfig};
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::time;
+
+/// Prefix of port names that refer to a serial-to-TCP bridge
+const TCP_PREFIX: &str = "tcp://";
+
+/// Timeout for device operations, e.g. connecting or querying a property
+const DEVICE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Exit code if the port couldn't be opened or the device didn't respond
+const EXIT_CONNECT: u8 = 3;
+
+/// Exit code if at least one property couldn't be queried
+const EXIT_QUERY: u8 = 4;
+
+/// Exit code if the action couldn't be triggered
+const EXIT_ACTION: u8 = 5;
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Arguments shared by all commands
+#[derive(clap::Args, Debug)]
+struct Connection {
+    /// Serial port path (e.g. /dev/ttyACM0 or COM3), or tcp://host:port for a serial-to-TCP bridge
+    #[arg(long, short)]
+    port: String,
+
+    /// Switch the direction of a half-duplex RS-485 adapter using the RTS line
+    ///
+    /// Only needed for adapters that don't switch automatically.
+    #[arg(long)]
+    rs485: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Query the properties of the device and print them
+    Read {
+        #[command(flatten)]
+        conn: Connection,
+
+        /// Only query properties of this kind
+        #[arg(long, value_enum)]
+        kind: Option<Kind>,
+
+        /// Print the properties as JSON, in the format of device snapshots
+        #[arg(long)]
+        json: bool,
+    },
+    /// Trigger an action of the device
+    Action {
+        #[command(flatten)]
+        conn: Connection,
+
+        /// Identifier of the action, as listed by the schema command of the TUI
+        #[arg(long)]
+        id: String,
+
+        /// Parameter of the action, if it expects one
+        #[arg(long)]
+        param: Option<String>,
+    },
+}
+
+/// Property kind as given on the command line
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Kind {
+    General,
+    Failure,
+    Warning,
+    Operation,
+    Io,
+}
+
+impl From<Kind> for PropertyKind {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::General => Self::General,
+            Kind::Failure => Self::Failure,
+            Kind::Warning => Self::Warning,
+            Kind::Operation => Self::Operation,
+            Kind::Io => Self::Io,
+        }
+    }
+}
+
+/// Error that ends the program with the given exit code
+struct Failure {
+    code: u8,
+    error: anyhow::Error,
+}
+
+impl Failure {
+    fn new(code: u8, error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            code,
+            error: error.into(),
+        }
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    env_logger::init();
+
+    let args = Args::parse();
+
+    match run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Failure { code, error }) => {
+            eprintln!("Error: {error:#}");
+
+            ExitCode::from(code)
+        }
+    }
+}
+
+async fn run(args: Args) -> Result<(), Failure> {
+    let (Command::Read { conn, .. } | Command::Action { conn, .. }) = &args.command;
+    let port = time::timeout(DEVICE_TIMEOUT, open_port(&conn.port, conn.rs485))
+        .await
+        .context("Timeout opening port")
+        .and_then(|res| res.context("Failed to open port"))
+        .map_err(|e| Failure::new(EXIT_CONNECT, e))?;
+    let mut dev = time::timeout(DEVICE_TIMEOUT, device::connect(port))
+        .await
+        .context("Timeout connecting to device")
+        .and_then(|res| res.context("Failed to connect to device"))
+        .map_err(|e| Failure::new(EXIT_CONNECT, e))?;
+
+    match args.command {
+        Command::Read { kind, json, .. } => read(dev.as_mut(), kind.map(Into::into), json).await,
+        Command::Action { id, param, .. } => action(dev.as_mut(), &id, param.as_deref()).await,
+    }
+}
+
+async fn open_port(name: &str, rs485: bool) -> freemdu::Result<Port, serial::PortError> {
+    let config = SerialConfig {
+        rs485: rs485.then(Rs485Config::default),
+        ..SerialConfig::default()
+    };
+
+    match name.strip_prefix(TCP_PREFIX) {
+        Some(addr) => serial::open_tcp(addr).await,
+        None => serial::open_with(name, &config),
+    }
+}
+
+/// Query the properties of the given kind, or all of them, and print them
+///
+/// Properties that fail are reported and skipped, failing once all others are printed.
+/// Properties rejected by the device aren't considered a failure.
+async fn read(
+    dev: &mut dyn Device<Port>,
+    kind: Option<PropertyKind>,
+    json: bool,
+) -> Result<(), Failure> {
+    let mut properties = Vec::new();
+    let mut failed = 0;
+
+    // Properties decoded from the same memory are read once
+    dev.interface().start_read_cache();
+
+    for prop in dev.properties() {
+        if kind.is_some_and(|kind| prop.kind != kind) {
+            continue;
+        }
+
+        let Ok(res) = time::timeout(DEVICE_TIMEOUT, dev.query_property(prop)).await else {
+            eprintln!("Timeout querying property {}", prop.id);
+            failed += 1;
+            continue;
+        };
+
+        match res {
+            Ok(value) => properties.push(PropertySnapshot {
+                kind: prop.kind,
+                id: prop.id.to_string(),
+                name: prop.name.to_string(),
+                unit: prop.unit.map(String::from),
+                value,
+            }),
+            Err(device::Error::Unsupported) => {
+                log::info!("Property {} not supported by device", prop.id);
+            }
+            Err(e) => {
+                eprintln!("Failed to query property {}: {e}", prop.id);
+                failed += 1;
+            }
+        }
+    }
+
+    dev.interface().stop_read_cache();
+
+    let snapshot = DeviceSnapshot {
+        version: device::SNAPSHOT_VERSION,
+        software_id: dev.software_id(),
+        kind: dev.kind(),
+        properties,
+    };
+
+    if json {
+        let json = serde_json::to_string_pretty(&snapshot)
+            .context("Failed to serialize properties")
+            .map_err(|e| Failure::new(1, e))?;
+
+        println!("{json}");
+    } else {
+        output::print_table(&snapshot, dev.properties());
+    }
+
+    if failed > 0 {
+        return Err(Failure::new(
+            EXIT_QUERY,
+            anyhow!("Failed to query {failed} properties"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Trigger the action with the given identifier and print its result, if any
+async fn action(dev: &mut dyn Device<Port>, id: &str, param: Option<&str>) -> Result<(), Failure> {
+    let action = dev
+        .actions()
+        .iter()
+        .find(|action| action.id == id)
+        .ok_or_else(|| Failure::new(EXIT_ACTION, anyhow!("Unknown action: {id}")))?;
+    let param = match (&action.params, param) {
+        (Some(params), Some(input)) => Some(params.parse_value(input).ok_or_else(|| {
+            Failure::new(EXIT_ACTION, anyhow!("Invalid parameter for {id}: {input}"))
+        })?),
+        (Some(_), None) => {
+            return Err(Failure::new(
+                EXIT_ACTION,
+                anyhow!("Action {id} expects a parameter"),
+            ));
+        }
+        (None, Some(_)) => {
+            return Err(Failure::new(
+                EXIT_ACTION,
+                anyhow!("Action {id} doesn't take a parameter"),
+            ));
+        }
+        (None, None) => None,
+    };
+
+    let res = time::timeout(DEVICE_TIMEOUT, dev.trigger_action(action, param))
+        .await
+        .with_context(|| format!("Timeout triggering action {id}"))
+        .and_then(|res| res.with_context(|| format!("Failed to trigger action {id}")))
+        .map_err(|e| Failure::new(EXIT_ACTION, e))?;
+
+    if let Some(value) = res {
+        println!("{}", value.format(None, 0));
+    }
+
+    Ok(())
+}
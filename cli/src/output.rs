@@ -0,0 +1,31 @@
+use freemdu::device::{DeviceSnapshot, Property};
+
+/// Prints the properties of a snapshot as a table, one property per line.
+///
+/// The definitions are used to look up the number of decimal places of fractional values.
+pub fn print_table(snapshot: &DeviceSnapshot, definitions: &[Property]) {
+    let rows: Vec<_> = snapshot
+        .properties
+        .iter()
+        .map(|prop| {
+            let decimals = definitions
+                .iter()
+                .find(|def| def.id.as_str() == prop.id)
+                .map_or(0, |def| def.decimals);
+
+            (
+                prop.id.as_str(),
+                prop.name.as_str(),
+                prop.value.format(prop.unit.as_deref(), decimals),
+            )
+        })
+        .collect();
+    let id_width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0);
+    let name_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0);
+
+    println!("{} (software ID {})", snapshot.kind, snapshot.software_id);
+
+    for (id, name, value) in rows {
+        println!("{id:<id_width$}  {name:<name_width$}  {value}");
+    }
+}
@@ -0,0 +1,255 @@
+//! Alerts raised when a failure becomes active
+//!
+//! Boolean failure properties and the flags of failure bitfields can be marked as alerting.
+//! Once one of them changes from inactive to active, a desktop notification is shown,
+//! a beep is played and the alert stays listed until it's acknowledged.
+
+use crate::worker::{PropertyData, PropertyValue};
+use std::{collections::HashMap, process::Command, thread};
+
+/// Storage key for persisting the alerting indicators
+pub const STORAGE_KEY: &str = "alerting_failures";
+
+/// Separator between a property ID and the label of a flag in indicator keys
+const FLAG_SEPARATOR: char = '/';
+
+/// Failure that can raise an alert, either a boolean property or a flag of a bitfield
+#[derive(Debug, Clone, PartialEq)]
+pub struct Indicator {
+    /// Identifies the indicator across sessions, e.g. `faults/Door lock`
+    pub key: String,
+    /// Name shown in notifications, e.g. `Faults: Door lock`
+    pub label: String,
+    pub active: bool,
+}
+
+/// Alert that hasn't been acknowledged yet
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub label: String,
+    /// Time the failure became active
+    pub since: jiff::Timestamp,
+}
+
+/// Detects failures becoming active, based on the values last seen for each indicator
+#[derive(Default)]
+pub struct AlertMonitor {
+    /// Last seen state of each indicator by key
+    last: HashMap<String, bool>,
+    /// Alerts in the order they were raised
+    pub active: Vec<Alert>,
+}
+
+impl AlertMonitor {
+    /// Update the last seen states with the given failure properties
+    ///
+    /// Returns the labels of alerting indicators that changed from inactive to active,
+    /// which are also added to the unacknowledged alerts. Indicators seen for the first time
+    /// never raise an alert, since it's unknown when they became active.
+    pub fn update(&mut self, alerting: &[String], data: &[PropertyData]) -> Vec<String> {
+        let mut raised = Vec::new();
+
+        for indicator in data.iter().flat_map(indicators) {
+            let previous = self.last.insert(indicator.key.clone(), indicator.active);
+
+            if indicator.active && previous == Some(false) && alerting.contains(&indicator.key) {
+                if !self
+                    .active
+                    .iter()
+                    .any(|alert| alert.label == indicator.label)
+                {
+                    self.active.push(Alert {
+                        label: indicator.label.clone(),
+                        since: jiff::Timestamp::now(),
+                    });
+                }
+
+                raised.push(indicator.label);
+            }
+        }
+
+        raised
+    }
+
+    /// Dismiss all alerts
+    pub fn acknowledge(&mut self) {
+        self.active.clear();
+    }
+}
+
+/// Indicators of a failure property, empty if its value can't be active
+pub fn indicators(prop: &PropertyData) -> Vec<Indicator> {
+    match &prop.value {
+        PropertyValue::Bool(active) => vec![Indicator {
+            key: prop.id.to_string(),
+            label: prop.name.clone(),
+            active: *active,
+        }],
+        PropertyValue::Flags(flags) => flags
+            .iter()
+            .map(|(flag, set)| Indicator {
+                key: format!("{}{FLAG_SEPARATOR}{flag}", prop.id),
+                label: format!("{}: {flag}", prop.name),
+                active: *set,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Load the keys of the alerting indicators, none if they were never saved
+pub fn load(storage: Option<&dyn eframe::Storage>) -> Vec<String> {
+    storage
+        .and_then(|storage| storage.get_string(STORAGE_KEY))
+        .map(|src| deserialize(&src))
+        .unwrap_or_default()
+}
+
+/// Serialize the keys of the alerting indicators, one per line
+pub fn serialize(alerting: &[String]) -> String {
+    alerting.join("\n")
+}
+
+/// Deserialize the keys of the alerting indicators, skipping empty lines
+pub fn deserialize(src: &str) -> Vec<String> {
+    src.lines()
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Show a desktop notification and play a beep for a failure that became active
+///
+/// Uses the notification tool of the platform, which runs in the background.
+/// Failures are only logged, as the banner in the window is shown regardless.
+pub fn notify(label: &str) {
+    let title = "FreeMDU: failure active";
+    let mut commands = Vec::new();
+
+    if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification \"{}\" with title \"{title}\" sound name \"Basso\"",
+            label.replace(['\\', '"'], "")
+        );
+
+        let mut command = Command::new("osascript");
+
+        command.args(["-e", &script]);
+        commands.push(command);
+    } else if cfg!(windows) {
+        let script = format!(
+            "[console]::beep(880, 400); \
+             Add-Type -AssemblyName System.Windows.Forms; \
+             $icon = New-Object System.Windows.Forms.NotifyIcon; \
+             $icon.Icon = [System.Drawing.SystemIcons]::Warning; \
+             $icon.Visible = $true; \
+             $icon.ShowBalloonTip(10000, '{title}', '{}', 'Warning'); \
+             Start-Sleep -Seconds 10; \
+             $icon.Dispose()",
+            label.replace('\'', "''")
+        );
+
+        let mut command = Command::new("powershell");
+
+        command.args(["-NoProfile", "-Command", &script]);
+        commands.push(command);
+    } else {
+        let mut notification = Command::new("notify-send");
+        let mut sound = Command::new("canberra-gtk-play");
+
+        notification.args(["--app-name=FreeMDU", "--urgency=critical", title, label]);
+        sound.arg("--id=dialog-warning");
+        commands.extend([notification, sound]);
+
+        // Heard if started from a terminal, even without a sound theme
+        eprint!("\x07");
+    }
+
+    for mut command in commands {
+        thread::spawn(move || {
+            if let Err(e) = command.status() {
+                log::warn!("Failed to run {:?}: {e}", command.get_program());
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn faults(flags: &[(&str, bool)]) -> PropertyData {
+        let value = PropertyValue::Flags(
+            flags
+                .iter()
+                .map(|&(flag, set)| (flag.to_string(), set))
+                .collect(),
+        );
+
+        PropertyData {
+            name: "Faults".to_string(),
+            ..PropertyData::stub("faults", value)
+        }
+    }
+
+    #[test]
+    fn raised_on_transition() {
+        let mut monitor = AlertMonitor::default();
+        let alerting = vec!["faults/Door lock".to_string()];
+
+        // Already active when first seen
+        assert!(
+            monitor
+                .update(
+                    &alerting,
+                    &[faults(&[("Door lock", true), ("Heater", false)])]
+                )
+                .is_empty(),
+            "first value should not raise alert"
+        );
+        assert!(
+            monitor
+                .update(
+                    &alerting,
+                    &[faults(&[("Door lock", false), ("Heater", false)])]
+                )
+                .is_empty(),
+            "inactive failure should not raise alert"
+        );
+        assert_eq!(
+            monitor.update(
+                &alerting,
+                &[faults(&[("Door lock", true), ("Heater", true)])]
+            ),
+            ["Faults: Door lock"],
+            "only alerting failure should raise alert"
+        );
+        assert!(
+            monitor
+                .update(
+                    &alerting,
+                    &[faults(&[("Door lock", true), ("Heater", true)])]
+                )
+                .is_empty(),
+            "failure staying active should not raise alert again"
+        );
+        assert_eq!(monitor.active.len(), 1, "alert should be kept");
+
+        monitor.acknowledge();
+
+        assert!(monitor.active.is_empty(), "alert should be acknowledged");
+    }
+
+    #[test]
+    fn indicator_keys() {
+        let prop = faults(&[("Door lock", false)]);
+        let keys: Vec<_> = indicators(&prop).into_iter().map(|ind| ind.key).collect();
+
+        assert_eq!(keys, ["faults/Door lock"], "keys should be correct");
+        assert_eq!(
+            deserialize(&serialize(&keys)),
+            keys,
+            "keys should be restored"
+        );
+    }
+}
@@ -1,8 +1,35 @@
+use crate::alert::{self, AlertMonitor};
+use crate::influx::{InfluxConfig, InfluxWriter};
+use crate::log_view::{LogBuffer, LogPanel};
+use crate::metrics::{self, MetricsServer};
+use crate::mqtt::{MqttConfig, MqttPublisher};
+use crate::plot::{self, History, OverlayScale, Resolution, Series};
+use crate::session_file::{
+    self, SavedAction, SavedDevice, SavedProperty, SavedSection, SessionFile,
+};
+use crate::smoothing::Smoother;
+use crate::stream::{EventStream, StreamConfig};
+use crate::watch::{self, WatchExpression};
 use crate::worker::{
-    DeviceInfo, PropertyData, PropertyValue, WorkerCommand, WorkerHandle, WorkerResponse,
+    serial_config, set_flags, ActionOutcome, ActionProgress, ConnectError, ConnectOptions,
+    DeviceInfo, PropertyData, PropertyValue, Timeouts, WarmUpOrder, WorkerCommand, WorkerHandle,
+    WorkerResponse, DEMO_PORT, MAX_RECONNECT_ATTEMPTS, REPLAY_PREFIX, TCP_PREFIX,
+};
+use egui::gui_zoom::kb_shortcuts;
+use egui::{Color32, Key, KeyboardShortcut, Modifiers, RichText, ThemePreference, Ui};
+use freemdu::device::{
+    ActionParameters, DeviceKind, FaultRecord, PropertyId, PropertyKind, RangeStatus,
 };
-use egui::{Color32, RichText, Ui};
-use freemdu::device::{ActionParameters, PropertyKind};
+use freemdu::replay::ReplayEnd;
+use freemdu::serial::SerialConfig;
+use freemdu::TransportStats;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// Connection state of the application
@@ -11,7 +38,216 @@ enum ConnectionState {
     Disconnected,
     Connecting,
     Connected(DeviceInfo),
-    Error(String),
+    Error(ConnectError),
+    /// Showing a saved session, without a device
+    Viewing(DeviceInfo),
+}
+
+/// Minimum time between two auto-refresh requests
+const REFRESH_THROTTLE: Duration = Duration::from_millis(500);
+
+/// Time after the last "Refresh All" request before the refresh starts,
+/// so that repeated requests result in a single refresh
+const REFRESH_ALL_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Repaint interval while the window is focused
+const ACTIVE_REPAINT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Repaint interval while the window is unfocused or minimized.
+///
+/// Matches the auto-refresh throttle, so properties are still polled at full rate.
+/// Worker responses are queued until the next frame, so no data is lost.
+const IDLE_REPAINT_INTERVAL: Duration = REFRESH_THROTTLE;
+
+/// Interval for checking whether serial ports were plugged in or removed
+const PORT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Shortcut for querying all properties again
+const REFRESH_ALL_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::R);
+
+/// Shortcut for connecting to the selected port, or disconnecting
+const CONNECT_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::K);
+
+/// Shortcut for refreshing the port list
+const REFRESH_PORTS_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F5);
+
+/// Default time to wait for an action to complete
+const DEFAULT_ACTION_TIMEOUT_SECS: u64 = 2;
+
+/// Default auto-refresh interval of each property kind, in order of priority
+const DEFAULT_REFRESH_INTERVALS: [(PropertyKind, Duration); 5] = [
+    (PropertyKind::Io, Duration::from_millis(500)),
+    (PropertyKind::Operation, Duration::from_secs(1)),
+    (PropertyKind::Failure, Duration::from_secs(5)),
+    (PropertyKind::Warning, Duration::from_secs(5)),
+    (PropertyKind::General, Duration::from_secs(30)),
+];
+
+/// Range of the adjustable refresh intervals, in milliseconds
+const REFRESH_INTERVAL_RANGE: std::ops::RangeInclusive<u64> = 100..=60_000;
+
+/// Default time after which a reading that didn't change while being polled is marked as stale
+const DEFAULT_STALE_WINDOW: Duration = Duration::from_secs(300);
+
+/// Range of the adjustable stale window, in seconds
+const STALE_WINDOW_RANGE: std::ops::RangeInclusive<u64> = 10..=3600;
+
+/// Storage key for persisting the stale window, in seconds
+const STALE_WINDOW_KEY: &str = "stale_window";
+
+/// Range of the zoom factor of the UI
+const ZOOM_RANGE: std::ops::RangeInclusive<f32> = 0.5..=3.0;
+
+/// Amount by which the zoom factor changes per step
+const ZOOM_STEP: f32 = 0.1;
+
+/// Storage key for persisting the zoom factor
+const ZOOM_FACTOR_KEY: &str = "zoom_factor";
+
+/// Share of the width taken by the left property column, unless resized
+const DEFAULT_COLUMN_SPLIT: f32 = 0.5;
+
+/// Range of the adjustable column split
+const COLUMN_SPLIT_RANGE: std::ops::RangeInclusive<f32> = 0.2..=0.8;
+
+/// Step the column split snaps to while dragging the splitter
+const COLUMN_SPLIT_STEP: f32 = 0.05;
+
+/// Width of the draggable splitter between the property columns
+const SPLITTER_WIDTH: f32 = 8.0;
+
+/// Available width below which the property sections are shown in a single column
+const SINGLE_COLUMN_WIDTH: f32 = 640.0;
+
+/// Minimum width of the name column of property grids
+const MIN_NAME_WIDTH: f32 = 120.0;
+
+/// Storage key for persisting the column split
+const COLUMN_SPLIT_KEY: &str = "column_split";
+
+/// Storage key for persisting the order of the warm-up after connecting
+const WARM_UP_ORDER_KEY: &str = "warm_up_order";
+
+/// Storage key for persisting the refresh intervals
+const REFRESH_INTERVALS_KEY: &str = "refresh_intervals";
+
+/// Storage key for persisting the name of the selected port
+const SELECTED_PORT_KEY: &str = "selected_port";
+
+/// Storage key for persisting the auto-refresh toggle
+const AUTO_REFRESH_KEY: &str = "auto_refresh";
+
+/// Storage key for persisting whether auto-refresh pauses while the window is unfocused
+const PAUSE_UNFOCUSED_KEY: &str = "pause_unfocused";
+
+/// Storage key for persisting the theme
+const THEME_KEY: &str = "theme";
+
+/// Storage key for persisting the temperature unit
+const TEMPERATURE_UNIT_KEY: &str = "temperature_unit";
+
+/// Storage key for showing the raw data of values
+const SHOW_RAW_KEY: &str = "show_raw";
+
+/// Storage key for persisting whether the metrics endpoint is enabled
+const METRICS_ENABLED_KEY: &str = "metrics_enabled";
+
+/// Storage key for persisting the port of the metrics endpoint
+const METRICS_PORT_KEY: &str = "metrics_port";
+
+/// Storage key for persisting the MQTT broker settings
+const MQTT_CONFIG_KEY: &str = "mqtt_config";
+
+/// Storage key for persisting the `InfluxDB` settings
+const INFLUX_CONFIG_KEY: &str = "influx_config";
+
+/// Storage key for persisting the event stream settings
+const STREAM_CONFIG_KEY: &str = "event_stream_config";
+
+/// Storage key for persisting the addresses of serial-to-TCP bridges, one per line
+const NETWORK_PORTS_KEY: &str = "network_ports";
+
+/// Storage key for persisting the layout profiles
+const LAYOUT_PROFILES_KEY: &str = "layout_profiles";
+
+/// Storage key for persisting the properties excluded from polling
+const UNPOLLED_KEY: &str = "unpolled_properties";
+
+/// Name of the profile that exists before any other is saved
+const DEFAULT_PROFILE: &str = "Default";
+
+/// Factor by which the last round trip must exceed the average latency to be highlighted
+const LATENCY_JUMP_FACTOR: f64 = 2.0;
+
+/// Maximum number of raw exchanges kept in the developer console
+const RAW_LOG_LIMIT: usize = 200;
+
+/// Raw request sent in the developer console and the reply, or an error
+type RawExchange = (Vec<u8>, Result<Vec<u8>, String>);
+
+/// Dialog for choosing the file of a session, with the path entered so far
+enum SessionFileDialog {
+    /// Save the current session to the file
+    Save(String),
+    /// Show the session saved in the file
+    Open(String),
+}
+
+/// Maximum number of executed actions kept in the action log
+const ACTION_LOG_LIMIT: usize = 500;
+
+/// Executed action shown in the action log
+struct ActionLogEntry {
+    /// Time the result was received
+    time: jiff::Timestamp,
+    name: String,
+    /// Argument as entered by the user, if any
+    param: Option<String>,
+    success: bool,
+    /// Returned value or reason for the failure
+    result: String,
+}
+
+impl fmt::Display for ActionLogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.time.strftime("%Y-%m-%d %H:%M:%S"),
+            self.name
+        )?;
+
+        if let Some(param) = &self.param {
+            write!(f, " ({param})")?;
+        }
+
+        let status = if self.success { "OK" } else { "FAILED" };
+
+        write!(f, ": {status}, {}", self.result)
+    }
+}
+
+impl ActionLogEntry {
+    fn to_saved(&self) -> SavedAction {
+        SavedAction {
+            time: self.time.to_string(),
+            name: self.name.clone(),
+            param: self.param.clone(),
+            success: self.success,
+            result: self.result.clone(),
+        }
+    }
+
+    /// Restore an entry of a saved session, `None` if its time is malformed
+    fn from_saved(saved: SavedAction) -> Option<Self> {
+        Some(Self {
+            time: saved.time.parse().ok()?,
+            name: saved.name,
+            param: saved.param,
+            success: saved.success,
+            result: saved.result,
+        })
+    }
 }
 
 /// Property storage by kind
@@ -19,509 +255,4625 @@ enum ConnectionState {
 struct PropertyStorage {
     general: (Vec<PropertyData>, Option<Instant>),
     failure: (Vec<PropertyData>, Option<Instant>),
+    warning: (Vec<PropertyData>, Option<Instant>),
     operation: (Vec<PropertyData>, Option<Instant>),
     io: (Vec<PropertyData>, Option<Instant>),
+    /// Location of each stored property by ID
+    index: HashMap<PropertyId, (PropertyKind, usize)>,
+    /// Read and change times of each property
+    times: HashMap<PropertyId, PropertyTimes>,
+}
+
+/// Times a property was last read and last changed its value
+#[derive(Clone, Copy, Debug)]
+struct PropertyTimes {
+    read: Instant,
+    /// Time of the first read until the value changes
+    changed: Instant,
+}
+
+/// Whether the properties of a kind were received yet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PollState {
+    /// Not queried since connecting or since all properties were cleared
+    NeverPolled,
+    /// Queried, but no property was received
+    Empty,
+    /// At least one property was received
+    HasData,
 }
 
 impl PropertyStorage {
-    fn get(&self, kind: PropertyKind) -> &(Vec<PropertyData>, Option<Instant>) {
+    fn of_kind(&self, kind: PropertyKind) -> &(Vec<PropertyData>, Option<Instant>) {
         match kind {
             PropertyKind::General => &self.general,
             PropertyKind::Failure => &self.failure,
+            PropertyKind::Warning => &self.warning,
             PropertyKind::Operation => &self.operation,
             PropertyKind::Io => &self.io,
         }
     }
 
-    fn get_mut(&mut self, kind: PropertyKind) -> &mut (Vec<PropertyData>, Option<Instant>) {
+    fn of_kind_mut(&mut self, kind: PropertyKind) -> &mut (Vec<PropertyData>, Option<Instant>) {
         match kind {
             PropertyKind::General => &mut self.general,
             PropertyKind::Failure => &mut self.failure,
+            PropertyKind::Warning => &mut self.warning,
             PropertyKind::Operation => &mut self.operation,
             PropertyKind::Io => &mut self.io,
         }
     }
 
+    /// Whether properties of a kind were queried and any were received
+    fn poll_state(&self, kind: PropertyKind) -> PollState {
+        match self.of_kind(kind) {
+            (_, None) => PollState::NeverPolled,
+            (data, Some(_)) if data.is_empty() => PollState::Empty,
+            _ => PollState::HasData,
+        }
+    }
+
+    /// Replace all properties of a kind with freshly queried data.
+    fn set(&mut self, kind: PropertyKind, data: Vec<PropertyData>) {
+        self.track_changes(&data);
+        self.index.retain(|_, (k, _)| *k != kind);
+        self.index.extend(
+            data.iter()
+                .enumerate()
+                .map(|(idx, prop)| (prop.id, (kind, idx))),
+        );
+
+        let storage = self.of_kind_mut(kind);
+        storage.0 = data;
+        storage.1 = Some(Instant::now());
+    }
+
+    /// Find a stored property by its ID string.
+    fn find(&self, id: &str) -> Option<&PropertyData> {
+        let (_, &(kind, idx)) = self
+            .index
+            .iter()
+            .find(|(prop_id, _)| prop_id.as_str() == id)?;
+
+        self.of_kind(kind).0.get(idx)
+    }
+
+    /// Find a stored property by its name, e.g. for expressions referring to it by name
+    fn find_by_name(&self, name: &str) -> Option<&PropertyData> {
+        PROPERTY_KINDS
+            .into_iter()
+            .find_map(|kind| self.of_kind(kind).0.iter().find(|prop| prop.name == name))
+    }
+
+    /// Replace a single property with freshly queried data, if it's stored.
+    fn update(&mut self, data: PropertyData) {
+        if self.index.contains_key(&data.id) {
+            self.track_changes(std::slice::from_ref(&data));
+        }
+
+        if let Some(prop) = self.find_mut(data.id) {
+            *prop = data;
+        }
+    }
+
+    /// Record the read time of the properties and whether their values changed.
+    fn track_changes(&mut self, data: &[PropertyData]) {
+        let now = Instant::now();
+
+        for prop in data {
+            let unchanged = self.get(prop.id).is_some_and(|old| old.value == prop.value);
+            let times = self.times.entry(prop.id).or_insert(PropertyTimes {
+                read: now,
+                changed: now,
+            });
+
+            times.read = now;
+
+            if !unchanged {
+                times.changed = now;
+            }
+        }
+    }
+
+    /// Returns how long the value has been the same, if it was read
+    /// repeatedly for longer than the window without changing.
+    fn unchanged_for(&self, id: PropertyId, window: Duration) -> Option<Duration> {
+        let times = self.times.get(&id)?;
+
+        (times.read.duration_since(times.changed) >= window).then(|| times.changed.elapsed())
+    }
+
+    /// Find a stored property by ID.
+    fn get(&self, id: PropertyId) -> Option<&PropertyData> {
+        let (kind, idx) = *self.index.get(&id)?;
+
+        self.of_kind(kind).0.get(idx)
+    }
+
+    /// Find a stored property by ID.
+    fn find_mut(&mut self, id: PropertyId) -> Option<&mut PropertyData> {
+        let (kind, idx) = *self.index.get(&id)?;
+
+        self.of_kind_mut(kind).0.get_mut(idx)
+    }
+
     fn clear(&mut self) {
         self.general = Default::default();
         self.failure = Default::default();
+        self.warning = Default::default();
         self.operation = Default::default();
         self.io = Default::default();
+        self.index.clear();
+        self.times.clear();
     }
 }
 
-/// Main application state
-pub struct FreeMduApp {
-    /// Available serial ports
-    available_ports: Vec<String>,
-    /// Selected port index
-    selected_port: usize,
+/// All property kinds, in display order
+const PROPERTY_KINDS: [PropertyKind; 5] = [
+    PropertyKind::General,
+    PropertyKind::Failure,
+    PropertyKind::Warning,
+    PropertyKind::Operation,
+    PropertyKind::Io,
+];
+
+/// Snapshot of the displayed properties, exported as JSON
+#[derive(Serialize)]
+struct SnapshotExport<'a> {
+    software_id: u16,
+    kind: DeviceKind,
+    sections: Vec<SectionExport<'a>>,
+}
+
+/// Properties of a single kind in a [`SnapshotExport`]
+#[derive(Serialize)]
+struct SectionExport<'a> {
+    kind: PropertyKind,
+    /// Time of the last update in RFC 3339 format, if queried yet
+    updated: Option<String>,
+    properties: &'a [PropertyData],
+}
+
+impl PropertyStorage {
+    /// Serialize all stored properties along with the device they belong to
+    fn to_json(&self, info: &DeviceInfo) -> serde_json::Result<String> {
+        let now = jiff::Timestamp::now();
+        let sections = PROPERTY_KINDS
+            .into_iter()
+            .map(|kind| {
+                let (props, time) = self.of_kind(kind);
+
+                SectionExport {
+                    kind,
+                    updated: time
+                        .and_then(|time| now.checked_sub(time.elapsed()).ok())
+                        .map(|time| time.to_string()),
+                    properties: props,
+                }
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&SnapshotExport {
+            software_id: info.software_id,
+            kind: info.kind,
+            sections,
+        })
+    }
+
+    /// All stored properties along with their read and change times, for saving the session
+    fn to_saved(&self) -> Vec<SavedSection> {
+        PROPERTY_KINDS
+            .into_iter()
+            .map(|kind| {
+                let (props, time) = self.of_kind(kind);
+
+                SavedSection {
+                    kind,
+                    updated: time.and_then(session_file::format_time),
+                    properties: props
+                        .iter()
+                        .map(|prop| {
+                            let times = self.times.get(&prop.id);
+
+                            SavedProperty::new(
+                                prop,
+                                times.map(|times| times.read),
+                                times.map(|times| times.changed),
+                            )
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Replace all properties with those of a saved session
+    ///
+    /// Sections that weren't queried yet are shown as updated at the given time,
+    /// so that the viewer doesn't wait for them.
+    fn restore(&mut self, software_id: u16, sections: Vec<SavedSection>, saved: Instant) {
+        self.clear();
+
+        for section in sections {
+            let mut data = Vec::new();
+
+            for prop in section.properties {
+                let read = prop.read.as_deref().and_then(session_file::parse_time);
+                let changed = prop.changed.as_deref().and_then(session_file::parse_time);
+
+                if let Some(prop) = prop.restore(software_id) {
+                    if let (Some(read), Some(changed)) = (read, changed) {
+                        self.times.insert(prop.id, PropertyTimes { read, changed });
+                    }
+
+                    self.index.insert(prop.id, (section.kind, data.len()));
+                    data.push(prop);
+                }
+            }
+
+            let updated = section
+                .updated
+                .as_deref()
+                .and_then(session_file::parse_time);
+
+            *self.of_kind_mut(section.kind) = (data, Some(updated.unwrap_or(saved)));
+        }
+    }
+}
+
+/// Request from a property's context menu
+enum PropertyRequest {
+    Refresh(PropertyId),
+    Smooth(PropertyId, bool),
+    Graph(PropertyId, bool),
+    Set(PropertyId, PropertyValue),
+    Favorite(PropertyKind, String, bool),
+    /// Copy the formatted value of the named property to the clipboard
+    Copy(String, String),
+    /// Copy the displayed properties of a section, which has the given title, as a text table
+    CopySection(PropertyKind, String),
+    /// Change the order of a section after its column header was clicked
+    Sort(PropertyKind, SortColumn),
+    /// Hide or show the properties of a section
+    Collapse(PropertyKind, bool),
+    /// Add a property to the overlaid graph or remove it
+    Overlay(PropertyId, bool),
+    /// Raise an alert once the failure indicator with the given key becomes active, or stop
+    Alert(String, bool),
+    /// Include the named property in refreshes of its section, or exclude it
+    Poll(PropertyKind, String, bool),
+    /// Save the samples of a graph, which is shown in the given area
+    Export(PropertyId, GraphExport, egui::Rect),
+}
+
+/// File format of an exported graph
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum GraphExport {
+    /// Samples as CSV
+    Csv,
+    /// Screenshot of the graph as PNG
+    Png,
+}
+
+impl GraphExport {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Png => "png",
+        }
+    }
+}
+
+/// Column the properties of a section can be sorted by
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+enum SortColumn {
+    Name,
+    Value,
+}
+
+/// Display order of the properties of a section, instead of the definition order
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+struct PropertySort {
+    column: SortColumn,
+    descending: bool,
+}
+
+impl PropertySort {
+    /// Order after clicking a column header, cycling from ascending
+    /// to descending and back to the definition order
+    fn toggle(sort: Option<Self>, column: SortColumn) -> Option<Self> {
+        match sort {
+            Some(sort) if sort.column == column && sort.descending => None,
+            Some(sort) if sort.column == column => Some(Self {
+                column,
+                descending: true,
+            }),
+            _ => Some(Self {
+                column,
+                descending: false,
+            }),
+        }
+    }
+
+    /// Sort the properties for display, numeric values by their number
+    fn apply(self, props: &mut [&PropertyData]) {
+        props.sort_by(|a, b| {
+            let order = match self.column {
+                SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortColumn::Value => compare_values(&a.value, &b.value),
+            };
+
+            if self.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+    }
+}
+
+/// Which properties are shown and how, independent of the connected device
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Layout {
+    /// Sections whose properties are hidden
+    collapsed: Vec<PropertyKind>,
+    /// Properties shown in the favorites panel by kind and name, in the order they were added
+    favorites: Vec<(PropertyKind, String)>,
+    /// Only properties whose name contains this text are shown
+    filter: String,
+    /// Display order of each section, the definition order if missing
+    sort: Vec<(PropertyKind, PropertySort)>,
+}
+
+/// Named layouts, one of which is in use
+///
+/// Changes to the layout apply to the profile in use,
+/// so that switching back restores it as it was left.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+struct LayoutProfiles {
+    /// Layouts by name, never empty
+    profiles: Vec<(String, Layout)>,
+    /// Index of the profile in use
+    active: usize,
+}
+
+impl Default for LayoutProfiles {
+    fn default() -> Self {
+        Self {
+            profiles: vec![(DEFAULT_PROFILE.to_string(), Layout::default())],
+            active: 0,
+        }
+    }
+}
+
+impl LayoutProfiles {
+    /// Load the persisted profiles, selecting the one used last
+    fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage
+            .and_then(|storage| storage.get_string(LAYOUT_PROFILES_KEY))
+            .and_then(|src| serde_json::from_str::<Self>(&src).ok())
+            .filter(|profiles| profiles.active < profiles.profiles.len())
+            .unwrap_or_default()
+    }
+
+    fn name(&self) -> &str {
+        &self.profiles[self.active].0
+    }
+
+    fn layout(&self) -> &Layout {
+        &self.profiles[self.active].1
+    }
+
+    fn layout_mut(&mut self) -> &mut Layout {
+        &mut self.profiles[self.active].1
+    }
+
+    /// Save the layout in use under the given name, replacing a profile of the same name,
+    /// and continue with the saved profile
+    fn save_as(&mut self, name: &str) {
+        let layout = self.layout().clone();
+
+        if let Some(idx) = self.profiles.iter().position(|(n, _)| n == name) {
+            self.profiles[idx].1 = layout;
+            self.active = idx;
+        } else {
+            self.profiles.push((name.to_string(), layout));
+            self.active = self.profiles.len() - 1;
+        }
+    }
+
+    /// Delete the profile in use, unless it's the only one, and continue with the first one
+    fn remove_active(&mut self) {
+        if self.profiles.len() > 1 {
+            self.profiles.remove(self.active);
+            self.active = 0;
+        }
+    }
+}
+
+/// Refresh of all property kinds requested via "Refresh All"
+#[derive(Debug, Clone, PartialEq)]
+enum FullRefresh {
+    /// Requested at the given time, started after [`REFRESH_ALL_DEBOUNCE`]
+    Pending(Instant),
+    /// Running, along with the kinds that were received so far
+    Running(Vec<PropertyKind>),
+    /// Run by the worker after connecting, along with the kinds that were received so far
+    WarmUp(Vec<PropertyKind>),
+}
+
+/// Destinations that queried property values are exported to, each if enabled
+#[derive(Clone, Copy)]
+struct Exports<'a> {
+    metrics: Option<&'a MetricsServer>,
+    mqtt: Option<&'a MqttPublisher>,
+    influx: Option<&'a InfluxWriter>,
+    stream: Option<&'a EventStream>,
+}
+
+/// State of a single device connection, shown in its own tab
+struct Session {
     /// Current connection state
     connection_state: ConnectionState,
     /// Worker handle for device communication
     worker: Option<WorkerHandle>,
     /// Property data organized by kind
     properties: PropertyStorage,
+    /// Values being edited for writable properties
+    property_edits: HashMap<PropertyId, PropertyValue>,
     /// Action input values
-    action_inputs: std::collections::HashMap<String, String>,
+    action_inputs: HashMap<String, String>,
+    /// Values returned by the most recent execution of each action
+    action_results: HashMap<String, PropertyValue>,
+    /// Time to wait for each action to complete, in seconds
+    action_timeouts: HashMap<String, u64>,
+    /// Progress of the currently running action
+    running_action: Option<ActionProgress>,
+    /// Current reconnection attempt after the serial link was lost
+    reconnect_attempt: Option<u32>,
+    /// Whether new port settings were requested and not applied yet
+    reconfiguring: bool,
+    /// Link quality reported by the worker
+    /// Link quality and time of connecting, as last reported by the worker
+    link_stats: Option<(TransportStats, Instant)>,
+    /// Average and last round-trip time of property queries, in milliseconds
+    latency: Option<(f64, f64)>,
+    /// Properties that are no longer polled by ID, kind and name
+    unsupported: Vec<(PropertyId, PropertyKind, String)>,
     /// Status message
     status_message: Option<(String, Instant, bool)>, // (message, time, is_error)
-    /// Auto-refresh enabled
-    auto_refresh: bool,
     /// Last refresh time
     last_refresh: Instant,
+    /// Refresh of all properties requested by the user, if any
+    full_refresh: Option<FullRefresh>,
+    /// Port used for the most recent connection attempt
+    last_port: Option<String>,
+    /// Display-only smoothing of noisy numeric values
+    smoother: Smoother,
+    /// Recent numeric values for graphs
+    history: History,
+    /// Properties with an open graph, in the order they were opened
+    graphs: Vec<PropertyId>,
+    /// File that property samples are currently logged to
+    csv_log: Option<PathBuf>,
+    /// Path entered in the start logging dialog, if open
+    csv_log_dialog: Option<String>,
+    /// Dialog for saving the session to a file or opening a saved one, if open
+    file_dialog: Option<SessionFileDialog>,
+    /// Faults stored by the device, read along with the failure properties
+    fault_history: Option<Result<Vec<FaultRecord>, String>>,
+    /// Show the console for sending raw requests
+    developer_tools: bool,
+    /// Hex bytes of the raw request being entered
+    raw_input: String,
+    /// Raw requests sent and their replies, oldest first
+    raw_log: Vec<RawExchange>,
+    /// Executed actions and their results, oldest first
+    action_log: Vec<ActionLogEntry>,
+    /// Failures that became active and haven't been acknowledged
+    alerts: AlertMonitor,
 }
 
-impl FreeMduApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+impl Session {
+    fn new() -> Self {
         Self {
-            available_ports: list_serial_ports(),
-            selected_port: 0,
             connection_state: ConnectionState::Disconnected,
             worker: None,
             properties: PropertyStorage::default(),
-            action_inputs: std::collections::HashMap::new(),
+            property_edits: HashMap::new(),
+            action_inputs: HashMap::new(),
+            action_results: HashMap::new(),
+            action_timeouts: HashMap::new(),
+            running_action: None,
+            reconnect_attempt: None,
+            reconfiguring: false,
+            link_stats: None,
+            latency: None,
+            unsupported: Vec::new(),
             status_message: None,
-            auto_refresh: true,
             last_refresh: Instant::now(),
+            full_refresh: None,
+            last_port: None,
+            smoother: Smoother::default(),
+            history: History::default(),
+            graphs: Vec::new(),
+            csv_log: None,
+            csv_log_dialog: None,
+            file_dialog: None,
+            fault_history: None,
+            developer_tools: false,
+            raw_input: String::new(),
+            raw_log: Vec::new(),
+            action_log: Vec::new(),
+            alerts: AlertMonitor::default(),
         }
     }
 
-    fn refresh_ports(&mut self) {
-        self.available_ports = list_serial_ports();
-        if self.selected_port >= self.available_ports.len() {
-            self.selected_port = 0;
+    /// Store a single property queried on request, exporting it if enabled
+    fn property_received(&mut self, data: PropertyData, alerting: &[String], exports: Exports) {
+        let kind = self.properties.index.get(&data.id).map(|&(kind, _)| kind);
+
+        if let Some(kind) = kind {
+            self.raise_alerts(alerting, kind, std::slice::from_ref(&data));
+        }
+
+        self.smoother.update(std::slice::from_ref(&data));
+        self.history.update(std::slice::from_ref(&data));
+
+        if let (Some(metrics), Some(port)) = (exports.metrics, &self.last_port) {
+            metrics.update(port, std::slice::from_ref(&data));
+        }
+
+        if let (Some(mqtt), ConnectionState::Connected(info), Some(kind)) =
+            (exports.mqtt, &self.connection_state, kind)
+        {
+            mqtt.publish(info, kind, std::slice::from_ref(&data));
         }
+
+        if let (Some(stream), Some(port), Some(kind)) = (exports.stream, &self.last_port, kind) {
+            stream.publish(port, kind, std::slice::from_ref(&data));
+        }
+
+        self.properties.update(data);
     }
 
-    fn connect(&mut self) {
-        if self.available_ports.is_empty() {
-            self.set_status("No serial ports available", true);
+    /// Notify about alerting failures that became active
+    fn raise_alerts(&mut self, alerting: &[String], kind: PropertyKind, data: &[PropertyData]) {
+        if kind != PropertyKind::Failure {
             return;
         }
 
-        let port_name = self.available_ports[self.selected_port].clone();
-        self.connection_state = ConnectionState::Connecting;
-        self.worker = Some(WorkerHandle::new(&port_name));
-        self.set_status(&format!("Connecting to {port_name}..."), false);
+        for label in self.alerts.update(alerting, data) {
+            log::warn!("Failure active: {label}");
+            alert::notify(&label);
+            self.set_status(&format!("Failure active: {label}"), true);
+        }
     }
 
-    fn disconnect(&mut self) {
-        self.worker = None;
-        self.connection_state = ConnectionState::Disconnected;
-        self.properties.clear();
-        self.set_status("Disconnected", false);
+    /// Label of the session's tab
+    fn title(&self) -> String {
+        match (&self.connection_state, &self.last_port) {
+            (ConnectionState::Connected(info), Some(port)) => format!("{} ({port})", info.kind),
+            (ConnectionState::Viewing(info), _) => format!("{} (saved)", info.kind),
+            (_, Some(port)) => port.clone(),
+            (_, None) => "New connection".to_string(),
+        }
     }
 
-    fn set_status(&mut self, message: &str, is_error: bool) {
-        self.status_message = Some((message.to_string(), Instant::now(), is_error));
+    /// Returns whether the session holds a port, i.e. is connected or connecting
+    fn is_open(&self) -> bool {
+        self.worker.is_some()
     }
 
-    fn process_worker_responses(&mut self) {
-        // Collect all responses first to avoid borrow issues
-        let responses: Vec<_> = {
-            let Some(worker) = &self.worker else { return };
-            let mut responses = Vec::new();
-            while let Some(response) = worker.try_recv() {
-                responses.push(response);
+    /// Color and description of the connection state
+    fn state_indicator(&self) -> (Color32, String) {
+        match (&self.connection_state, self.reconnect_attempt) {
+            (ConnectionState::Disconnected, _) => (Color32::GRAY, "Disconnected".to_string()),
+            (ConnectionState::Connecting, _) => (Color32::YELLOW, "Connecting...".to_string()),
+            (ConnectionState::Connected(_), Some(attempt)) => (
+                Color32::YELLOW,
+                format!("Reconnecting ({attempt}/{MAX_RECONNECT_ATTEMPTS})…"),
+            ),
+            (ConnectionState::Connected(info), None) if info.read_only => {
+                (Color32::GREEN, "Connected (read-only)".to_string())
             }
-            responses
-        };
-
-        for response in responses {
-            match response {
-                WorkerResponse::Connected(info) => {
-                    self.set_status(
-                        &format!("Connected to {} (ID: {})", info.kind, info.software_id),
-                        false,
-                    );
-                    self.connection_state = ConnectionState::Connected(info);
-                }
-                WorkerResponse::Properties(kind, data) => {
-                    let storage = self.properties.get_mut(kind);
-                    storage.0 = data;
-                    storage.1 = Some(Instant::now());
-                }
-                WorkerResponse::ActionResult(action_name, success, message) => {
-                    if success {
-                        self.set_status(&format!("Action '{action_name}' executed"), false);
-                    } else {
-                        self.set_status(&format!("Action '{action_name}' failed: {message}"), true);
-                    }
-                }
-                WorkerResponse::Error(e) => {
-                    self.connection_state = ConnectionState::Error(e.clone());
-                    self.set_status(&format!("Error: {e}"), true);
-                }
-                WorkerResponse::Disconnected => {
-                    self.connection_state = ConnectionState::Disconnected;
-                    self.worker = None;
-                    self.set_status("Device disconnected", true);
-                }
+            (ConnectionState::Connected(_), None) => (Color32::GREEN, "Connected".to_string()),
+            (ConnectionState::Error(_), _) => (Color32::RED, "Error".to_string()),
+            (ConnectionState::Viewing(_), _) => {
+                (Color32::LIGHT_BLUE, "Viewing saved session".to_string())
             }
         }
     }
 
-    fn request_property_update(&mut self, kind: PropertyKind) {
-        if let Some(worker) = &self.worker {
-            worker.send(WorkerCommand::QueryProperties(kind));
+    /// Write the graph samples of a property to a CSV file, reporting the result
+    fn export_samples(&mut self, id: PropertyId, path: &Path) {
+        let (Some(samples), Some(prop)) = (self.history.get(id), self.properties.get(id)) else {
+            return;
+        };
+        let res = File::create(path).and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            let now = (Instant::now(), jiff::Timestamp::now());
+
+            plot::write_csv(&mut writer, samples, &prop.name, prop.unit.as_deref(), now)?;
+            writer.flush()
+        });
+
+        match res {
+            Ok(()) => self.set_status(&format!("Saved samples to {}", path.display()), false),
+            Err(e) => self.set_status(&format!("Failed to save samples: {e}"), true),
         }
     }
 
-    fn auto_refresh_properties(&mut self) {
-        if !self.auto_refresh {
-            return;
+    /// Request a refresh of all properties
+    ///
+    /// Requests while one is pending or running are merged into it.
+    fn refresh_all(&mut self) {
+        if !matches!(
+            self.full_refresh,
+            Some(FullRefresh::Running(_) | FullRefresh::WarmUp(_))
+        ) {
+            self.full_refresh = Some(FullRefresh::Pending(Instant::now()));
         }
+    }
 
-        if !matches!(self.connection_state, ConnectionState::Connected(_)) {
+    /// Start the requested refresh of all properties once no further request followed
+    ///
+    /// Kinds without data are queried by [`Session::auto_refresh_properties`].
+    fn start_full_refresh(&mut self) {
+        let Some(FullRefresh::Pending(time)) = self.full_refresh else {
             return;
-        }
+        };
 
-        let now = Instant::now();
-        if now.duration_since(self.last_refresh) < Duration::from_millis(500) {
-            return;
+        if time.elapsed() >= REFRESH_ALL_DEBOUNCE {
+            self.properties.general.1 = None;
+            self.properties.failure.1 = None;
+            self.properties.warning.1 = None;
+            self.properties.operation.1 = None;
+            self.properties.io.1 = None;
+            self.full_refresh = Some(FullRefresh::Running(Vec::new()));
+
+            // Give properties that are no longer polled another chance
+            if let Some(worker) = &self.worker {
+                worker.send(WorkerCommand::ProbeUnsupported);
+            }
         }
-        self.last_refresh = now;
+    }
 
-        // Refresh I/O properties most frequently, then operation, then others
-        let kinds = [
-            (PropertyKind::Io, Duration::from_millis(500)),
-            (PropertyKind::Operation, Duration::from_secs(1)),
-            (PropertyKind::Failure, Duration::from_secs(5)),
-            (PropertyKind::General, Duration::from_secs(30)),
-        ];
+    /// Account for received properties in the running refresh of all properties
+    fn full_refresh_received(&mut self, kind: PropertyKind) {
+        if let Some(FullRefresh::Running(received) | FullRefresh::WarmUp(received)) =
+            &mut self.full_refresh
+        {
+            if !received.contains(&kind) {
+                received.push(kind);
+            }
 
-        for (kind, interval) in kinds {
-            let last_update = self.properties.get(kind).1;
-            let should_update = last_update.map_or(true, |t| now.duration_since(t) >= interval);
+            if received.len() == PROPERTY_KINDS.len() {
+                self.full_refresh = None;
+            }
+        }
+    }
 
-            if should_update {
-                self.request_property_update(kind);
-                break; // Only request one at a time
+    /// Number of kinds received by the refresh of all properties, if requested
+    fn full_refresh_progress(&self) -> Option<usize> {
+        match &self.full_refresh {
+            Some(FullRefresh::Pending(_)) => Some(0),
+            Some(FullRefresh::Running(received) | FullRefresh::WarmUp(received)) => {
+                Some(received.len())
             }
+            None => None,
         }
     }
-}
 
-impl eframe::App for FreeMduApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Process worker responses
-        self.process_worker_responses();
+    fn disconnect(&mut self) {
+        self.worker = None;
+        self.connection_state = ConnectionState::Disconnected;
+        self.properties.clear();
+        self.fault_history = None;
+        self.action_results.clear();
+        self.running_action = None;
+        self.reconnect_attempt = None;
+        self.reconfiguring = false;
+        self.link_stats = None;
+        self.latency = None;
+        self.unsupported.clear();
+        self.full_refresh = None;
+        self.smoother.reset();
+        self.history.reset();
+        self.property_edits.clear();
+        self.csv_log = None;
+        self.set_status("Disconnected", false);
+    }
 
-        // Auto-refresh properties
-        self.auto_refresh_properties();
+    fn set_status(&mut self, message: &str, is_error: bool) {
+        self.status_message = Some((message.to_string(), Instant::now(), is_error));
+    }
 
-        // Request repaint for continuous updates
-        if matches!(self.connection_state, ConnectionState::Connected(_)) {
-            ctx.request_repaint_after(Duration::from_millis(100));
+    /// Apply new settings to the serial port without disconnecting
+    fn reconfigure(&mut self, config: SerialConfig) {
+        if let Some(worker) = &self.worker {
+            worker.send(WorkerCommand::Reconfigure(config));
+            self.reconfiguring = true;
+            self.set_status(&format!("Switching to {} baud...", config.baud_rate), false);
         }
+    }
 
-        // Top panel with connection controls
-        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
-            ui.add_space(4.0);
-            ui.horizontal(|ui| {
-                ui.heading("FreeMDU");
-                ui.separator();
-                self.render_connection_controls(ui);
-            });
-            ui.add_space(4.0);
-        });
+    /// Export a batch of queried properties to the enabled destinations
+    fn export_batch(&self, kind: PropertyKind, data: &[PropertyData], exports: Exports) {
+        if let Some(port) = &self.last_port {
+            if let Some(metrics) = exports.metrics {
+                metrics.update(port, data);
+            }
 
-        // Bottom panel with status bar
-        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
-            ui.add_space(2.0);
-            self.render_status_bar(ui);
-            ui.add_space(2.0);
-        });
+            if let Some(stream) = exports.stream {
+                stream.publish(port, kind, data);
+            }
+        }
 
-        // Left panel with actions (if connected)
-        if let ConnectionState::Connected(ref info) = self.connection_state {
-            let actions = info.actions.clone();
-            egui::SidePanel::left("actions_panel")
-                .resizable(true)
-                .default_width(200.0)
-                .show(ctx, |ui| {
-                    ui.heading("Actions");
-                    ui.separator();
-                    self.render_actions(ui, &actions);
-                });
+        let ConnectionState::Connected(info) = &self.connection_state else {
+            return;
+        };
+
+        if let Some(mqtt) = exports.mqtt {
+            mqtt.publish(info, kind, data);
         }
 
-        // Central panel with properties
-        egui::CentralPanel::default().show(ctx, |ui| match &self.connection_state {
-            ConnectionState::Disconnected => {
-                ui.centered_and_justified(|ui| {
-                    ui.label("Select a serial port and click Connect to start.");
-                });
-            }
-            ConnectionState::Connecting => {
-                ui.centered_and_justified(|ui| {
-                    ui.spinner();
-                    ui.label("Connecting to device...");
-                });
-            }
-            ConnectionState::Connected(_) => {
-                self.render_properties(ui);
-            }
-            ConnectionState::Error(e) => {
-                ui.centered_and_justified(|ui| {
-                    ui.colored_label(Color32::RED, format!("Error: {e}"));
-                });
-            }
-        });
+        // Written per batch like the CSV log, single updates are left out
+        if let Some(influx) = exports.influx {
+            influx.write(info, data);
+        }
     }
-}
 
-impl FreeMduApp {
-    fn render_connection_controls(&mut self, ui: &mut Ui) {
-        let is_connected = matches!(
-            self.connection_state,
-            ConnectionState::Connected(_) | ConnectionState::Connecting
-        );
+    /// Show how the device was connected and send it the properties excluded from polling
+    fn device_connected(&mut self, info: DeviceInfo, unpolled: &[(PropertyKind, String)]) {
+        // The worker queries every kind once when it first connects
+        let warm_up = self.reconnect_attempt.is_none()
+            && !self.reconfiguring
+            && !matches!(self.connection_state, ConnectionState::Connected(_));
 
-        // Refresh ports button
-        if ui
-            .add_enabled(!is_connected, egui::Button::new("🔄"))
-            .on_hover_text("Refresh port list")
-            .clicked()
-        {
-            self.refresh_ports();
+        if self.reconnect_attempt.take().is_some() {
+            // Properties and refresh times are kept across reconnections
+            self.set_status("Reconnected", false);
+        } else if std::mem::take(&mut self.reconfiguring) {
+            // The refresh running before was dropped
+            self.full_refresh = None;
+            self.set_status(&format!("Switched to {} baud", info.baud_rate), false);
+        } else if matches!(self.connection_state, ConnectionState::Connected(_)) {
+            // Previous port settings were restored, the failure stays shown
+        } else if info.baud_rate == freemdu::serial::BAUD_RATES[0] {
+            self.set_status(&format!("Connected to {}", describe_device(&info)), false);
+        } else {
+            // Device kept a baud rate configured by a previous session
+            self.set_status(
+                &format!(
+                    "Connected to {} at {} baud, power-cycle the device to restore the default",
+                    describe_device(&info),
+                    info.baud_rate
+                ),
+                false,
+            );
         }
 
-        // Port selector
-        let port_label = if self.available_ports.is_empty() {
-            "No ports found".to_string()
-        } else {
-            self.available_ports[self.selected_port].clone()
+        if let Some(worker) = &self.worker {
+            worker.send(WorkerCommand::SetUnpolled(unpolled.to_vec()));
+        }
+
+        if warm_up {
+            self.full_refresh = Some(FullRefresh::WarmUp(Vec::new()));
+        }
+
+        self.connection_state = ConnectionState::Connected(info);
+        self.link_stats = None;
+        self.latency = None;
+    }
+
+    /// Handle queued worker responses, exporting queried values to the enabled destinations
+    fn process_worker_responses(
+        &mut self,
+        alerting: &[String],
+        unpolled: &[(PropertyKind, String)],
+        temperature_unit: TemperatureUnit,
+        exports: Exports,
+    ) {
+        // Collect all responses first to avoid borrow issues
+        let responses: Vec<_> = {
+            let Some(worker) = &self.worker else { return };
+            let mut responses = Vec::new();
+            while let Some(response) = worker.try_recv() {
+                responses.push(response);
+            }
+            responses
         };
 
-        ui.add_enabled_ui(!is_connected, |ui| {
-            egui::ComboBox::from_id_salt("port_selector")
-                .selected_text(&port_label)
-                .show_ui(ui, |ui| {
-                    for (i, port) in self.available_ports.iter().enumerate() {
-                        ui.selectable_value(&mut self.selected_port, i, port);
+        for response in responses {
+            match response {
+                WorkerResponse::Connected(info) => self.device_connected(info, unpolled),
+                WorkerResponse::LinkStats { stats, connected } => {
+                    self.link_stats = Some((stats, connected));
+                }
+                WorkerResponse::Latency { avg_ms, last_ms } => {
+                    self.latency = Some((avg_ms, last_ms));
+                }
+                WorkerResponse::Unsupported(props) => self.unsupported = props,
+                WorkerResponse::Properties(kind, data) => {
+                    self.full_refresh_received(kind);
+                    self.raise_alerts(alerting, kind, &data);
+                    self.smoother.update(&data);
+                    self.history.update(&data);
+                    self.export_batch(kind, &data, exports);
+
+                    self.properties.set(kind, data);
+                }
+                WorkerResponse::Property(data) => {
+                    self.property_received(data, alerting, exports);
+                }
+                WorkerResponse::FaultHistory(res) => self.fault_history = Some(res),
+                WorkerResponse::PropertyWritten(name, res) => match res {
+                    Ok(()) => self.set_status(&format!("{name} changed"), false),
+                    Err(e) => self.set_status(&format!("Failed to change {name}: {e}"), true),
+                },
+                WorkerResponse::ActionProgress(progress) => {
+                    self.running_action = Some(progress);
+                }
+                WorkerResponse::ActionResult(outcome) => {
+                    self.handle_action_result(outcome, temperature_unit);
+                }
+                WorkerResponse::Reconnecting(attempt) => {
+                    self.reconnect_attempt = Some(attempt);
+                    self.running_action = None;
+                    self.full_refresh = None;
+                    self.set_status("Serial link lost, reconnecting...", true);
+                }
+                WorkerResponse::ReconfigureFailed(e) => {
+                    self.reconfiguring = false;
+                    self.full_refresh = None;
+                    self.set_status(&format!("Failed to change port settings: {e}"), true);
+                }
+                WorkerResponse::Error(e) => {
+                    self.connection_state = ConnectionState::Error(e.clone());
+                    self.full_refresh = None;
+                    self.set_status(&format!("Error: {e}"), true);
+                }
+                WorkerResponse::RawResponse(req, res) => {
+                    if self.raw_log.len() == RAW_LOG_LIMIT {
+                        let _ = self.raw_log.remove(0);
                     }
-                });
-        });
 
-        // Connect/Disconnect button
-        if is_connected {
-            if ui.button("Disconnect").clicked() {
-                self.disconnect();
+                    self.raw_log.push((req, res));
+                }
+                WorkerResponse::LoggingError(e) => {
+                    self.csv_log = None;
+                    self.set_status(&format!("Logging stopped: {e}"), true);
+                }
+                WorkerResponse::Disconnected => {
+                    self.connection_state = ConnectionState::Disconnected;
+                    self.full_refresh = None;
+                    self.reconnect_attempt = None;
+                    self.worker = None;
+                    self.csv_log = None;
+                    self.set_status("Device disconnected", true);
+                }
             }
-        } else if ui
-            .add_enabled(
-                !self.available_ports.is_empty(),
-                egui::Button::new("Connect"),
-            )
-            .clicked()
-        {
-            self.connect();
         }
+    }
 
-        ui.separator();
+    fn handle_action_result(&mut self, outcome: ActionOutcome, temperature_unit: TemperatureUnit) {
+        let ActionOutcome {
+            id,
+            name,
+            param,
+            success,
+            message,
+            value,
+        } = outcome;
+        let result = match &value {
+            Some(value) if success => format_value(value, None, 0, false, temperature_unit),
+            _ => message,
+        };
+        let param = param.map(|input| match &self.connection_state {
+            ConnectionState::Connected(info) => info
+                .actions
+                .iter()
+                .find(|action| action.id == id)
+                .and_then(|action| action.params.as_ref())
+                .map_or_else(|| input.clone(), |params| params.describe(&input)),
+            _ => input,
+        });
 
-        // Auto-refresh toggle
-        ui.checkbox(&mut self.auto_refresh, "Auto-refresh");
+        self.running_action = None;
 
-        // Manual refresh button
-        if matches!(self.connection_state, ConnectionState::Connected(_))
-            && ui.button("Refresh All").clicked()
-        {
-            // Clear last update times to force refresh
-            self.properties.general.1 = None;
-            self.properties.failure.1 = None;
-            self.properties.operation.1 = None;
-            self.properties.io.1 = None;
+        if !success {
+            self.action_results.remove(&id);
+            self.set_status(&format!("Action '{name}' failed: {result}"), true);
+        } else if let Some(value) = value {
+            self.set_status(&format!("Action '{name}' returned {result}"), false);
+            self.action_results.insert(id, value);
+        } else {
+            self.action_results.remove(&id);
+            self.set_status(&format!("Action '{name}' executed"), false);
+        }
+
+        if self.action_log.len() == ACTION_LOG_LIMIT {
+            let _ = self.action_log.remove(0);
         }
+
+        self.action_log.push(ActionLogEntry {
+            time: jiff::Timestamp::now(),
+            name,
+            param,
+            success,
+            result,
+        });
     }
 
-    fn render_status_bar(&self, ui: &mut Ui) {
-        ui.horizontal(|ui| {
-            // Connection status indicator
-            let (color, text) = match &self.connection_state {
-                ConnectionState::Disconnected => (Color32::GRAY, "Disconnected"),
-                ConnectionState::Connecting => (Color32::YELLOW, "Connecting..."),
-                ConnectionState::Connected(_) => (Color32::GREEN, "Connected"),
-                ConnectionState::Error(_) => (Color32::RED, "Error"),
-            };
+    fn request_property_update(&mut self, kind: PropertyKind) {
+        if let Some(worker) = &self.worker {
+            worker.send(WorkerCommand::QueryProperties(kind));
 
-            ui.colored_label(color, "●");
-            ui.label(text);
+            if kind == PropertyKind::Failure {
+                worker.send(WorkerCommand::QueryFaultHistory);
+            }
+        }
+    }
 
-            ui.separator();
+    /// Returns whether the worker can take property queries
+    fn can_refresh(&self) -> bool {
+        // The worker is busy until the action completes or the device is reconnected
+        matches!(self.connection_state, ConnectionState::Connected(_))
+            && self.running_action.is_none()
+            && self.reconnect_attempt.is_none()
+    }
 
-            // Status message
-            if let Some((msg, time, is_error)) = &self.status_message {
-                let elapsed = time.elapsed();
-                if elapsed < Duration::from_secs(10) {
-                    let color = if *is_error {
-                        Color32::RED
-                    } else {
-                        Color32::GRAY
-                    };
-                    ui.colored_label(color, msg);
-                }
+    /// Query a kind of properties right away, unless the worker is busy
+    fn refresh_now(&mut self, kind: PropertyKind) {
+        if self.can_refresh() {
+            self.request_property_update(kind);
+        }
+    }
+
+    /// Query properties that are due according to their refresh interval
+    ///
+    /// If `missing_only` is set, only kinds without data are queried,
+    /// e.g. after connecting or [`Session::refresh_all`].
+    fn auto_refresh_properties(
+        &mut self,
+        refresh_intervals: &[(PropertyKind, Duration); 5],
+        missing_only: bool,
+    ) {
+        self.start_full_refresh();
+
+        // Requests would only delay the kinds still to be queried by the worker
+        if !self.can_refresh() || matches!(self.full_refresh, Some(FullRefresh::WarmUp(_))) {
+            return;
+        }
+
+        // Don't throttle kinds that are configured to refresh faster
+        let throttle = refresh_intervals
+            .iter()
+            .map(|&(_, interval)| interval)
+            .fold(REFRESH_THROTTLE, Duration::min);
+        let now = Instant::now();
+        if now.duration_since(self.last_refresh) < throttle {
+            return;
+        }
+        self.last_refresh = now;
+
+        // Kinds earlier in the list take priority, e.g. I/O before general properties
+        for &(kind, interval) in refresh_intervals {
+            let last_update = self.properties.of_kind(kind).1;
+            let should_update =
+                last_update.map_or(true, |t| !missing_only && now.duration_since(t) >= interval);
+
+            if should_update {
+                self.request_property_update(kind);
+                break; // Only request one at a time
             }
+        }
+    }
+}
 
-            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                ui.label(format!("v{}", env!("CARGO_PKG_VERSION")));
+/// Main application state
+pub struct FreeMduApp {
+    /// Available serial ports
+    available_ports: Vec<String>,
+    /// Descriptions of the USB adapters behind serial ports, by port name
+    port_labels: HashMap<String, String>,
+    /// Serial-to-TCP bridges and recordings added by the user, listed after the serial ports
+    network_ports: Vec<String>,
+    /// Address of a serial-to-TCP bridge being entered
+    network_port_input: String,
+    /// Path of a recording to replay being entered
+    replay_input: String,
+    /// File that sessions are recorded to when connecting, not recording if empty
+    recording_path: String,
+    /// Selected port index
+    selected_port: usize,
+    /// Device connections, one per tab
+    sessions: Vec<Session>,
+    /// Index of the session in the selected tab
+    active_session: usize,
+    /// Layouts of the property sections, including the one in use
+    profiles: LayoutProfiles,
+    /// Name of the profile being saved
+    new_profile: String,
+    /// Auto-refresh settings
+    auto_refresh: AutoRefresh,
+    /// Time at which auto-refresh was paused because the window lost focus
+    paused_since: Option<Instant>,
+    /// Zoom factor of the UI, persisted separately from egui's own setting
+    zoom_factor: f32,
+    /// Share of the width taken by the left property column
+    column_split: f32,
+    /// Area of a graph and the file it's saved to once the requested screenshot arrives
+    pending_screenshot: Option<(egui::Rect, PathBuf)>,
+    /// Light or dark theme, or following the system's preference
+    theme: ThemePreference,
+    /// Unit used to display temperatures
+    temperature_unit: TemperatureUnit,
+    /// Whether the data each value was decoded from is shown after it
+    show_raw: bool,
+    /// Auto-refresh interval of each property kind, in order of priority
+    refresh_intervals: [(PropertyKind, Duration); 5],
+    /// Readings that don't change for this long while being polled are marked as stale
+    stale_window: Duration,
+    /// Options for the next connection
+    connect_options: ConnectOptions,
+    /// User-defined expressions over property values
+    watches: Vec<WatchExpression>,
+    /// Keys of the failure indicators that raise an alert once active, see [`alert::indicators`]
+    alerting: Vec<String>,
+    /// Properties excluded from polling by kind and name, shown without a value
+    unpolled: Vec<(PropertyKind, String)>,
+    /// Properties graphed together on a shared time axis, in the order they were added
+    overlay: Vec<PropertyId>,
+    /// Scaling of the overlaid graph
+    overlay_scale: OverlayScale,
+    /// Resolution of all graphs
+    resolution: Resolution,
+    /// Name, expression and unit of the watch being added
+    new_watch: (String, String, String),
+    /// Save settings during the next frame instead of waiting for the app to close
+    save_requested: bool,
+    /// Recent log records of all threads
+    logs: LogPanel,
+    /// Last time the port list was refreshed automatically
+    last_port_poll: Instant,
+    /// Serve property values to Prometheus while a device is connected
+    metrics_enabled: bool,
+    /// Port of the metrics endpoint
+    metrics_port: u16,
+    /// Server of the metrics endpoint, if running
+    metrics: Option<MetricsServer>,
+    /// MQTT broker settings, which are applied when the publisher is restarted
+    mqtt_config: MqttConfig,
+    /// Publisher of property values, if enabled
+    mqtt: Option<MqttPublisher>,
+    influx_config: InfluxConfig,
+    /// Writer of property values to `InfluxDB`, if enabled
+    influx: Option<InfluxWriter>,
+    /// Event stream settings, which are applied when the stream is restarted
+    stream_config: StreamConfig,
+    /// Server of the event stream, if enabled
+    stream: Option<EventStream>,
+}
+
+impl FreeMduApp {
+    pub fn new(cc: &eframe::CreationContext<'_>, logs: Arc<LogBuffer>) -> Self {
+        let watches = cc
+            .storage
+            .and_then(|storage| storage.get_string(watch::STORAGE_KEY))
+            .map(|src| watch::deserialize(&src))
+            .unwrap_or_default();
+        let refresh_intervals = cc
+            .storage
+            .and_then(|storage| storage.get_string(REFRESH_INTERVALS_KEY))
+            .map_or(DEFAULT_REFRESH_INTERVALS, |src| {
+                deserialize_refresh_intervals(&src)
             });
-        });
+        let network_ports: Vec<String> = cc
+            .storage
+            .and_then(|storage| storage.get_string(NETWORK_PORTS_KEY))
+            .map(|src| src.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        let (available_ports, port_labels) = list_ports(&network_ports);
+        // Fall back to the first port if the remembered one is gone
+        let selected_port = cc
+            .storage
+            .and_then(|storage| storage.get_string(SELECTED_PORT_KEY))
+            .and_then(|name| find_port(&available_ports, &name))
+            .unwrap_or(0);
+        let stale_window = cc
+            .storage
+            .and_then(|storage| storage.get_string(STALE_WINDOW_KEY))
+            .and_then(|src| src.parse().ok())
+            .map_or(DEFAULT_STALE_WINDOW, Duration::from_secs);
+        let auto_refresh = AutoRefresh::load(cc.storage);
+        let zoom_factor = cc
+            .storage
+            .and_then(|storage| storage.get_string(ZOOM_FACTOR_KEY))
+            .and_then(|src| src.parse().ok())
+            .filter(|factor: &f32| factor.is_finite())
+            .map_or(1.0, clamp_zoom);
+        let theme = cc
+            .storage
+            .and_then(|storage| storage.get_string(THEME_KEY))
+            .map_or(ThemePreference::System, |src| deserialize_theme(&src));
+        let temperature_unit = cc
+            .storage
+            .and_then(|storage| storage.get_string(TEMPERATURE_UNIT_KEY))
+            .map(|src| TemperatureUnit::deserialize(&src))
+            .unwrap_or_default();
+        let metrics_enabled = load_flag(cc.storage, METRICS_ENABLED_KEY);
+        let metrics_port = cc
+            .storage
+            .and_then(|storage| storage.get_string(METRICS_PORT_KEY))
+            .and_then(|src| src.parse().ok())
+            .unwrap_or(metrics::DEFAULT_PORT);
+        let mqtt_config = load_json(cc.storage, MQTT_CONFIG_KEY);
+        let influx_config = load_json(cc.storage, INFLUX_CONFIG_KEY);
+        let stream_config = load_json(cc.storage, STREAM_CONFIG_KEY);
+
+        // Zooming is handled by the app, so that the factor is clamped and persisted
+        cc.egui_ctx.options_mut(|o| o.zoom_with_keyboard = false);
+        cc.egui_ctx.set_zoom_factor(zoom_factor);
+        // The system theme is detected by eframe, falling back to dark if unavailable
+        cc.egui_ctx.set_theme(theme);
+
+        Self {
+            available_ports,
+            port_labels,
+            network_ports,
+            network_port_input: String::new(),
+            replay_input: String::new(),
+            recording_path: String::new(),
+            selected_port,
+            sessions: vec![Session::new()],
+            active_session: 0,
+            profiles: LayoutProfiles::load(cc.storage),
+            new_profile: String::new(),
+            auto_refresh,
+            paused_since: None,
+            zoom_factor,
+            column_split: load_column_split(cc.storage),
+            pending_screenshot: None,
+            theme,
+            temperature_unit,
+            show_raw: load_flag(cc.storage, SHOW_RAW_KEY),
+            refresh_intervals,
+            stale_window,
+            connect_options: load_connect_options(cc.storage),
+            watches,
+            alerting: alert::load(cc.storage),
+            unpolled: load_json(cc.storage, UNPOLLED_KEY),
+            overlay: Vec::new(),
+            overlay_scale: OverlayScale::default(),
+            resolution: Resolution::default(),
+            new_watch: Default::default(),
+            save_requested: false,
+            logs: LogPanel::new(logs),
+            last_port_poll: Instant::now(),
+            metrics_enabled,
+            metrics_port,
+            metrics: None,
+            mqtt_config,
+            mqtt: None,
+            influx_config,
+            influx: None,
+            stream_config,
+            stream: None,
+        }
     }
 
-    fn render_properties(&self, ui: &mut Ui) {
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            ui.columns(2, |columns| {
-                // Left column: General and Operation
-                columns[0].vertical(|ui| {
-                    self.render_property_section(ui, PropertyKind::General, "General Information");
-                    ui.add_space(10.0);
-                    self.render_property_section(ui, PropertyKind::Operation, "Operating State");
-                });
+    fn session(&self) -> &Session {
+        &self.sessions[self.active_session]
+    }
 
-                // Right column: Failure and I/O
-                columns[1].vertical(|ui| {
-                    self.render_property_section(ui, PropertyKind::Failure, "Failure Information");
-                    ui.add_space(10.0);
-                    self.render_property_section(ui, PropertyKind::Io, "Input/Output State");
-                });
-            });
-        });
+    fn session_mut(&mut self) -> &mut Session {
+        &mut self.sessions[self.active_session]
     }
 
-    fn render_property_section(&self, ui: &mut Ui, kind: PropertyKind, title: &str) {
-        let header_color = match kind {
-            PropertyKind::General => Color32::from_rgb(76, 175, 80),
-            PropertyKind::Failure => Color32::from_rgb(244, 67, 54),
-            PropertyKind::Operation => Color32::from_rgb(33, 150, 243),
-            PropertyKind::Io => Color32::from_rgb(156, 39, 176),
-        };
+    /// Update the list of available ports, keeping the selected port if it's still present
+    fn refresh_ports(&mut self) {
+        let selected = self.available_ports.get(self.selected_port).cloned();
 
-        egui::Frame::group(ui.style())
-            .fill(ui.style().visuals.extreme_bg_color)
-            .show(ui, |ui| {
-                ui.horizontal(|ui| {
-                    ui.colored_label(header_color, RichText::new(title).strong());
-
-                    // Show last update time
-                    let storage = self.properties.get(kind);
-                    if let Some(time) = storage.1 {
-                        let elapsed = time.elapsed();
-                        let text = if elapsed < Duration::from_secs(1) {
-                            "just now".to_string()
-                        } else {
-                            format!("{}s ago", elapsed.as_secs())
-                        };
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.small(text);
-                        });
-                    }
-                });
+        (self.available_ports, self.port_labels) = list_ports(&self.network_ports);
+        self.selected_port = selected
+            .and_then(|name| find_port(&self.available_ports, &name))
+            .unwrap_or(0);
+    }
 
-                ui.separator();
+    /// Periodically refresh the port list to pick up adapters plugged in or removed
+    fn poll_ports(&mut self) {
+        // The port can't be changed while connected
+        if self.session().is_open() || self.last_port_poll.elapsed() < PORT_POLL_INTERVAL {
+            return;
+        }
 
-                let storage = self.properties.get(kind);
-                let props = &storage.0;
-                let has_data = storage.1.is_some();
+        self.last_port_poll = Instant::now();
 
-                if !has_data {
-                    ui.horizontal(|ui| {
-                        ui.spinner();
-                        ui.label("Loading...");
-                    });
-                } else if props.is_empty() {
-                    ui.label("No properties available");
-                } else {
-                    egui::Grid::new(format!("props_{kind:?}"))
-                        .num_columns(2)
-                        .striped(true)
-                        .spacing([20.0, 4.0])
-                        .show(ui, |ui| {
-                            for prop in props {
-                                ui.label(&prop.name);
-                                ui.label(format_value(&prop.value, prop.unit.as_deref()));
-                                ui.end_row();
-                            }
-                        });
-                }
-            });
+        let previous = self.available_ports.clone();
+        let selected = previous.get(self.selected_port).cloned();
+
+        self.refresh_ports();
+
+        let (added, removed) = diff_ports(&previous, &self.available_ports);
+        let status = if let Some(port) = selected.filter(|port| removed.contains(&port.as_str())) {
+            Some((format!("Port {port} was removed"), true))
+        } else if added.is_empty() {
+            None
+        } else {
+            Some((format!("New port: {}", added.join(", ")), false))
+        };
+
+        if let Some((message, is_error)) = status {
+            self.session_mut().set_status(&message, is_error);
+        }
     }
 
-    fn render_actions(&mut self, ui: &mut Ui, actions: &[ActionInfo]) {
-        if actions.is_empty() {
-            ui.label("No actions available");
+    fn connect(&mut self) {
+        if self.available_ports.is_empty() {
+            self.session_mut()
+                .set_status("No serial ports available", true);
             return;
         }
 
-        egui::ScrollArea::vertical().show(ui, |ui| {
+        let port_name = self.available_ports[self.selected_port].clone();
+
+        // Offline devices aren't backed by a port, so they can be opened any number of times
+        let offline = port_name == DEMO_PORT || port_name.starts_with(REPLAY_PREFIX);
+        let in_use = !offline
+            && self.sessions.iter().enumerate().any(|(idx, session)| {
+                idx != self.active_session
+                    && session.is_open()
+                    && session.last_port.as_ref() == Some(&port_name)
+            });
+
+        if in_use {
+            self.session_mut()
+                .set_status(&format!("{port_name} is already open in another tab"), true);
+            return;
+        }
+
+        let options = self.connect_options;
+        // Only sessions with real devices are worth recording
+        let recording = Some(self.recording_path.trim())
+            .filter(|path| !path.is_empty() && !offline)
+            .map(PathBuf::from);
+        let status = match &recording {
+            Some(path) => format!(
+                "Connecting to {port_name}, recording to {}...",
+                path.display()
+            ),
+            None => format!("Connecting to {port_name}..."),
+        };
+        let session = self.session_mut();
+
+        session.connection_state = ConnectionState::Connecting;
+        session.worker = Some(WorkerHandle::new(&port_name, options, recording));
+        session.set_status(&status, false);
+        session.last_port = Some(port_name);
+    }
+
+    /// Retry connecting to the most recently used port.
+    ///
+    /// Returns `false` if that port is no longer available.
+    fn retry_connection(&mut self) -> bool {
+        let Some(port_name) = self.session().last_port.clone() else {
+            return false;
+        };
+
+        self.refresh_ports();
+
+        if let Some(idx) = find_port(&self.available_ports, &port_name) {
+            self.selected_port = idx;
+            self.connect();
+
+            true
+        } else {
+            self.session_mut()
+                .set_status(&format!("Port {port_name} is no longer available"), true);
+
+            false
+        }
+    }
+
+    fn disconnect(&mut self) {
+        self.session_mut().disconnect();
+        self.save_requested = true;
+    }
+
+    /// Start or stop the metrics server, which only runs while a device is connected
+    fn update_metrics_server(&mut self) {
+        let devices: Vec<_> = self
+            .sessions
+            .iter()
+            .filter(|session| matches!(session.connection_state, ConnectionState::Connected(_)))
+            .filter_map(|session| session.last_port.as_deref())
+            .collect();
+
+        if !self.metrics_enabled || devices.is_empty() {
+            self.metrics = None;
+            return;
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.retain_devices(&devices);
+            return;
+        }
+
+        match MetricsServer::start(self.metrics_port) {
+            Ok(metrics) => {
+                let msg = format!("Serving metrics on port {}", metrics.port());
+
+                self.metrics = Some(metrics);
+                self.session_mut().set_status(&msg, false);
+            }
+            Err(e) => {
+                self.metrics_enabled = false;
+                self.session_mut()
+                    .set_status(&format!("Failed to serve metrics: {e}"), true);
+            }
+        }
+    }
+
+    /// Start or stop publishing to the MQTT broker
+    fn update_mqtt_publisher(&mut self) {
+        if !self.mqtt_config.enabled {
+            self.mqtt = None;
+        } else if self.mqtt.is_none() {
+            self.mqtt = Some(MqttPublisher::start(self.mqtt_config.clone()));
+        }
+    }
+
+    /// Start or stop writing to `InfluxDB`
+    fn update_influx_writer(&mut self) {
+        if !self.influx_config.enabled {
+            self.influx = None;
+        } else if self.influx.is_none() {
+            match InfluxWriter::start(self.influx_config.clone()) {
+                Ok(influx) => self.influx = Some(influx),
+                Err(e) => {
+                    self.influx_config.enabled = false;
+                    self.session_mut()
+                        .set_status(&format!("Failed to write to InfluxDB: {e}"), true);
+                }
+            }
+        }
+    }
+
+    /// Start or stop the event stream
+    fn update_event_stream(&mut self) {
+        if !self.stream_config.enabled {
+            self.stream = None;
+        } else if self.stream.is_none() {
+            match EventStream::start(self.stream_config.clone()) {
+                Ok(stream) => self.stream = Some(stream),
+                Err(e) => {
+                    self.stream_config.enabled = false;
+                    self.session_mut()
+                        .set_status(&format!("Failed to start event stream: {e}"), true);
+                }
+            }
+        }
+    }
+
+    /// Handle keyboard shortcuts, unless a text input has focus
+    ///
+    /// Zoom shortcuts are always handled, like in a browser.
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.input_mut(|i| {
+            i.consume_shortcut(&kb_shortcuts::ZOOM_IN)
+                || i.consume_shortcut(&kb_shortcuts::ZOOM_IN_SECONDARY)
+        }) {
+            self.set_zoom(ctx, self.zoom_factor + ZOOM_STEP);
+        }
+
+        if ctx.input_mut(|i| i.consume_shortcut(&kb_shortcuts::ZOOM_OUT)) {
+            self.set_zoom(ctx, self.zoom_factor - ZOOM_STEP);
+        }
+
+        if ctx.input_mut(|i| i.consume_shortcut(&kb_shortcuts::ZOOM_RESET)) {
+            self.set_zoom(ctx, 1.0);
+        }
+
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let is_open = self.session().is_open();
+
+        if ctx.input_mut(|i| i.consume_shortcut(&CONNECT_SHORTCUT)) {
+            if is_open {
+                self.disconnect();
+            } else {
+                self.connect();
+            }
+        }
+
+        if ctx.input_mut(|i| i.consume_shortcut(&REFRESH_ALL_SHORTCUT)) {
+            self.session_mut().refresh_all();
+        }
+
+        // The port can't be changed while connected
+        if ctx.input_mut(|i| i.consume_shortcut(&REFRESH_PORTS_SHORTCUT)) && !is_open {
+            self.refresh_ports();
+        }
+    }
+
+    /// Change the zoom factor of the UI, clamped to [`ZOOM_RANGE`]
+    fn set_zoom(&mut self, ctx: &egui::Context, factor: f32) {
+        self.zoom_factor = clamp_zoom(factor);
+        ctx.set_zoom_factor(self.zoom_factor);
+    }
+
+    /// Render a button for each theme, applying the selection right away
+    fn render_theme_controls(&mut self, ui: &mut Ui) {
+        let previous = self.theme;
+
+        ui.selectable_value(&mut self.theme, ThemePreference::Light, "☀")
+            .on_hover_text("Light theme");
+        ui.selectable_value(&mut self.theme, ThemePreference::Dark, "🌙")
+            .on_hover_text("Dark theme");
+        ui.selectable_value(&mut self.theme, ThemePreference::System, "💻")
+            .on_hover_text("Follow the system theme");
+
+        if self.theme != previous {
+            ui.ctx().set_theme(self.theme);
+        }
+    }
+
+    /// Render the current zoom factor with a menu for changing it
+    fn render_zoom_controls(&mut self, ui: &mut Ui) {
+        let ctx = ui.ctx().clone();
+        let percent = (self.zoom_factor * 100.0).round();
+
+        ui.menu_button(format!("🔍 {percent}%"), |ui| {
+            if ui
+                .add_enabled(
+                    self.zoom_factor < *ZOOM_RANGE.end(),
+                    egui::Button::new("Zoom in")
+                        .shortcut_text(ctx.format_shortcut(&kb_shortcuts::ZOOM_IN)),
+                )
+                .clicked()
+            {
+                self.set_zoom(&ctx, self.zoom_factor + ZOOM_STEP);
+            }
+
+            if ui
+                .add_enabled(
+                    self.zoom_factor > *ZOOM_RANGE.start(),
+                    egui::Button::new("Zoom out")
+                        .shortcut_text(ctx.format_shortcut(&kb_shortcuts::ZOOM_OUT)),
+                )
+                .clicked()
+            {
+                self.set_zoom(&ctx, self.zoom_factor - ZOOM_STEP);
+            }
+
+            if ui
+                .add_enabled(
+                    (self.zoom_factor - 1.0).abs() > f32::EPSILON,
+                    egui::Button::new("Reset to 100%")
+                        .shortcut_text(ctx.format_shortcut(&kb_shortcuts::ZOOM_RESET)),
+                )
+                .clicked()
+            {
+                self.set_zoom(&ctx, 1.0);
+                ui.close_menu();
+            }
+        })
+        .response
+        .on_hover_text("Scale the interface, e.g. on high-resolution displays");
+    }
+
+    /// Save the area of the pending graph export once the screenshot has arrived
+    fn save_screenshot(&mut self, ctx: &egui::Context) {
+        if self.pending_screenshot.is_none() {
+            return;
+        }
+
+        let Some(image) = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                _ => None,
+            })
+        }) else {
+            return;
+        };
+        let Some((rect, path)) = self.pending_screenshot.take() else {
+            return;
+        };
+        let region = image.region(&rect, Some(ctx.pixels_per_point()));
+        let res = image::save_buffer(
+            &path,
+            region.as_raw(),
+            region.width() as u32,
+            region.height() as u32,
+            image::ExtendedColorType::Rgba8,
+        );
+
+        match res {
+            Ok(()) => self
+                .session_mut()
+                .set_status(&format!("Saved graph to {}", path.display()), false),
+            Err(e) => self
+                .session_mut()
+                .set_status(&format!("Failed to save graph: {e}"), true),
+        }
+    }
+
+    /// Pause auto-refresh while the window is unfocused, if enabled
+    ///
+    /// I/O properties are queried right away once the window is focused again.
+    fn update_refresh_pause(&mut self, ctx: &egui::Context) {
+        let unfocused = ctx.input(|i| i.viewport().focused == Some(false));
+
+        match (
+            self.auto_refresh.pause_unfocused && unfocused,
+            self.paused_since,
+        ) {
+            (true, None) => {
+                log::debug!("Window unfocused, pausing auto-refresh");
+                self.paused_since = Some(Instant::now());
+            }
+            (false, Some(since)) => {
+                log::debug!("Resuming auto-refresh after {:?}", since.elapsed());
+                self.paused_since = None;
+
+                if self.auto_refresh.enabled {
+                    for session in &mut self.sessions {
+                        session.refresh_now(PropertyKind::Io);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Request repaints for continuous updates, less often when in background
+    fn schedule_repaint(&self, ctx: &egui::Context) {
+        // Ports are polled while the active tab is closed
+        if !self.session().is_open() {
+            ctx.request_repaint_after(PORT_POLL_INTERVAL);
+        }
+
+        if self
+            .sessions
+            .iter()
+            .any(|session| matches!(session.connection_state, ConnectionState::Connected(_)))
+        {
+            let idle = ctx.input(|i| {
+                let viewport = i.viewport();
+
+                viewport.focused == Some(false) || viewport.minimized == Some(true)
+            });
+            let interval = if idle {
+                IDLE_REPAINT_INTERVAL
+            } else {
+                ACTIVE_REPAINT_INTERVAL
+            };
+
+            ctx.request_repaint_after(interval);
+        }
+    }
+
+    /// Close a tab, dropping its worker. Another tab is opened if it was the last one.
+    fn close_session(&mut self, idx: usize) {
+        self.sessions.remove(idx);
+
+        if self.sessions.is_empty() {
+            self.sessions.push(Session::new());
+        }
+
+        if self.active_session > idx || self.active_session == self.sessions.len() {
+            self.active_session -= 1;
+        }
+    }
+}
+
+impl eframe::App for FreeMduApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        storage.set_string(watch::STORAGE_KEY, watch::serialize(&self.watches));
+        storage.set_string(alert::STORAGE_KEY, alert::serialize(&self.alerting));
+        storage.set_string(
+            REFRESH_INTERVALS_KEY,
+            serialize_refresh_intervals(&self.refresh_intervals),
+        );
+        storage.set_string(STALE_WINDOW_KEY, self.stale_window.as_secs().to_string());
+
+        if let Some(port) = self.available_ports.get(self.selected_port) {
+            storage.set_string(SELECTED_PORT_KEY, port.clone());
+        }
+
+        storage.set_string(NETWORK_PORTS_KEY, self.network_ports.join("\n"));
+
+        if let Ok(profiles) = serde_json::to_string(&self.profiles) {
+            storage.set_string(LAYOUT_PROFILES_KEY, profiles);
+        }
+
+        if let Ok(unpolled) = serde_json::to_string(&self.unpolled) {
+            storage.set_string(UNPOLLED_KEY, unpolled);
+        }
+
+        storage.set_string(AUTO_REFRESH_KEY, self.auto_refresh.enabled.to_string());
+        storage.set_string(
+            PAUSE_UNFOCUSED_KEY,
+            self.auto_refresh.pause_unfocused.to_string(),
+        );
+        storage.set_string(ZOOM_FACTOR_KEY, self.zoom_factor.to_string());
+        storage.set_string(COLUMN_SPLIT_KEY, self.column_split.to_string());
+        storage.set_string(THEME_KEY, serialize_theme(self.theme).to_string());
+        storage.set_string(
+            TEMPERATURE_UNIT_KEY,
+            self.temperature_unit.serialize().to_string(),
+        );
+        storage.set_string(SHOW_RAW_KEY, self.show_raw.to_string());
+        storage.set_string(METRICS_ENABLED_KEY, self.metrics_enabled.to_string());
+        storage.set_string(METRICS_PORT_KEY, self.metrics_port.to_string());
+
+        if let Ok(config) = serde_json::to_string(&self.mqtt_config) {
+            storage.set_string(MQTT_CONFIG_KEY, config);
+        }
+
+        if let Ok(config) = serde_json::to_string(&self.influx_config) {
+            storage.set_string(INFLUX_CONFIG_KEY, config);
+        }
+
+        if let Ok(config) = serde_json::to_string(&self.stream_config) {
+            storage.set_string(STREAM_CONFIG_KEY, config);
+        }
+
+        if let Ok(order) = serde_json::to_string(&self.connect_options.warm_up) {
+            storage.set_string(WARM_UP_ORDER_KEY, order);
+        }
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.update_refresh_pause(ctx);
+
+        // Sections without data are queried even if paused, so that "Refresh All" keeps working
+        let missing_only = !self.auto_refresh.enabled || self.paused_since.is_some();
+
+        let exports = Exports {
+            metrics: self.metrics.as_ref(),
+            mqtt: self.mqtt.as_ref(),
+            influx: self.influx.as_ref(),
+            stream: self.stream.as_ref(),
+        };
+
+        // Sessions in background tabs keep polling, so their properties and logs stay current
+        for session in &mut self.sessions {
+            session.process_worker_responses(
+                &self.alerting,
+                &self.unpolled,
+                self.temperature_unit,
+                exports,
+            );
+            session.auto_refresh_properties(&self.refresh_intervals, missing_only);
+        }
+
+        self.update_metrics_server();
+        self.update_mqtt_publisher();
+        self.update_influx_writer();
+        self.update_event_stream();
+
+        if self.save_requested {
+            self.save_requested = false;
+
+            if let Some(storage) = frame.storage_mut() {
+                self.save(storage);
+                storage.flush();
+            }
+        }
+
+        self.poll_ports();
+        self.save_screenshot(ctx);
+        self.handle_shortcuts(ctx);
+        self.schedule_repaint(ctx);
+
+        // Top panel with connection controls and tabs
+        egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.heading("FreeMDU");
+                ui.separator();
+                self.render_connection_controls(ui);
+            });
+            ui.add_space(4.0);
+            self.render_tabs(ui);
+            ui.add_space(2.0);
+        });
+
+        self.session_mut().render_logging_dialog(ctx);
+        self.session_mut().render_file_dialog(ctx);
+        self.render_alert_banner(ctx);
+
+        // Bottom panel with status bar
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.add_space(2.0);
+            self.render_status_bar(ui);
+            ui.add_space(2.0);
+        });
+
+        if self.logs.open {
+            egui::TopBottomPanel::bottom("log_panel")
+                .resizable(true)
+                .default_height(160.0)
+                .show(ctx, |ui| self.logs.show(ui));
+        }
+
+        if self.session().developer_tools
+            && matches!(
+                self.session().connection_state,
+                ConnectionState::Connected(_)
+            )
+        {
+            egui::TopBottomPanel::bottom("raw_console")
+                .resizable(true)
+                .default_height(160.0)
+                .show(ctx, |ui| self.session_mut().render_raw_console(ui));
+        }
+
+        self.render_side_panel(ctx);
+
+        // Central panel with properties
+        egui::CentralPanel::default().show(ctx, |ui| {
+            match self.session().connection_state.clone() {
+                ConnectionState::Disconnected => {
+                    ui.centered_and_justified(|ui| {
+                        ui.label("Select a serial port and click Connect to start.");
+                    });
+                }
+                ConnectionState::Connecting => {
+                    ui.centered_and_justified(|ui| {
+                        ui.spinner();
+                        ui.label("Connecting to device...");
+                    });
+                }
+                ConnectionState::Connected(_) | ConnectionState::Viewing(_) => {
+                    self.render_properties(ui);
+                }
+                ConnectionState::Error(e) => {
+                    self.render_error(ui, &e);
+                }
+            }
+        });
+    }
+}
+
+impl FreeMduApp {
+    /// Render the actions of a device connected with write access,
+    /// or the action log of a saved session
+    fn render_side_panel(&mut self, ctx: &egui::Context) {
+        // Left panel with actions (if connected with write access), hidden if there are none
+        // to leave the width to the properties
+        let actions = match &self.session().connection_state {
+            ConnectionState::Connected(info) if !info.read_only && !info.actions.is_empty() => {
+                Some(info.actions.clone())
+            }
+            _ => None,
+        };
+
+        if let Some(actions) = actions {
+            let temperature_unit = self.temperature_unit;
+
+            egui::SidePanel::left("actions_panel")
+                .resizable(true)
+                .default_width(200.0)
+                .show(ctx, |ui| {
+                    ui.heading("Actions");
+                    ui.separator();
+                    self.session_mut()
+                        .render_actions(ui, &actions, temperature_unit);
+                });
+        }
+
+        // Left panel with the action log of a saved session
+        if matches!(self.session().connection_state, ConnectionState::Viewing(_))
+            && !self.session().action_log.is_empty()
+        {
+            egui::SidePanel::left("saved_actions_panel")
+                .resizable(true)
+                .default_width(200.0)
+                .show(ctx, |ui| self.session_mut().render_action_log(ui));
+        }
+    }
+
+    /// Render the controls for adding and removing serial-to-TCP bridges
+    fn render_network_ports(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut self.network_port_input)
+                    .hint_text("tcp://192.168.1.50:23")
+                    .desired_width(180.0),
+            );
+            let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let address = parse_network_port(&self.network_port_input).map(str::to_string);
+            let clicked = ui
+                .add_enabled(address.is_some(), egui::Button::new("Add"))
+                .clicked();
+
+            if let Some(address) = address.filter(|_| clicked || submitted) {
+                self.add_network_port(&format!("{TCP_PREFIX}{address}"));
+                self.network_port_input.clear();
+            }
+        });
+
+        self.render_custom_ports(ui, TCP_PREFIX);
+    }
+
+    /// Render the controls for replaying recorded sessions and recording new ones
+    fn render_replay_controls(&mut self, ui: &mut Ui) {
+        ui.label("Replay a recording:");
+        ui.horizontal(|ui| {
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut self.replay_input)
+                    .hint_text("session.freemdu")
+                    .desired_width(180.0),
+            );
+            let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let path = self.replay_input.trim().to_string();
+            let clicked = ui
+                .add_enabled(!path.is_empty(), egui::Button::new("Add"))
+                .clicked();
+
+            if !path.is_empty() && (clicked || submitted) {
+                self.add_network_port(&format!("{REPLAY_PREFIX}{path}"));
+                self.replay_input.clear();
+            }
+        });
+
+        self.render_custom_ports(ui, REPLAY_PREFIX);
+
+        let mut looped = self.connect_options.replay_end == ReplayEnd::Loop;
+
+        if ui
+            .checkbox(&mut looped, "Loop at end")
+            .on_hover_text("Start over at the end of the recording instead of disconnecting")
+            .changed()
+        {
+            self.connect_options.replay_end = if looped {
+                ReplayEnd::Loop
+            } else {
+                ReplayEnd::Stop
+            };
+        }
+
+        ui.separator();
+        ui.label("Record sessions to:");
+        ui.add(
+            egui::TextEdit::singleline(&mut self.recording_path)
+                .hint_text("Not recording")
+                .desired_width(240.0),
+        )
+        .on_hover_text(
+            "Frames exchanged with the device are written to this file, which is overwritten",
+        );
+    }
+
+    /// Render the ports with the given prefix added by the user, with buttons to remove them
+    fn render_custom_ports(&mut self, ui: &mut Ui, prefix: &str) {
+        let mut removed = None;
+
+        for port in self.network_ports.iter().filter(|p| p.starts_with(prefix)) {
+            ui.horizontal(|ui| {
+                if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                    removed = Some(port.clone());
+                }
+
+                ui.label(port);
+            });
+        }
+
+        if let Some(port) = removed {
+            self.network_ports.retain(|p| *p != port);
+            self.refresh_ports();
+            self.save_requested = true;
+        }
+    }
+
+    /// Add a serial-to-TCP bridge or a recording to the port list and select it
+    fn add_network_port(&mut self, port: &str) {
+        if !self.network_ports.iter().any(|p| p == port) {
+            self.network_ports.push(port.to_string());
+        }
+
+        self.refresh_ports();
+
+        if let Some(idx) = self.available_ports.iter().position(|p| *p == port) {
+            self.selected_port = idx;
+        }
+
+        self.save_requested = true;
+    }
+
+    /// Returns the properties matching the filter, in display order
+    fn displayed_properties<'a>(
+        &self,
+        kind: PropertyKind,
+        props: &'a [PropertyData],
+    ) -> Vec<&'a PropertyData> {
+        let filter = self.profiles.layout().filter.to_lowercase();
+        let mut props: Vec<_> = props
+            .iter()
+            .filter(|prop| matches_filter(&prop.name, &filter))
+            .collect();
+
+        // Only the displayed order changes, the stored properties are left as they are
+        if let Some(sort) = self.property_sort(kind) {
+            sort.apply(&mut props);
+        }
+
+        props
+    }
+
+    /// Format the displayed properties of a section as an aligned text table,
+    /// headed by the title and the time of the last update
+    fn section_table(&self, kind: PropertyKind, title: &str) -> String {
+        let (props, updated) = self.session().properties.of_kind(kind);
+        let rows: Vec<_> = self
+            .displayed_properties(kind, props)
+            .into_iter()
+            .map(|prop| {
+                let (value, unit) = format_value_parts(
+                    &prop.value,
+                    prop.unit.as_deref(),
+                    prop.decimals,
+                    prop.cumulative,
+                    self.temperature_unit,
+                );
+
+                [
+                    prop.name.clone(),
+                    value,
+                    unit.unwrap_or_default().to_string(),
+                ]
+            })
+            .collect();
+        let updated =
+            updated.and_then(|time| jiff::Timestamp::now().checked_sub(time.elapsed()).ok());
+
+        format_table(title, updated, &rows)
+    }
+
+    /// Returns the display order of a section, or `None` for the definition order
+    fn property_sort(&self, kind: PropertyKind) -> Option<PropertySort> {
+        self.profiles
+            .layout()
+            .sort
+            .iter()
+            .find(|&&(k, _)| k == kind)
+            .map(|&(_, sort)| sort)
+    }
+
+    /// Returns the name of a port along with the description of its adapter, if known
+    fn port_label(&self, port: &str) -> String {
+        match self.port_labels.get(port) {
+            Some(label) => format!("{label} – {port}"),
+            None => port.to_string(),
+        }
+    }
+
+    /// Render the port list along with the controls for changing it
+    fn render_port_selector(&mut self, ui: &mut Ui, is_connected: bool) {
+        // Refresh ports button
+        if ui
+            .add_enabled(!is_connected, egui::Button::new("🔄"))
+            .on_hover_text(shortcut_hint(
+                ui,
+                "Refresh port list",
+                REFRESH_PORTS_SHORTCUT,
+            ))
+            .clicked()
+        {
+            self.refresh_ports();
+        }
+
+        // Port selector
+        let port_label = if self.available_ports.is_empty() {
+            "No ports found".to_string()
+        } else {
+            self.port_label(&self.available_ports[self.selected_port])
+        };
+
+        ui.add_enabled_ui(!is_connected, |ui| {
+            egui::ComboBox::from_id_salt("port_selector")
+                .selected_text(&port_label)
+                .show_ui(ui, |ui| {
+                    for (i, port) in self.available_ports.iter().enumerate() {
+                        let label = self.port_label(port);
+
+                        ui.selectable_value(&mut self.selected_port, i, label);
+                    }
+                });
+            ui.menu_button("TCP", |ui| self.render_network_ports(ui))
+                .response
+                .on_hover_text("Connect via a serial-to-TCP bridge");
+            ui.menu_button("Replay", |ui| self.render_replay_controls(ui))
+                .response
+                .on_hover_text("Replay a recorded session, or record the next ones");
+        });
+    }
+
+    /// Render the button for connecting or disconnecting, or for closing a saved session
+    fn render_connect_button(&mut self, ui: &mut Ui, is_connected: bool) {
+        if matches!(self.session().connection_state, ConnectionState::Viewing(_)) {
+            if ui
+                .button("Close")
+                .on_hover_text("Close the saved session")
+                .clicked()
+            {
+                self.disconnect();
+            }
+        } else if is_connected {
+            if ui
+                .button("Disconnect")
+                .on_hover_text(shortcut_hint(ui, "Disconnect", CONNECT_SHORTCUT))
+                .clicked()
+            {
+                self.disconnect();
+            }
+        } else {
+            if ui
+                .add_enabled(
+                    !self.available_ports.is_empty(),
+                    egui::Button::new("Connect"),
+                )
+                .on_hover_text(shortcut_hint(ui, "Connect", CONNECT_SHORTCUT))
+                .clicked()
+            {
+                self.connect();
+            }
+
+            if ui
+                .button("Open Session…")
+                .on_hover_text("Show a saved session without connecting to a device")
+                .clicked()
+            {
+                self.session_mut().file_dialog = Some(SessionFileDialog::Open(String::new()));
+            }
+        }
+    }
+
+    fn render_connection_controls(&mut self, ui: &mut Ui) {
+        let is_connected = matches!(
+            self.session().connection_state,
+            ConnectionState::Connected(_)
+                | ConnectionState::Connecting
+                | ConnectionState::Viewing(_)
+        );
+
+        self.render_port_selector(ui, is_connected);
+
+        ui.add_enabled_ui(!is_connected, |ui| {
+            ui.checkbox(&mut self.connect_options.read_only, "Read-only")
+                .on_hover_text("Only query properties, never trigger actions");
+            ui.checkbox(&mut self.connect_options.auto_baud, "Auto-baud")
+                .on_hover_text("Try other baud rates if the device doesn't respond");
+            ui.menu_button("Timeouts", |ui| {
+                render_timeout_controls(ui, &mut self.connect_options.timeouts);
+            })
+            .response
+            .on_hover_text("Increase for slow links, e.g. serial bridges over TCP");
+        });
+
+        self.render_connect_button(ui, is_connected);
+
+        ui.separator();
+
+        // Auto-refresh toggle
+        if ui
+            .checkbox(&mut self.auto_refresh.enabled, "Auto-refresh")
+            .changed()
+            && !self.auto_refresh.enabled
+        {
+            // Don't keep the device busy with refreshes that are no longer wanted,
+            // including the warm-up after connecting
+            for session in &mut self.sessions {
+                if let Some(worker) = &session.worker {
+                    worker.send(WorkerCommand::Cancel);
+                }
+
+                if matches!(session.full_refresh, Some(FullRefresh::WarmUp(_))) {
+                    session.full_refresh = None;
+                }
+            }
+        }
+
+        let session = self.session_mut();
+
+        session.render_refresh_button(ui);
+
+        if let ConnectionState::Connected(info) | ConnectionState::Viewing(info) =
+            &session.connection_state
+        {
+            if ui
+                .button("Copy as JSON")
+                .on_hover_text("Copy all property values to the clipboard")
+                .clicked()
+            {
+                match session.properties.to_json(info) {
+                    Ok(json) => {
+                        ui.ctx().copy_text(json);
+                        session.set_status("Copied properties to clipboard", false);
+                    }
+                    Err(e) => {
+                        session.set_status(&format!("Failed to export properties: {e}"), true);
+                    }
+                }
+            }
+
+            if ui
+                .add_enabled(
+                    session.file_dialog.is_none(),
+                    egui::Button::new("Save Session…"),
+                )
+                .on_hover_text("Save the properties and the action log for inspecting them offline")
+                .clicked()
+            {
+                let name = jiff::Timestamp::now().strftime("freemdu-session-%Y%m%d-%H%M%S.json");
+
+                session.file_dialog = Some(SessionFileDialog::Save(name.to_string()));
+            }
+        }
+
+        session.render_logging_controls(ui);
+
+        if matches!(session.connection_state, ConnectionState::Connected(_)) {
+            ui.toggle_value(&mut session.developer_tools, "Developer tools")
+                .on_hover_text(
+                    "Send raw requests to the device.\n\
+                     Careless writes can leave the appliance unusable.",
+                );
+        }
+
+        ui.separator();
+
+        // Temperature unit toggle
+        ui.selectable_value(
+            &mut self.temperature_unit,
+            TemperatureUnit::Celsius,
+            TemperatureUnit::CELSIUS,
+        );
+        ui.selectable_value(
+            &mut self.temperature_unit,
+            TemperatureUnit::Fahrenheit,
+            TemperatureUnit::FAHRENHEIT,
+        );
+
+        ui.separator();
+        ui.checkbox(&mut self.show_raw, "Show raw")
+            .on_hover_text("Show the data each value was decoded from in hex");
+        ui.separator();
+        self.render_theme_controls(ui);
+        self.render_zoom_controls(ui);
+    }
+
+    /// Render a tab for each session and a button to open another one
+    fn render_tabs(&mut self, ui: &mut Ui) {
+        let mut closed = None;
+
+        ui.horizontal_wrapped(|ui| {
+            for (idx, session) in self.sessions.iter().enumerate() {
+                let (color, state) = session.state_indicator();
+
+                ui.colored_label(color, "●");
+
+                if !session.alerts.active.is_empty() {
+                    ui.label("🔔").on_hover_text("Failures became active");
+                }
+
+                if ui
+                    .selectable_label(idx == self.active_session, session.title())
+                    .on_hover_text(state)
+                    .clicked()
+                {
+                    self.active_session = idx;
+                }
+
+                if ui
+                    .small_button("✕")
+                    .on_hover_text("Close this connection")
+                    .clicked()
+                {
+                    closed = Some(idx);
+                }
+
+                ui.separator();
+            }
+
+            if ui
+                .small_button("➕")
+                .on_hover_text("Open another connection")
+                .clicked()
+            {
+                self.sessions.push(Session::new());
+                self.active_session = self.sessions.len() - 1;
+            }
+        });
+
+        if let Some(idx) = closed {
+            self.close_session(idx);
+        }
+    }
+
+    fn render_error(&mut self, ui: &mut Ui, error: &ConnectError) {
+        let last_port = self.session().last_port.clone();
+        let port_available = last_port
+            .as_ref()
+            .is_some_and(|port| self.available_ports.contains(port));
+
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 3.0);
+            ui.colored_label(Color32::RED, format!("Error: {error}"));
+
+            match error {
+                ConnectError::Timeout if !self.connect_options.auto_baud => {
+                    ui.label("Check the cable, or enable auto-baud if the device uses another baud rate.");
+                }
+                ConnectError::Timeout => {
+                    ui.label("Check the cable and make sure the device is switched on.");
+                }
+                ConnectError::UnexpectedResponse(_) if !self.connect_options.auto_baud => {
+                    ui.label("The baud rate may be wrong, try enabling auto-baud.");
+                }
+                ConnectError::UnexpectedResponse(_) => {
+                    ui.label("Make sure the port belongs to the diagnostic interface and not another device.");
+                }
+                ConnectError::PortBusy(_) => {
+                    ui.label("Close any other program using the port and try again.");
+                }
+                ConnectError::UnsupportedDevice(_) => {
+                    ui.label("This device is not supported yet.");
+                }
+                ConnectError::UnsupportedKind(kind, _) => {
+                    ui.label(format!(
+                        "The {kind} was recognized, but no properties or actions are defined for it yet."
+                    ));
+                    ui.label("Please file an issue with your appliance model and the software ID:");
+                    ui.hyperlink(concat!(env!("CARGO_PKG_REPOSITORY"), "/issues"));
+                }
+                _ => {}
+            }
+
+            ui.add_space(10.0);
+
+            if port_available {
+                if ui
+                    .button(RichText::new("Retry connection").strong())
+                    .clicked()
+                {
+                    self.retry_connection();
+                }
+            } else {
+                if let Some(port) = &last_port {
+                    ui.label(format!("Port {port} is no longer available."));
+                    ui.add_space(5.0);
+                }
+
+                if ui.button("Rescan ports").clicked() && !self.retry_connection() {
+                    // Port didn't come back, let the user pick another one
+                    self.session_mut().connection_state = ConnectionState::Disconnected;
+                }
+            }
+        });
+    }
+
+    fn render_status_bar(&mut self, ui: &mut Ui) {
+        // Borrowed separately from the log toggle
+        let session = &self.sessions[self.active_session];
+        let mut baud_rate = None;
+
+        ui.horizontal(|ui| {
+            // Connection status indicator
+            let (color, text) = session.state_indicator();
+
+            ui.colored_label(color, "●");
+            ui.label(text);
+
+            if let ConnectionState::Connected(info) = &session.connection_state {
+                // Bridges and offline devices have no port settings to change
+                if session.last_port.as_deref().is_some_and(is_serial_port) {
+                    let enabled = !session.reconfiguring && session.reconnect_attempt.is_none();
+
+                    baud_rate = render_baud_rate_selector(ui, info.baud_rate, enabled);
+                } else {
+                    ui.label(format!("{} baud", info.baud_rate));
+                }
+
+                if let Some((stats, connected)) = &session.link_stats {
+                    render_link_stats(ui, stats, connected.elapsed());
+                }
+
+                if let Some((avg_ms, last_ms)) = session.latency {
+                    render_latency(ui, avg_ms, last_ms);
+                }
+            }
+
+            ui.separator();
+
+            // Status message
+            if let Some((msg, time, is_error)) = &session.status_message {
+                let elapsed = time.elapsed();
+                if elapsed < Duration::from_secs(10) {
+                    let color = if *is_error {
+                        Color32::RED
+                    } else {
+                        Color32::GRAY
+                    };
+                    ui.colored_label(color, msg);
+                }
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(format!("v{}", env!("CARGO_PKG_VERSION")));
+                ui.toggle_value(&mut self.logs.open, "Logs")
+                    .on_hover_text("Show the log messages of the app");
+            });
+        });
+
+        if let Some(baud_rate) = baud_rate {
+            let config = serial_config(baud_rate, self.connect_options.timeouts);
+
+            self.session_mut().reconfigure(config);
+        }
+    }
+
+    fn render_properties(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            let filter = &mut self.profiles.layout_mut().filter;
+
+            ui.label("🔍");
+            ui.add(egui::TextEdit::singleline(filter).hint_text("Filter properties"));
+
+            if !filter.is_empty() && ui.small_button("✕").clicked() {
+                filter.clear();
+            }
+
+            ui.separator();
+            self.render_profile_controls(ui);
+        });
+        self.render_smoothing_controls(ui);
+        self.render_metrics_controls(ui);
+        self.render_mqtt_controls(ui);
+        self.render_influx_controls(ui);
+        self.render_stream_controls(ui);
+        ui.add_space(4.0);
+
+        // Requests from property context menus and editors, applied after rendering
+        let mut requests = Vec::new();
+        let mut edits = std::mem::take(&mut self.session_mut().property_edits);
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            self.render_favorites(ui, &mut requests);
+            self.render_overlay(ui, &mut requests);
+            self.render_graphs(ui, &mut requests);
+            self.render_property_columns(ui, &mut requests, &mut edits);
+        });
+
+        self.session_mut().property_edits = edits;
+
+        self.apply_property_requests(ui, requests);
+    }
+
+    /// Render the property sections in two columns separated by a draggable splitter
+    ///
+    /// The split snaps to [`COLUMN_SPLIT_STEP`] and is persisted. Windows narrower than
+    /// [`SINGLE_COLUMN_WIDTH`] show all sections in a single column instead.
+    fn render_property_columns(
+        &mut self,
+        ui: &mut Ui,
+        requests: &mut Vec<PropertyRequest>,
+        edits: &mut HashMap<PropertyId, PropertyValue>,
+    ) {
+        let width = ui.available_width();
+
+        if width < SINGLE_COLUMN_WIDTH {
+            self.render_left_sections(ui, requests, edits);
+            ui.add_space(10.0);
+            self.render_right_sections(ui, requests, edits);
+            return;
+        }
+
+        let spacing = ui.spacing().item_spacing.x;
+        let usable = width - SPLITTER_WIDTH - 2.0 * spacing;
+        let left_width = (usable * self.column_split).round();
+
+        ui.horizontal_top(|ui| {
+            let left = ui
+                .allocate_ui_with_layout(
+                    egui::vec2(left_width, 0.0),
+                    egui::Layout::top_down(egui::Align::Min),
+                    |ui| {
+                        ui.set_width(left_width);
+                        self.render_left_sections(ui, requests, edits);
+                    },
+                )
+                .response
+                .rect;
+            let (rect, splitter) = ui.allocate_exact_size(
+                egui::vec2(SPLITTER_WIDTH, left.height()),
+                egui::Sense::click_and_drag(),
+            );
+
+            if splitter.dragged() {
+                if let Some(pos) = splitter.interact_pointer_pos() {
+                    self.column_split = snap_column_split((pos.x - left.left()) / usable);
+                }
+            }
+
+            if splitter.double_clicked() {
+                self.column_split = DEFAULT_COLUMN_SPLIT;
+            }
+
+            let stroke = if splitter.hovered() || splitter.dragged() {
+                ui.visuals().widgets.hovered.fg_stroke
+            } else {
+                ui.visuals().widgets.noninteractive.bg_stroke
+            };
+
+            splitter
+                .on_hover_cursor(egui::CursorIcon::ResizeHorizontal)
+                .on_hover_text("Drag to resize the columns, double-click to reset");
+
+            let right = ui
+                .vertical(|ui| self.render_right_sections(ui, requests, edits))
+                .response
+                .rect;
+
+            // Spans both columns, whichever is taller
+            ui.painter().vline(
+                rect.center().x,
+                rect.top()..=rect.bottom().max(right.bottom()),
+                stroke,
+            );
+        });
+    }
+
+    /// Render the watch expressions and the general and operation properties
+    fn render_left_sections(
+        &mut self,
+        ui: &mut Ui,
+        requests: &mut Vec<PropertyRequest>,
+        edits: &mut HashMap<PropertyId, PropertyValue>,
+    ) {
+        self.render_watch_expressions(ui);
+        ui.add_space(10.0);
+        self.render_property_section(
+            ui,
+            PropertyKind::General,
+            "General Information",
+            requests,
+            edits,
+        );
+        ui.add_space(10.0);
+        self.render_property_section(
+            ui,
+            PropertyKind::Operation,
+            "Operating State",
+            requests,
+            edits,
+        );
+    }
+
+    /// Render the failure, warning and I/O properties along with the fault history
+    fn render_right_sections(
+        &mut self,
+        ui: &mut Ui,
+        requests: &mut Vec<PropertyRequest>,
+        edits: &mut HashMap<PropertyId, PropertyValue>,
+    ) {
+        self.render_property_section(
+            ui,
+            PropertyKind::Failure,
+            "Failure Information",
+            requests,
+            edits,
+        );
+        self.render_fault_history(ui);
+        ui.add_space(10.0);
+        self.render_property_section(
+            ui,
+            PropertyKind::Warning,
+            "Warning Information",
+            requests,
+            edits,
+        );
+        ui.add_space(10.0);
+        self.render_property_section(ui, PropertyKind::Io, "Input/Output State", requests, edits);
+    }
+
+    /// Apply the requests collected while rendering the properties
+    fn apply_property_requests(&mut self, ui: &Ui, requests: Vec<PropertyRequest>) {
+        for request in requests {
+            let session = &mut self.sessions[self.active_session];
+
+            match request {
+                PropertyRequest::Refresh(id) => {
+                    if let Some(worker) = &session.worker {
+                        worker.send(WorkerCommand::QueryProperty(id));
+                    }
+                }
+                PropertyRequest::Smooth(id, enabled) => session.smoother.set_enabled(id, enabled),
+                PropertyRequest::Graph(id, true) => session.graphs.push(id),
+                PropertyRequest::Graph(id, false) => session.graphs.retain(|graph| *graph != id),
+                PropertyRequest::Favorite(kind, name, true) => {
+                    self.profiles.layout_mut().favorites.push((kind, name));
+                }
+                PropertyRequest::Favorite(kind, name, false) => {
+                    let favorites = &mut self.profiles.layout_mut().favorites;
+
+                    favorites.retain(|fav| *fav != (kind, name.clone()));
+                }
+                PropertyRequest::Set(id, value) => {
+                    // The edit box is reset to the value read back from the device
+                    session.property_edits.remove(&id);
+
+                    if let Some(worker) = &session.worker {
+                        worker.send(WorkerCommand::SetProperty(id, value));
+                    }
+                }
+                PropertyRequest::Copy(name, value) => {
+                    ui.ctx().copy_text(value);
+                    session.set_status(&format!("Copied {name}"), false);
+                }
+                PropertyRequest::CopySection(kind, title) => {
+                    ui.ctx().copy_text(self.section_table(kind, &title));
+                    self.session_mut()
+                        .set_status(&format!("Copied {title}"), false);
+                }
+                PropertyRequest::Export(id, format, rect) => {
+                    let name = jiff::Timestamp::now().strftime("%Y%m%d-%H%M%S");
+                    let path = PathBuf::from(format!("freemdu-{id}-{name}.{}", format.extension()));
+
+                    match format {
+                        GraphExport::Csv => session.export_samples(id, &path),
+                        GraphExport::Png => {
+                            // The screenshot is delivered as an event in a later frame
+                            ui.ctx()
+                                .send_viewport_cmd(egui::ViewportCommand::Screenshot);
+                            self.pending_screenshot = Some((rect, path));
+                        }
+                    }
+                }
+                PropertyRequest::Sort(kind, column) => {
+                    let sort = PropertySort::toggle(self.property_sort(kind), column);
+                    let sorts = &mut self.profiles.layout_mut().sort;
+
+                    sorts.retain(|&(k, _)| k != kind);
+                    sorts.extend(sort.map(|sort| (kind, sort)));
+                }
+                PropertyRequest::Overlay(id, true) => self.overlay.push(id),
+                PropertyRequest::Overlay(id, false) => self.overlay.retain(|other| *other != id),
+                PropertyRequest::Alert(key, true) => self.alerting.push(key),
+                PropertyRequest::Alert(key, false) => self.alerting.retain(|k| *k != key),
+                PropertyRequest::Poll(kind, name, polled) => self.set_polled(kind, name, polled),
+                PropertyRequest::Collapse(kind, collapsed) => {
+                    let sections = &mut self.profiles.layout_mut().collapsed;
+
+                    sections.retain(|&k| k != kind);
+
+                    if collapsed {
+                        sections.push(kind);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Render the selector of the layout profile along with buttons to save and delete profiles
+    fn render_profile_controls(&mut self, ui: &mut Ui) {
+        let mut selected = self.profiles.active;
+
+        ui.label("Profile:");
+        egui::ComboBox::from_id_salt("layout_profile")
+            .selected_text(self.profiles.name())
+            .show_ui(ui, |ui| {
+                for (idx, (name, _)) in self.profiles.profiles.iter().enumerate() {
+                    ui.selectable_value(&mut selected, idx, name);
+                }
+            });
+
+        if selected != self.profiles.active {
+            self.profiles.active = selected;
+            self.save_requested = true;
+        }
+
+        ui.menu_button("Save as", |ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_profile).hint_text("Profile name"));
+
+            let name = self.new_profile.trim().to_string();
+
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save"))
+                .clicked()
+            {
+                self.profiles.save_as(&name);
+                self.new_profile.clear();
+                self.save_requested = true;
+                ui.close_menu();
+            }
+        })
+        .response
+        .on_hover_text("Save the sections, favorites, filter and sort order as a named profile");
+
+        if ui
+            .add_enabled(self.profiles.profiles.len() > 1, egui::Button::new("🗑"))
+            .on_hover_text("Delete this profile")
+            .clicked()
+        {
+            self.profiles.remove_active();
+            self.save_requested = true;
+        }
+    }
+
+    /// Render the latest values of the favorite properties
+    fn render_favorites(&self, ui: &mut Ui, requests: &mut Vec<PropertyRequest>) {
+        let favorites = &self.profiles.layout().favorites;
+
+        if favorites.is_empty() {
+            return;
+        }
+
+        let session = self.session();
+
+        egui::Frame::group(ui.style())
+            .fill(ui.style().visuals.extreme_bg_color)
+            .show(ui, |ui| {
+                ui.label(RichText::new("Favorites").strong());
+                ui.separator();
+
+                egui::Grid::new("favorites")
+                    .num_columns(3)
+                    .striped(true)
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        for (kind, name) in favorites {
+                            let prop = session
+                                .properties
+                                .of_kind(*kind)
+                                .0
+                                .iter()
+                                .find(|prop| prop.name == *name);
+
+                            ui.label(name);
+
+                            match prop {
+                                Some(prop) => {
+                                    let value = session
+                                        .smoother
+                                        .smoothed(prop)
+                                        .unwrap_or_else(|| prop.value.clone());
+
+                                    ui.label(format_value(
+                                        &value,
+                                        prop.unit.as_deref(),
+                                        prop.decimals,
+                                        prop.cumulative,
+                                        self.temperature_unit,
+                                    ))
+                                }
+                                None => ui.weak("—"),
+                            };
+
+                            if ui.small_button("✕").on_hover_text("Remove").clicked() {
+                                requests.push(PropertyRequest::Favorite(
+                                    *kind,
+                                    name.clone(),
+                                    false,
+                                ));
+                            }
+
+                            ui.end_row();
+                        }
+                    });
+            });
+        ui.add_space(10.0);
+    }
+
+    /// Returns whether the property is shown in the favorites panel
+    fn is_favorite(&self, kind: PropertyKind, name: &str) -> bool {
+        self.profiles
+            .layout()
+            .favorites
+            .iter()
+            .any(|fav| fav.0 == kind && fav.1 == name)
+    }
+
+    /// Include a property in refreshes of its section or exclude it, in all sessions
+    fn set_polled(&mut self, kind: PropertyKind, name: String, polled: bool) {
+        self.unpolled.retain(|prop| *prop != (kind, name.clone()));
+
+        if !polled {
+            self.unpolled.push((kind, name));
+        }
+
+        for session in &self.sessions {
+            if let Some(worker) = &session.worker {
+                worker.send(WorkerCommand::SetUnpolled(self.unpolled.clone()));
+            }
+        }
+    }
+
+    /// Names of the properties of a kind that are excluded from polling, matching the filter
+    ///
+    /// Only properties of the connected device are included,
+    /// unless they are still shown with the value queried before excluding them.
+    fn unpolled_properties(&self, kind: PropertyKind, stored: &[PropertyData]) -> Vec<&str> {
+        let ConnectionState::Connected(info) = &self.session().connection_state else {
+            return Vec::new();
+        };
+        let Some(schema) = freemdu::device::schema(info.software_id) else {
+            return Vec::new();
+        };
+        let filter = self.profiles.layout().filter.to_lowercase();
+
+        self.unpolled
+            .iter()
+            .filter(|(k, name)| {
+                *k == kind
+                    && matches_filter(name, &filter)
+                    && schema
+                        .properties
+                        .iter()
+                        .any(|prop| prop.kind == kind && prop.name == name)
+                    && !stored.iter().any(|prop| prop.name == *name)
+            })
+            .map(|(_, name)| name.as_str())
+            .collect()
+    }
+
+    fn render_graphs(&mut self, ui: &mut Ui, requests: &mut Vec<PropertyRequest>) {
+        let session = &self.sessions[self.active_session];
+        let resolution = &mut self.resolution;
+
+        for &id in &session.graphs {
+            let Some(prop) = session.properties.get(id) else {
+                continue;
+            };
+
+            let samples = session.history.get(id);
+            let mut export = None;
+
+            egui::Frame::group(ui.style())
+                .fill(ui.style().visuals.extreme_bg_color)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(&prop.name).strong());
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("✕").on_hover_text("Close graph").clicked() {
+                                requests.push(PropertyRequest::Graph(id, false));
+                            }
+
+                            resolution_toggle(ui, resolution);
+                            ui.add_enabled_ui(samples.is_some(), |ui| {
+                                if ui
+                                    .small_button("PNG")
+                                    .on_hover_text("Save a picture of the graph")
+                                    .clicked()
+                                {
+                                    export = Some(GraphExport::Png);
+                                }
+
+                                if ui
+                                    .small_button("CSV")
+                                    .on_hover_text("Save the samples with their timestamps")
+                                    .clicked()
+                                {
+                                    export = Some(GraphExport::Csv);
+                                }
+                            });
+                        });
+                    });
+
+                    if let Some(samples) = samples {
+                        let rect = plot::show(ui, samples, prop.unit.as_deref(), *resolution);
+
+                        if let Some(format) = export {
+                            requests.push(PropertyRequest::Export(id, format, rect));
+                        }
+                    }
+                });
+            ui.add_space(10.0);
+        }
+    }
+
+    /// Render the properties selected for overlaying on a single graph
+    fn render_overlay(&mut self, ui: &mut Ui, requests: &mut Vec<PropertyRequest>) {
+        if self.overlay.is_empty() {
+            return;
+        }
+
+        let session = &self.sessions[self.active_session];
+        let scale = &mut self.overlay_scale;
+        let resolution = &mut self.resolution;
+
+        egui::Frame::group(ui.style())
+            .fill(ui.style().visuals.extreme_bg_color)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("Overlay").strong());
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✕").on_hover_text("Close graph").clicked() {
+                            for &id in &self.overlay {
+                                requests.push(PropertyRequest::Overlay(id, false));
+                            }
+                        }
+
+                        resolution_toggle(ui, resolution);
+                        ui.selectable_value(scale, OverlayScale::Shared, "Shared axis")
+                            .on_hover_text("Compare properties with the same unit");
+                        ui.selectable_value(scale, OverlayScale::Separate, "Own axes")
+                            .on_hover_text("Scale each property to its own range");
+                    });
+                });
+
+                // Properties the device doesn't support or that weren't queried yet are skipped
+                let series: Vec<_> = self
+                    .overlay
+                    .iter()
+                    .filter_map(|&id| {
+                        let prop = session.properties.get(id)?;
+
+                        Some(Series {
+                            name: &prop.name,
+                            unit: prop.unit.as_deref(),
+                            samples: session.history.get(id)?,
+                        })
+                    })
+                    .collect();
+
+                plot::show_overlay(ui, &series, *scale, *resolution);
+            });
+        ui.add_space(10.0);
+    }
+
+    fn render_smoothing_controls(&mut self, ui: &mut Ui) {
+        let smoother = &mut self.sessions[self.active_session].smoother;
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut smoother.enabled, "Smooth noisy values")
+                .on_hover_text(
+                    "Display an exponential moving average of numeric values.\n\
+                     Right-click a value to toggle smoothing for that property only.",
+                );
+            ui.add(
+                egui::Slider::new(&mut smoother.alpha, 0.05..=1.0)
+                    .text("Alpha")
+                    .fixed_decimals(2),
+            )
+            .on_hover_text("Weight of the newest sample; lower values smooth more");
+        });
+
+        egui::CollapsingHeader::new("Refresh intervals").show(ui, |ui| {
+            for (kind, interval) in &mut self.refresh_intervals {
+                let mut millis = interval.as_millis() as u64;
+                let label = short_kind_label(*kind);
+
+                if ui
+                    .add(
+                        egui::Slider::new(&mut millis, REFRESH_INTERVAL_RANGE)
+                            .logarithmic(true)
+                            .suffix(" ms")
+                            .text(label),
+                    )
+                    .changed()
+                {
+                    *interval = Duration::from_millis(millis);
+                }
+            }
+
+            let mut stale_secs = self.stale_window.as_secs();
+
+            if ui
+                .add(
+                    egui::Slider::new(&mut stale_secs, STALE_WINDOW_RANGE)
+                        .logarithmic(true)
+                        .suffix(" s")
+                        .text("Stale after"),
+                )
+                .on_hover_text(
+                    "Mark I/O readings that don't change for this long while being polled",
+                )
+                .changed()
+            {
+                self.stale_window = Duration::from_secs(stale_secs);
+            }
+
+            ui.checkbox(
+                &mut self.auto_refresh.pause_unfocused,
+                "Pause while the window is in the background",
+            )
+            .on_hover_text(
+                "Free the serial link for other tools while FreeMDU is unfocused.\n\
+                 Logging and exports don't receive new values while paused.",
+            );
+
+            render_warm_up_controls(ui, &mut self.connect_options.warm_up);
+
+            if ui.button("Reset to defaults").clicked() {
+                self.refresh_intervals = DEFAULT_REFRESH_INTERVALS;
+                self.stale_window = DEFAULT_STALE_WINDOW;
+                self.connect_options.warm_up = WarmUpOrder::default();
+            }
+        });
+    }
+
+    fn render_metrics_controls(&mut self, ui: &mut Ui) {
+        egui::CollapsingHeader::new("Metrics export").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.metrics_enabled, "Serve Prometheus metrics")
+                    .on_hover_text(
+                        "Expose the latest property values at /metrics on all network interfaces.\n\
+                         The endpoint is only available while a device is connected.",
+                    );
+
+                // Restarted at the new port during the next frame
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut self.metrics_port)
+                            .range(1..=u16::MAX)
+                            .prefix("Port "),
+                    )
+                    .changed()
+                {
+                    self.metrics = None;
+                }
+            });
+
+            if let Some(metrics) = &self.metrics {
+                ui.weak(format!("http://localhost:{}/metrics", metrics.port()));
+            }
+        });
+    }
+
+    fn render_mqtt_controls(&mut self, ui: &mut Ui) {
+        egui::CollapsingHeader::new("MQTT publishing").show(ui, |ui| {
+            ui.checkbox(&mut self.mqtt_config.enabled, "Publish to MQTT broker")
+                .on_hover_text(
+                    "Publish each property value as a retained message at\n\
+                     <base topic>/<kind>/<property ID>.",
+                );
+
+            let config = &mut self.mqtt_config;
+
+            egui::Grid::new("mqtt_config")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Broker");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(&mut config.host).desired_width(140.0));
+                        ui.add(egui::DragValue::new(&mut config.port).range(1..=u16::MAX));
+                    });
+                    ui.end_row();
+
+                    ui.label("Base topic");
+                    ui.add(egui::TextEdit::singleline(&mut config.base_topic).desired_width(140.0));
+                    ui.end_row();
+
+                    ui.checkbox(&mut config.discovery, "Discovery")
+                        .on_hover_text("Announce properties as Home Assistant entities");
+                    ui.add_enabled(
+                        config.discovery,
+                        egui::TextEdit::singleline(&mut config.discovery_prefix)
+                            .desired_width(140.0),
+                    );
+                    ui.end_row();
+
+                    ui.label("User name");
+                    ui.add(egui::TextEdit::singleline(&mut config.username).desired_width(140.0));
+                    ui.end_row();
+
+                    ui.label("Password");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut config.password)
+                            .password(true)
+                            .desired_width(140.0),
+                    )
+                    .on_hover_text("Stored unencrypted with the other settings");
+                    ui.end_row();
+                });
+
+            // Reconnected with the new settings during the next frame
+            if let Some(mqtt) = &self.mqtt {
+                if mqtt.config() == &self.mqtt_config {
+                    if mqtt.is_connected() {
+                        ui.weak("Connected to broker");
+                    } else {
+                        ui.weak("Connecting to broker…");
+                    }
+                } else if ui.button("Apply").clicked() {
+                    self.mqtt = None;
+                }
+            }
+        });
+    }
+
+    fn render_influx_controls(&mut self, ui: &mut Ui) {
+        egui::CollapsingHeader::new("InfluxDB export").show(ui, |ui| {
+            ui.checkbox(&mut self.influx_config.enabled, "Write to InfluxDB")
+                .on_hover_text(
+                    "Write each batch of queried properties as a point of a measurement\n\
+                     named after the device kind, using the line protocol.",
+                );
+
+            let config = &mut self.influx_config;
+
+            egui::Grid::new("influx_config")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Write URL");
+                    ui.add(egui::TextEdit::singleline(&mut config.url).desired_width(240.0))
+                        .on_hover_text("e.g. http://localhost:8086/write?db=freemdu");
+                    ui.end_row();
+
+                    ui.label("Token");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut config.token)
+                            .password(true)
+                            .desired_width(240.0),
+                    )
+                    .on_hover_text("Stored unencrypted with the other settings");
+                    ui.end_row();
+                });
+
+            // Restarted with the new settings during the next frame
+            if let Some(influx) = &self.influx {
+                if influx.config() != &self.influx_config {
+                    if ui.button("Apply").clicked() {
+                        self.influx = None;
+                    }
+                } else if let Some(e) = influx.error() {
+                    ui.colored_label(Color32::RED, format!("Write failed: {e}"));
+                } else {
+                    ui.weak("Writing to InfluxDB");
+                }
+            }
+        });
+    }
+
+    fn render_stream_controls(&mut self, ui: &mut Ui) {
+        egui::CollapsingHeader::new("Event stream").show(ui, |ui| {
+            ui.checkbox(&mut self.stream_config.enabled, "Stream property updates")
+                .on_hover_text(
+                    "Send each queried property to connected clients\n\
+                     as a JSON object per line, e.g. for `nc localhost 9851`.",
+                );
+
+            ui.horizontal(|ui| {
+                ui.label("Listen on");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.stream_config.address)
+                        .desired_width(240.0),
+                )
+                .on_hover_text("host:port, or unix:<path> for a Unix socket");
+            });
+
+            // Restarted with the new settings during the next frame
+            if let Some(stream) = &self.stream {
+                if stream.config() == &self.stream_config {
+                    ui.weak(format!("Listening on {}", stream.address()));
+                } else if ui.button("Apply").clicked() {
+                    self.stream = None;
+                }
+            }
+        });
+    }
+
+    fn render_watch_expressions(&mut self, ui: &mut Ui) {
+        egui::Frame::group(ui.style())
+            .fill(ui.style().visuals.extreme_bg_color)
+            .show(ui, |ui| {
+                ui.label(RichText::new("Watch Expressions").strong())
+                    .on_hover_text(
+                        "Values derived from properties, e.g. \"target_water_amount / 1000\".\n\
+                         Properties are referenced by ID or by name in double quotes\n\
+                         and combined using + - * / and parentheses.",
+                    );
+                ui.separator();
+
+                let properties = &self.sessions[self.active_session].properties;
+                let lookup = |id: &str| {
+                    properties
+                        .find(id)
+                        .or_else(|| properties.find_by_name(id))
+                        .and_then(|prop| watch::numeric_value(&prop.value))
+                };
+                let mut removed = None;
+
+                egui::Grid::new("watch_expressions")
+                    .num_columns(3)
+                    .striped(true)
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        for (idx, watch) in self.watches.iter().enumerate() {
+                            ui.label(&watch.name).on_hover_text(&watch.source);
+
+                            match &watch.expr {
+                                Ok(expr) => {
+                                    let unit = watch
+                                        .unit
+                                        .as_ref()
+                                        .map(|unit| format!(" {unit}"))
+                                        .unwrap_or_default();
+                                    let text = expr.eval(&lookup).map_or_else(
+                                        || "—".to_string(),
+                                        |val| format!("{val:.2}{unit}"),
+                                    );
+
+                                    ui.label(text);
+                                }
+                                Err(e) => {
+                                    ui.colored_label(Color32::RED, "Invalid").on_hover_text(e);
+                                }
+                            }
+
+                            if ui.small_button("🗑").on_hover_text("Remove").clicked() {
+                                removed = Some(idx);
+                            }
+
+                            ui.end_row();
+                        }
+                    });
+
+                if let Some(idx) = removed {
+                    self.watches.remove(idx);
+                }
+
+                ui.horizontal(|ui| {
+                    let (name, source, unit) = &mut self.new_watch;
+
+                    ui.add(
+                        egui::TextEdit::singleline(name)
+                            .hint_text("Name")
+                            .desired_width(80.0),
+                    );
+                    ui.add(
+                        egui::TextEdit::singleline(source)
+                            .hint_text("Expression")
+                            .desired_width(160.0),
+                    );
+                    ui.add(
+                        egui::TextEdit::singleline(unit)
+                            .hint_text("Unit")
+                            .desired_width(40.0),
+                    );
+
+                    let valid = WatchExpression::is_storable(name, source, unit);
+
+                    if ui.add_enabled(valid, egui::Button::new("Add")).clicked() {
+                        let (name, source, unit) = std::mem::take(&mut self.new_watch);
+                        let unit = Some(unit.trim().to_string()).filter(|unit| !unit.is_empty());
+
+                        self.watches.push(WatchExpression::new(
+                            name.trim().to_string(),
+                            source.trim().to_string(),
+                            unit,
+                        ));
+                    }
+                });
+            });
+    }
+
+    /// Render the faults stored by the device as a table
+    fn render_fault_history(&self, ui: &mut Ui) {
+        let Some(history) = &self.session().fault_history else {
+            return;
+        };
+
+        ui.add_space(10.0);
+        egui::Frame::group(ui.style())
+            .fill(ui.style().visuals.extreme_bg_color)
+            .show(ui, |ui| {
+                render_section_header(ui, "Stored Faults", Color32::from_rgb(244, 67, 54), None);
+                ui.separator();
+
+                let records = match history {
+                    Ok(records) if records.is_empty() => {
+                        ui.label("No stored faults");
+                        return;
+                    }
+                    Ok(records) => records,
+                    Err(e) => {
+                        ui.colored_label(
+                            Color32::RED,
+                            format!("Failed to read stored faults: {e}"),
+                        );
+                        return;
+                    }
+                };
+                // Only shown if the device stores them
+                let counts = records.iter().any(|record| record.count.is_some());
+                let timestamps = records.iter().any(|record| record.timestamp.is_some());
+
+                egui::Grid::new("fault_history")
+                    .striped(true)
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.strong("Code");
+                        ui.strong("Fault");
+
+                        if counts {
+                            ui.strong("Count");
+                        }
+
+                        if timestamps {
+                            ui.strong("Last Occurrence");
+                        }
+
+                        ui.end_row();
+
+                        for record in records {
+                            ui.monospace(format!("{:#06x}", record.code));
+
+                            if let Some(description) = record.description {
+                                ui.label(description);
+                            } else {
+                                ui.weak("Unknown");
+                            }
+
+                            if counts {
+                                ui.label(record.count.map_or("-".to_string(), |c| c.to_string()));
+                            }
+
+                            if timestamps {
+                                ui.label(record.timestamp.map_or("-".to_string(), |time| {
+                                    format_value(
+                                        &PropertyValue::Duration(time),
+                                        None,
+                                        0,
+                                        true,
+                                        self.temperature_unit,
+                                    )
+                                }));
+                            }
+
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    fn render_property_section(
+        &self,
+        ui: &mut Ui,
+        kind: PropertyKind,
+        title: &str,
+        requests: &mut Vec<PropertyRequest>,
+        edits: &mut HashMap<PropertyId, PropertyValue>,
+    ) {
+        let header_color = section_color(kind, ui.visuals().dark_mode);
+        let session = self.session();
+
+        egui::Frame::group(ui.style())
+            .fill(ui.style().visuals.extreme_bg_color)
+            .show(ui, |ui| {
+                let storage = session.properties.of_kind(kind);
+                let collapsed = self.profiles.layout().collapsed.contains(&kind);
+
+                render_section_controls(ui, kind, title, storage.1, collapsed, requests);
+
+                if collapsed {
+                    return;
+                }
+
+                ui.separator();
+
+                let props = self.displayed_properties(kind, &storage.0);
+                let unsupported =
+                    session.unsupported_properties(kind, &self.profiles.layout().filter);
+                let unpolled = self.unpolled_properties(kind, &storage.0);
+                let sort = self.property_sort(kind);
+
+                let read_only = matches!(
+                    &session.connection_state,
+                    ConnectionState::Connected(info) if info.read_only
+                );
+
+                let state = session.properties.poll_state(kind);
+
+                if state == PollState::NeverPolled {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Loading...");
+                    });
+                } else if state == PollState::Empty
+                    && unpolled.is_empty()
+                    && !session.unsupported.iter().any(|p| p.1 == kind)
+                {
+                    // Neither rejected by the device nor excluded from polling
+                    ui.label(format!(
+                        "This model exposes no {} properties",
+                        describe_kind(kind)
+                    ));
+                } else if props.is_empty() && unsupported.is_empty() && unpolled.is_empty() {
+                    ui.label("No matching properties");
+                } else {
+                    egui::Grid::new(format!("props_{kind:?}"))
+                        .num_columns(2)
+                        .min_col_width(MIN_NAME_WIDTH)
+                        .striped(true)
+                        .spacing([20.0, 4.0])
+                        .show(ui, |ui| {
+                            render_sort_headers(ui, kind, sort, requests);
+
+                            for prop in props {
+                                let raw = format_value(
+                                    &prop.value,
+                                    prop.unit.as_deref(),
+                                    prop.decimals,
+                                    prop.cumulative,
+                                    self.temperature_unit,
+                                );
+
+                                self.render_property_name(ui, kind, prop, requests);
+
+                                let response = ui
+                                    .horizontal(|ui| {
+                                        let response = self.render_value_label(
+                                            ui,
+                                            kind,
+                                            prop,
+                                            &raw,
+                                            header_color,
+                                        );
+
+                                        if ui
+                                            .small_button("📋")
+                                            .on_hover_text("Copy value")
+                                            .clicked()
+                                        {
+                                            requests.push(PropertyRequest::Copy(
+                                                prop.name.clone(),
+                                                raw,
+                                            ));
+                                        }
+
+                                        if prop.writable {
+                                            if let Some(value) =
+                                                render_property_editor(ui, prop, edits, !read_only)
+                                            {
+                                                requests.push(PropertyRequest::Set(prop.id, value));
+                                            }
+                                        }
+
+                                        response
+                                    })
+                                    .inner;
+
+                                response.context_menu(|ui| {
+                                    self.render_property_menu(ui, kind, prop, requests);
+                                });
+
+                                ui.end_row();
+                            }
+
+                            render_unpolled_rows(ui, kind, &unpolled, requests);
+                            render_unsupported_rows(ui, &unsupported);
+                        });
+                }
+            });
+    }
+
+    /// Render the value of a property, smoothed and highlighted if enabled
+    ///
+    /// Readings that stopped changing while being polled are marked as stale.
+    fn render_value_label(
+        &self,
+        ui: &mut Ui,
+        kind: PropertyKind,
+        prop: &PropertyData,
+        raw: &str,
+        header_color: Color32,
+    ) -> egui::Response {
+        let session = self.session();
+        let response = if let PropertyValue::Flags(flags) = &prop.value {
+            render_flags(ui, flags).on_hover_text(format!("Set: {raw}"))
+        } else if let Some(value) = session.smoother.smoothed(prop) {
+            let text = format_value(
+                &value,
+                prop.unit.as_deref(),
+                prop.decimals,
+                prop.cumulative,
+                self.temperature_unit,
+            );
+
+            ui.label(range_text(text, prop))
+                .on_hover_text(format!("Raw: {raw}"))
+        } else if kind == PropertyKind::Warning && matches!(prop.value, PropertyValue::Bool(true)) {
+            // Active warnings are highlighted, but less alarming than faults
+            ui.colored_label(header_color, raw)
+        } else {
+            ui.label(range_text(raw.to_string(), prop))
+        };
+
+        // Only live readings are expected to change, other properties are often constant
+        let reading = kind == PropertyKind::Io
+            && matches!(
+                prop.value,
+                PropertyValue::Number(_) | PropertyValue::Float(_) | PropertyValue::Sensor(..)
+            );
+
+        if let Some(unchanged) = session
+            .properties
+            .unchanged_for(prop.id, self.stale_window)
+            .filter(|_| reading)
+        {
+            ui.weak("⏸").on_hover_text(format!(
+                "Unchanged for {} while being polled, the sensor may be stuck",
+                format_age(unchanged)
+            ));
+        }
+
+        if let Some(data) = prop.raw.as_ref().filter(|_| self.show_raw) {
+            ui.label(RichText::new(format_hex(data)).monospace().weak());
+        }
+
+        response
+    }
+
+    /// Render the name of a property with a button to toggle it as favorite
+    ///
+    /// The description of the property is shown when hovering the name.
+    fn render_property_name(
+        &self,
+        ui: &mut Ui,
+        kind: PropertyKind,
+        prop: &PropertyData,
+        requests: &mut Vec<PropertyRequest>,
+    ) {
+        let favorite = self.is_favorite(kind, &prop.name);
+        let (icon, hint) = if favorite {
+            ("★", "Remove from favorites")
+        } else {
+            ("☆", "Add to favorites")
+        };
+
+        ui.horizontal(|ui| {
+            if ui.small_button(icon).on_hover_text(hint).clicked() {
+                requests.push(PropertyRequest::Favorite(
+                    kind,
+                    prop.name.clone(),
+                    !favorite,
+                ));
+            }
+
+            let label = ui.label(&prop.name);
+
+            if let Some(description) = prop.description {
+                label.on_hover_text(description);
+            }
+        });
+    }
+
+    /// Render the context menu of a property value
+    fn render_property_menu(
+        &self,
+        ui: &mut Ui,
+        kind: PropertyKind,
+        prop: &PropertyData,
+        requests: &mut Vec<PropertyRequest>,
+    ) {
+        if ui.button("Refresh").clicked() {
+            requests.push(PropertyRequest::Refresh(prop.id));
+            ui.close_menu();
+        }
+
+        let mut polled = !self.unpolled.contains(&(kind, prop.name.clone()));
+
+        if ui
+            .checkbox(&mut polled, "Poll")
+            .on_hover_text("Query the property whenever its section is refreshed")
+            .changed()
+        {
+            requests.push(PropertyRequest::Poll(kind, prop.name.clone(), polled));
+            ui.close_menu();
+        }
+
+        if Smoother::is_applicable(&prop.value) {
+            let mut enabled = self.session().smoother.is_enabled(prop.id);
+
+            if ui.checkbox(&mut enabled, "Smooth value").changed() {
+                requests.push(PropertyRequest::Smooth(prop.id, enabled));
+                ui.close_menu();
+            }
+        }
+
+        if plot::is_plottable(&prop.value) {
+            let mut open = self.session().graphs.contains(&prop.id);
+
+            if ui.checkbox(&mut open, "Graph").changed() {
+                requests.push(PropertyRequest::Graph(prop.id, open));
+                ui.close_menu();
+            }
+
+            let mut overlaid = self.overlay.contains(&prop.id);
+
+            if ui
+                .checkbox(&mut overlaid, "Overlay")
+                .on_hover_text("Graph together with other properties on a shared time axis")
+                .changed()
+            {
+                requests.push(PropertyRequest::Overlay(prop.id, overlaid));
+                ui.close_menu();
+            }
+        }
+
+        let indicators = if kind == PropertyKind::Failure {
+            alert::indicators(prop)
+        } else {
+            Vec::new()
+        };
+
+        if !indicators.is_empty() {
+            ui.menu_button("🔔 Alert when active", |ui| {
+                for indicator in indicators {
+                    let mut alerting = self.alerting.contains(&indicator.key);
+
+                    if ui.checkbox(&mut alerting, indicator.label).changed() {
+                        requests.push(PropertyRequest::Alert(indicator.key, alerting));
+                    }
+                }
+            });
+        }
+    }
+
+    /// Render the unacknowledged alerts of the current session above the properties
+    fn render_alert_banner(&mut self, ctx: &egui::Context) {
+        let alerts = &self.session().alerts.active;
+
+        if alerts.is_empty() {
+            return;
+        }
+
+        let color = section_color(PropertyKind::Failure, ctx.style().visuals.dark_mode);
+        let mut acknowledged = false;
+
+        egui::TopBottomPanel::top("alert_banner").show(ctx, |ui| {
+            ui.add_space(2.0);
+            ui.horizontal_wrapped(|ui| {
+                for alert in alerts {
+                    ui.colored_label(
+                        color,
+                        RichText::new(format!(
+                            "⚠ {} since {} UTC",
+                            alert.label,
+                            alert.since.strftime("%Y-%m-%d %H:%M:%S")
+                        ))
+                        .strong(),
+                    );
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    acknowledged = ui.button("Acknowledge").clicked();
+                });
+            });
+            ui.add_space(2.0);
+        });
+
+        if acknowledged {
+            self.session_mut().alerts.acknowledge();
+        }
+    }
+}
+
+impl Session {
+    /// Render the manual refresh button, disabled until a requested refresh has completed
+    fn render_refresh_button(&mut self, ui: &mut Ui) {
+        if !matches!(self.connection_state, ConnectionState::Connected(_)) {
+            return;
+        }
+
+        let progress = self.full_refresh_progress();
+        let label = progress.map_or("Refresh All".to_string(), |received| {
+            format!("Refreshing {received}/{}", PROPERTY_KINDS.len())
+        });
+
+        if ui
+            .add_enabled(progress.is_none(), egui::Button::new(label))
+            .on_hover_text(shortcut_hint(
+                ui,
+                "Query all properties again",
+                REFRESH_ALL_SHORTCUT,
+            ))
+            .on_disabled_hover_text("Sections received so far")
+            .clicked()
+        {
+            self.refresh_all();
+        }
+    }
+
+    /// Names of the properties of a kind that are no longer polled, matching the filter
+    fn unsupported_properties(&self, kind: PropertyKind, filter: &str) -> Vec<&str> {
+        let filter = filter.to_lowercase();
+
+        self.unsupported
+            .iter()
+            .filter(|(_, k, name)| *k == kind && matches_filter(name, &filter))
+            .map(|(_, _, name)| name.as_str())
+            .collect()
+    }
+
+    fn render_logging_controls(&mut self, ui: &mut Ui) {
+        if !matches!(self.connection_state, ConnectionState::Connected(_)) {
+            return;
+        }
+
+        if let Some(path) = &self.csv_log {
+            if ui
+                .button("Stop Logging")
+                .on_hover_text(format!("Logging to {}", path.display()))
+                .clicked()
+            {
+                if let Some(worker) = &self.worker {
+                    worker.send(WorkerCommand::StopLogging);
+                }
+
+                let msg = format!("Saved log to {}", path.display());
+
+                self.csv_log = None;
+                self.set_status(&msg, false);
+            }
+        } else if ui
+            .add_enabled(
+                self.reconnect_attempt.is_none() && self.csv_log_dialog.is_none(),
+                egui::Button::new("Start Logging…"),
+            )
+            .on_hover_text("Log all queried property values to a CSV file")
+            .clicked()
+        {
+            let name = jiff::Timestamp::now().strftime("freemdu-%Y%m%d-%H%M%S.csv");
+
+            self.csv_log_dialog = Some(name.to_string());
+        }
+    }
+
+    /// Render the console for sending raw requests and inspecting the replies
+    fn render_raw_console(&mut self, ui: &mut Ui) {
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            let req = parse_hex(&self.raw_input);
+            let edit = ui.add(
+                egui::TextEdit::singleline(&mut self.raw_input)
+                    .font(egui::TextStyle::Monospace)
+                    .hint_text("11 00 00 02")
+                    .desired_width(240.0),
+            );
+            let submitted = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            let send = ui
+                .add_enabled(
+                    req.is_some() && self.reconnect_attempt.is_none(),
+                    egui::Button::new("Send"),
+                )
+                .on_hover_text("Send the bytes in hex, a checksum is appended to every chunk");
+
+            if let (Some(req), Some(worker)) = (req, &self.worker) {
+                if send.clicked() || submitted {
+                    worker.send(WorkerCommand::RawTransaction(req));
+                }
+            }
+
+            if ui.button("Clear").clicked() {
+                self.raw_log.clear();
+            }
+        });
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for (req, reply) in &self.raw_log {
+                    ui.monospace(format!("→ {}", format_hex(req)));
+
+                    match reply {
+                        Ok(reply) if reply.is_empty() => {
+                            ui.weak("← (no data)");
+                        }
+                        Ok(reply) => {
+                            ui.monospace(format!("← {}", format_hex(reply)));
+                        }
+                        Err(e) => {
+                            ui.colored_label(Color32::RED, format!("← {e}"));
+                        }
+                    }
+                }
+            });
+    }
+
+    /// Render the dialog for choosing the CSV log file
+    fn render_logging_dialog(&mut self, ctx: &egui::Context) {
+        let Some(path) = &mut self.csv_log_dialog else {
+            return;
+        };
+        let mut start = false;
+        let mut cancel = false;
+
+        egui::Window::new("Start Logging")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    "Property values are appended to this file, which is overwritten if it exists:",
+                );
+                ui.add(egui::TextEdit::singleline(path).desired_width(360.0));
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    start = ui
+                        .add_enabled(!path.trim().is_empty(), egui::Button::new("Start"))
+                        .clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+
+        if start {
+            let path = PathBuf::from(path.trim());
+
+            if let Some(worker) = &self.worker {
+                worker.send(WorkerCommand::StartLogging(path.clone()));
+                self.set_status(&format!("Logging to {}", path.display()), false);
+                self.csv_log = Some(path);
+            }
+        }
+
+        if start || cancel {
+            self.csv_log_dialog = None;
+        }
+    }
+
+    /// Render the dialog for choosing the file to save the session to or open one from
+    fn render_file_dialog(&mut self, ctx: &egui::Context) {
+        let (title, text, button, path) = match &mut self.file_dialog {
+            Some(SessionFileDialog::Save(path)) => (
+                "Save Session",
+                "The device, all properties and the action log are saved to this file, \
+                 which is overwritten if it exists:",
+                "Save",
+                path,
+            ),
+            Some(SessionFileDialog::Open(path)) => (
+                "Open Session",
+                "The saved session is shown read-only, without connecting to a device:",
+                "Open",
+                path,
+            ),
+            None => return,
+        };
+        let mut confirm = false;
+        let mut cancel = false;
+
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(text);
+                ui.add(egui::TextEdit::singleline(path).desired_width(360.0));
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    confirm = ui
+                        .add_enabled(!path.trim().is_empty(), egui::Button::new(button))
+                        .clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+
+        if confirm {
+            match self.file_dialog.take() {
+                Some(SessionFileDialog::Save(path)) => self.save_session(Path::new(path.trim())),
+                Some(SessionFileDialog::Open(path)) => self.open_session(Path::new(path.trim())),
+                None => {}
+            }
+        }
+
+        if cancel {
+            self.file_dialog = None;
+        }
+    }
+
+    /// Save the device, the stored properties and the action log to a file
+    fn save_session(&mut self, path: &Path) {
+        let (ConnectionState::Connected(info) | ConnectionState::Viewing(info)) =
+            &self.connection_state
+        else {
+            return;
+        };
+        let session = SessionFile {
+            version: session_file::VERSION,
+            saved: jiff::Timestamp::now().to_string(),
+            device: SavedDevice::new(info),
+            sections: self.properties.to_saved(),
+            actions: self
+                .action_log
+                .iter()
+                .map(ActionLogEntry::to_saved)
+                .collect(),
+        };
+
+        match session_file::save(path, &session) {
+            Ok(()) => self.set_status(&format!("Saved session to {}", path.display()), false),
+            Err(e) => self.set_status(&format!("Failed to save session: {e}"), true),
+        }
+    }
+
+    /// Show a saved session in place of a device
+    fn open_session(&mut self, path: &Path) {
+        let session = match session_file::load(path) {
+            Ok(session) => session,
+            Err(e) => {
+                self.set_status(&format!("Failed to open session: {e}"), true);
+                return;
+            }
+        };
+        let saved = session_file::parse_time(&session.saved).unwrap_or_else(Instant::now);
+        let info = session.device.info();
+
+        self.disconnect();
+        self.last_port = None;
+        self.properties
+            .restore(info.software_id, session.sections, saved);
+        self.action_log = session
+            .actions
+            .into_iter()
+            .filter_map(ActionLogEntry::from_saved)
+            .collect();
+        self.connection_state = ConnectionState::Viewing(info);
+
+        let msg = match session.saved.parse::<jiff::Timestamp>() {
+            Ok(time) => format!(
+                "Opened session saved {} UTC",
+                time.strftime("%Y-%m-%d %H:%M:%S")
+            ),
+            Err(_) => format!("Opened session {}", path.display()),
+        };
+
+        self.set_status(&msg, false);
+    }
+
+    fn render_actions(
+        &mut self,
+        ui: &mut Ui,
+        actions: &[ActionInfo],
+        temperature_unit: TemperatureUnit,
+    ) {
+        egui::ScrollArea::vertical().show(ui, |ui| {
             for action in actions {
                 ui.group(|ui| {
                     ui.label(RichText::new(&action.name).strong());
 
-                    // Handle action parameters
-                    if let Some(params) = &action.params {
-                        match params {
-                            ActionParamsInfo::Enumeration(options) => {
-                                let current =
-                                    self.action_inputs.entry(action.id.clone()).or_insert_with(
-                                        || options.first().cloned().unwrap_or_default(),
-                                    );
+                    // Handle action parameters
+                    if let Some(params) = &action.params {
+                        match params {
+                            ActionParamsInfo::Enumeration(options) => {
+                                let current =
+                                    self.action_inputs.entry(action.id.clone()).or_insert_with(
+                                        || options.first().cloned().unwrap_or_default(),
+                                    );
+
+                                egui::ComboBox::from_id_salt(&action.id)
+                                    .selected_text(current.as_str())
+                                    .show_ui(ui, |ui| {
+                                        for opt in options {
+                                            ui.selectable_value(current, opt.clone(), opt);
+                                        }
+                                    });
+                            }
+                            ActionParamsInfo::Flags(flags) => {
+                                // Stored as the bitmask of the selected flags
+                                let current = self
+                                    .action_inputs
+                                    .entry(action.id.clone())
+                                    .or_insert_with(|| "0".to_string());
+                                let mut mask: u32 = current.trim().parse().unwrap_or_default();
+
+                                ui.horizontal_wrapped(|ui| {
+                                    for (bit, name) in flags {
+                                        let mut checked = mask & bit != 0;
+
+                                        if ui.checkbox(&mut checked, name).changed() {
+                                            mask ^= bit;
+                                            *current = mask.to_string();
+                                        }
+                                    }
+                                });
+                            }
+                            &ActionParamsInfo::Number { min, max, step } => {
+                                let current = self
+                                    .action_inputs
+                                    .entry(action.id.clone())
+                                    .or_insert_with(|| min.to_string());
+                                let mut num = current.trim().parse().unwrap_or(min);
+
+                                if ui
+                                    .add(
+                                        egui::DragValue::new(&mut num).range(min..=max).speed(step),
+                                    )
+                                    .changed()
+                                {
+                                    *current = num.to_string();
+                                }
+                            }
+                            ActionParamsInfo::FreeText => {
+                                let current =
+                                    self.action_inputs.entry(action.id.clone()).or_default();
+
+                                ui.add(
+                                    egui::TextEdit::singleline(current)
+                                        .hint_text("Argument")
+                                        .desired_width(f32::INFINITY),
+                                )
+                                .on_hover_text(
+                                    "Passed to the device unchanged, without validation",
+                                );
+                            }
+                        }
+                    }
+
+                    self.render_action_controls(ui, action);
+
+                    if let Some(value) = self.action_results.get(&action.id) {
+                        ui.horizontal(|ui| {
+                            ui.small("Result:");
+                            ui.small(
+                                RichText::new(format_value(
+                                    value,
+                                    None,
+                                    0,
+                                    false,
+                                    temperature_unit,
+                                ))
+                                .strong(),
+                            );
+                        });
+                    }
+                });
+                ui.add_space(5.0);
+            }
+
+            self.render_action_log(ui);
+        });
+    }
+
+    /// Render the executed actions of the session, newest first
+    fn render_action_log(&mut self, ui: &mut Ui) {
+        egui::CollapsingHeader::new(format!("Action log ({})", self.action_log.len()))
+            .id_salt("action_log")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!self.action_log.is_empty(), egui::Button::new("Copy"))
+                        .on_hover_text("Copy the log as text, oldest first")
+                        .clicked()
+                    {
+                        let text: Vec<_> =
+                            self.action_log.iter().map(ToString::to_string).collect();
+
+                        ui.ctx().copy_text(text.join("\n"));
+                    }
+
+                    if ui
+                        .add_enabled(!self.action_log.is_empty(), egui::Button::new("Clear"))
+                        .clicked()
+                    {
+                        self.action_log.clear();
+                    }
+                });
+
+                if self.action_log.is_empty() {
+                    ui.weak("No actions executed yet");
+                    return;
+                }
+
+                egui::Grid::new("action_log")
+                    .striped(true)
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        ui.strong("Time");
+                        ui.strong("Action");
+                        ui.strong("Argument");
+                        ui.strong("Result");
+                        ui.end_row();
+
+                        for entry in self.action_log.iter().rev() {
+                            ui.monospace(entry.time.strftime("%Y-%m-%d %H:%M:%S").to_string());
+                            ui.label(&entry.name);
+                            ui.label(entry.param.as_deref().unwrap_or("-"));
+
+                            if entry.success {
+                                ui.label(&entry.result);
+                            } else {
+                                ui.colored_label(Color32::RED, &entry.result);
+                            }
+
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+    /// Render the timeout setting and either the execute button or the progress of the action
+    fn render_action_controls(&mut self, ui: &mut Ui, action: &ActionInfo) {
+        let timeout = self
+            .action_timeouts
+            .entry(action.id.clone())
+            .or_insert(DEFAULT_ACTION_TIMEOUT_SECS);
+
+        ui.horizontal(|ui| {
+            ui.label("Timeout:");
+            ui.add(egui::DragValue::new(timeout).range(1..=600).suffix(" s"));
+        });
+
+        let timeout = Duration::from_secs(*timeout);
+
+        match &self.running_action {
+            Some(progress) if progress.id == action.id => {
+                ui.add(egui::ProgressBar::new(progress.fraction()).text(format!(
+                    "{:.1} s / {} s",
+                    progress.elapsed.as_secs_f32(),
+                    progress.timeout.as_secs()
+                )));
+
+                if ui
+                    .button("Abort")
+                    .on_hover_text("Stop waiting for the device to finish")
+                    .clicked()
+                {
+                    if let Some(worker) = &self.worker {
+                        worker.abort_action();
+                    }
+                }
+            }
+            running => {
+                let idle = running.is_none();
+                let valid = action.params.as_ref().map_or(true, |params| {
+                    params.is_valid(
+                        self.action_inputs
+                            .get(&action.id)
+                            .map_or("", String::as_str),
+                    )
+                });
+
+                if ui
+                    .add_enabled(idle && valid, egui::Button::new("Execute"))
+                    .on_disabled_hover_text(if valid {
+                        "Another action is running"
+                    } else {
+                        "Argument is out of range"
+                    })
+                    .clicked()
+                {
+                    let param = self.action_inputs.get(&action.id).cloned();
+                    let unknown = action
+                        .params
+                        .as_ref()
+                        .zip(param.as_deref())
+                        .and_then(|(params, input)| params.unknown_option(input));
+
+                    if let Some(option) = unknown {
+                        self.set_status(
+                            &format!("{} not executed: {option} is not accepted", action.name),
+                            true,
+                        );
+                    } else if let Some(worker) = &self.worker {
+                        worker.send(WorkerCommand::TriggerAction(
+                            action.id.clone(),
+                            param,
+                            timeout,
+                        ));
+                        self.running_action = Some(ActionProgress {
+                            id: action.id.clone(),
+                            elapsed: Duration::ZERO,
+                            timeout,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Describe the device kind and software, including its version if known
+fn describe_device(info: &DeviceInfo) -> String {
+    match freemdu::device::software_version(info.software_id) {
+        Some(version) => format!("{} ({version}, ID: {})", info.kind, info.software_id),
+        None => format!("{} (ID: {})", info.kind, info.software_id),
+    }
+}
+
+/// Render the column headers of a property section, which change the order when clicked
+fn render_sort_headers(
+    ui: &mut Ui,
+    kind: PropertyKind,
+    sort: Option<PropertySort>,
+    requests: &mut Vec<PropertyRequest>,
+) {
+    for (column, title) in [(SortColumn::Name, "Name"), (SortColumn::Value, "Value")] {
+        let text = match sort {
+            Some(sort) if sort.column == column && sort.descending => format!("{title} ⏷"),
+            Some(sort) if sort.column == column => format!("{title} ⏶"),
+            _ => title.to_string(),
+        };
+
+        if ui
+            .add(egui::Button::new(RichText::new(text).strong()).frame(false))
+            .on_hover_text("Sort by this column")
+            .clicked()
+        {
+            requests.push(PropertyRequest::Sort(kind, column));
+        }
+    }
+
+    ui.end_row();
+}
+
+/// Compare values for sorting, numbers numerically and before text
+fn compare_values(a: &PropertyValue, b: &PropertyValue) -> std::cmp::Ordering {
+    match (watch::numeric_value(a), watch::numeric_value(b)) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => match (a, b) {
+            (PropertyValue::String(a), PropertyValue::String(b)) => {
+                a.to_lowercase().cmp(&b.to_lowercase())
+            }
+            _ => std::cmp::Ordering::Equal,
+        },
+    }
+}
 
-                                egui::ComboBox::from_id_salt(&action.id)
-                                    .selected_text(current.as_str())
-                                    .show_ui(ui, |ui| {
-                                        for opt in options {
-                                            ui.selectable_value(current, opt.clone(), opt);
-                                        }
-                                    });
-                            }
-                            ActionParamsInfo::Flags(flags) => {
-                                let current =
-                                    self.action_inputs.entry(action.id.clone()).or_default();
+/// Hover text describing a control, followed by its keyboard shortcut
+fn shortcut_hint(ui: &Ui, text: &str, shortcut: KeyboardShortcut) -> String {
+    format!("{text} ({})", ui.ctx().format_shortcut(&shortcut))
+}
 
-                                ui.horizontal_wrapped(|ui| {
-                                    for flag in flags {
-                                        let is_set = current.contains(flag.as_str());
-                                        let mut checked = is_set;
-                                        if ui.checkbox(&mut checked, flag).changed() {
-                                            if checked {
-                                                if !current.is_empty() {
-                                                    current.push_str(" | ");
-                                                }
-                                                current.push_str(flag);
-                                            } else {
-                                                // Remove the flag
-                                                *current = current
-                                                    .split(" | ")
-                                                    .filter(|s| s != flag)
-                                                    .collect::<Vec<_>>()
-                                                    .join(" | ");
-                                            }
-                                        }
-                                    }
-                                });
-                            }
-                        }
-                    }
+/// Clamp a zoom factor to [`ZOOM_RANGE`], rounded to whole percent
+///
+/// Rounding keeps repeated steps from accumulating floating-point errors.
+fn clamp_zoom(factor: f32) -> f32 {
+    ((factor * 100.0).round() / 100.0).clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end())
+}
 
-                    if ui.button("Execute").clicked() {
-                        if let Some(worker) = &self.worker {
-                            let param = self.action_inputs.get(&action.id).cloned();
-                            worker.send(WorkerCommand::TriggerAction(action.id.clone(), param));
-                        }
-                    }
-                });
-                ui.add_space(5.0);
+/// Snap the share of the left property column to [`COLUMN_SPLIT_STEP`],
+/// clamped to [`COLUMN_SPLIT_RANGE`]
+fn snap_column_split(split: f32) -> f32 {
+    ((split / COLUMN_SPLIT_STEP).round() * COLUMN_SPLIT_STEP)
+        .clamp(*COLUMN_SPLIT_RANGE.start(), *COLUMN_SPLIT_RANGE.end())
+}
+
+/// Parse the address of a serial-to-TCP bridge, with or without the `tcp://` prefix
+///
+/// Returns `None` unless the address consists of a host and a port number.
+fn parse_network_port(src: &str) -> Option<&str> {
+    let addr = src.trim();
+    let addr = addr.strip_prefix(TCP_PREFIX).unwrap_or(addr);
+    let (host, port) = addr.rsplit_once(':')?;
+
+    (!host.is_empty() && !host.contains(char::is_whitespace) && port.parse::<u16>().is_ok())
+        .then_some(addr)
+}
+
+/// Short name of a kind of properties, e.g. for settings
+fn short_kind_label(kind: PropertyKind) -> &'static str {
+    match kind {
+        PropertyKind::General => "General",
+        PropertyKind::Failure => "Failure",
+        PropertyKind::Warning => "Warning",
+        PropertyKind::Operation => "Operation",
+        PropertyKind::Io => "I/O",
+    }
+}
+
+/// Name of a kind of properties within a sentence
+fn describe_kind(kind: PropertyKind) -> &'static str {
+    match kind {
+        PropertyKind::General => "general",
+        PropertyKind::Failure => "failure",
+        PropertyKind::Warning => "warning",
+        PropertyKind::Operation => "operating state",
+        PropertyKind::Io => "I/O",
+    }
+}
+
+/// Render the order in which all kinds are queried once after connecting
+fn render_warm_up_controls(ui: &mut Ui, order: &mut WarmUpOrder) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Load after connecting:").on_hover_text(
+            "Every section is queried once in this order, before the intervals apply",
+        );
+
+        let mut moved = None;
+
+        for (idx, &kind) in order.0.iter().enumerate() {
+            if idx > 0 && ui.small_button("⏴").on_hover_text("Load earlier").clicked() {
+                moved = Some(idx);
             }
-        });
+
+            ui.label(short_kind_label(kind));
+        }
+
+        if let Some(idx) = moved {
+            order.move_earlier(idx);
+        }
+    });
+}
+
+/// Render the settings for the time allowed for connecting and querying
+fn render_timeout_controls(ui: &mut Ui, timeouts: &mut Timeouts) {
+    let mut connect = timeouts.connect.as_secs();
+    let mut query = u64::try_from(timeouts.query.as_millis()).unwrap_or(u64::MAX);
+    let mut inter_byte = timeouts
+        .inter_byte
+        .map_or(0, |dur| u64::try_from(dur.as_millis()).unwrap_or(u64::MAX));
+
+    egui::Grid::new("timeouts").num_columns(2).show(ui, |ui| {
+        ui.label("Connect:");
+        ui.add(
+            egui::DragValue::new(&mut connect)
+                .range(1..=60)
+                .suffix(" s"),
+        );
+        ui.end_row();
+
+        ui.label("Query:");
+        ui.add(
+            egui::DragValue::new(&mut query)
+                .range(100..=30_000)
+                .speed(10)
+                .suffix(" ms"),
+        );
+        ui.end_row();
+
+        ui.label("Inter-byte:");
+        ui.add(
+            egui::DragValue::new(&mut inter_byte)
+                .range(0..=5_000)
+                .speed(10)
+                .custom_formatter(|val, _| {
+                    if val < 1.0 {
+                        "Off".to_string()
+                    } else {
+                        format!("{val} ms")
+                    }
+                }),
+        )
+        .on_hover_text(
+            "Maximum gap between the fragments of a frame, for adapters that buffer data",
+        );
+        ui.end_row();
+    });
+
+    if ui.button("Reset to defaults").clicked() {
+        *timeouts = Timeouts::default();
+    } else {
+        timeouts.connect = Duration::from_secs(connect);
+        timeouts.query = Duration::from_millis(query);
+        timeouts.inter_byte = (inter_byte > 0).then(|| Duration::from_millis(inter_byte));
+    }
+}
+
+/// Parse bytes entered in hex, separated by whitespace or written in groups like `0x1100`
+///
+/// Returns `None` if the input is empty or isn't valid hex.
+fn parse_hex(src: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+
+    for word in src.split_whitespace() {
+        let digits = word.trim_start_matches("0x");
+
+        if digits.len() % 2 != 0 {
+            return None;
+        }
+
+        for idx in (0..digits.len()).step_by(2) {
+            bytes.push(u8::from_str_radix(digits.get(idx..idx + 2)?, 16).ok()?);
+        }
+    }
+
+    (!bytes.is_empty()).then_some(bytes)
+}
+
+/// Load a setting that is either enabled or disabled, disabled if it was never saved
+fn load_flag(storage: Option<&dyn eframe::Storage>, key: &str) -> bool {
+    storage
+        .and_then(|storage| storage.get_string(key))
+        .is_some_and(|src| src == "true")
+}
+
+/// Load the share of the left property column, the default if it was never saved
+fn load_column_split(storage: Option<&dyn eframe::Storage>) -> f32 {
+    storage
+        .and_then(|storage| storage.get_string(COLUMN_SPLIT_KEY))
+        .and_then(|src| src.parse().ok())
+        .filter(|split: &f32| split.is_finite())
+        .map_or(DEFAULT_COLUMN_SPLIT, snap_column_split)
+}
+
+/// Load the persisted connection options, the defaults for those that are missing or malformed
+fn load_connect_options(storage: Option<&dyn eframe::Storage>) -> ConnectOptions {
+    ConnectOptions {
+        warm_up: Some(load_json(storage, WARM_UP_ORDER_KEY))
+            .filter(|order: &WarmUpOrder| order.is_valid())
+            .unwrap_or_default(),
+        ..ConnectOptions::default()
+    }
+}
+
+/// Load a setting persisted as JSON, the default if it's missing or malformed
+fn load_json<T: DeserializeOwned + Default>(storage: Option<&dyn eframe::Storage>, key: &str) -> T {
+    storage
+        .and_then(|storage| storage.get_string(key))
+        .and_then(|src| serde_json::from_str(&src).ok())
+        .unwrap_or_default()
+}
+
+fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the text of a value, colored if the raw value is outside the expected range
+///
+/// Smoothed values are colored by the raw value, so that spikes aren't hidden.
+fn range_text(text: String, prop: &PropertyData) -> RichText {
+    match prop.range_status() {
+        Some(RangeStatus::OutOfRange) => RichText::new(text).color(Color32::RED),
+        Some(RangeStatus::Warning) => RichText::new(text).color(Color32::from_rgb(255, 152, 0)),
+        Some(RangeStatus::Normal) | None => RichText::new(text),
+    }
+}
+
+/// Format a duration roughly, e.g. "42 s" or "12 min"
+fn format_age(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    if secs < 60 {
+        format!("{secs} s")
+    } else {
+        format!("{} min", secs / 60)
+    }
+}
+
+/// Render the title of a property section along with the time of its last update
+/// Color of the header of a property section
+///
+/// Darker shades are used on light backgrounds, where the bright ones are hard to read.
+fn section_color(kind: PropertyKind, dark_mode: bool) -> Color32 {
+    match (kind, dark_mode) {
+        (PropertyKind::General, true) => Color32::from_rgb(76, 175, 80),
+        (PropertyKind::General, false) => Color32::from_rgb(46, 125, 50),
+        (PropertyKind::Failure, true) => Color32::from_rgb(244, 67, 54),
+        (PropertyKind::Failure, false) => Color32::from_rgb(198, 40, 40),
+        (PropertyKind::Warning, true) => Color32::from_rgb(255, 152, 0),
+        (PropertyKind::Warning, false) => Color32::from_rgb(230, 81, 0),
+        (PropertyKind::Operation, true) => Color32::from_rgb(33, 150, 243),
+        (PropertyKind::Operation, false) => Color32::from_rgb(21, 101, 192),
+        (PropertyKind::Io, true) => Color32::from_rgb(156, 39, 176),
+        (PropertyKind::Io, false) => Color32::from_rgb(106, 27, 154),
+    }
+}
+
+fn serialize_theme(theme: ThemePreference) -> &'static str {
+    match theme {
+        ThemePreference::Dark => "dark",
+        ThemePreference::Light => "light",
+        ThemePreference::System => "system",
+    }
+}
+
+/// Deserialize a theme saved by [`serialize_theme`], defaulting to the system theme
+fn deserialize_theme(src: &str) -> ThemePreference {
+    match src {
+        "dark" => ThemePreference::Dark,
+        "light" => ThemePreference::Light,
+        _ => ThemePreference::System,
+    }
+}
+
+/// Render the header of a property section, with buttons for collapsing and copying it
+fn render_section_controls(
+    ui: &mut Ui,
+    kind: PropertyKind,
+    title: &str,
+    updated: Option<Instant>,
+    collapsed: bool,
+    requests: &mut Vec<PropertyRequest>,
+) {
+    let color = section_color(kind, ui.visuals().dark_mode);
+
+    ui.horizontal(|ui| {
+        let (icon, hint) = if collapsed {
+            ("▸", "Show properties")
+        } else {
+            ("▾", "Hide properties")
+        };
+
+        if ui.small_button(icon).on_hover_text(hint).clicked() {
+            requests.push(PropertyRequest::Collapse(kind, !collapsed));
+        }
+
+        render_section_header(ui, title, color, updated)
+            .on_hover_text("Right-click to copy the section")
+            .context_menu(|ui| {
+                if ui.button("📋 Copy section").clicked() {
+                    requests.push(PropertyRequest::CopySection(kind, title.to_string()));
+                    ui.close_menu();
+                }
+            });
+    });
+}
+
+/// Render the title of a section along with the time of its last update
+///
+/// Returns the response of the title, which senses clicks for a context menu.
+fn render_section_header(
+    ui: &mut Ui,
+    title: &str,
+    color: Color32,
+    updated: Option<Instant>,
+) -> egui::Response {
+    ui.horizontal(|ui| {
+        let response = ui.add(
+            egui::Label::new(RichText::new(title).color(color).strong())
+                .sense(egui::Sense::click()),
+        );
+
+        if let Some(time) = updated {
+            let elapsed = time.elapsed();
+            let text = if elapsed < Duration::from_secs(1) {
+                "just now".to_string()
+            } else {
+                format!("{}s ago", elapsed.as_secs())
+            };
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.small(text);
+            });
+        }
+
+        response
+    })
+    .inner
+}
+
+/// Render a row for each property excluded from polling, with a checkbox to include it again
+fn render_unpolled_rows(
+    ui: &mut Ui,
+    kind: PropertyKind,
+    names: &[&str],
+    requests: &mut Vec<PropertyRequest>,
+) {
+    for name in names {
+        ui.label(*name);
+
+        let mut polled = false;
+
+        if ui
+            .checkbox(&mut polled, "Poll")
+            .on_hover_text("Not queried when the section is refreshed")
+            .changed()
+        {
+            requests.push(PropertyRequest::Poll(kind, (*name).to_string(), true));
+        }
+
+        ui.end_row();
+    }
+}
+
+/// Render a row for each property that is no longer polled
+fn render_unsupported_rows(ui: &mut Ui, names: &[&str]) {
+    for name in names {
+        ui.label(*name);
+        ui.weak("N/A").on_hover_text(
+            "Not supported by the device, no longer polled.\n\
+             Refresh All polls it again.",
+        );
+        ui.end_row();
+    }
+}
+
+/// Render an edit box and set button for a writable property
+///
+/// Returns the new value if the set button was clicked.
+fn render_property_editor(
+    ui: &mut Ui,
+    prop: &PropertyData,
+    edits: &mut HashMap<PropertyId, PropertyValue>,
+    enabled: bool,
+) -> Option<PropertyValue> {
+    let edit = edits.entry(prop.id).or_insert_with(|| prop.value.clone());
+
+    match edit {
+        PropertyValue::Bool(b) => ui.add_enabled(enabled, egui::Checkbox::without_text(b)),
+        PropertyValue::Number(num) => ui.add_enabled(enabled, egui::DragValue::new(num)),
+        PropertyValue::Float(num) => ui.add_enabled(
+            enabled,
+            egui::DragValue::new(num).fixed_decimals(prop.decimals.into()),
+        ),
+        PropertyValue::String(s) => {
+            ui.add_enabled(enabled, egui::TextEdit::singleline(s).desired_width(120.0))
+        }
+        PropertyValue::Flags(flags) => {
+            ui.horizontal(|ui| {
+                for (label, set) in flags {
+                    ui.add_enabled(enabled, egui::Checkbox::new(set, label.as_str()));
+                }
+            })
+            .response
+        }
+        // Sensor readings and durations can't be changed
+        PropertyValue::Sensor(..) | PropertyValue::Duration(_) => return None,
+    };
+
+    ui.add_enabled(enabled, egui::Button::new("Set"))
+        .on_disabled_hover_text("Connected in read-only mode")
+        .clicked()
+        .then(|| edit.clone())
+}
+
+/// Serialize refresh intervals for storage, in milliseconds and priority order
+fn serialize_refresh_intervals(intervals: &[(PropertyKind, Duration); 5]) -> String {
+    intervals
+        .iter()
+        .map(|(_, interval)| interval.as_millis().to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Deserialize refresh intervals saved by [`serialize_refresh_intervals`],
+/// keeping the default for invalid values
+fn deserialize_refresh_intervals(src: &str) -> [(PropertyKind, Duration); 5] {
+    let mut intervals = DEFAULT_REFRESH_INTERVALS;
+
+    for ((_, interval), millis) in intervals.iter_mut().zip(src.split(',')) {
+        if let Some(millis) = millis
+            .trim()
+            .parse()
+            .ok()
+            .filter(|millis| REFRESH_INTERVAL_RANGE.contains(millis))
+        {
+            *interval = Duration::from_millis(millis);
+        }
     }
+
+    intervals
 }
 
 /// Format a property value for display
-fn format_value(value: &PropertyValue, unit: Option<&str>) -> String {
-    let val_str = match value {
+///
+/// Fractional values are shown with the given number of decimal places.
+/// Temperatures in degrees Celsius are converted to the given unit.
+fn format_value(
+    value: &PropertyValue,
+    unit: Option<&str>,
+    decimals: u8,
+    cumulative: bool,
+    temperature_unit: TemperatureUnit,
+) -> String {
+    match format_value_parts(value, unit, decimals, cumulative, temperature_unit) {
+        (val_str, Some(unit)) => format!("{val_str} {unit}"),
+        (val_str, None) => val_str,
+    }
+}
+
+/// Format a value for display like [`format_value`], returning the unit separately
+fn format_value_parts<'a>(
+    value: &PropertyValue,
+    unit: Option<&'a str>,
+    decimals: u8,
+    cumulative: bool,
+    temperature_unit: TemperatureUnit,
+) -> (String, Option<&'a str>) {
+    let (value, unit) = match (temperature_unit, unit) {
+        (TemperatureUnit::Fahrenheit, Some(TemperatureUnit::CELSIUS)) => (
+            TemperatureUnit::to_fahrenheit(value),
+            Some(TemperatureUnit::FAHRENHEIT),
+        ),
+        _ => (value.clone(), unit),
+    };
+    let val_str = match &value {
         PropertyValue::Bool(b) => {
             if *b {
                 "Yes".to_string()
@@ -530,6 +4882,7 @@ fn format_value(value: &PropertyValue, unit: Option<&str>) -> String {
             }
         }
         PropertyValue::Number(n) => n.to_string(),
+        PropertyValue::Float(n) => format!("{n:.prec$}", prec = usize::from(decimals)),
         PropertyValue::Sensor(current, target) => format!("{current} / {target}"),
         PropertyValue::String(s) => {
             if s.is_empty() {
@@ -538,28 +4891,354 @@ fn format_value(value: &PropertyValue, unit: Option<&str>) -> String {
                 s.clone()
             }
         }
-        PropertyValue::Duration(d) => {
-            let secs = d.as_secs();
-            let hours = secs / 3600;
-            let mins = (secs % 3600) / 60;
-            format!("{hours}h {mins}m")
+        PropertyValue::Duration(d) => format_duration(*d, cumulative),
+        PropertyValue::Flags(flags) => {
+            let set = set_flags(flags);
+
+            if set.is_empty() {
+                "-".to_string()
+            } else {
+                set
+            }
+        }
+    };
+
+    (val_str, unit)
+}
+
+/// Format rows of name, value and unit as a text table with aligned columns
+///
+/// The table is headed by the title and the time of the last update, if known.
+fn format_table(title: &str, updated: Option<jiff::Timestamp>, rows: &[[String; 3]]) -> String {
+    let header = ["Name", "Value", "Unit"].map(String::from);
+    let mut widths = header.clone().map(|col| col.chars().count());
+
+    for row in rows {
+        for (width, col) in widths.iter_mut().zip(row) {
+            *width = (*width).max(col.chars().count());
+        }
+    }
+
+    let mut table = match updated {
+        Some(time) => format!(
+            "{title} (updated {} UTC)\n",
+            time.strftime("%Y-%m-%d %H:%M:%S")
+        ),
+        None => format!("{title}\n"),
+    };
+    let separator = widths.map(|width| "-".repeat(width));
+
+    for row in std::iter::once(&header).chain([&separator]).chain(rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(col, width)| format!("{col:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+
+    table
+}
+
+/// Format a duration as days, hours and minutes
+///
+/// Cumulative counters such as the operating time are shown in total hours instead,
+/// which is how they are usually stated, e.g. in service documentation.
+fn format_duration(duration: Duration, cumulative: bool) -> String {
+    let secs = duration.as_secs();
+    let hours = secs / 3600;
+    let mins = (secs % 3600) / 60;
+
+    if cumulative {
+        format!("{hours} h")
+    } else if hours >= 24 {
+        format!("{}d {}h {mins}m", hours / 24, hours % 24)
+    } else {
+        format!("{hours}h {mins}m")
+    }
+}
+
+/// Settings for querying properties periodically
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AutoRefresh {
+    /// Query properties according to their refresh interval
+    enabled: bool,
+    /// Stop querying while the window is unfocused
+    pause_unfocused: bool,
+}
+
+impl AutoRefresh {
+    /// Load the persisted settings, enabled without pausing by default
+    fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        let get = |key| storage.and_then(|storage| storage.get_string(key));
+
+        Self {
+            enabled: get(AUTO_REFRESH_KEY).map_or(true, |src| src != "false"),
+            pause_unfocused: get(PAUSE_UNFOCUSED_KEY).is_some_and(|src| src == "true"),
+        }
+    }
+}
+
+/// Unit used to display temperatures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    /// Unit of temperature properties reported by devices
+    const CELSIUS: &'static str = "°C";
+    const FAHRENHEIT: &'static str = "°F";
+
+    fn serialize(self) -> &'static str {
+        match self {
+            Self::Celsius => "celsius",
+            Self::Fahrenheit => "fahrenheit",
+        }
+    }
+
+    /// Deserialize a unit saved by [`TemperatureUnit::serialize`], defaulting to Celsius
+    fn deserialize(src: &str) -> Self {
+        match src {
+            "fahrenheit" => Self::Fahrenheit,
+            _ => Self::Celsius,
+        }
+    }
+
+    /// Convert a temperature in degrees Celsius to degrees Fahrenheit
+    ///
+    /// Integer values are rounded to the nearest degree.
+    fn to_fahrenheit(value: &PropertyValue) -> PropertyValue {
+        let convert = |celsius: u32| (celsius * 9 + 2) / 5 + 32;
+
+        match value {
+            PropertyValue::Number(n) => PropertyValue::Number(convert(*n)),
+            PropertyValue::Float(n) => PropertyValue::Float(n * 1.8 + 32.0),
+            PropertyValue::Sensor(current, target) => {
+                PropertyValue::Sensor(convert(*current), convert(*target))
+            }
+            value => value.clone(),
+        }
+    }
+}
+
+/// Show the bits of a bitfield as a row of labeled indicators
+fn render_flags(ui: &mut Ui, flags: &[(String, bool)]) -> egui::Response {
+    ui.horizontal(|ui| {
+        for (label, set) in flags {
+            if *set {
+                ui.colored_label(Color32::from_rgb(76, 175, 80), "●");
+                ui.label(label);
+            } else {
+                ui.weak("○");
+                ui.weak(label);
+            }
         }
+    })
+    .response
+}
+
+/// Show the uptime and transfers of the connection, highlighted if there were link errors
+fn render_link_stats(ui: &mut Ui, stats: &TransportStats, uptime: Duration) {
+    let errors = stats.checksum_errors + stats.timeouts;
+    let text = format!(
+        "Up {} \u{b7} {} frames \u{b7} {errors} errors",
+        format_uptime(uptime),
+        stats.frames_ok
+    );
+    let label = if errors == 0 {
+        ui.weak(text)
+    } else {
+        ui.colored_label(Color32::from_rgb(255, 152, 0), text)
+    };
+
+    label.on_hover_text(format!(
+        "{} chunks transferred successfully, {} checksum errors and {} timeouts since connecting",
+        stats.frames_ok, stats.checksum_errors, stats.timeouts
+    ));
+}
+
+/// Format the time since connecting as hours, minutes and seconds, e.g. "00:12:43"
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+/// Returns whether the port name refers to a local serial port,
+/// rather than a bridge, the demo device or a recording
+fn is_serial_port(port_name: &str) -> bool {
+    port_name != DEMO_PORT
+        && !port_name.starts_with(TCP_PREFIX)
+        && !port_name.starts_with(REPLAY_PREFIX)
+}
+
+/// Show the baud rate of the port with a dropdown for switching it
+///
+/// Returns the newly selected baud rate, if any.
+fn render_baud_rate_selector(ui: &mut Ui, current: u32, enabled: bool) -> Option<u32> {
+    let mut selected = current;
+
+    ui.add_enabled_ui(enabled, |ui| {
+        egui::ComboBox::from_id_salt("baud_rate")
+            .selected_text(format!("{current} baud"))
+            .show_ui(ui, |ui| {
+                for baud_rate in freemdu::serial::BAUD_RATES {
+                    ui.selectable_value(&mut selected, baud_rate, format!("{baud_rate} baud"));
+                }
+            })
+            .response
+            .on_hover_text(
+                "Baud rate of the port, changed without disconnecting. \
+                 The device has to use the same baud rate.",
+            );
+    });
+
+    (selected != current).then_some(selected)
+}
+
+/// Show the average round-trip time, highlighted if the last one was much slower
+fn render_latency(ui: &mut Ui, avg_ms: f64, last_ms: f64) {
+    let text = format!("{avg_ms:.0} ms");
+    let label = if last_ms > avg_ms * LATENCY_JUMP_FACTOR {
+        ui.colored_label(Color32::from_rgb(255, 152, 0), text)
+    } else {
+        ui.weak(text)
     };
 
-    if let Some(unit) = unit {
-        format!("{val_str} {unit}")
+    label.on_hover_text(format!(
+        "Average round-trip time of recent property queries, last {last_ms:.0} ms"
+    ));
+}
+
+/// Returns whether the property name contains the lowercase filter text
+fn matches_filter(name: &str, filter: &str) -> bool {
+    name.to_lowercase().contains(filter)
+}
+
+/// Returns the ports that were added and removed between two port lists
+fn diff_ports<'a>(previous: &'a [String], current: &'a [String]) -> (Vec<&'a str>, Vec<&'a str>) {
+    let added = current
+        .iter()
+        .filter(|port| !previous.contains(port))
+        .map(String::as_str)
+        .collect();
+    let removed = previous
+        .iter()
+        .filter(|port| !current.contains(port))
+        .map(String::as_str)
+        .collect();
+
+    (added, removed)
+}
+
+/// Toggle for drawing every sample of graphs, shared by all graphs
+fn resolution_toggle(ui: &mut Ui, resolution: &mut Resolution) {
+    let mut raw = *resolution == Resolution::Raw;
+
+    ui.toggle_value(&mut raw, "Raw").on_hover_text(
+        "Draw every sample instead of reducing long histories to the width of the graph.\n\
+         CSV exports always contain every sample.",
+    );
+
+    *resolution = if raw {
+        Resolution::Raw
     } else {
-        val_str
+        Resolution::Downsampled
+    };
+}
+
+/// List available serial ports, followed by the given network ports and the demo device
+/// List the serial ports followed by the given network ports and the demo device
+///
+/// Also returns the descriptions of the USB adapters behind serial ports, by port name.
+fn list_ports(network_ports: &[String]) -> (Vec<String>, HashMap<String, String>) {
+    let serial_ports = dedupe_ports(
+        serialport::available_ports().unwrap_or_default(),
+        canonical_port,
+    );
+    let labels = serial_ports
+        .iter()
+        .filter_map(|(name, label)| Some((name.clone(), label.clone()?)))
+        .collect();
+    let names = serial_ports
+        .into_iter()
+        .map(|(name, _)| name)
+        .chain(network_ports.iter().cloned())
+        .chain([DEMO_PORT.to_string()])
+        .collect();
+
+    (names, labels)
+}
+
+/// Merge serial ports that refer to the same device, along with the description of their adapter
+///
+/// On Linux, a device can be listed both as its device node and a `/dev/serial/by-id` symlink.
+/// Ports are listed by their canonical name, in the order they were first found.
+fn dedupe_ports(
+    ports: Vec<serialport::SerialPortInfo>,
+    canonicalize: impl Fn(&str) -> String,
+) -> Vec<(String, Option<String>)> {
+    let mut merged: Vec<(String, Option<String>)> = Vec::new();
+
+    for port in ports {
+        let name = canonicalize(&port.port_name);
+        let label = adapter_label(&port.port_type);
+
+        if let Some((_, existing)) = merged.iter_mut().find(|(other, _)| *other == name) {
+            *existing = existing.take().or(label);
+        } else {
+            merged.push((name, label));
+        }
     }
+
+    merged
+}
+
+/// Resolve symlinks in the path of a serial port
+///
+/// Names that aren't paths of existing files, e.g. on Windows, are returned unchanged.
+pub fn canonical_port(name: &str) -> String {
+    std::fs::canonicalize(name)
+        .ok()
+        .and_then(|path| path.to_str().map(str::to_string))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Returns the index of a port, which may also be given by a symlink to it
+fn find_port(ports: &[String], name: &str) -> Option<usize> {
+    let canonical = canonical_port(name);
+
+    ports
+        .iter()
+        .position(|port| *port == name || *port == canonical)
 }
 
-/// List available serial ports
-fn list_serial_ports() -> Vec<String> {
-    serialport::available_ports()
-        .unwrap_or_default()
-        .into_iter()
-        .map(|p| p.port_name)
-        .collect()
+/// Describe a USB adapter by its product name and serial number
+fn adapter_label(port_type: &serialport::SerialPortType) -> Option<String> {
+    let serialport::SerialPortType::UsbPort(usb) = port_type else {
+        return None;
+    };
+    let product = usb
+        .product
+        .clone()
+        .or_else(|| usb.manufacturer.clone())
+        .unwrap_or_else(|| format!("USB {:04x}:{:04x}", usb.vid, usb.pid));
+
+    Some(match &usb.serial_number {
+        Some(serial) => format!("{product} ({serial})"),
+        None => product,
+    })
 }
 
 /// Action information (cloneable version for UI)
@@ -573,7 +5252,64 @@ pub struct ActionInfo {
 #[derive(Clone, Debug)]
 pub enum ActionParamsInfo {
     Enumeration(Vec<String>),
-    Flags(Vec<String>),
+    /// Bit value and name of each flag
+    Flags(Vec<(u32, String)>),
+    Number {
+        min: u32,
+        max: u32,
+        step: u32,
+    },
+    /// Any text, passed on unchanged
+    FreeText,
+}
+
+impl ActionParamsInfo {
+    /// Returns whether the input is acceptable, i.e. a number within range if one is expected
+    /// or a bitmask if flags are expected
+    pub fn is_valid(&self, input: &str) -> bool {
+        match *self {
+            ActionParamsInfo::Number { min, max, .. } => input
+                .trim()
+                .parse()
+                .is_ok_and(|num: u32| (min..=max).contains(&num)),
+            ActionParamsInfo::Flags(_) => input.trim().parse::<u32>().is_ok(),
+            ActionParamsInfo::Enumeration(_) | ActionParamsInfo::FreeText => true,
+        }
+    }
+
+    /// Returns the first part of the input that isn't one of the declared options or flags
+    ///
+    /// Guards against inputs that the UI didn't offer, e.g. restored from stale state.
+    pub fn unknown_option<'a>(&self, input: &'a str) -> Option<&'a str> {
+        match self {
+            ActionParamsInfo::Enumeration(options) => {
+                (!options.iter().any(|opt| opt == input)).then_some(input)
+            }
+            ActionParamsInfo::Flags(flags) => {
+                let mask: u32 = input.trim().parse().unwrap_or_default();
+                let unknown = flags.iter().fold(mask, |rest, (bit, _)| rest & !bit);
+
+                (unknown != 0).then_some(input)
+            }
+            ActionParamsInfo::Number { .. } | ActionParamsInfo::FreeText => None,
+        }
+    }
+
+    /// Describe the input for the action log, i.e. the names of the selected flags
+    pub fn describe(&self, input: &str) -> String {
+        match self {
+            ActionParamsInfo::Flags(flags) => match input.trim().parse::<u32>() {
+                Ok(mask) => flags
+                    .iter()
+                    .filter(|(bit, _)| mask & bit != 0)
+                    .map(|(_, name)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                Err(_) => input.to_string(),
+            },
+            _ => input.to_string(),
+        }
+    }
 }
 
 impl ActionInfo {
@@ -582,9 +5318,16 @@ impl ActionInfo {
             ActionParameters::Enumeration(opts) => {
                 ActionParamsInfo::Enumeration(opts.iter().map(|s| (*s).to_string()).collect())
             }
-            ActionParameters::Flags(flags) => {
-                ActionParamsInfo::Flags(flags.iter().map(|s| (*s).to_string()).collect())
+            ActionParameters::Flags(flags) => ActionParamsInfo::Flags(
+                flags
+                    .iter()
+                    .map(|&(bit, name)| (bit, name.to_string()))
+                    .collect(),
+            ),
+            &ActionParameters::Number { min, max, step } => {
+                ActionParamsInfo::Number { min, max, step }
             }
+            ActionParameters::FreeText => ActionParamsInfo::FreeText,
         });
 
         ActionInfo {
@@ -594,3 +5337,910 @@ impl ActionInfo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn property_sorting() {
+        let data: Vec<_> = [
+            ("Spin Speed", PropertyValue::Number(900)),
+            ("program", PropertyValue::String("Cotton".to_string())),
+            ("Water Level", PropertyValue::Float(10.5)),
+            ("Temperature", PropertyValue::Sensor(40, 60)),
+        ]
+        .into_iter()
+        .map(|(name, value)| PropertyData {
+            name: name.to_string(),
+            ..PropertyData::stub("test", value)
+        })
+        .collect();
+        let sorted = |column, descending| {
+            let mut props: Vec<_> = data.iter().collect();
+
+            PropertySort { column, descending }.apply(&mut props);
+            props
+                .iter()
+                .map(|prop| prop.name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sorted(SortColumn::Name, false),
+            ["program", "Spin Speed", "Temperature", "Water Level"],
+            "names should be sorted ignoring case"
+        );
+        assert_eq!(
+            sorted(SortColumn::Value, false),
+            ["Water Level", "Temperature", "Spin Speed", "program"],
+            "numbers should be sorted numerically and before text"
+        );
+        assert_eq!(
+            sorted(SortColumn::Value, true),
+            ["program", "Spin Speed", "Temperature", "Water Level"],
+            "descending order should be reversed"
+        );
+
+        let asc = PropertySort::toggle(None, SortColumn::Value);
+        let desc = PropertySort::toggle(asc, SortColumn::Value);
+
+        assert_eq!(
+            asc,
+            Some(PropertySort {
+                column: SortColumn::Value,
+                descending: false
+            }),
+            "first click should sort ascending"
+        );
+        assert!(
+            desc.is_some_and(|sort| sort.descending),
+            "second click should sort descending"
+        );
+        assert_eq!(
+            PropertySort::toggle(desc, SortColumn::Value),
+            None,
+            "third click should restore the definition order"
+        );
+        assert_eq!(
+            PropertySort::toggle(desc, SortColumn::Name).map(|sort| sort.column),
+            Some(SortColumn::Name),
+            "other column should be sorted"
+        );
+    }
+
+    #[test]
+    fn snapshot_json_round_trip() {
+        let mut storage = PropertyStorage::default();
+
+        for (name, value) in [
+            ("Temperature", PropertyValue::Sensor(42, 60)),
+            (
+                "Operating Time",
+                PropertyValue::Duration(Duration::from_secs(3725)),
+            ),
+        ] {
+            storage.general.0.push(PropertyData {
+                name: name.to_string(),
+                ..PropertyData::stub("test", value)
+            });
+        }
+
+        let info = DeviceInfo::stub(629);
+        let json: serde_json::Value =
+            serde_json::from_str(&storage.to_json(&info).expect("snapshot should serialize"))
+                .expect("snapshot should be valid JSON");
+        let props = &json["sections"][0]["properties"];
+        let value = |idx: usize| {
+            serde_json::from_value::<PropertyValue>(props[idx]["value"].clone())
+                .expect("value should deserialize")
+        };
+
+        assert_eq!(json["software_id"], 629, "software ID should be correct");
+        assert_eq!(
+            value(0),
+            PropertyValue::Sensor(42, 60),
+            "sensor value should round-trip"
+        );
+        assert_eq!(
+            value(1),
+            PropertyValue::Duration(Duration::from_secs(3725)),
+            "duration should round-trip"
+        );
+    }
+
+    #[test]
+    fn action_log_bounded() {
+        let mut session = Session::new();
+        let outcome = |success| ActionOutcome {
+            id: "set_program".to_string(),
+            name: "Set Program".to_string(),
+            param: Some("Cottons".to_string()),
+            success,
+            message: if success { "Success" } else { "Timeout" }.to_string(),
+            value: None,
+        };
+
+        session.handle_action_result(outcome(false), TemperatureUnit::Celsius);
+
+        let entry = &session.action_log[0];
+
+        assert_eq!(entry.name, "Set Program", "name should be correct");
+        assert_eq!(
+            entry.param.as_deref(),
+            Some("Cottons"),
+            "argument should be correct"
+        );
+        assert!(!entry.success, "entry should be failed");
+        assert_eq!(entry.result, "Timeout", "failure reason should be correct");
+
+        for _ in 0..ACTION_LOG_LIMIT {
+            session.handle_action_result(outcome(true), TemperatureUnit::Celsius);
+        }
+
+        assert_eq!(
+            session.action_log.len(),
+            ACTION_LOG_LIMIT,
+            "log should be bounded"
+        );
+        assert!(
+            session.action_log.iter().all(|entry| entry.success),
+            "oldest entry should be dropped"
+        );
+    }
+
+    #[test]
+    fn layout_profiles() {
+        let mut profiles = LayoutProfiles::default();
+
+        profiles.layout_mut().filter = "temp".to_string();
+        profiles.save_as("Service");
+        profiles.layout_mut().collapsed.push(PropertyKind::Io);
+
+        assert_eq!(profiles.name(), "Service", "saved profile should be in use");
+        assert_eq!(
+            profiles.profiles[0].1.collapsed,
+            [],
+            "changes should only apply to the profile in use"
+        );
+
+        profiles.save_as(DEFAULT_PROFILE);
+
+        assert_eq!(profiles.profiles.len(), 2, "profile should be replaced");
+        assert_eq!(
+            profiles.profiles[0].1.collapsed,
+            [PropertyKind::Io],
+            "replaced profile should be correct"
+        );
+
+        let json = serde_json::to_string(&profiles).expect("profiles should be serializable");
+
+        assert_eq!(
+            serde_json::from_str::<LayoutProfiles>(&json).ok(),
+            Some(profiles.clone()),
+            "deserialized profiles should be correct"
+        );
+
+        profiles.remove_active();
+        profiles.remove_active();
+
+        assert_eq!(profiles.name(), "Service", "last profile should be kept");
+    }
+
+    #[test]
+    fn refresh_all_coalesced() {
+        let mut session = Session::new();
+        let requested = Instant::now()
+            .checked_sub(REFRESH_ALL_DEBOUNCE)
+            .expect("instant should be representable");
+
+        session.properties.set(PropertyKind::General, Vec::new());
+        session.refresh_all();
+        session.start_full_refresh();
+
+        assert_eq!(
+            session.full_refresh_progress(),
+            Some(0),
+            "refresh should be pending"
+        );
+        assert!(
+            session.properties.general.1.is_some(),
+            "refresh should wait for further requests"
+        );
+
+        session.full_refresh = Some(FullRefresh::Pending(requested));
+        session.start_full_refresh();
+        session.refresh_all();
+
+        assert_eq!(
+            session.full_refresh,
+            Some(FullRefresh::Running(Vec::new())),
+            "request during refresh should be merged"
+        );
+        assert!(
+            session.properties.general.1.is_none(),
+            "all kinds should be queried again"
+        );
+
+        for kind in PROPERTY_KINDS {
+            session.full_refresh_received(kind);
+            session.full_refresh_received(kind);
+        }
+
+        assert_eq!(
+            session.full_refresh_progress(),
+            None,
+            "refresh should complete once all kinds were received"
+        );
+    }
+
+    #[test]
+    fn warm_up_tracked() {
+        let mut session = Session::new();
+
+        session.full_refresh = Some(FullRefresh::WarmUp(Vec::new()));
+        session.refresh_all();
+
+        assert_eq!(
+            session.full_refresh_progress(),
+            Some(0),
+            "request during warm-up should be merged"
+        );
+
+        for kind in PROPERTY_KINDS {
+            session.full_refresh_received(kind);
+        }
+
+        assert_eq!(
+            session.full_refresh_progress(),
+            None,
+            "warm-up should complete once all kinds were received"
+        );
+    }
+
+    #[test]
+    fn session_title_follows_connection() {
+        let mut session = Session::new();
+
+        assert_eq!(
+            session.title(),
+            "New connection",
+            "title of unused session should be correct"
+        );
+
+        session.last_port = Some("/dev/ttyUSB1".to_string());
+        assert_eq!(
+            session.title(),
+            "/dev/ttyUSB1",
+            "title of disconnected session should be correct"
+        );
+
+        session.connection_state = ConnectionState::Connected(DeviceInfo::stub(629));
+        assert_eq!(
+            session.title(),
+            "Washing Machine (/dev/ttyUSB1)",
+            "title of connected session should be correct"
+        );
+    }
+
+    #[test]
+    fn session_saved_and_opened() {
+        let path =
+            std::env::temp_dir().join(format!("freemdu-session-{}.json", std::process::id()));
+        let mut session = Session::new();
+
+        session.connection_state = ConnectionState::Connected(DeviceInfo::stub(629));
+        session.properties.set(
+            PropertyKind::General,
+            vec![PropertyData {
+                name: "Operating Time".to_string(),
+                cumulative: true,
+                ..PropertyData::stub(
+                    "operating_time",
+                    PropertyValue::Duration(Duration::from_secs(7200)),
+                )
+            }],
+        );
+        session.handle_action_result(
+            ActionOutcome {
+                id: "start_program".to_string(),
+                name: "Start Program".to_string(),
+                param: None,
+                success: true,
+                message: "Success".to_string(),
+                value: None,
+            },
+            TemperatureUnit::Celsius,
+        );
+        session.save_session(&path);
+
+        let mut viewer = Session::new();
+
+        viewer.open_session(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(
+            matches!(
+                viewer.connection_state,
+                ConnectionState::Viewing(DeviceInfo {
+                    software_id: 629,
+                    read_only: true,
+                    ..
+                })
+            ),
+            "saved session should be viewed read-only"
+        );
+        assert_eq!(
+            viewer
+                .properties
+                .find("operating_time")
+                .map(|prop| &prop.value),
+            Some(&PropertyValue::Duration(Duration::from_secs(7200))),
+            "property should be restored"
+        );
+        assert!(
+            viewer.properties.of_kind(PropertyKind::Io).1.is_some(),
+            "sections not queried yet should not be loading"
+        );
+        assert_eq!(viewer.action_log.len(), 1, "action log should be restored");
+        assert!(!viewer.can_refresh(), "viewer should not refresh");
+    }
+
+    #[test]
+    fn device_description() {
+        let mut info = DeviceInfo::stub(629);
+
+        assert_eq!(
+            describe_device(&info),
+            "Washing Machine (W 2xxx series, EDPL 126-B board, ID: 629)",
+            "description of known software should be correct"
+        );
+
+        info.software_id = 1234;
+        assert_eq!(
+            describe_device(&info),
+            "Washing Machine (ID: 1234)",
+            "description of unknown software should be correct"
+        );
+    }
+
+    #[test]
+    fn network_port_address() {
+        assert_eq!(
+            parse_network_port("tcp://192.168.1.50:23"),
+            Some("192.168.1.50:23"),
+            "address with prefix should be correct"
+        );
+        assert_eq!(
+            parse_network_port(" bridge.local:2323 "),
+            Some("bridge.local:2323"),
+            "address without prefix should be correct"
+        );
+        assert_eq!(
+            parse_network_port("[::1]:23"),
+            Some("[::1]:23"),
+            "IPv6 address should be correct"
+        );
+
+        for src in [
+            "",
+            "tcp://",
+            "192.168.1.50",
+            ":23",
+            "host:port",
+            "host:65536",
+            "a b:23",
+        ] {
+            assert_eq!(parse_network_port(src), None, "{src:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn hex_bytes() {
+        assert_eq!(
+            parse_hex("11 00 00 02"),
+            Some(vec![0x11, 0x00, 0x00, 0x02]),
+            "separated bytes should be parsed"
+        );
+        assert_eq!(
+            parse_hex("0x30cdAB 01"),
+            Some(vec![0x30, 0xcd, 0xab, 0x01]),
+            "contiguous bytes should be parsed"
+        );
+
+        for src in ["", "1", "1 2", "zz", "é0"] {
+            assert_eq!(parse_hex(src), None, "{src:?} should be rejected");
+        }
+
+        assert_eq!(
+            format_hex(&[0x75, 0x02]),
+            "75 02",
+            "bytes should be formatted"
+        );
+    }
+
+    #[test]
+    fn port_changes() {
+        let previous = ["/dev/ttyUSB0".to_string(), DEMO_PORT.to_string()];
+        let current = ["/dev/ttyUSB1".to_string(), DEMO_PORT.to_string()];
+        let (added, removed) = diff_ports(&previous, &current);
+
+        assert_eq!(added, ["/dev/ttyUSB1"], "added ports should be correct");
+        assert_eq!(removed, ["/dev/ttyUSB0"], "removed ports should be correct");
+    }
+
+    #[test]
+    fn symlinked_ports_merged() {
+        use serialport::{SerialPortInfo, SerialPortType, UsbPortInfo};
+
+        let usb = SerialPortType::UsbPort(UsbPortInfo {
+            vid: 0x0403,
+            pid: 0x6001,
+            serial_number: Some("A50285BI".to_string()),
+            manufacturer: Some("FTDI".to_string()),
+            product: Some("FT232R USB UART".to_string()),
+        });
+        let ports = vec![
+            SerialPortInfo {
+                port_name: "/dev/ttyUSB0".to_string(),
+                port_type: SerialPortType::Unknown,
+            },
+            SerialPortInfo {
+                port_name: "/dev/serial/by-id/usb-FTDI_FT232R_USB_UART_A50285BI-if00-port0"
+                    .to_string(),
+                port_type: usb,
+            },
+            SerialPortInfo {
+                port_name: "/dev/ttyS0".to_string(),
+                port_type: SerialPortType::Unknown,
+            },
+        ];
+        let canonicalize = |name: &str| {
+            if name.starts_with("/dev/serial/by-id/") {
+                "/dev/ttyUSB0".to_string()
+            } else {
+                name.to_string()
+            }
+        };
+
+        assert_eq!(
+            dedupe_ports(ports, canonicalize),
+            [
+                (
+                    "/dev/ttyUSB0".to_string(),
+                    Some("FT232R USB UART (A50285BI)".to_string())
+                ),
+                ("/dev/ttyS0".to_string(), None),
+            ],
+            "ports should be merged by their canonical name"
+        );
+    }
+
+    #[test]
+    fn filter_ignores_case() {
+        assert!(
+            matches_filter("Flow Temperature", "flow temp"),
+            "filter should ignore case"
+        );
+        assert!(
+            matches_filter("Flow Temperature", ""),
+            "empty filter should match everything"
+        );
+        assert!(
+            !matches_filter("Water Level", "temp"),
+            "filter should not match other names"
+        );
+    }
+
+    #[test]
+    fn property_storage_routes_kinds() {
+        let kinds = [
+            PropertyKind::General,
+            PropertyKind::Failure,
+            PropertyKind::Warning,
+            PropertyKind::Operation,
+            PropertyKind::Io,
+        ];
+        let mut storage = PropertyStorage::default();
+
+        for (i, kind) in kinds.into_iter().enumerate() {
+            storage.of_kind_mut(kind).0.push(PropertyData {
+                name: format!("{kind:?}"),
+                ..PropertyData::stub("test", PropertyValue::Number(i as u32))
+            });
+        }
+
+        for kind in kinds {
+            let props = &storage.of_kind(kind).0;
+
+            assert_eq!(props.len(), 1, "each kind should have its own storage");
+            assert_eq!(
+                props[0].name,
+                format!("{kind:?}"),
+                "kind should be routed correctly"
+            );
+        }
+
+        storage.clear();
+
+        assert!(
+            kinds
+                .into_iter()
+                .all(|kind| storage.of_kind(kind).0.is_empty()),
+            "storage should be empty after clearing"
+        );
+    }
+
+    #[test]
+    fn property_storage_poll_state() {
+        let mut storage = PropertyStorage::default();
+
+        assert_eq!(
+            storage.poll_state(PropertyKind::Warning),
+            PollState::NeverPolled,
+            "kind should not be polled yet"
+        );
+
+        storage.set(PropertyKind::Warning, Vec::new());
+
+        assert_eq!(
+            storage.poll_state(PropertyKind::Warning),
+            PollState::Empty,
+            "empty result should be distinguished"
+        );
+        assert_eq!(
+            storage.poll_state(PropertyKind::Io),
+            PollState::NeverPolled,
+            "other kinds should not be affected"
+        );
+    }
+
+    #[test]
+    fn property_storage_finds_by_id() {
+        let prop = |id, num| PropertyData::stub(id, PropertyValue::Number(num));
+        let mut storage = PropertyStorage::default();
+
+        storage.set(PropertyKind::General, vec![prop("a", 1), prop("b", 2)]);
+        storage.set(PropertyKind::Io, vec![prop("c", 3)]);
+
+        assert_eq!(
+            storage.find_mut(PropertyId("b")).map(|p| p.name.clone()),
+            Some("b".to_string()),
+            "property should be found by ID"
+        );
+
+        // Replacing a kind drops IDs that are no longer present
+        storage.set(PropertyKind::General, vec![prop("b", 4)]);
+
+        assert!(
+            storage.find_mut(PropertyId("a")).is_none(),
+            "stale property should not be found"
+        );
+        assert!(
+            matches!(
+                storage.find_mut(PropertyId("b")).map(|p| &p.value),
+                Some(PropertyValue::Number(4))
+            ),
+            "property should point to new data"
+        );
+        assert!(
+            storage.find_mut(PropertyId("c")).is_some(),
+            "other kinds should be unaffected"
+        );
+    }
+
+    #[test]
+    fn property_storage_tracks_changes() {
+        let prop = |num| PropertyData::stub("a", PropertyValue::Number(num));
+        let mut storage = PropertyStorage::default();
+
+        storage.set(PropertyKind::Io, vec![prop(1)]);
+        std::thread::sleep(Duration::from_millis(20));
+        storage.update(prop(1));
+
+        assert!(
+            storage
+                .unchanged_for(PropertyId("a"), Duration::from_millis(10))
+                .is_some(),
+            "repeated reading should be stale"
+        );
+
+        storage.update(prop(2));
+
+        assert!(
+            storage
+                .unchanged_for(PropertyId("a"), Duration::from_millis(10))
+                .is_none(),
+            "changed reading should not be stale"
+        );
+        assert!(
+            storage
+                .unchanged_for(PropertyId("a"), Duration::ZERO)
+                .is_some(),
+            "window should be inclusive"
+        );
+    }
+
+    #[test]
+    fn format_value_precision() {
+        assert_eq!(
+            format_value(
+                &PropertyValue::Float(42.46),
+                Some("°C"),
+                1,
+                false,
+                TemperatureUnit::Celsius
+            ),
+            "42.5 °C",
+            "fractional value should be rounded to the declared precision"
+        );
+        assert_eq!(
+            format_value(
+                &PropertyValue::Number(42),
+                Some("°C"),
+                0,
+                false,
+                TemperatureUnit::Celsius
+            ),
+            "42 °C",
+            "integer value should be correct"
+        );
+    }
+
+    #[test]
+    fn section_table_aligned() {
+        let rows = [
+            [
+                "Serial Number".to_string(),
+                "9314023903".to_string(),
+                String::new(),
+            ],
+            [
+                "Operating Time".to_string(),
+                "1234 h".to_string(),
+                String::new(),
+            ],
+            [
+                "Temperature".to_string(),
+                "58".to_string(),
+                "°C".to_string(),
+            ],
+        ];
+        let updated = jiff::Timestamp::from_second(1_700_000_000).ok();
+
+        assert_eq!(
+            format_table("General Information", updated, &rows),
+            "General Information (updated 2023-11-14 22:13:20 UTC)\n\
+             Name            Value       Unit\n\
+             --------------  ----------  ----\n\
+             Serial Number   9314023903\n\
+             Operating Time  1234 h\n\
+             Temperature     58          °C\n",
+            "table should be correct"
+        );
+    }
+
+    #[test]
+    fn duration_formatting() {
+        let hours = |h: u64| Duration::from_secs(h * 3600);
+
+        assert_eq!(
+            format_duration(Duration::ZERO, false),
+            "0h 0m",
+            "zero duration should be correct"
+        );
+        assert_eq!(
+            format_duration(Duration::from_secs(24 * 3600 - 60), false),
+            "23h 59m",
+            "duration below a day should be correct"
+        );
+        assert_eq!(
+            format_duration(hours(24), false),
+            "1d 0h 0m",
+            "duration of a day should be correct"
+        );
+        assert_eq!(
+            format_duration(hours(5123) + Duration::from_secs(1800), false),
+            "213d 11h 30m",
+            "long duration should include days"
+        );
+        assert_eq!(
+            format_duration(Duration::ZERO, true),
+            "0 h",
+            "zero counter should be correct"
+        );
+        assert_eq!(
+            format_duration(hours(24), true),
+            "24 h",
+            "counter of a day should be correct"
+        );
+        assert_eq!(
+            format_duration(hours(5123) + Duration::from_secs(1800), true),
+            "5123 h",
+            "counter should be shown in total hours"
+        );
+    }
+
+    #[test]
+    fn uptime_formatting() {
+        assert_eq!(
+            format_uptime(Duration::from_secs(12 * 60 + 43)),
+            "00:12:43",
+            "uptime should be correct"
+        );
+        assert_eq!(
+            format_uptime(Duration::from_secs(100 * 3600 + 5)),
+            "100:00:05",
+            "uptime above a day should be shown in hours"
+        );
+    }
+
+    #[test]
+    fn format_value_fahrenheit() {
+        assert_eq!(
+            format_value(
+                &PropertyValue::Sensor(42, 60),
+                Some("°C"),
+                0,
+                false,
+                TemperatureUnit::Fahrenheit
+            ),
+            "108 / 140 °F",
+            "both sensor values should be converted"
+        );
+        assert_eq!(
+            format_value(
+                &PropertyValue::Float(37.5),
+                Some("°C"),
+                1,
+                false,
+                TemperatureUnit::Fahrenheit
+            ),
+            "99.5 °F",
+            "fractional value should be converted"
+        );
+        assert_eq!(
+            format_value(
+                &PropertyValue::Number(42),
+                Some("rpm"),
+                0,
+                false,
+                TemperatureUnit::Fahrenheit
+            ),
+            "42 rpm",
+            "other units should be unchanged"
+        );
+    }
+
+    #[test]
+    fn refresh_intervals_round_trip() {
+        let mut intervals = DEFAULT_REFRESH_INTERVALS;
+
+        intervals[0].1 = Duration::from_secs(2);
+
+        let restored = deserialize_refresh_intervals(&serialize_refresh_intervals(&intervals));
+
+        assert_eq!(restored, intervals, "intervals should be restored");
+        assert_eq!(
+            deserialize_refresh_intervals("50,abc"),
+            DEFAULT_REFRESH_INTERVALS,
+            "invalid intervals should fall back to defaults"
+        );
+    }
+
+    #[test]
+    fn theme_persisted() {
+        for theme in [
+            ThemePreference::Dark,
+            ThemePreference::Light,
+            ThemePreference::System,
+        ] {
+            assert_eq!(
+                deserialize_theme(serialize_theme(theme)),
+                theme,
+                "theme should be restored"
+            );
+        }
+
+        assert_eq!(
+            deserialize_theme("sepia"),
+            ThemePreference::System,
+            "unknown theme should follow the system"
+        );
+    }
+
+    #[test]
+    fn column_split_snapped() {
+        assert!(
+            (snap_column_split(0.62) - 0.6).abs() < f32::EPSILON,
+            "split should snap to step"
+        );
+        assert!(
+            (snap_column_split(0.0) - 0.2).abs() < f32::EPSILON,
+            "split should be limited to minimum"
+        );
+    }
+
+    #[test]
+    fn zoom_clamped() {
+        let mut factor = 1.0;
+
+        for _ in 0..3 {
+            factor = clamp_zoom(factor + ZOOM_STEP);
+        }
+
+        assert!(
+            (factor - 1.3).abs() < f32::EPSILON,
+            "zoom steps should not accumulate errors"
+        );
+        assert!(
+            (clamp_zoom(10.0) - 3.0).abs() < f32::EPSILON,
+            "zoom should be limited to maximum"
+        );
+        assert!(
+            (clamp_zoom(0.1) - 0.5).abs() < f32::EPSILON,
+            "zoom should be limited to minimum"
+        );
+    }
+
+    #[test]
+    fn number_param_range() {
+        let params = ActionParamsInfo::Number {
+            min: 1,
+            max: 60,
+            step: 1,
+        };
+
+        assert!(params.is_valid("30"), "number in range should be valid");
+        assert!(
+            !params.is_valid("0"),
+            "number below range should be invalid"
+        );
+        assert!(!params.is_valid(""), "empty input should be invalid");
+    }
+
+    #[test]
+    fn unknown_param_options() {
+        let spin = ActionParamsInfo::Enumeration(vec!["Rpm400".to_string(), "Rpm800".to_string()]);
+        let options = ActionParamsInfo::Flags(vec![
+            (0x10, "Soak".to_string()),
+            (0x20, "Prewash".to_string()),
+        ]);
+
+        assert_eq!(
+            spin.unknown_option("Rpm800"),
+            None,
+            "option should be accepted"
+        );
+        assert_eq!(
+            spin.unknown_option("Rpm1600"),
+            Some("Rpm1600"),
+            "unknown option should be rejected"
+        );
+        assert_eq!(
+            options.unknown_option("48"),
+            None,
+            "flags should be accepted"
+        );
+        assert_eq!(
+            options.unknown_option("0"),
+            None,
+            "no flags should be accepted"
+        );
+        assert_eq!(
+            options.unknown_option("80"),
+            Some("80"),
+            "unknown bit should be rejected"
+        );
+        assert_eq!(
+            options.describe("48"),
+            "Soak | Prewash",
+            "flags should be described by name"
+        );
+        assert_eq!(
+            ActionParamsInfo::FreeText.unknown_option("anything"),
+            None,
+            "free text should be accepted"
+        );
+    }
+}
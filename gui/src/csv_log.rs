@@ -0,0 +1,188 @@
+use crate::worker::{set_flags, PropertyData, PropertyValue};
+use freemdu::device::{Property, PropertyId};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Writes property samples to a CSV file, one column per property.
+///
+/// Each batch of queried properties becomes one row. Columns of
+/// properties that weren't part of the batch are left empty.
+pub struct CsvLogger<W: Write> {
+    writer: W,
+    /// Property IDs in column order
+    columns: Vec<PropertyId>,
+}
+
+impl CsvLogger<BufWriter<File>> {
+    /// Create the file at the given path, overwriting it if it exists
+    pub fn create(path: &Path, properties: &[Property]) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), properties)
+    }
+}
+
+impl<W: Write> CsvLogger<W> {
+    /// Write the header row listing all properties of the device
+    pub fn new(mut writer: W, properties: &[Property]) -> io::Result<Self> {
+        let mut header = vec!["Timestamp (UTC)".to_string()];
+
+        header.extend(properties.iter().map(|prop| match prop.unit {
+            Some(unit) => format!("{} ({unit})", prop.name),
+            None => prop.name.to_string(),
+        }));
+        write_row(&mut writer, &header)?;
+
+        Ok(Self {
+            writer,
+            columns: properties.iter().map(|prop| prop.id).collect(),
+        })
+    }
+
+    /// Append a row with the given samples
+    pub fn log(&mut self, timestamp: jiff::Timestamp, data: &[PropertyData]) -> io::Result<()> {
+        let mut row = vec![timestamp.strftime("%Y-%m-%d %H:%M:%S").to_string()];
+
+        row.extend(self.columns.iter().map(|id| {
+            data.iter()
+                .find(|prop| prop.id == *id)
+                .map(|prop| format_value(&prop.value, prop.decimals))
+                .unwrap_or_default()
+        }));
+
+        write_row(&mut self.writer, &row)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Format a value so that spreadsheet applications recognize it
+fn format_value(value: &PropertyValue, decimals: u8) -> String {
+    match value {
+        PropertyValue::Bool(b) => u8::from(*b).to_string(),
+        PropertyValue::Number(num) => num.to_string(),
+        PropertyValue::Float(num) => format!("{num:.prec$}", prec = usize::from(decimals)),
+        // Only the current reading is logged, targets rarely change
+        PropertyValue::Sensor(current, _) => current.to_string(),
+        PropertyValue::String(s) => s.clone(),
+        PropertyValue::Duration(dur) => {
+            let secs = dur.as_secs();
+
+            format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+        }
+        PropertyValue::Flags(flags) => set_flags(flags),
+    }
+}
+
+/// Write a row of fields, quoting them where necessary
+fn write_row(writer: &mut impl Write, fields: &[String]) -> io::Result<()> {
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+
+    writeln!(writer, "{}", fields.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freemdu::device::PropertyKind;
+    use std::time::Duration;
+
+    const PROPERTIES: [Property; 3] = [
+        Property {
+            kind: PropertyKind::General,
+            id: PropertyId("operating_time"),
+            name: "Operating Time",
+            unit: None,
+            writable: false,
+            decimals: 0,
+            decoding: None,
+            register: None,
+            cumulative: false,
+            range: None,
+            description: None,
+        },
+        Property {
+            kind: PropertyKind::Io,
+            id: PropertyId("temperature"),
+            name: "Temperature",
+            unit: Some("°C"),
+            writable: false,
+            decimals: 0,
+            decoding: None,
+            register: None,
+            cumulative: false,
+            range: None,
+            description: None,
+        },
+        Property {
+            kind: PropertyKind::Operation,
+            id: PropertyId("program_options"),
+            name: "Program Options",
+            unit: None,
+            writable: false,
+            decimals: 0,
+            decoding: None,
+            register: None,
+            cumulative: false,
+            range: None,
+            description: None,
+        },
+    ];
+
+    fn data(prop: &Property, value: PropertyValue) -> PropertyData {
+        PropertyData {
+            name: prop.name.to_string(),
+            unit: prop.unit.map(String::from),
+            decimals: prop.decimals,
+            ..PropertyData::stub(prop.id.0, value)
+        }
+    }
+
+    #[test]
+    fn rows_have_all_columns() {
+        let timestamp: jiff::Timestamp = "2025-01-02T03:04:05Z"
+            .parse()
+            .expect("timestamp should parse");
+        let mut logger = CsvLogger::new(Vec::new(), &PROPERTIES).expect("header should be written");
+
+        logger
+            .log(
+                timestamp,
+                &[
+                    data(
+                        &PROPERTIES[0],
+                        PropertyValue::Duration(Duration::from_secs(3725)),
+                    ),
+                    data(
+                        &PROPERTIES[2],
+                        PropertyValue::String("A, \"B\"".to_string()),
+                    ),
+                ],
+            )
+            .expect("row should be written");
+        logger
+            .log(
+                timestamp,
+                &[data(&PROPERTIES[1], PropertyValue::Sensor(42, 60))],
+            )
+            .expect("row should be written");
+
+        assert_eq!(
+            String::from_utf8(logger.writer).expect("output should be UTF-8"),
+            "Timestamp (UTC),Operating Time,Temperature (°C),Program Options\n\
+             2025-01-02 03:04:05,1:02:05,,\"A, \"\"B\"\"\"\n\
+             2025-01-02 03:04:05,,42,\n",
+            "CSV contents should be correct"
+        );
+    }
+}
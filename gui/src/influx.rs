@@ -0,0 +1,420 @@
+use crate::worker::{set_flags, DeviceInfo, PropertyData, PropertyValue};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Time allowed for connecting to the server and for its reply
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time lines are collected for before they are written, also the delay after a failed write
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Number of lines after which they are written without waiting for the interval
+const BATCH_SIZE: usize = 100;
+
+/// Lines kept while the server is unreachable, the oldest ones are dropped first
+const MAX_PENDING_LINES: usize = 10_000;
+
+/// Settings of the `InfluxDB` server
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InfluxConfig {
+    /// Write property values while the app is running
+    pub enabled: bool,
+    /// Write endpoint including the query, e.g. the database
+    pub url: String,
+    /// API token, or empty if authentication is disabled
+    pub token: String,
+}
+
+impl Default for InfluxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "http://localhost:8086/write?db=freemdu".to_string(),
+            token: String::new(),
+        }
+    }
+}
+
+/// Server address and request path parsed from the write URL
+#[derive(PartialEq, Eq, Debug)]
+struct Endpoint {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+/// Writer sending property values to `InfluxDB` using the line protocol.
+///
+/// Every batch of queried properties becomes one point of the measurement
+/// named after the device kind, tagged with the software ID. Sensor values
+/// are split into `<property ID>_current` and `<property ID>_target` fields.
+/// Points are written in batches by a background thread. They are kept
+/// while the server is unreachable, up to [`MAX_PENDING_LINES`].
+pub struct InfluxWriter {
+    config: InfluxConfig,
+    tx: Sender<String>,
+    /// Error of the last failed write, cleared once a write succeeds
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl InfluxWriter {
+    /// Start writing to the configured server, failing if the URL is invalid
+    pub fn start(config: InfluxConfig) -> Result<Self, String> {
+        let endpoint = parse_url(&config.url)?;
+        let (tx, rx) = mpsc::channel();
+        let error = Arc::new(Mutex::new(None));
+
+        // Not joined when dropped, as writing the remaining lines might block for a while
+        thread::spawn({
+            let token = config.token.clone();
+            let error = Arc::clone(&error);
+
+            move || run(&endpoint, &token, &rx, &error)
+        });
+
+        Ok(Self { config, tx, error })
+    }
+
+    pub fn config(&self) -> &InfluxConfig {
+        &self.config
+    }
+
+    /// Returns the error of the last write, if it failed
+    pub fn error(&self) -> Option<String> {
+        self.error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Queue a batch of freshly queried values for writing
+    pub fn write(&self, device: &DeviceInfo, data: &[PropertyData]) {
+        if let Some(line) = format_line(device, data, jiff::Timestamp::now()) {
+            let _ = self.tx.send(line);
+        }
+    }
+}
+
+/// Write queued lines in batches until the writer is dropped
+fn run(endpoint: &Endpoint, token: &str, rx: &Receiver<String>, error: &Mutex<Option<String>>) {
+    let mut pending = Vec::new();
+    let mut deadline = None;
+    let mut failing = false;
+
+    loop {
+        let timeout = deadline.map_or(FLUSH_INTERVAL, |deadline: Instant| {
+            deadline.saturating_duration_since(Instant::now())
+        });
+
+        match rx.recv_timeout(timeout) {
+            Ok(line) => {
+                pending.push(line);
+                deadline.get_or_insert_with(|| Instant::now() + FLUSH_INTERVAL);
+
+                // Failed writes are only retried after the interval
+                if pending.len() < BATCH_SIZE || failing {
+                    continue;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) if pending.is_empty() => {
+                deadline = None;
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                if !pending.is_empty() {
+                    let _ = post(endpoint, token, &pending.join("\n"));
+                }
+
+                return;
+            }
+        }
+
+        let res = post(endpoint, token, &pending.join("\n"));
+
+        failing = res.is_err();
+
+        if let Err(e) = &res {
+            log::warn!("Failed to write to InfluxDB: {e}");
+
+            let excess = pending.len().saturating_sub(MAX_PENDING_LINES);
+
+            pending.drain(..excess);
+        } else {
+            pending.clear();
+        }
+
+        deadline = (!pending.is_empty()).then(|| Instant::now() + FLUSH_INTERVAL);
+        *error.lock().unwrap_or_else(PoisonError::into_inner) = res.err().map(|e| e.to_string());
+    }
+}
+
+/// Send the lines to the server, succeeding only if it accepted them
+fn post(endpoint: &Endpoint, token: &str, body: &str) -> io::Result<()> {
+    let addr = (endpoint.host.as_str(), endpoint.port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "unknown host"))?;
+    let mut stream = TcpStream::connect_timeout(&addr, HTTP_TIMEOUT)?;
+    let auth = if token.is_empty() {
+        String::new()
+    } else {
+        format!("Authorization: Token {token}\r\n")
+    };
+
+    stream.set_read_timeout(Some(HTTP_TIMEOUT))?;
+    stream.set_write_timeout(Some(HTTP_TIMEOUT))?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\n\
+         Host: {}:{}\r\n\
+         Content-Type: text/plain; charset=utf-8\r\n\
+         Content-Length: {}\r\n\
+         {auth}\
+         Connection: close\r\n\r\n\
+         {body}",
+        endpoint.path,
+        endpoint.host,
+        endpoint.port,
+        body.len()
+    )?;
+
+    let mut status = String::new();
+
+    BufReader::new(stream).read_line(&mut status)?;
+
+    // Status line like "HTTP/1.1 204 No Content"
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        Some(_) => Err(io::Error::other(format!(
+            "server responded with {}",
+            status.trim_end()
+        ))),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid response",
+        )),
+    }
+}
+
+/// Parse a URL like `http://host:8086/write?db=freemdu`
+///
+/// Only plain HTTP is supported, the port defaults to 80.
+fn parse_url(url: &str) -> Result<Endpoint, String> {
+    let rest = url
+        .trim()
+        .strip_prefix("http://")
+        .ok_or("URL must start with http://")?;
+    let (authority, path) = rest
+        .find(['/', '?'])
+        .map_or((rest, "/"), |idx| rest.split_at(idx));
+    let (host, port) = match authority.rsplit_once(':') {
+        // Brackets of IPv6 addresses are not part of the host name
+        Some((host, port)) if !port.contains(']') => (
+            host,
+            port.parse().map_err(|_| format!("Invalid port {port}"))?,
+        ),
+        _ => (authority, 80),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    if host.is_empty() {
+        return Err("URL must contain a host".to_string());
+    }
+
+    Ok(Endpoint {
+        host: host.to_string(),
+        port,
+        path: if path.starts_with('?') {
+            format!("/{path}")
+        } else {
+            path.to_string()
+        },
+    })
+}
+
+/// Format the values as a point in the line protocol, or `None` if there are none
+fn format_line(
+    device: &DeviceInfo,
+    data: &[PropertyData],
+    timestamp: jiff::Timestamp,
+) -> Option<String> {
+    let fields: Vec<_> = data
+        .iter()
+        .flat_map(|prop| {
+            let key = escape_key(prop.id.0);
+
+            match &prop.value {
+                PropertyValue::Sensor(current, target) => vec![
+                    format!("{key}_current={current}i"),
+                    format!("{key}_target={target}i"),
+                ],
+                // Not representable in the line protocol
+                PropertyValue::Float(num) if !num.is_finite() => vec![],
+                value => vec![format!("{key}={}", format_value(value))],
+            }
+        })
+        .collect();
+
+    if fields.is_empty() {
+        return None;
+    }
+
+    let measurement = device.kind.to_string().to_lowercase().replace(' ', "_");
+
+    Some(format!(
+        "{},software_id={} {} {}",
+        escape_key(&measurement),
+        device.software_id,
+        fields.join(","),
+        timestamp.as_nanosecond()
+    ))
+}
+
+fn format_value(value: &PropertyValue) -> String {
+    match value {
+        PropertyValue::Bool(b) => b.to_string(),
+        PropertyValue::Number(num) | PropertyValue::Sensor(num, _) => format!("{num}i"),
+        PropertyValue::Float(num) => num.to_string(),
+        PropertyValue::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        PropertyValue::Duration(dur) => format!("{}i", dur.as_secs()),
+        PropertyValue::Flags(flags) => format_value(&PropertyValue::String(set_flags(flags))),
+    }
+}
+
+/// Escape a measurement name or field key
+fn escape_key(src: &str) -> String {
+    src.replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::{Ipv4Addr, TcpListener};
+
+    #[test]
+    fn line_protocol() {
+        let data = [
+            PropertyData::stub("door_open", PropertyValue::Bool(true)),
+            PropertyData::stub("temperature", PropertyValue::Sensor(42, 60)),
+            PropertyData::stub("water_level", PropertyValue::Float(1.5)),
+            PropertyData::stub(
+                "program",
+                PropertyValue::String("Cotton \"Eco\"".to_string()),
+            ),
+            PropertyData::stub(
+                "operating_time",
+                PropertyValue::Duration(Duration::from_secs(3600)),
+            ),
+            PropertyData::stub("unknown", PropertyValue::Float(f64::NAN)),
+        ];
+        let timestamp = jiff::Timestamp::from_second(1_700_000_000).expect("time should be valid");
+
+        assert_eq!(
+            format_line(&DeviceInfo::stub(629), &data, timestamp).as_deref(),
+            Some(
+                "washing_machine,software_id=629 door_open=true,temperature_current=42i,\
+                 temperature_target=60i,water_level=1.5,program=\"Cotton \\\"Eco\\\"\",\
+                 operating_time=3600i 1700000000000000000"
+            ),
+            "line should be correct"
+        );
+        assert_eq!(
+            format_line(&DeviceInfo::stub(629), &[], timestamp),
+            None,
+            "empty batch should be skipped"
+        );
+    }
+
+    #[test]
+    fn write_url() {
+        assert_eq!(
+            parse_url("http://localhost:8086/write?db=freemdu"),
+            Ok(Endpoint {
+                host: "localhost".to_string(),
+                port: 8086,
+                path: "/write?db=freemdu".to_string(),
+            }),
+            "URL should be correct"
+        );
+        assert_eq!(
+            parse_url("http://[::1]?db=x"),
+            Ok(Endpoint {
+                host: "::1".to_string(),
+                port: 80,
+                path: "/?db=x".to_string(),
+            }),
+            "default port should be used"
+        );
+        assert!(
+            parse_url("https://localhost/write").is_err(),
+            "HTTPS should be rejected"
+        );
+        assert!(
+            parse_url("http://:8086/").is_err(),
+            "host should be required"
+        );
+        assert!(
+            parse_url("http://host:port/").is_err(),
+            "port should be a number"
+        );
+    }
+
+    #[test]
+    fn write_to_server() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("server should start");
+        let port = listener.local_addr().expect("port should be known").port();
+        let writer = InfluxWriter::start(InfluxConfig {
+            enabled: true,
+            url: format!("http://127.0.0.1:{port}/write?db=test"),
+            token: "secret".to_string(),
+        })
+        .expect("writer should start");
+
+        writer.write(
+            &DeviceInfo::stub(629),
+            &[PropertyData::stub(
+                "spin_speed",
+                PropertyValue::Number(1200),
+            )],
+        );
+        // Remaining lines are written when the writer is dropped
+        drop(writer);
+
+        let (mut stream, _) = listener.accept().expect("writer should connect");
+        let mut request = String::new();
+
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+            .expect("response should be sent");
+        stream
+            .read_to_string(&mut request)
+            .expect("request should be received");
+
+        let (head, body) = request
+            .split_once("\r\n\r\n")
+            .expect("request should have a body");
+
+        assert!(
+            head.starts_with("POST /write?db=test HTTP/1.1\r\n"),
+            "request line should be correct"
+        );
+        assert!(
+            head.contains("\r\nAuthorization: Token secret\r\n"),
+            "token should be sent"
+        );
+        assert!(
+            body.starts_with("washing_machine,software_id=629 spin_speed=1200i "),
+            "body should be correct"
+        );
+    }
+}
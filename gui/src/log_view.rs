@@ -0,0 +1,226 @@
+use egui::{Color32, Ui};
+use log::{Level, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Maximum number of records kept in the buffer
+pub const LOG_LIMIT: usize = 1000;
+
+/// Least severe level kept in the buffer, regardless of `RUST_LOG`
+const BUFFER_LEVEL: Level = Level::Info;
+
+/// Levels that can be selected for filtering the log panel, most severe first
+const FILTER_LEVELS: [Level; 3] = [Level::Error, Level::Warn, Level::Info];
+
+const WARN_COLOR: Color32 = Color32::from_rgb(255, 152, 0);
+
+/// Log record kept for display
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    /// Time the record was logged
+    pub time: jiff::Timestamp,
+    pub level: Level,
+    /// Module that logged the record
+    pub target: String,
+    pub message: String,
+}
+
+/// Bounded buffer of recent log records, shared by all threads
+#[derive(Default)]
+pub struct LogBuffer {
+    records: Mutex<VecDeque<LogRecord>>,
+}
+
+impl LogBuffer {
+    /// Append a record, dropping the oldest one once full
+    pub fn push(&self, record: LogRecord) {
+        let mut records = self.lock();
+
+        if records.len() == LOG_LIMIT {
+            records.pop_front();
+        }
+
+        records.push_back(record);
+    }
+
+    /// Copy the records at least as severe as the given level, oldest first
+    pub fn records(&self, level: Level) -> Vec<LogRecord> {
+        self.lock()
+            .iter()
+            .filter(|record| record.level <= level)
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<LogRecord>> {
+        // A panic while holding the lock can't leave the records inconsistent
+        self.records.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Logger that prints to stderr as configured by `RUST_LOG`,
+/// while also keeping records in a [`LogBuffer`]
+struct Logger {
+    stderr: env_logger::Logger,
+    buffer: Arc<LogBuffer>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= BUFFER_LEVEL || self.stderr.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        // Only prints records matching the filter
+        self.stderr.log(record);
+
+        if record.level() <= BUFFER_LEVEL {
+            self.buffer.push(LogRecord {
+                time: jiff::Timestamp::now(),
+                level: record.level(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            });
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+    }
+}
+
+/// Install the logger, returning the buffer the records are kept in
+///
+/// The records are still printed to stderr, which isn't visible in the windowed build.
+pub fn init() -> Arc<LogBuffer> {
+    let stderr = env_logger::Logger::from_default_env();
+    let max_level = stderr.filter().max(BUFFER_LEVEL.to_level_filter());
+    let buffer = Arc::new(LogBuffer::default());
+    let logger = Logger {
+        stderr,
+        buffer: Arc::clone(&buffer),
+    };
+
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(max_level);
+    }
+
+    buffer
+}
+
+/// Panel showing the records of a [`LogBuffer`]
+pub struct LogPanel {
+    buffer: Arc<LogBuffer>,
+    /// Whether the panel is shown
+    pub open: bool,
+    /// Least severe level shown
+    level: Level,
+}
+
+impl LogPanel {
+    pub fn new(buffer: Arc<LogBuffer>) -> Self {
+        Self {
+            buffer,
+            open: false,
+            level: Level::Warn,
+        }
+    }
+
+    /// Show the buffered records at least as severe as the selected level, newest at the bottom
+    pub fn show(&mut self, ui: &mut Ui) {
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label("Level:");
+            egui::ComboBox::from_id_salt("log_level")
+                .selected_text(self.level.as_str())
+                .show_ui(ui, |ui| {
+                    for level in FILTER_LEVELS {
+                        ui.selectable_value(&mut self.level, level, level.as_str());
+                    }
+                });
+
+            if ui.button("Clear").clicked() {
+                self.buffer.clear();
+            }
+        });
+        ui.separator();
+
+        let records = self.buffer.records(self.level);
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, records.len(), |ui, rows| {
+                for record in &records[rows] {
+                    ui.horizontal(|ui| {
+                        ui.monospace(record.time.strftime("%H:%M:%S").to_string());
+
+                        let level = egui::RichText::new(format!("{:5}", record.level)).monospace();
+
+                        match record.level {
+                            Level::Error => ui.colored_label(Color32::RED, level),
+                            Level::Warn => ui.colored_label(WARN_COLOR, level),
+                            _ => ui.label(level),
+                        };
+
+                        ui.weak(&record.target);
+                        ui.label(&record.message);
+                    });
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(level: Level, message: &str) -> LogRecord {
+        LogRecord {
+            time: jiff::Timestamp::UNIX_EPOCH,
+            level,
+            target: "freemdu_gui::worker".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn buffer_bounded() {
+        let buffer = LogBuffer::default();
+
+        for i in 0..=LOG_LIMIT {
+            buffer.push(record(Level::Info, &i.to_string()));
+        }
+
+        let records = buffer.records(Level::Info);
+
+        assert_eq!(records.len(), LOG_LIMIT, "buffer should be bounded");
+        assert_eq!(records[0].message, "1", "oldest record should be dropped");
+    }
+
+    #[test]
+    fn records_filtered_by_level() {
+        let buffer = LogBuffer::default();
+
+        buffer.push(record(Level::Info, "Connected"));
+        buffer.push(record(Level::Warn, "Serial link lost"));
+        buffer.push(record(Level::Error, "Failed to bind"));
+
+        let messages: Vec<_> = buffer
+            .records(Level::Warn)
+            .into_iter()
+            .map(|record| record.message)
+            .collect();
+
+        assert_eq!(
+            messages,
+            ["Serial link lost", "Failed to bind"],
+            "filtered records should be correct"
+        );
+    }
+}
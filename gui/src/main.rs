@@ -1,13 +1,24 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod alert;
 mod app;
+mod csv_log;
+mod influx;
+mod log_view;
+mod metrics;
+mod mqtt;
+mod plot;
+mod session_file;
+mod smoothing;
+mod stream;
+mod watch;
 mod worker;
 
 use anyhow::Result;
 use app::FreeMduApp;
 
 fn main() -> Result<()> {
-    env_logger::init();
+    let logs = log_view::init();
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -20,7 +31,7 @@ fn main() -> Result<()> {
     eframe::run_native(
         "FreeMDU",
         options,
-        Box::new(|cc| Ok(Box::new(FreeMduApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(FreeMduApp::new(cc, logs)))),
     )
     .map_err(|e| anyhow::anyhow!("Failed to run application: {e}"))
 }
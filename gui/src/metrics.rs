@@ -0,0 +1,321 @@
+use crate::worker::{PropertyData, PropertyValue};
+use freemdu::device::PropertyId;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Default port of the metrics endpoint
+pub const DEFAULT_PORT: u16 = 9850;
+
+/// Interval for checking whether the server should stop
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Time allowed for a client to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Latest property values by property ID and device
+type Values = BTreeMap<(PropertyId, String), PropertyData>;
+
+/// HTTP server exposing the latest property values at `/metrics`
+/// in the Prometheus text format.
+///
+/// The server stops when dropped.
+pub struct MetricsServer {
+    values: Arc<Mutex<Values>>,
+    stop: Arc<AtomicBool>,
+    port: u16,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    /// Listen on all network interfaces at the given port, or any free port if zero
+    pub fn start(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))?;
+        let port = listener.local_addr()?.port();
+        let values = Arc::new(Mutex::new(Values::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // Polled, so that the thread notices when it should stop
+        listener.set_nonblocking(true)?;
+
+        let handle = thread::spawn({
+            let values = Arc::clone(&values);
+            let stop = Arc::clone(&stop);
+
+            move || serve(&listener, &values, &stop)
+        });
+
+        Ok(Self {
+            values,
+            stop,
+            port,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Store freshly queried values of a device, identified by its port name
+    pub fn update(&self, device: &str, data: &[PropertyData]) {
+        let mut values = lock(&self.values);
+
+        for prop in data {
+            values.insert((prop.id, device.to_string()), prop.clone());
+        }
+    }
+
+    /// Remove the values of devices that are no longer connected
+    pub fn retain_devices(&self, devices: &[&str]) {
+        lock(&self.values).retain(|(_, device), _| devices.contains(&device.as_str()));
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn lock(values: &Mutex<Values>) -> MutexGuard<'_, Values> {
+    values.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Answer requests one at a time until stopped
+fn serve(listener: &TcpListener, values: &Mutex<Values>, stop: &AtomicBool) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(e) = respond(stream, values) {
+                    log::debug!("Failed to answer metrics request: {e}");
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+            Err(e) => {
+                log::warn!("Failed to accept metrics connection: {e}");
+                thread::sleep(ACCEPT_POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+fn respond(mut stream: TcpStream, values: &Mutex<Values>) -> io::Result<()> {
+    // Accepted sockets inherit non-blocking mode on some platforms
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    let mut header = String::new();
+
+    reader.read_line(&mut request)?;
+
+    // Read the headers, which are ignored, so that closing the connection doesn't reset it
+    while reader.read_line(&mut header)? > 0 && header.trim_end() != "" {
+        header.clear();
+    }
+
+    let (status, body) = match request.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+        ["GET", "/metrics"] => ("200 OK", render(&lock(values))),
+        _ => ("404 Not Found", "Not found\n".to_string()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: text/plain; version=0.0.4; charset=utf-8\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Render the values in the Prometheus text format, with one gauge per property.
+///
+/// Metrics are named after the property ID. Sensor readings are exported as
+/// two series for the current and target values, durations in seconds.
+/// String values can't be represented and are skipped.
+fn render(values: &Values) -> String {
+    let mut out = String::new();
+    let mut family = None;
+
+    for ((id, device), prop) in values {
+        let samples = match &prop.value {
+            PropertyValue::Bool(b) => vec![(None, f64::from(u8::from(*b)))],
+            PropertyValue::Number(num) => vec![(None, f64::from(*num))],
+            PropertyValue::Float(num) => vec![(None, *num)],
+            PropertyValue::Sensor(current, target) => vec![
+                (Some("current"), f64::from(*current)),
+                (Some("target"), f64::from(*target)),
+            ],
+            PropertyValue::Duration(dur) => vec![(None, dur.as_secs_f64())],
+            // Each bit is exported as a separate series
+            PropertyValue::Flags(flags) => flags
+                .iter()
+                .map(|(label, set)| (Some(label.as_str()), f64::from(u8::from(*set))))
+                .collect(),
+            PropertyValue::String(_) => continue,
+        };
+        let name = metric_name(*id);
+
+        // Samples of the same metric have to be grouped below a single header
+        if family != Some(*id) {
+            let _ = writeln!(out, "# HELP {name} {}", escape(&prop.name));
+            let _ = writeln!(out, "# TYPE {name} gauge");
+            family = Some(*id);
+        }
+
+        let unit = match (&prop.value, prop.unit.as_deref()) {
+            (_, Some(unit)) => Some(unit),
+            (PropertyValue::Duration(_), None) => Some("s"),
+            _ => None,
+        };
+
+        for (series, val) in samples {
+            let mut labels = format!("device=\"{}\"", escape(device));
+
+            if let Some(unit) = unit {
+                let _ = write!(labels, ",unit=\"{}\"", escape(unit));
+            }
+
+            if let Some(series) = series {
+                let _ = write!(labels, ",series=\"{}\"", escape(series));
+            }
+
+            let _ = writeln!(out, "{name}{{{labels}}} {val}");
+        }
+    }
+
+    out
+}
+
+/// Returns a valid metric name for the property
+fn metric_name(id: PropertyId) -> String {
+    let id: String = id
+        .as_str()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    format!("freemdu_{id}")
+}
+
+/// Escape a label value or help text
+fn escape(src: &str) -> String {
+    src.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn data(
+        id: &'static str,
+        name: &str,
+        value: PropertyValue,
+        unit: Option<&str>,
+    ) -> PropertyData {
+        PropertyData {
+            name: name.to_string(),
+            unit: unit.map(String::from),
+            ..PropertyData::stub(id, value)
+        }
+    }
+
+    fn sample_data() -> [PropertyData; 3] {
+        [
+            data(
+                "temperature",
+                "Temperature",
+                PropertyValue::Sensor(42, 60),
+                Some("°C"),
+            ),
+            data(
+                "operating_time",
+                "Operating Time",
+                PropertyValue::Duration(Duration::from_secs(3600)),
+                None,
+            ),
+            data(
+                "serial_number",
+                "Serial Number",
+                PropertyValue::String("93140239".to_string()),
+                None,
+            ),
+        ]
+    }
+
+    #[test]
+    fn render_gauges() {
+        let mut values = Values::new();
+
+        for device in ["/dev/ttyUSB0", "/dev/ttyUSB1"] {
+            for prop in sample_data() {
+                values.insert((prop.id, device.to_string()), prop);
+            }
+        }
+
+        assert_eq!(
+            render(&values),
+            "# HELP freemdu_operating_time Operating Time\n\
+             # TYPE freemdu_operating_time gauge\n\
+             freemdu_operating_time{device=\"/dev/ttyUSB0\",unit=\"s\"} 3600\n\
+             freemdu_operating_time{device=\"/dev/ttyUSB1\",unit=\"s\"} 3600\n\
+             # HELP freemdu_temperature Temperature\n\
+             # TYPE freemdu_temperature gauge\n\
+             freemdu_temperature{device=\"/dev/ttyUSB0\",unit=\"°C\",series=\"current\"} 42\n\
+             freemdu_temperature{device=\"/dev/ttyUSB0\",unit=\"°C\",series=\"target\"} 60\n\
+             freemdu_temperature{device=\"/dev/ttyUSB1\",unit=\"°C\",series=\"current\"} 42\n\
+             freemdu_temperature{device=\"/dev/ttyUSB1\",unit=\"°C\",series=\"target\"} 60\n",
+            "metrics should be correct"
+        );
+    }
+
+    #[test]
+    fn serve_metrics() {
+        let server = MetricsServer::start(0).expect("server should start");
+
+        server.update("/dev/ttyUSB0", &sample_data());
+        server.update("/dev/ttyUSB1", &sample_data());
+        server.retain_devices(&["/dev/ttyUSB1"]);
+
+        let mut stream =
+            TcpStream::connect((Ipv4Addr::LOCALHOST, server.port())).expect("server should accept");
+        let mut resp = String::new();
+
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .expect("request should be sent");
+        stream
+            .read_to_string(&mut resp)
+            .expect("response should be received");
+
+        assert!(
+            resp.starts_with("HTTP/1.1 200 OK\r\n"),
+            "status should be correct"
+        );
+        assert!(
+            resp.contains("freemdu_operating_time{device=\"/dev/ttyUSB1\",unit=\"s\"} 3600\n"),
+            "metrics of connected device should be served"
+        );
+        assert!(
+            !resp.contains("/dev/ttyUSB0"),
+            "metrics of disconnected device should be removed"
+        );
+    }
+}
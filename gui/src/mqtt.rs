@@ -0,0 +1,667 @@
+use crate::worker::{set_flags, DeviceInfo, PropertyData, PropertyValue};
+use freemdu::device::PropertyKind;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Keep-alive interval announced to the broker
+const KEEP_ALIVE: Duration = Duration::from_secs(60);
+
+/// Time allowed for connecting to the broker and for each of its replies
+const BROKER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Delay before reconnecting after the connection to the broker was lost
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Packet types of MQTT 3.1.1, in the upper nibble of the first header byte
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const PINGREQ: u8 = 0xc0;
+const PINGRESP: u8 = 0xd0;
+const DISCONNECT: u8 = 0xe0;
+
+/// Flag of PUBLISH packets asking the broker to keep the last value of a topic
+const RETAIN: u8 = 0x01;
+
+/// Connection settings of the MQTT broker
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    /// Publish property values while the app is running
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// Prefix of all published topics
+    pub base_topic: String,
+    /// Announce properties to Home Assistant, so that they appear as entities
+    pub discovery: bool,
+    /// Prefix of the topics Home Assistant expects discovery configs at
+    pub discovery_prefix: String,
+    /// User name, or empty for anonymous access
+    pub username: String,
+    pub password: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            base_topic: "freemdu".to_string(),
+            discovery: true,
+            discovery_prefix: "homeassistant".to_string(),
+            username: String::new(),
+            password: String::new(),
+        }
+    }
+}
+
+/// Topics and payloads of a property update
+type Messages = Vec<(String, String)>;
+
+/// Publisher mirroring property values to an MQTT broker.
+///
+/// Every value is published as a retained message at
+/// `<base topic>/<kind>/<property ID>`, sensor targets at `.../target`.
+/// The connection is reestablished in the background whenever it drops,
+/// republishing the latest values. It is closed once the publisher is dropped.
+///
+/// If enabled, Home Assistant discovery configs are published along with the
+/// values, grouping the properties of each device under a single device entry.
+pub struct MqttPublisher {
+    config: MqttConfig,
+    tx: Sender<Messages>,
+    connected: Arc<AtomicBool>,
+}
+
+impl MqttPublisher {
+    pub fn start(config: MqttConfig) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let connected = Arc::new(AtomicBool::new(false));
+
+        // Not joined when dropped, as connecting might block for a while
+        thread::spawn({
+            let config = config.clone();
+            let connected = Arc::clone(&connected);
+
+            move || run(&config, &rx, &connected)
+        });
+
+        Self {
+            config,
+            tx,
+            connected,
+        }
+    }
+
+    pub fn config(&self) -> &MqttConfig {
+        &self.config
+    }
+
+    /// Returns whether the broker accepted the connection
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// Queue freshly queried values of a property kind for publishing
+    pub fn publish(&self, device: &DeviceInfo, kind: PropertyKind, data: &[PropertyData]) {
+        let mut messages = Vec::new();
+
+        // Configs come first, so that Home Assistant picks up the initial values
+        if self.config.discovery {
+            messages.extend(discovery_messages(&self.config, device, kind, data));
+        }
+
+        messages.extend(state_messages(&self.config.base_topic, kind, data));
+
+        if !messages.is_empty() {
+            let _ = self.tx.send(messages);
+        }
+    }
+}
+
+/// Keep connecting to the broker until the publisher is dropped
+fn run(config: &MqttConfig, rx: &Receiver<Messages>, connected: &AtomicBool) {
+    // Latest payload of each topic, republished after reconnecting
+    let mut retained = BTreeMap::new();
+
+    loop {
+        match connect(config) {
+            Ok(mut stream) => {
+                log::info!("Connected to MQTT broker {}:{}", config.host, config.port);
+                connected.store(true, Ordering::Relaxed);
+
+                let res = publish_all(&mut stream, rx, &mut retained);
+
+                connected.store(false, Ordering::Relaxed);
+
+                match res {
+                    Ok(()) => {
+                        let _ = stream.write_all(&[DISCONNECT, 0]);
+                        return;
+                    }
+                    Err(e) => log::warn!("Lost connection to MQTT broker: {e}"),
+                }
+            }
+            Err(e) => log::warn!("Failed to connect to MQTT broker: {e}"),
+        }
+
+        let deadline = Instant::now() + RECONNECT_INTERVAL;
+
+        // Values arriving in the meantime are published after reconnecting
+        loop {
+            match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(messages) => retained.extend(messages),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    }
+}
+
+fn connect(config: &MqttConfig) -> io::Result<TcpStream> {
+    let addr = (config.host.as_str(), config.port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "unknown host"))?;
+    let mut stream = TcpStream::connect_timeout(&addr, BROKER_TIMEOUT)?;
+
+    stream.set_read_timeout(Some(BROKER_TIMEOUT))?;
+    stream.set_write_timeout(Some(BROKER_TIMEOUT))?;
+    stream.write_all(&encode_connect(
+        config,
+        &format!("freemdu-{}", std::process::id()),
+    ))?;
+
+    match read_packet(&mut stream)? {
+        (CONNACK, body) if body.len() == 2 && body[1] == 0 => Ok(stream),
+        (CONNACK, body) => Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "connection refused with code {}",
+                body.get(1).copied().unwrap_or_default()
+            ),
+        )),
+        (kind, _) => Err(unexpected_packet(kind)),
+    }
+}
+
+/// Publish retained values, then new ones as they arrive, until the publisher is dropped
+fn publish_all(
+    stream: &mut TcpStream,
+    rx: &Receiver<Messages>,
+    retained: &mut BTreeMap<String, String>,
+) -> io::Result<()> {
+    for (topic, payload) in &*retained {
+        stream.write_all(&encode_publish(topic, payload))?;
+    }
+
+    loop {
+        match rx.recv_timeout(KEEP_ALIVE / 2) {
+            Ok(messages) => {
+                for (topic, payload) in messages {
+                    // Unchanged values are already retained by the broker
+                    if retained.get(&topic) != Some(&payload) {
+                        let packet = encode_publish(&topic, &payload);
+
+                        // Stored first, so that it's republished if sending fails
+                        retained.insert(topic, payload);
+                        stream.write_all(&packet)?;
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                // Detects a broker that went away without closing the connection
+                stream.write_all(&[PINGREQ, 0])?;
+
+                match read_packet(stream)? {
+                    (PINGRESP, _) => {}
+                    (kind, _) => return Err(unexpected_packet(kind)),
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+fn unexpected_packet(kind: u8) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unexpected packet {kind:#04x}"),
+    )
+}
+
+/// Returns the topic of a property value
+fn state_topic(base_topic: &str, kind: PropertyKind, prop: &PropertyData) -> String {
+    let kind = match kind {
+        PropertyKind::General => "general",
+        PropertyKind::Failure => "failure",
+        PropertyKind::Warning => "warning",
+        PropertyKind::Operation => "operation",
+        PropertyKind::Io => "io",
+    };
+
+    format!(
+        "{}/{kind}/{}",
+        base_topic.trim_end_matches('/'),
+        prop.id.as_str()
+    )
+}
+
+/// Returns the topics and payloads of the properties.
+///
+/// Payloads contain plain values without units, so that they can be parsed easily.
+fn state_messages(base_topic: &str, kind: PropertyKind, data: &[PropertyData]) -> Messages {
+    let mut messages = Vec::new();
+
+    for prop in data {
+        let topic = state_topic(base_topic, kind, prop);
+        let payload = match &prop.value {
+            PropertyValue::Bool(b) => (if *b { "ON" } else { "OFF" }).to_string(),
+            PropertyValue::Number(n) => n.to_string(),
+            PropertyValue::Float(n) => format!("{n:.prec$}", prec = usize::from(prop.decimals)),
+            PropertyValue::Sensor(current, target) => {
+                messages.push((format!("{topic}/target"), target.to_string()));
+                current.to_string()
+            }
+            PropertyValue::String(s) => s.clone(),
+            PropertyValue::Duration(d) => d.as_secs().to_string(),
+            PropertyValue::Flags(flags) => set_flags(flags),
+        };
+
+        messages.push((topic, payload));
+    }
+
+    messages
+}
+
+/// Returns the Home Assistant discovery configs of the properties.
+///
+/// Booleans become binary sensors, failure booleans with the problem device class.
+/// Other values become sensors, whose device class is derived from the unit.
+/// Numeric values are measurements unless they are general properties like model numbers,
+/// while strings and bitfields are published as text.
+fn discovery_messages(
+    config: &MqttConfig,
+    device: &DeviceInfo,
+    kind: PropertyKind,
+    data: &[PropertyData],
+) -> Messages {
+    let node_id = format!("freemdu_{}", device.software_id);
+    let model = freemdu::device::software_version(device.software_id)
+        .map_or_else(|| device.kind.to_string(), String::from);
+    let device_config = json!({
+        "identifiers": [node_id],
+        "name": format!("Miele {}", device.kind),
+        "manufacturer": "Miele",
+        "model": model,
+        "sw_version": device.software_id.to_string(),
+    });
+    let prefix = config.discovery_prefix.trim_end_matches('/');
+    let mut messages = Vec::new();
+
+    for prop in data {
+        let topic = state_topic(&config.base_topic, kind, prop);
+        let entities = match prop.value {
+            PropertyValue::Sensor(..) => vec![
+                (String::new(), prop.name.clone()),
+                ("/target".to_string(), format!("{} Target", prop.name)),
+            ],
+            _ => vec![(String::new(), prop.name.clone())],
+        };
+
+        for (suffix, name) in entities {
+            let object_id = format!("{}{}", prop.id.as_str(), suffix.replace('/', "_"));
+            let mut entity = json!({
+                "name": name,
+                "unique_id": format!("{node_id}_{object_id}"),
+                "state_topic": format!("{topic}{suffix}"),
+                "device": device_config,
+            });
+            let (device_class, unit) = match (&prop.value, prop.unit.as_deref()) {
+                (PropertyValue::Bool(_), _) => {
+                    entity["payload_on"] = "ON".into();
+                    entity["payload_off"] = "OFF".into();
+
+                    ((kind == PropertyKind::Failure).then_some("problem"), None)
+                }
+                (PropertyValue::String(_) | PropertyValue::Flags(_), _) => (None, None),
+                (PropertyValue::Duration(_), _) => (Some("duration"), Some("s")),
+                (_, Some("°C")) => (Some("temperature"), Some("°C")),
+                (_, Some("ml")) => (Some("volume"), Some("mL")),
+                (_, unit) => (None, unit),
+            };
+
+            if let Some(device_class) = device_class {
+                entity["device_class"] = device_class.into();
+            }
+
+            if let Some(unit) = unit {
+                entity["unit_of_measurement"] = unit.into();
+            }
+
+            let numeric = !matches!(
+                prop.value,
+                PropertyValue::Bool(_) | PropertyValue::String(_) | PropertyValue::Flags(_)
+            );
+
+            if numeric && kind != PropertyKind::General {
+                entity["state_class"] = "measurement".into();
+            }
+
+            let component = if let PropertyValue::Bool(_) = prop.value {
+                "binary_sensor"
+            } else {
+                "sensor"
+            };
+
+            messages.push((
+                format!("{prefix}/{component}/{node_id}/{object_id}/config"),
+                entity.to_string(),
+            ));
+        }
+    }
+
+    messages
+}
+
+fn encode_connect(config: &MqttConfig, client_id: &str) -> Vec<u8> {
+    let mut flags = 0x02; // Clean session
+    let mut body = Vec::new();
+
+    encode_string(&mut body, "MQTT");
+    body.push(4); // Protocol level of MQTT 3.1.1
+
+    if !config.username.is_empty() {
+        flags |= 0x80;
+
+        if !config.password.is_empty() {
+            flags |= 0x40;
+        }
+    }
+
+    body.push(flags);
+    body.extend_from_slice(&(KEEP_ALIVE.as_secs() as u16).to_be_bytes());
+    encode_string(&mut body, client_id);
+
+    if !config.username.is_empty() {
+        encode_string(&mut body, &config.username);
+
+        if !config.password.is_empty() {
+            encode_string(&mut body, &config.password);
+        }
+    }
+
+    encode_packet(CONNECT, &body)
+}
+
+fn encode_publish(topic: &str, payload: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+
+    // Sent at most once, so without a packet identifier
+    encode_string(&mut body, topic);
+    body.extend_from_slice(payload.as_bytes());
+
+    encode_packet(PUBLISH | RETAIN, &body)
+}
+
+fn encode_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// Prepend the fixed header, which includes the variable-length size of the body
+fn encode_packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![header];
+    let mut len = body.len();
+
+    loop {
+        let byte = (len % 128) as u8;
+
+        len /= 128;
+
+        if len == 0 {
+            packet.push(byte);
+            break;
+        }
+
+        packet.push(byte | 0x80);
+    }
+
+    packet.extend_from_slice(body);
+    packet
+}
+
+/// Read a packet, returning its type and body
+fn read_packet(stream: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut byte = [0];
+    let mut len = 0;
+
+    stream.read_exact(&mut byte)?;
+
+    let kind = byte[0] & 0xf0;
+
+    // The size takes up to four bytes
+    for shift in (0..4).map(|i| i * 7) {
+        let mut size = [0];
+
+        stream.read_exact(&mut size)?;
+        len |= usize::from(size[0] & 0x7f) << shift;
+
+        if size[0] & 0x80 == 0 {
+            let mut body = vec![0; len];
+
+            stream.read_exact(&mut body)?;
+
+            return Ok((kind, body));
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "invalid packet size",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, TcpListener};
+
+    fn data(id: &'static str, value: PropertyValue, decimals: u8) -> PropertyData {
+        PropertyData {
+            decimals,
+            ..PropertyData::stub(id, value)
+        }
+    }
+
+    #[test]
+    fn property_messages() {
+        let data = [
+            data("door_open", PropertyValue::Bool(true), 0),
+            data("temperature", PropertyValue::Sensor(42, 60), 0),
+            data("water_level", PropertyValue::Float(1.234), 1),
+            data(
+                "operating_time",
+                PropertyValue::Duration(Duration::from_secs(3600)),
+                0,
+            ),
+        ];
+        let msg = |topic: &str, payload: &str| (topic.to_string(), payload.to_string());
+
+        assert_eq!(
+            state_messages("home/washer/", PropertyKind::Io, &data),
+            [
+                msg("home/washer/io/door_open", "ON"),
+                msg("home/washer/io/temperature/target", "60"),
+                msg("home/washer/io/temperature", "42"),
+                msg("home/washer/io/water_level", "1.2"),
+                msg("home/washer/io/operating_time", "3600"),
+            ],
+            "messages should be correct"
+        );
+    }
+
+    #[test]
+    fn discovery_configs() {
+        let data = [
+            PropertyData {
+                unit: Some("°C".to_string()),
+                ..data("temperature", PropertyValue::Sensor(42, 60), 0)
+            },
+            data("heater_fault", PropertyValue::Bool(false), 0),
+            data(
+                "active_actuators",
+                PropertyValue::Flags(vec![("Pump 1".to_string(), true)]),
+                0,
+            ),
+        ];
+        let messages = discovery_messages(
+            &MqttConfig::default(),
+            &DeviceInfo::stub(419),
+            PropertyKind::Failure,
+            &data,
+        );
+        let topics: Vec<_> = messages.iter().map(|(topic, _)| topic.as_str()).collect();
+
+        assert_eq!(
+            topics,
+            [
+                "homeassistant/sensor/freemdu_419/temperature/config",
+                "homeassistant/sensor/freemdu_419/temperature_target/config",
+                "homeassistant/binary_sensor/freemdu_419/heater_fault/config",
+                "homeassistant/sensor/freemdu_419/active_actuators/config",
+            ],
+            "topics should be correct"
+        );
+
+        let parse = |idx: usize| {
+            serde_json::from_str::<serde_json::Value>(&messages[idx].1)
+                .expect("config should be valid JSON")
+        };
+        let target = parse(1);
+        let fault = parse(2);
+        let actuators = parse(3);
+
+        assert_eq!(
+            target,
+            json!({
+                "name": "temperature Target",
+                "unique_id": "freemdu_419_temperature_target",
+                "state_topic": "freemdu/failure/temperature/target",
+                "device_class": "temperature",
+                "unit_of_measurement": "°C",
+                "state_class": "measurement",
+                "device": {
+                    "identifiers": ["freemdu_419"],
+                    "name": "Miele Washing Machine",
+                    "manufacturer": "Miele",
+                    "model": freemdu::device::software_version(419).expect("ID should be known"),
+                    "sw_version": "419",
+                },
+            }),
+            "sensor config should be correct"
+        );
+        assert_eq!(
+            fault["device_class"], "problem",
+            "failure flag should be a problem"
+        );
+        assert_eq!(fault["payload_on"], "ON", "payload should be correct");
+        assert!(
+            fault.get("state_class").is_none(),
+            "flag shouldn't be a measurement"
+        );
+        assert!(
+            actuators.get("state_class").is_none(),
+            "bitfield shouldn't be a measurement"
+        );
+    }
+
+    #[test]
+    fn packet_encoding() {
+        let config = MqttConfig {
+            username: "user".to_string(),
+            password: "pw".to_string(),
+            ..MqttConfig::default()
+        };
+
+        assert_eq!(
+            encode_connect(&config, "id"),
+            b"\x10\x18\x00\x04MQTT\x04\xc2\x00\x3c\x00\x02id\x00\x04user\x00\x02pw",
+            "connect packet should be correct"
+        );
+        assert_eq!(
+            encode_publish("a/b", "1"),
+            b"\x31\x06\x00\x03a/b1",
+            "publish packet should be correct"
+        );
+
+        let packet = encode_publish("t", &"x".repeat(200));
+
+        assert_eq!(
+            packet[..3],
+            [0x31, 0xcb, 0x01],
+            "size should take two bytes"
+        );
+        assert_eq!(
+            read_packet(&mut &packet[..]).expect("packet should be read"),
+            (PUBLISH, packet[3..].to_vec()),
+            "packet should be read back"
+        );
+    }
+
+    #[test]
+    fn publish_to_broker() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("broker should start");
+        let publisher = MqttPublisher::start(MqttConfig {
+            host: Ipv4Addr::LOCALHOST.to_string(),
+            port: listener.local_addr().expect("port should be known").port(),
+            discovery: false,
+            ..MqttConfig::default()
+        });
+        let (mut stream, _) = listener.accept().expect("publisher should connect");
+
+        assert_eq!(
+            read_packet(&mut stream)
+                .expect("connect should be received")
+                .0,
+            CONNECT,
+            "connect packet should be sent first"
+        );
+        stream
+            .write_all(&[CONNACK, 2, 0, 0])
+            .expect("connack should be sent");
+
+        let data = [data("program_phase", PropertyValue::Number(3), 0)];
+
+        // Unchanged values are only published once
+        publisher.publish(&DeviceInfo::stub(419), PropertyKind::Operation, &data);
+        publisher.publish(&DeviceInfo::stub(419), PropertyKind::Operation, &data);
+        drop(publisher);
+
+        assert_eq!(
+            read_packet(&mut stream).expect("publish should be received"),
+            (
+                PUBLISH,
+                b"\x00\x1ffreemdu/operation/program_phase3".to_vec()
+            ),
+            "value should be published"
+        );
+        assert_eq!(
+            read_packet(&mut stream)
+                .expect("disconnect should be received")
+                .0,
+            DISCONNECT,
+            "publisher should disconnect when dropped"
+        );
+    }
+}
@@ -0,0 +1,614 @@
+use crate::worker::{PropertyData, PropertyValue};
+use egui::{Color32, Pos2, Rect, Sense, Shape, Stroke, Ui, Vec2};
+use freemdu::device::PropertyId;
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Write};
+use std::time::Instant;
+
+/// Maximum number of samples kept per property
+pub const MAX_SAMPLES: usize = 3600;
+
+/// Height of a single chart
+const PLOT_HEIGHT: f32 = 140.0;
+
+/// Width of the chart lines
+const LINE_WIDTH: f32 = 1.5;
+
+const CURRENT_COLOR: Color32 = Color32::from_rgb(33, 150, 243);
+const TARGET_COLOR: Color32 = Color32::from_rgb(255, 152, 0);
+
+/// Colors of the lines of an overlaid chart, repeated if there are more lines
+const SERIES_COLORS: [Color32; 6] = [
+    Color32::from_rgb(33, 150, 243),
+    Color32::from_rgb(255, 152, 0),
+    Color32::from_rgb(76, 175, 80),
+    Color32::from_rgb(233, 30, 99),
+    Color32::from_rgb(156, 39, 176),
+    Color32::from_rgb(0, 188, 212),
+];
+
+/// Numeric sample of a property at a point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub time: Instant,
+    pub current: f64,
+    /// Target value of sensor readings
+    pub target: Option<f64>,
+}
+
+/// Bounded history of numeric property values for plotting
+#[derive(Default)]
+pub struct History {
+    samples: HashMap<PropertyId, VecDeque<Sample>>,
+}
+
+impl History {
+    /// Record freshly queried values, dropping the oldest samples once full.
+    pub fn update(&mut self, data: &[PropertyData]) {
+        let time = Instant::now();
+
+        for prop in data {
+            let (current, target) = match prop.value {
+                PropertyValue::Number(num) => (f64::from(num), None),
+                PropertyValue::Float(num) => (num, None),
+                PropertyValue::Sensor(current, target) => {
+                    (f64::from(current), Some(f64::from(target)))
+                }
+                _ => continue,
+            };
+            let samples = self.samples.entry(prop.id).or_default();
+
+            if samples.len() == MAX_SAMPLES {
+                samples.pop_front();
+            }
+
+            samples.push_back(Sample {
+                time,
+                current,
+                target,
+            });
+        }
+    }
+
+    pub fn get(&self, id: PropertyId) -> Option<&VecDeque<Sample>> {
+        self.samples.get(&id)
+    }
+
+    /// Clear all samples, e.g. after disconnecting.
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
+
+/// Scaling of the lines of an overlaid chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverlayScale {
+    /// Each line is normalized to its own range, as if it had its own y-axis
+    #[default]
+    Separate,
+    /// All lines share a y-axis, for comparing properties with the same unit
+    Shared,
+}
+
+/// Resolution at which samples are drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Resolution {
+    /// Long histories are reduced to the width of the chart, which keeps drawing fast
+    #[default]
+    Downsampled,
+    /// Every sample is drawn
+    Raw,
+}
+
+/// Line of an overlaid chart
+pub struct Series<'a> {
+    pub name: &'a str,
+    pub unit: Option<&'a str>,
+    pub samples: &'a VecDeque<Sample>,
+}
+
+/// Returns whether a graph can be shown for the given value.
+pub fn is_plottable(value: &PropertyValue) -> bool {
+    matches!(
+        value,
+        PropertyValue::Number(_) | PropertyValue::Float(_) | PropertyValue::Sensor(..)
+    )
+}
+
+/// Reduce the samples to at most about the given number of points for drawing
+///
+/// The samples are split into buckets of equal size, of which the minimum and maximum are kept
+/// in their original order, so that peaks stay visible. The first and last sample are always
+/// kept, so that the line spans the same time. Samples are copied unchanged if they fit.
+pub fn downsample(samples: &VecDeque<Sample>, max_points: usize) -> Vec<Sample> {
+    let buckets = max_points / 2;
+
+    if buckets == 0 || samples.len() <= max_points {
+        return samples.iter().copied().collect();
+    }
+
+    let size = samples.len().div_ceil(buckets);
+    let mut points = Vec::with_capacity(buckets * 2 + 2);
+
+    points.extend(samples.front());
+
+    for start in (0..samples.len()).step_by(size) {
+        let end = (start + size).min(samples.len());
+        let (mut min, mut max) = (start, start);
+
+        for idx in start..end {
+            if samples[idx].current < samples[min].current {
+                min = idx;
+            }
+
+            if samples[idx].current > samples[max].current {
+                max = idx;
+            }
+        }
+
+        let (first, second) = if min <= max { (min, max) } else { (max, min) };
+
+        // The first sample was already added, the last one is added below
+        for idx in [first, second] {
+            if idx != 0 && idx != samples.len() - 1 && points.last() != Some(&samples[idx]) {
+                points.push(samples[idx]);
+            }
+        }
+    }
+
+    points.extend(samples.back().filter(|_| samples.len() > 1));
+
+    points
+}
+
+/// Samples to draw on a chart of the given width in pixels
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn visible_samples(samples: &VecDeque<Sample>, width: f32, resolution: Resolution) -> Vec<Sample> {
+    match resolution {
+        Resolution::Downsampled => downsample(samples, width.max(0.0) as usize),
+        Resolution::Raw => samples.iter().copied().collect(),
+    }
+}
+
+/// Draw a line chart of the samples, with the newest sample on the right.
+///
+/// Sensor readings are drawn as two lines for the current and target values.
+/// Long histories are downsampled to the width of the chart at [`Resolution::Downsampled`].
+/// Returns the area of the chart, e.g. for taking a screenshot of it.
+#[allow(clippy::cast_possible_truncation)]
+pub fn show(
+    ui: &mut Ui,
+    samples: &VecDeque<Sample>,
+    unit: Option<&str>,
+    resolution: Resolution,
+) -> Rect {
+    let (response, painter) =
+        ui.allocate_painter(Vec2::new(ui.available_width(), PLOT_HEIGHT), Sense::hover());
+    let rect = response.rect;
+    let visuals = ui.visuals();
+
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+
+    let (Some(first), Some(last)) = (samples.front(), samples.back()) else {
+        return rect;
+    };
+
+    let (min, max) = value_range(
+        samples
+            .iter()
+            .flat_map(|sample| std::iter::once(sample.current).chain(sample.target)),
+    );
+    let span = last.time.duration_since(first.time).as_secs_f64().max(1.0);
+    let plot_rect = rect.shrink2(Vec2::new(4.0, 14.0));
+    let to_pos = |time: Instant, val: f64| {
+        let x = time.duration_since(first.time).as_secs_f64() / span;
+        let y = (val - min) / (max - min);
+
+        Pos2::new(
+            plot_rect.left() + x as f32 * plot_rect.width(),
+            plot_rect.bottom() - y as f32 * plot_rect.height(),
+        )
+    };
+
+    let visible = visible_samples(samples, plot_rect.width(), resolution);
+    let current: Vec<_> = visible.iter().map(|s| to_pos(s.time, s.current)).collect();
+    let target: Vec<_> = visible
+        .iter()
+        .filter_map(|s| s.target.map(|target| to_pos(s.time, target)))
+        .collect();
+
+    if !target.is_empty() {
+        painter.add(Shape::line(target, Stroke::new(LINE_WIDTH, TARGET_COLOR)));
+    }
+
+    painter.add(Shape::line(current, Stroke::new(LINE_WIDTH, CURRENT_COLOR)));
+
+    // Axis labels
+    let unit = unit.map(|unit| format!(" {unit}")).unwrap_or_default();
+    let font = egui::FontId::monospace(10.0);
+    let text_color = visuals.weak_text_color();
+
+    painter.text(
+        rect.left_top() + Vec2::new(4.0, 1.0),
+        egui::Align2::LEFT_TOP,
+        format!("{max:.1}{unit}"),
+        font.clone(),
+        text_color,
+    );
+    painter.text(
+        rect.left_bottom() + Vec2::new(4.0, -1.0),
+        egui::Align2::LEFT_BOTTOM,
+        format!("{min:.1}{unit}"),
+        font.clone(),
+        text_color,
+    );
+    painter.text(
+        rect.right_bottom() + Vec2::new(-4.0, -1.0),
+        egui::Align2::RIGHT_BOTTOM,
+        format!("last {}s", span.round()),
+        font.clone(),
+        text_color,
+    );
+
+    if last.target.is_some() {
+        legend(ui, rect, &font);
+    }
+
+    rect
+}
+
+/// Draw the current values of several properties on a shared time axis.
+///
+/// The lines are labeled below the chart, along with their range if they're scaled separately.
+/// Long histories are downsampled to the width of the chart at [`Resolution::Downsampled`].
+/// Returns the area of the chart, e.g. for taking a screenshot of it.
+#[allow(clippy::cast_possible_truncation)]
+pub fn show_overlay(
+    ui: &mut Ui,
+    series: &[Series],
+    scale: OverlayScale,
+    resolution: Resolution,
+) -> Rect {
+    let (response, painter) =
+        ui.allocate_painter(Vec2::new(ui.available_width(), PLOT_HEIGHT), Sense::hover());
+    let rect = response.rect;
+    let visuals = ui.visuals();
+    let font = egui::FontId::monospace(10.0);
+    let text_color = visuals.weak_text_color();
+
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+
+    let start = series
+        .iter()
+        .filter_map(|series| series.samples.front())
+        .map(|sample| sample.time)
+        .min();
+    let end = series
+        .iter()
+        .filter_map(|series| series.samples.back())
+        .map(|sample| sample.time)
+        .max();
+    let ranges = overlay_ranges(series, scale);
+
+    if let (Some(start), Some(end)) = (start, end) {
+        let span = end.duration_since(start).as_secs_f64().max(1.0);
+        let plot_rect = rect.shrink2(Vec2::new(4.0, 14.0));
+
+        for (idx, (series, &(min, max))) in series.iter().zip(&ranges).enumerate() {
+            let points = visible_samples(series.samples, plot_rect.width(), resolution)
+                .iter()
+                .map(|sample| {
+                    let x = sample.time.duration_since(start).as_secs_f64() / span;
+                    let y = (sample.current - min) / (max - min);
+
+                    Pos2::new(
+                        plot_rect.left() + x as f32 * plot_rect.width(),
+                        plot_rect.bottom() - y as f32 * plot_rect.height(),
+                    )
+                })
+                .collect();
+
+            painter.add(Shape::line(
+                points,
+                Stroke::new(LINE_WIDTH, series_color(idx)),
+            ));
+        }
+
+        painter.text(
+            rect.right_bottom() + Vec2::new(-4.0, -1.0),
+            egui::Align2::RIGHT_BOTTOM,
+            format!("last {}s", span.round()),
+            font.clone(),
+            text_color,
+        );
+    }
+
+    // A shared axis is labeled on the chart, separate ones in the legend
+    if let (OverlayScale::Shared, Some(&(min, max))) = (scale, ranges.first()) {
+        let unit = shared_unit(series)
+            .map(|unit| format!(" {unit}"))
+            .unwrap_or_default();
+
+        painter.text(
+            rect.left_top() + Vec2::new(4.0, 1.0),
+            egui::Align2::LEFT_TOP,
+            format!("{max:.1}{unit}"),
+            font.clone(),
+            text_color,
+        );
+        painter.text(
+            rect.left_bottom() + Vec2::new(4.0, -1.0),
+            egui::Align2::LEFT_BOTTOM,
+            format!("{min:.1}{unit}"),
+            font,
+            text_color,
+        );
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for (idx, (series, &(min, max))) in series.iter().zip(&ranges).enumerate() {
+            let label = match scale {
+                OverlayScale::Separate => {
+                    let unit = series
+                        .unit
+                        .map(|unit| format!(" {unit}"))
+                        .unwrap_or_default();
+
+                    format!("{} ({min:.1} – {max:.1}{unit})", series.name)
+                }
+                OverlayScale::Shared => series.name.to_string(),
+            };
+
+            ui.colored_label(series_color(idx), "━");
+            ui.label(label);
+        }
+    });
+
+    rect
+}
+
+/// Range of the y-axis of each line of an overlaid chart
+///
+/// Lines without samples still get a range, so that the ranges match the lines.
+fn overlay_ranges<'a>(series: &[Series<'a>], scale: OverlayScale) -> Vec<(f64, f64)> {
+    let current = |series: &Series<'a>| series.samples.iter().map(|sample| sample.current);
+
+    match scale {
+        OverlayScale::Separate => series.iter().map(|s| value_range(current(s))).collect(),
+        OverlayScale::Shared => {
+            let range = value_range(series.iter().flat_map(current));
+
+            vec![range; series.len()]
+        }
+    }
+}
+
+/// Unit of all lines, or `None` if they differ
+fn shared_unit<'a>(series: &[Series<'a>]) -> Option<&'a str> {
+    let unit = series.first()?.unit;
+
+    series.iter().all(|s| s.unit == unit).then_some(unit)?
+}
+
+/// Minimum and maximum of the values, widened so that flat lines stay in the middle of the chart
+fn value_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (mut min, mut max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), val| {
+        (min.min(val), max.max(val))
+    });
+
+    if !min.is_finite() || !max.is_finite() {
+        return (-1.0, 1.0);
+    }
+
+    if (max - min).abs() < f64::EPSILON {
+        min -= 1.0;
+        max += 1.0;
+    }
+
+    (min, max)
+}
+
+fn series_color(idx: usize) -> Color32 {
+    SERIES_COLORS[idx % SERIES_COLORS.len()]
+}
+
+/// Write the samples as CSV, with a column for the target values of sensor readings
+///
+/// Sample times are converted to UTC based on `now`, the current time as both
+/// [`Instant`] and timestamp.
+pub fn write_csv<W: Write>(
+    mut writer: W,
+    samples: &VecDeque<Sample>,
+    name: &str,
+    unit: Option<&str>,
+    now: (Instant, jiff::Timestamp),
+) -> io::Result<()> {
+    let unit = unit.map(|unit| format!(" ({unit})")).unwrap_or_default();
+    let sensor = samples.iter().any(|sample| sample.target.is_some());
+
+    write!(writer, "Timestamp (UTC),{name}{unit}")?;
+
+    if sensor {
+        write!(writer, ",Target{unit}")?;
+    }
+
+    writeln!(writer)?;
+
+    for sample in samples {
+        let age = jiff::SignedDuration::try_from(now.0.duration_since(sample.time))
+            .map_err(io::Error::other)?;
+        let time = now.1.checked_sub(age).map_err(io::Error::other)?;
+
+        write!(
+            writer,
+            "{},{}",
+            time.strftime("%Y-%m-%d %H:%M:%S%.3f"),
+            sample.current
+        )?;
+
+        if sensor {
+            write!(writer, ",")?;
+
+            if let Some(target) = sample.target {
+                write!(writer, "{target}")?;
+            }
+        }
+
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+/// Label the current and target lines of sensor charts
+fn legend(ui: &Ui, rect: Rect, font: &egui::FontId) {
+    let painter = ui.painter();
+    let mut pos = rect.right_top() + Vec2::new(-4.0, 1.0);
+
+    for (label, color) in [("target", TARGET_COLOR), ("current", CURRENT_COLOR)] {
+        let galley = painter.layout_no_wrap(label.to_string(), font.clone(), color);
+
+        pos.x -= galley.size().x;
+        painter.galley(pos, galley, color);
+        pos.x -= 8.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn sensor(current: u32, target: u32) -> PropertyData {
+        PropertyData {
+            name: "Water Level".to_string(),
+            ..PropertyData::stub("water_level", PropertyValue::Sensor(current, target))
+        }
+    }
+
+    #[test]
+    fn csv_export() {
+        let start = Instant::now();
+        let samples = VecDeque::from([
+            Sample {
+                time: start,
+                current: 20.0,
+                target: Some(40.0),
+            },
+            Sample {
+                time: start + Duration::from_millis(1500),
+                current: 22.5,
+                target: None,
+            },
+        ]);
+        let now = (
+            start + Duration::from_secs(2),
+            jiff::Timestamp::from_second(1_700_000_000).expect("timestamp should be valid"),
+        );
+        let mut csv = Vec::new();
+
+        write_csv(&mut csv, &samples, "Temperature", Some("°C"), now)
+            .expect("samples should be written");
+
+        assert_eq!(
+            String::from_utf8(csv).expect("CSV should be valid UTF-8"),
+            "Timestamp (UTC),Temperature (°C),Target (°C)\n\
+             2023-11-14 22:13:18.000,20,40\n\
+             2023-11-14 22:13:19.500,22.5,\n",
+            "CSV should be correct"
+        );
+    }
+
+    #[test]
+    fn overlay_scales() {
+        let start = Instant::now();
+        let samples = |values: &[f64]| -> VecDeque<Sample> {
+            values
+                .iter()
+                .enumerate()
+                .map(|(idx, &current)| Sample {
+                    time: start + Duration::from_secs(idx as u64),
+                    current,
+                    target: None,
+                })
+                .collect()
+        };
+        let frequency = samples(&[30.0, 60.0]);
+        let temperature = samples(&[35.0, 35.0]);
+        let series = [
+            Series {
+                name: "Compressor Frequency",
+                unit: Some("Hz"),
+                samples: &frequency,
+            },
+            Series {
+                name: "Flow Temperature",
+                unit: Some("°C"),
+                samples: &temperature,
+            },
+        ];
+
+        assert_eq!(
+            overlay_ranges(&series, OverlayScale::Separate),
+            [(30.0, 60.0), (34.0, 36.0)],
+            "separate ranges should be correct"
+        );
+        assert_eq!(
+            overlay_ranges(&series, OverlayScale::Shared),
+            [(30.0, 60.0), (30.0, 60.0)],
+            "shared range should be correct"
+        );
+        assert_eq!(shared_unit(&series), None, "units should differ");
+    }
+
+    #[test]
+    fn downsampled_to_extremes() {
+        let start = Instant::now();
+        let samples: VecDeque<_> = [1.0, 5.0, 2.0, 3.0, 0.0, 4.0, 2.0, 2.0, 6.0, 1.0]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, current)| Sample {
+                time: start + Duration::from_secs(idx as u64),
+                current,
+                target: None,
+            })
+            .collect();
+        let values = |points: &[Sample]| points.iter().map(|s| s.current).collect::<Vec<_>>();
+
+        assert_eq!(
+            values(&downsample(&samples, 4)),
+            [1.0, 5.0, 0.0, 6.0, 1.0],
+            "extremes of each bucket should be kept"
+        );
+        assert_eq!(
+            downsample(&samples, samples.len()),
+            Vec::from(samples.clone()),
+            "samples that fit should be kept"
+        );
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut history = History::default();
+
+        for i in 0..=MAX_SAMPLES as u32 {
+            history.update(&[sensor(i, 40)]);
+        }
+
+        let samples = history
+            .get(PropertyId("water_level"))
+            .expect("samples should be recorded");
+
+        assert_eq!(samples.len(), MAX_SAMPLES, "history should be bounded");
+        assert_eq!(
+            samples.front().map(|s| s.current),
+            Some(1.0),
+            "oldest sample should be dropped"
+        );
+        assert_eq!(
+            samples.back().and_then(|s| s.target),
+            Some(40.0),
+            "target should be recorded"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Sessions saved to a file for inspecting them offline
+//!
+//! A saved session contains the connected device, the stored properties along with the times
+//! they were read and the action log. Opened sessions are shown read-only, without a device.
+//! Times are stored in RFC 3339 format and restored relative to the current time.
+
+use crate::worker::{DeviceInfo, PropertyData, PropertyValue};
+use freemdu::device::{self, DeviceKind, PropertyKind};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// Version of the file format, increased on incompatible changes
+pub const VERSION: u32 = 1;
+
+/// Contents of a saved session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionFile {
+    pub version: u32,
+    /// Time the session was saved
+    pub saved: String,
+    pub device: SavedDevice,
+    /// Stored properties by kind
+    pub sections: Vec<SavedSection>,
+    /// Executed actions, oldest first
+    pub actions: Vec<SavedAction>,
+}
+
+/// Device of a saved session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedDevice {
+    pub software_id: u16,
+    pub kind: DeviceKind,
+    /// Whether the device was connected in read-only mode
+    pub read_only: bool,
+    pub baud_rate: u32,
+}
+
+impl SavedDevice {
+    pub fn new(info: &DeviceInfo) -> Self {
+        Self {
+            software_id: info.software_id,
+            kind: info.kind,
+            read_only: info.read_only,
+            baud_rate: info.baud_rate,
+        }
+    }
+
+    /// Device information for the viewer, which is always read-only and has no actions
+    pub fn info(&self) -> DeviceInfo {
+        DeviceInfo {
+            software_id: self.software_id,
+            kind: self.kind,
+            actions: Vec::new(),
+            read_only: true,
+            baud_rate: self.baud_rate,
+        }
+    }
+}
+
+/// Properties of a single kind in a saved session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedSection {
+    pub kind: PropertyKind,
+    /// Time of the last update, if queried yet
+    pub updated: Option<String>,
+    pub properties: Vec<SavedProperty>,
+}
+
+/// Property in a saved session
+///
+/// The parts of the definition that aren't shown, e.g. the expected range,
+/// are looked up by ID when the session is opened.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedProperty {
+    pub id: String,
+    pub name: String,
+    pub value: PropertyValue,
+    pub unit: Option<String>,
+    pub decimals: u8,
+    pub writable: bool,
+    /// Time the property was last read
+    pub read: Option<String>,
+    /// Time the value last changed
+    pub changed: Option<String>,
+}
+
+impl SavedProperty {
+    pub fn new(data: &PropertyData, read: Option<Instant>, changed: Option<Instant>) -> Self {
+        Self {
+            id: data.id.to_string(),
+            name: data.name.clone(),
+            value: data.value.clone(),
+            unit: data.unit.clone(),
+            decimals: data.decimals,
+            writable: data.writable,
+            read: read.and_then(format_time),
+            changed: changed.and_then(format_time),
+        }
+    }
+
+    /// Property data for display, `None` if the device doesn't define the property
+    pub fn restore(self, software_id: u16) -> Option<PropertyData> {
+        let Some(prop) = device::schema(software_id).and_then(|schema| {
+            schema
+                .properties
+                .iter()
+                .find(|prop| prop.id.as_str() == self.id)
+        }) else {
+            log::warn!("Skipping unknown property {} of saved session", self.id);
+            return None;
+        };
+
+        Some(PropertyData {
+            id: prop.id,
+            name: self.name,
+            value: self.value,
+            unit: self.unit,
+            decimals: self.decimals,
+            writable: self.writable,
+            cumulative: prop.cumulative,
+            range: prop.range,
+            description: prop.description,
+            raw: None,
+        })
+    }
+}
+
+/// Entry of the action log in a saved session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedAction {
+    /// Time the result was received
+    pub time: String,
+    pub name: String,
+    pub param: Option<String>,
+    pub success: bool,
+    pub result: String,
+}
+
+/// Write a session to a file, which is overwritten if it exists
+pub fn save(path: &Path, session: &SessionFile) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(session).map_err(|e| e.to_string())?;
+
+    fs::write(path, json).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Read a session from a file
+pub fn load(path: &Path) -> Result<SessionFile, String> {
+    let src = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let session: SessionFile =
+        serde_json::from_str(&src).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    if session.version != VERSION {
+        return Err(format!(
+            "{}: unsupported version {}",
+            path.display(),
+            session.version
+        ));
+    }
+
+    Ok(session)
+}
+
+/// Format the time of an instant in RFC 3339 format
+pub fn format_time(instant: Instant) -> Option<String> {
+    jiff::Timestamp::now()
+        .checked_sub(instant.elapsed())
+        .ok()
+        .map(|time| time.to_string())
+}
+
+/// Parse a time saved by [`format_time`] into an instant,
+/// `None` if it's malformed or too long ago to be represented
+pub fn parse_time(time: &str) -> Option<Instant> {
+    let time: jiff::Timestamp = time.parse().ok()?;
+    let age = Duration::try_from(jiff::Timestamp::now().duration_since(time)).unwrap_or_default();
+
+    Instant::now().checked_sub(age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn property_restored() {
+        let data = PropertyData {
+            name: "Operating Time".to_string(),
+            raw: Some(vec![0x01]),
+            ..PropertyData::stub(
+                "operating_time",
+                PropertyValue::Duration(Duration::from_secs(3600)),
+            )
+        };
+        let read = Instant::now();
+        let json = serde_json::to_string(&SavedProperty::new(&data, Some(read), Some(read)))
+            .expect("property should serialize");
+        let saved: SavedProperty = serde_json::from_str(&json).expect("property should parse");
+
+        assert!(
+            saved
+                .read
+                .as_deref()
+                .and_then(parse_time)
+                .is_some_and(|time| time.duration_since(read) < Duration::from_secs(1)
+                    && read.duration_since(time) < Duration::from_secs(1)),
+            "read time should be restored"
+        );
+
+        let restored = saved.restore(629).expect("property should be defined");
+
+        assert_eq!(restored.value, data.value, "value should be restored");
+        assert!(restored.cumulative, "definition should be looked up by ID");
+        assert!(
+            SavedProperty::new(&data, None, None).restore(0).is_none(),
+            "property of unknown device should be skipped"
+        );
+    }
+}
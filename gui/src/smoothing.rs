@@ -0,0 +1,100 @@
+use crate::worker::{PropertyData, PropertyValue};
+use freemdu::device::PropertyId;
+use std::collections::HashMap;
+
+/// Default smoothing factor (weight of the newest sample)
+pub const DEFAULT_ALPHA: f64 = 0.3;
+
+/// Client-side exponential moving average for noisy numeric properties.
+///
+/// This is purely a display aid: the raw values stored in the property
+/// storage are never modified.
+pub struct Smoother {
+    /// Whether smoothing is enabled for properties without an override
+    pub enabled: bool,
+    /// Weight of the newest sample, in the range `(0, 1]`
+    pub alpha: f64,
+    /// Per-property overrides of the global toggle
+    overrides: HashMap<PropertyId, bool>,
+    /// Current moving average by property ID
+    averages: HashMap<PropertyId, f64>,
+}
+
+impl Default for Smoother {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            alpha: DEFAULT_ALPHA,
+            overrides: HashMap::new(),
+            averages: HashMap::new(),
+        }
+    }
+}
+
+impl Smoother {
+    /// Feed freshly queried raw values into the moving averages.
+    pub fn update(&mut self, data: &[PropertyData]) {
+        for prop in data {
+            let Some(sample) = sample(&prop.value) else {
+                continue;
+            };
+
+            self.averages
+                .entry(prop.id)
+                .and_modify(|avg| *avg += self.alpha * (sample - *avg))
+                .or_insert(sample);
+        }
+    }
+
+    /// Returns the smoothed value for display, or `None` if smoothing
+    /// is disabled or not applicable for the property.
+    // Averages of unsigned integer samples are never negative
+    #[allow(clippy::cast_sign_loss)]
+    pub fn smoothed(&self, prop: &PropertyData) -> Option<PropertyValue> {
+        if !self.is_enabled(prop.id) {
+            return None;
+        }
+
+        let avg = *self.averages.get(&prop.id)?;
+
+        match prop.value {
+            PropertyValue::Number(_) => Some(PropertyValue::Number(avg.round() as u32)),
+            PropertyValue::Float(_) => Some(PropertyValue::Float(avg)),
+            PropertyValue::Sensor(_, target) => {
+                Some(PropertyValue::Sensor(avg.round() as u32, target))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether smoothing can be applied to the given value.
+    pub fn is_applicable(value: &PropertyValue) -> bool {
+        sample(value).is_some()
+    }
+
+    pub fn is_enabled(&self, id: PropertyId) -> bool {
+        self.overrides.get(&id).copied().unwrap_or(self.enabled)
+    }
+
+    pub fn set_enabled(&mut self, id: PropertyId, enabled: bool) {
+        if enabled == self.enabled {
+            self.overrides.remove(&id);
+        } else {
+            self.overrides.insert(id, enabled);
+        }
+    }
+
+    /// Clear all moving averages, e.g. after disconnecting.
+    pub fn reset(&mut self) {
+        self.averages.clear();
+    }
+}
+
+fn sample(value: &PropertyValue) -> Option<f64> {
+    match *value {
+        PropertyValue::Number(num) => Some(f64::from(num)),
+        PropertyValue::Float(num) => Some(num),
+        PropertyValue::Sensor(current, _) => Some(f64::from(current)),
+        _ => None,
+    }
+}
@@ -0,0 +1,308 @@
+//! Stream of property updates as newline-delimited JSON
+//!
+//! Clients connect to a TCP or Unix socket and receive one JSON object per queried property,
+//! starting with the next update. Nothing is buffered for clients that connect later, and
+//! clients that don't keep up are disconnected.
+
+use crate::worker::{PropertyData, PropertyValue};
+use freemdu::device::PropertyKind;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// Prefix of listen addresses that refer to a Unix socket
+const UNIX_PREFIX: &str = "unix:";
+
+/// Interval for accepting new clients while no updates are sent
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Time allowed for writing an update to a client before it's disconnected
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Settings of the event stream
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamConfig {
+    /// Accept clients while the app is running
+    pub enabled: bool,
+    /// TCP address, e.g. `127.0.0.1:9851`, or `unix:<path>` for a Unix socket
+    pub address: String,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: "127.0.0.1:9851".to_string(),
+        }
+    }
+}
+
+/// Property update as sent to clients
+#[derive(Serialize)]
+struct Event<'a> {
+    /// Time the update was received, in RFC 3339 format
+    time: String,
+    /// Port name of the device
+    device: &'a str,
+    kind: PropertyKind,
+    id: &'a str,
+    name: &'a str,
+    value: &'a PropertyValue,
+    unit: Option<&'a str>,
+}
+
+/// Socket that connected clients are accepted on
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixListener, std::path::PathBuf),
+}
+
+impl Listener {
+    fn bind(address: &str) -> io::Result<Self> {
+        let listener = match address.strip_prefix(UNIX_PREFIX) {
+            #[cfg(unix)]
+            Some(path) => {
+                use std::os::unix::fs::FileTypeExt;
+
+                // Left behind if the app wasn't closed properly
+                if std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+                    std::fs::remove_file(path)?;
+                }
+
+                let listener = std::os::unix::net::UnixListener::bind(path)?;
+
+                listener.set_nonblocking(true)?;
+                Self::Unix(listener, path.into())
+            }
+            #[cfg(not(unix))]
+            Some(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Unix sockets aren't supported on this platform",
+                ))
+            }
+            None => {
+                let listener = TcpListener::bind(address)?;
+
+                listener.set_nonblocking(true)?;
+                Self::Tcp(listener)
+            }
+        };
+
+        Ok(listener)
+    }
+
+    /// Address clients connect to, with the actual port if any port was requested
+    fn local_addr(&self) -> io::Result<String> {
+        match self {
+            Self::Tcp(listener) => Ok(listener.local_addr()?.to_string()),
+            #[cfg(unix)]
+            Self::Unix(_, path) => Ok(format!("{UNIX_PREFIX}{}", path.display())),
+        }
+    }
+
+    /// Accept a waiting client, `None` if there is none
+    fn accept(&self) -> io::Result<Option<Box<dyn Write + Send>>> {
+        let res = match self {
+            Self::Tcp(listener) => listener.accept().and_then(|(stream, _)| {
+                // Accepted sockets inherit non-blocking mode on some platforms
+                stream.set_nonblocking(false)?;
+                stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+                Ok(Box::new(stream) as Box<dyn Write + Send>)
+            }),
+            #[cfg(unix)]
+            Self::Unix(listener, _) => listener.accept().and_then(|(stream, _)| {
+                stream.set_nonblocking(false)?;
+                stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+                Ok(Box::new(stream) as Box<dyn Write + Send>)
+            }),
+        };
+
+        match res {
+            Ok(client) => Ok(Some(client)),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Self::Unix(_, path) = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Server sending property updates to connected clients as newline-delimited JSON.
+///
+/// Updates are written by a background thread, which stops once the stream is dropped.
+pub struct EventStream {
+    config: StreamConfig,
+    address: String,
+    tx: Sender<String>,
+}
+
+impl EventStream {
+    /// Listen at the configured address
+    pub fn start(config: StreamConfig) -> io::Result<Self> {
+        let listener = Listener::bind(&config.address)?;
+        let address = listener.local_addr()?;
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || run(&listener, &rx));
+
+        Ok(Self {
+            config,
+            address,
+            tx,
+        })
+    }
+
+    pub fn config(&self) -> &StreamConfig {
+        &self.config
+    }
+
+    /// Address clients connect to
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Send freshly queried values of a device, identified by its port name
+    pub fn publish(&self, device: &str, kind: PropertyKind, data: &[PropertyData]) {
+        let lines = format_events(device, kind, data, jiff::Timestamp::now());
+
+        if !lines.is_empty() {
+            let _ = self.tx.send(lines);
+        }
+    }
+}
+
+/// Accept clients and send them the queued updates until the stream is dropped
+fn run(listener: &Listener, rx: &Receiver<String>) {
+    let mut clients = Vec::new();
+
+    loop {
+        loop {
+            match listener.accept() {
+                Ok(Some(client)) => clients.push(client),
+                Ok(None) => break,
+                Err(e) => {
+                    log::warn!("Failed to accept event stream client: {e}");
+                    break;
+                }
+            }
+        }
+
+        match rx.recv_timeout(ACCEPT_POLL_INTERVAL) {
+            Ok(lines) => {
+                clients.retain_mut(|client| match client.write_all(lines.as_bytes()) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        log::debug!("Disconnecting event stream client: {e}");
+                        false
+                    }
+                });
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+/// Format the values as newline-delimited JSON, one object per property
+fn format_events(
+    device: &str,
+    kind: PropertyKind,
+    data: &[PropertyData],
+    now: jiff::Timestamp,
+) -> String {
+    let time = now.to_string();
+
+    data.iter()
+        .filter_map(|prop| {
+            let event = Event {
+                time: time.clone(),
+                device,
+                kind,
+                id: prop.id.as_str(),
+                name: &prop.name,
+                value: &prop.value,
+                unit: prop.unit.as_deref(),
+            };
+
+            serde_json::to_string(&event).ok()
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpStream;
+
+    fn temperature() -> PropertyData {
+        PropertyData {
+            name: "Temperature".to_string(),
+            unit: Some("°C".to_string()),
+            ..PropertyData::stub("temperature", PropertyValue::Sensor(42, 60))
+        }
+    }
+
+    #[test]
+    fn events_formatted() {
+        let now = jiff::Timestamp::from_second(1_700_000_000).expect("timestamp should be valid");
+
+        assert_eq!(
+            format_events(
+                "/dev/ttyUSB0",
+                PropertyKind::Operation,
+                &[temperature()],
+                now
+            ),
+            "{\"time\":\"2023-11-14T22:13:20Z\",\"device\":\"/dev/ttyUSB0\",\
+             \"kind\":\"Operation\",\"id\":\"temperature\",\"name\":\"Temperature\",\
+             \"value\":{\"type\":\"Sensor\",\"value\":[42,60]},\"unit\":\"°C\"}\n",
+            "events should be correct"
+        );
+    }
+
+    #[test]
+    fn stream_events() {
+        let stream = EventStream::start(StreamConfig {
+            enabled: true,
+            address: "127.0.0.1:0".to_string(),
+        })
+        .expect("stream should start");
+        let client = TcpStream::connect(stream.address()).expect("stream should accept");
+        let mut reader = BufReader::new(&client);
+        let mut line = String::new();
+
+        client
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .expect("timeout should be set");
+
+        // Updates sent before the client is accepted aren't received
+        for _ in 0..20 {
+            stream.publish("/dev/ttyUSB0", PropertyKind::Operation, &[temperature()]);
+
+            if reader.read_line(&mut line).is_ok_and(|len| len > 0) {
+                break;
+            }
+        }
+
+        let event: serde_json::Value = serde_json::from_str(&line).expect("event should be JSON");
+
+        assert_eq!(event["id"], "temperature", "event should be received");
+    }
+}
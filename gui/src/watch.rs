@@ -0,0 +1,349 @@
+use crate::worker::PropertyValue;
+
+/// Storage key for persisting watch expressions
+pub const STORAGE_KEY: &str = "watch_expressions";
+
+/// Separator between an expression and its unit in storage
+const UNIT_SEPARATOR: char = '|';
+
+/// Arithmetic expression over property values, e.g. `temp_out - temp_in`
+#[derive(Debug, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Property(String),
+    Neg(Box<Expr>),
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(Op),
+    LParen,
+    RParen,
+}
+
+impl Expr {
+    /// Parse an expression consisting of numbers, property IDs or names in double quotes,
+    /// `+ - * /` and parentheses.
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let tokens = tokenize(src)?;
+        let mut pos = 0;
+        let expr = parse_sum(&tokens, &mut pos)?;
+
+        if pos < tokens.len() {
+            return Err("Unexpected input after expression".to_string());
+        }
+
+        Ok(expr)
+    }
+
+    /// Evaluate the expression, looking up property values by ID or name.
+    ///
+    /// Returns `None` if any referenced property is missing or not numeric,
+    /// or if the result is not a finite number (e.g. division by zero).
+    pub fn eval(&self, lookup: &impl Fn(&str) -> Option<f64>) -> Option<f64> {
+        let val = match self {
+            Expr::Number(num) => *num,
+            Expr::Property(id) => lookup(id)?,
+            Expr::Neg(expr) => -expr.eval(lookup)?,
+            Expr::Binary(lhs, op, rhs) => {
+                let (lhs, rhs) = (lhs.eval(lookup)?, rhs.eval(lookup)?);
+
+                match op {
+                    Op::Add => lhs + rhs,
+                    Op::Sub => lhs - rhs,
+                    Op::Mul => lhs * rhs,
+                    Op::Div => lhs / rhs,
+                }
+            }
+        };
+
+        val.is_finite().then_some(val)
+    }
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '0'..='9' | '.' => {
+                let mut end = start;
+
+                while let Some(&(idx, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || c == '.') {
+                        break;
+                    }
+
+                    end = idx + c.len_utf8();
+                    chars.next();
+                }
+
+                let num = &src[start..end];
+
+                Token::Number(num.parse().map_err(|_| format!("Invalid number: {num}"))?)
+            }
+            '"' => {
+                let rest = &src[start + 1..];
+                let name = &rest[..rest.find('"').ok_or("Missing closing quote")?];
+
+                // Skip the name along with both quotes
+                for _ in 0..name.chars().count() + 2 {
+                    chars.next();
+                }
+
+                Token::Ident(name.to_string())
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = start;
+
+                while let Some(&(idx, c)) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+
+                    end = idx + c.len_utf8();
+                    chars.next();
+                }
+
+                Token::Ident(src[start..end].to_string())
+            }
+            _ => {
+                chars.next();
+
+                match c {
+                    '+' => Token::Op(Op::Add),
+                    '-' => Token::Op(Op::Sub),
+                    '*' => Token::Op(Op::Mul),
+                    '/' => Token::Op(Op::Div),
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => return Err(format!("Unexpected character: {c}")),
+                }
+            }
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// sum := product (('+' | '-') product)*
+fn parse_sum(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+    let mut expr = parse_product(tokens, pos)?;
+
+    while let Some(Token::Op(op @ (Op::Add | Op::Sub))) = tokens.get(*pos) {
+        *pos += 1;
+        expr = Expr::Binary(Box::new(expr), *op, Box::new(parse_product(tokens, pos)?));
+    }
+
+    Ok(expr)
+}
+
+/// product := factor (('*' | '/') factor)*
+fn parse_product(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+    let mut expr = parse_factor(tokens, pos)?;
+
+    while let Some(Token::Op(op @ (Op::Mul | Op::Div))) = tokens.get(*pos) {
+        *pos += 1;
+        expr = Expr::Binary(Box::new(expr), *op, Box::new(parse_factor(tokens, pos)?));
+    }
+
+    Ok(expr)
+}
+
+/// factor := '-' factor | number | property | '(' sum ')'
+fn parse_factor(tokens: &[Token], pos: &mut usize) -> Result<Expr, String> {
+    let token = tokens.get(*pos).ok_or("Unexpected end of expression")?;
+
+    *pos += 1;
+
+    match token {
+        Token::Op(Op::Sub) => Ok(Expr::Neg(Box::new(parse_factor(tokens, pos)?))),
+        Token::Number(num) => Ok(Expr::Number(*num)),
+        Token::Ident(id) => Ok(Expr::Property(id.clone())),
+        Token::LParen => {
+            let expr = parse_sum(tokens, pos)?;
+
+            if tokens.get(*pos) != Some(&Token::RParen) {
+                return Err("Missing closing parenthesis".to_string());
+            }
+
+            *pos += 1;
+
+            Ok(expr)
+        }
+        _ => Err("Expected number, property or parenthesis".to_string()),
+    }
+}
+
+/// Named expression defined by the user
+pub struct WatchExpression {
+    pub name: String,
+    pub source: String,
+    /// Unit shown after the value, if any
+    pub unit: Option<String>,
+    pub expr: Result<Expr, String>,
+}
+
+impl WatchExpression {
+    pub fn new(name: String, source: String, unit: Option<String>) -> Self {
+        let expr = Expr::parse(&source);
+
+        Self {
+            name,
+            source,
+            unit,
+            expr,
+        }
+    }
+
+    /// Returns whether the parts can be stored, see [`serialize`]
+    pub fn is_storable(name: &str, source: &str, unit: &str) -> bool {
+        !name.trim().is_empty()
+            && !name.contains('=')
+            && !source.trim().is_empty()
+            && !source.contains(UNIT_SEPARATOR)
+            && !unit.contains(UNIT_SEPARATOR)
+    }
+}
+
+/// Numeric value of a property for use in expressions
+pub fn numeric_value(value: &PropertyValue) -> Option<f64> {
+    match value {
+        PropertyValue::Bool(b) => Some(f64::from(u8::from(*b))),
+        PropertyValue::Number(num) | PropertyValue::Sensor(num, _) => Some(f64::from(*num)),
+        PropertyValue::Float(num) => Some(*num),
+        PropertyValue::Duration(dur) => Some(dur.as_secs_f64()),
+        PropertyValue::String(s) => s.trim().parse().ok(),
+        PropertyValue::Flags(_) => None,
+    }
+}
+
+/// Serialize expressions for storage, one `name = expression` per line,
+/// followed by `| unit` if the expression has a unit
+pub fn serialize(exprs: &[WatchExpression]) -> String {
+    exprs
+        .iter()
+        .map(|watch| match &watch.unit {
+            Some(unit) => format!("{} = {} {UNIT_SEPARATOR} {unit}", watch.name, watch.source),
+            None => format!("{} = {}", watch.name, watch.source),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Deserialize expressions saved by [`serialize`], skipping malformed lines
+pub fn deserialize(src: &str) -> Vec<WatchExpression> {
+    src.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(name, rest)| {
+            let (source, unit) = match rest.split_once(UNIT_SEPARATOR) {
+                Some((source, unit)) => (source, Some(unit.trim().to_string())),
+                None => (rest, None),
+            };
+
+            WatchExpression::new(name.trim().to_string(), source.trim().to_string(), unit)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(id: &str) -> Option<f64> {
+        match id {
+            "temp_in" => Some(20.0),
+            "temp_out" => Some(35.5),
+            "zero" => Some(0.0),
+            "Flow Temperature" => Some(40.0),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn eval_precedence() {
+        let expr =
+            Expr::parse("2 + 3 * (temp_out - temp_in) / -3").expect("expression should parse");
+
+        assert_eq!(expr.eval(&lookup), Some(-13.5), "result should be correct");
+    }
+
+    #[test]
+    fn eval_quoted_names() {
+        let expr = Expr::parse("\"Flow Temperature\" - temp_in").expect("expression should parse");
+
+        assert_eq!(
+            expr.eval(&lookup),
+            Some(20.0),
+            "quoted name should be looked up"
+        );
+    }
+
+    #[test]
+    fn eval_missing_inputs() {
+        let missing = Expr::parse("temp_out - unknown").expect("expression should parse");
+        let div_zero = Expr::parse("temp_in / zero").expect("expression should parse");
+
+        assert_eq!(
+            missing.eval(&lookup),
+            None,
+            "missing property should yield no value"
+        );
+        assert_eq!(
+            div_zero.eval(&lookup),
+            None,
+            "division by zero should yield no value"
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        for src in ["", "1 +", "(1 + 2", "1 2", "a % b", "\"Flow Temperature"] {
+            assert!(Expr::parse(src).is_err(), "'{src}' should not parse");
+        }
+    }
+
+    #[test]
+    fn storage_round_trip() {
+        let exprs = vec![
+            WatchExpression::new(
+                "Delta T".to_string(),
+                "temp_out - temp_in".to_string(),
+                Some("°C".to_string()),
+            ),
+            WatchExpression::new("Double".to_string(), "temp_in * 2".to_string(), None),
+        ];
+        let restored = deserialize(&serialize(&exprs));
+
+        assert_eq!(restored.len(), 2, "all expressions should be restored");
+        assert_eq!(restored[0].name, "Delta T", "name should be correct");
+        assert_eq!(
+            restored[1].source, "temp_in * 2",
+            "source should be correct"
+        );
+        assert_eq!(
+            restored[0].unit.as_deref(),
+            Some("°C"),
+            "unit should be restored"
+        );
+        assert_eq!(restored[1].unit, None, "missing unit should be restored");
+    }
+}
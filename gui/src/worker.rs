@@ -1,43 +1,538 @@
-use crate::app::ActionInfo;
-use freemdu::device::{DeviceKind, PropertyKind, Value};
-use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use crate::app::{canonical_port, ActionInfo};
+use crate::csv_log::CsvLogger;
+use freemdu::device::{
+    self, Action, Decode, Device, DeviceKind, FaultRecord, Property, PropertyId, PropertyKind,
+    RangeStatus, Value, ValueRange,
+};
+use freemdu::embedded_io_async::{Read, Write};
+use freemdu::mock::MockPort;
+use freemdu::observer::FrameObserver;
+use freemdu::replay::{RecordingObserver, ReplayEnd};
+use freemdu::serial::{Port, SerialConfig};
+use freemdu::{Interface, TransportStats};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::Arc;
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Interval between progress updates of a running action
+const ACTION_PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Default time allowed for connecting to the device
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default time allowed for querying a single property
+const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Number of most recent round trips averaged for the reported latency
+const LATENCY_SAMPLES: usize = 20;
+
+/// Number of reconnection attempts after the serial link was lost
+pub const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+
+/// Delay before the first reconnection attempt, doubled after each failure
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Upper limit for the delay between reconnection attempts
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(4);
+
+/// Time without incoming data after which the input is considered drained
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Time to wait for the worker thread to close the port when the handle is dropped
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Interval for checking whether the worker thread has stopped
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Time to collect the reply to the identify frame sent after a connection timeout
+const PROBE_WINDOW: Duration = Duration::from_millis(300);
+
+/// Maximum number of bytes collected while probing the port
+const PROBE_LIMIT: usize = 64;
+
+/// Software ID request including its checksum, as sent by [`Interface::query_software_id`]
+const IDENTIFY_FRAME: [u8; 5] = [0x11, 0x00, 0x00, 0x02, 0x13];
+
+/// Consecutive failed queries after which a property is no longer polled
+const UNSUPPORTED_FAILURES: u32 = 3;
+
+/// Interval for checking the command queue while waiting for the device
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Port name that connects to an emulated device instead of a serial port
+pub const DEMO_PORT: &str = "Demo mode";
+
+/// Prefix of port names that connect to a serial-to-TCP bridge, followed by `host:port`
+pub const TCP_PREFIX: &str = "tcp://";
+
+/// Prefix of port names that replay a recorded session, followed by the path of the recording
+pub const REPLAY_PREFIX: &str = "replay:";
 
 /// Commands sent from UI to worker
 #[derive(Debug)]
 pub enum WorkerCommand {
     QueryProperties(PropertyKind),
-    TriggerAction(String, Option<String>),
+    QueryProperty(PropertyId),
+    /// Read the faults stored by the device
+    QueryFaultHistory,
+    /// Change the value of a writable property and query it again
+    SetProperty(PropertyId, PropertyValue),
+    /// Trigger an action with an optional parameter, waiting at most the given duration
+    TriggerAction(String, Option<String>, Duration),
+    /// Send a raw request to the device, bypassing the property and action definitions
+    RawTransaction(Vec<u8>),
+    /// Append every batch of queried properties to a CSV file
+    StartLogging(PathBuf),
+    StopLogging,
+    /// Drop the property refresh that is currently running, along with queued ones
+    Cancel,
+    /// Poll the properties that are no longer polled again, e.g. after a firmware update
+    ProbeUnsupported,
+    /// Skip the properties with the given kind and name in property refreshes,
+    /// starting with the next one
+    SetUnpolled(Vec<(PropertyKind, String)>),
+    /// Apply new settings to the serial port, keeping the session along with its CSV log
+    ///
+    /// Falls back to reopening the port if the settings can't be changed while it's open.
+    Reconfigure(SerialConfig),
     Disconnect,
 }
 
+impl WorkerCommand {
+    /// Whether the command is interactive, so that it preempts property refreshes
+    fn interrupts_refresh(&self) -> bool {
+        matches!(
+            self,
+            Self::QueryProperty(_)
+                | Self::SetProperty(..)
+                | Self::TriggerAction(..)
+                | Self::RawTransaction(_)
+                | Self::Cancel
+                | Self::Reconfigure(_)
+                | Self::Disconnect
+        )
+    }
+}
+
+/// Commands from the UI, including those received while refreshing properties
+struct CommandQueue<'a> {
+    rx: &'a Receiver<WorkerCommand>,
+    /// Commands received while refreshing, handled before new ones
+    pending: VecDeque<WorkerCommand>,
+    /// Kinds still to be queried once after connecting, each while no command is waiting
+    warm_up: VecDeque<PropertyKind>,
+}
+
+/// Reason for stopping a running property refresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Interrupt {
+    /// An interactive command is waiting, the refresh continues afterwards
+    Preempted,
+    /// The refresh was canceled or the UI is gone
+    Canceled,
+}
+
+impl<'a> CommandQueue<'a> {
+    fn new(rx: &'a Receiver<WorkerCommand>) -> Self {
+        Self {
+            rx,
+            pending: VecDeque::new(),
+            warm_up: VecDeque::new(),
+        }
+    }
+
+    /// Query all kinds of properties in the given order before any requested refresh
+    fn warming_up(mut self, order: WarmUpOrder) -> Self {
+        self.warm_up = order.0.into();
+        self
+    }
+
+    /// Receive the next command, interactive ones first
+    ///
+    /// Queued refreshes are handled in order once no interactive command is waiting.
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<WorkerCommand, RecvTimeoutError> {
+        self.receive();
+        self.cancel();
+
+        let idx = self
+            .pending
+            .iter()
+            .position(WorkerCommand::interrupts_refresh)
+            .unwrap_or(0);
+
+        match self.pending.remove(idx) {
+            Some(cmd) => Ok(cmd),
+            None => self.rx.recv_timeout(timeout),
+        }
+    }
+
+    /// Receive the commands sent in the meantime, returning whether and how
+    /// a running property refresh should be interrupted.
+    ///
+    /// Refreshes requested before a [`WorkerCommand::Cancel`] are dropped.
+    fn refresh_interrupted(&mut self) -> Option<Interrupt> {
+        if !self.receive() || self.cancel() {
+            return Some(Interrupt::Canceled);
+        }
+
+        self.pending
+            .iter()
+            .any(WorkerCommand::interrupts_refresh)
+            .then_some(Interrupt::Preempted)
+    }
+
+    /// Next kind to query once after connecting, none once all were queried or canceled
+    fn next_warm_up(&mut self) -> Option<PropertyKind> {
+        self.warm_up.pop_front()
+    }
+
+    /// Move the commands sent in the meantime to the pending ones
+    ///
+    /// Returns `false` if the UI has disconnected.
+    fn receive(&mut self) -> bool {
+        loop {
+            match self.rx.try_recv() {
+                Ok(cmd) => self.pending.push_back(cmd),
+                Err(TryRecvError::Empty) => return true,
+                Err(TryRecvError::Disconnected) => return false,
+            }
+        }
+    }
+
+    /// Drop the refreshes requested before the last [`WorkerCommand::Cancel`],
+    /// along with the remaining warm-up, returning whether there was one.
+    fn cancel(&mut self) -> bool {
+        let Some(idx) = self
+            .pending
+            .iter()
+            .rposition(|cmd| matches!(cmd, WorkerCommand::Cancel))
+        else {
+            return false;
+        };
+        let later = self.pending.split_off(idx + 1);
+
+        self.pending.retain(|cmd| {
+            !matches!(
+                cmd,
+                WorkerCommand::QueryProperties(_) | WorkerCommand::Cancel
+            )
+        });
+        self.pending.extend(later);
+        self.warm_up.clear();
+
+        true
+    }
+}
+
 /// Responses sent from worker to UI
 #[derive(Debug)]
 pub enum WorkerResponse {
     Connected(DeviceInfo),
     Properties(PropertyKind, Vec<PropertyData>),
-    ActionResult(String, bool, String),
-    Error(String),
+    Property(PropertyData),
+    FaultHistory(Result<Vec<FaultRecord>, String>),
+    /// Result of changing a property, by property name
+    PropertyWritten(String, Result<(), String>),
+    ActionProgress(ActionProgress),
+    ActionResult(ActionOutcome),
+    /// Reply to a raw request, along with the request itself
+    RawResponse(Vec<u8>, Result<Vec<u8>, String>),
+    /// The serial link was lost, reconnection attempt number (starting at 1)
+    Reconnecting(u32),
+    /// New port settings couldn't be applied, the previous ones are used again
+    ReconfigureFailed(String),
+    /// Writing the CSV log failed, logging has stopped
+    LoggingError(String),
+    /// Link quality since connecting, sent after each batch of queried properties
+    LinkStats {
+        stats: TransportStats,
+        /// Time the device was connected, reset when reconnecting
+        connected: Instant,
+    },
+    /// Round-trip time of property queries, sent after each batch of queried properties
+    Latency {
+        avg_ms: f64,
+        last_ms: f64,
+    },
+    /// Properties that are no longer polled by ID, kind and name, sent whenever they change
+    Unsupported(Vec<(PropertyId, PropertyKind, String)>),
+    Error(ConnectError),
     Disconnected,
 }
 
+/// Options for connecting to a device
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConnectOptions {
+    /// Connect in read-only mode, without actions
+    pub read_only: bool,
+    /// Try all supported baud rates if the device doesn't respond at the default one
+    pub auto_baud: bool,
+    /// Retries of property queries that time out
+    pub retry: RetryPolicy,
+    /// Time allowed for each operation
+    pub timeouts: Timeouts,
+    /// Behavior at the end of a replayed recording
+    pub replay_end: ReplayEnd,
+    /// Order in which all properties are queried once after connecting
+    pub warm_up: WarmUpOrder,
+}
+
+/// Order in which each kind of properties is queried once after connecting,
+/// so that every section is filled before the refresh intervals of the UI take over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WarmUpOrder(pub [PropertyKind; 5]);
+
+impl Default for WarmUpOrder {
+    fn default() -> Self {
+        // Changing values first, general information rarely changes
+        Self([
+            PropertyKind::Io,
+            PropertyKind::Operation,
+            PropertyKind::Failure,
+            PropertyKind::Warning,
+            PropertyKind::General,
+        ])
+    }
+}
+
+impl WarmUpOrder {
+    /// Whether every kind is listed exactly once
+    pub fn is_valid(self) -> bool {
+        self.0
+            .iter()
+            .enumerate()
+            .all(|(idx, kind)| !self.0[..idx].contains(kind))
+    }
+
+    /// Swap the kind at the given position with the one before it
+    pub fn move_earlier(&mut self, idx: usize) {
+        if (1..self.0.len()).contains(&idx) {
+            self.0.swap(idx - 1, idx);
+        }
+    }
+}
+
+/// Time allowed for operations on the device
+///
+/// Slow links, e.g. serial bridges over TCP, may need longer timeouts than the defaults.
+/// Actions have their own timeout, which is chosen for each action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Time allowed for connecting, which includes unlocking the device
+    pub connect: Duration,
+    /// Time allowed for querying a single property, and for other single requests
+    pub query: Duration,
+    /// Maximum gap between the fragments of a frame, or `None` to only limit whole requests
+    ///
+    /// Only applies to serial ports, see [`SerialConfig::inter_byte_timeout`].
+    pub inter_byte: Option<Duration>,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: DEFAULT_CONNECT_TIMEOUT,
+            query: DEFAULT_QUERY_TIMEOUT,
+            inter_byte: None,
+        }
+    }
+}
+
+/// Retry behavior for property queries that time out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt
+    pub retries: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { retries: 2 }
+    }
+}
+
+impl RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub fn attempts(self) -> u32 {
+        self.retries + 1
+    }
+}
+
 /// Device information
 #[derive(Debug, Clone)]
 pub struct DeviceInfo {
     pub software_id: u16,
     pub kind: DeviceKind,
     pub actions: Vec<ActionInfo>,
+    /// Whether the device was connected in read-only mode
+    pub read_only: bool,
+    /// Baud rate the connection was established at
+    pub baud_rate: u32,
 }
 
-/// Cloneable property value for UI display
+#[cfg(test)]
+impl DeviceInfo {
+    /// Information of a washing machine connected at the default baud rate
+    pub fn stub(software_id: u16) -> Self {
+        Self {
+            software_id,
+            kind: DeviceKind::WashingMachine,
+            actions: Vec::new(),
+            read_only: false,
+            baud_rate: 2400,
+        }
+    }
+}
+
+/// The serial link failed, so the device has to be reconnected
+#[derive(Debug)]
+struct LinkLost(String);
+
+/// Reason for a connection session to end
+enum SessionEnd {
+    /// Disconnect requested by the UI
+    Closed,
+    LinkLost(String),
+    /// New port settings requested by the UI, the session continues with them
+    Reconfigure(SerialConfig),
+}
+
+/// Error connecting to a device
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectError {
+    /// The worker's async runtime couldn't be created
+    Runtime(String),
+    /// The port is in use by another program
+    PortBusy(String),
+    /// The port couldn't be opened
+    Port(String),
+    /// The device didn't respond in time
+    Timeout,
+    /// Data was received at the given baud rate, but it isn't a valid response
+    UnexpectedResponse(u32),
+    /// The device responded with an unknown software ID
+    UnsupportedDevice(u16),
+    /// The device was recognized, but nothing is defined for it yet
+    UnsupportedKind(DeviceKind, u16),
+    /// The device didn't respond as expected
+    Device(String),
+    /// The file for recording the session couldn't be created
+    Recording(String),
+}
+
+impl ConnectError {
+    /// Returns whether the port itself is unusable, so retrying at another baud rate won't help
+    fn is_port_error(&self) -> bool {
+        matches!(self, Self::PortBusy(_) | Self::Port(_))
+    }
+}
+
+impl fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Runtime(e) => write!(f, "Failed to create runtime: {e}"),
+            Self::PortBusy(port) => write!(f, "Port {port} is in use by another program"),
+            Self::Port(e) => write!(f, "Failed to open port: {e}"),
+            Self::Timeout => write!(f, "Connection timeout"),
+            Self::UnexpectedResponse(baud_rate) => {
+                write!(f, "Unexpected response at {baud_rate} baud")
+            }
+            Self::UnsupportedDevice(id) => write!(f, "Unsupported device (software ID {id})"),
+            Self::UnsupportedKind(kind, id) => write!(f, "Unsupported {kind} (software ID {id})"),
+            Self::Device(e) => write!(f, "Failed to connect: {e}"),
+            Self::Recording(e) => write!(f, "Failed to create recording: {e}"),
+        }
+    }
+}
+
+/// Outcome of a triggered action
+#[derive(Debug, Clone)]
+pub struct ActionOutcome {
+    pub id: String,
+    pub name: String,
+    /// Argument as entered by the user, if any
+    pub param: Option<String>,
+    pub success: bool,
+    pub message: String,
+    /// Value returned by the action, if any
+    pub value: Option<PropertyValue>,
+}
+
+/// Progress of a running action
 #[derive(Debug, Clone)]
+pub struct ActionProgress {
+    pub id: String,
+    /// Time spent waiting for the device so far
+    pub elapsed: Duration,
+    /// Time after which the action is reported as timed out
+    pub timeout: Duration,
+}
+
+impl ActionProgress {
+    /// Fraction of the timeout that has elapsed, in the range `[0, 1]`
+    pub fn fraction(&self) -> f32 {
+        if self.timeout.is_zero() {
+            return 1.0;
+        }
+
+        (self.elapsed.as_secs_f32() / self.timeout.as_secs_f32()).min(1.0)
+    }
+}
+
+/// Cloneable property value for UI display
+///
+/// Serialized with the variant name as tag, so that values can be restored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub enum PropertyValue {
     Bool(bool),
     Number(u32),
+    Float(f64),
     Sensor(u32, u32),
     String(String),
     Duration(std::time::Duration),
+    /// Labeled bits of a bitfield and whether they are set
+    Flags(Vec<(String, bool)>),
+}
+
+impl PropertyValue {
+    /// Convert a queried value, splitting bitfields into their labeled bits
+    pub fn from_property(prop: &Property, value: &Value) -> Self {
+        match (prop.decoding, value) {
+            (Some(Decode::Bitfield(labels)), Value::String(s)) => {
+                let set: Vec<_> = s.split(FLAG_SEPARATOR).collect();
+
+                Self::Flags(
+                    labels
+                        .iter()
+                        .map(|label| ((*label).to_string(), set.contains(label)))
+                        .collect(),
+                )
+            }
+            _ => value.into(),
+        }
+    }
+}
+
+/// Separator between the labels of set bits, as used by [`Property::decode`]
+const FLAG_SEPARATOR: &str = " | ";
+
+/// Labels of the set bits of a bitfield, in the format used by [`Property::decode`]
+pub fn set_flags(flags: &[(String, bool)]) -> String {
+    flags
+        .iter()
+        .filter(|(_, set)| *set)
+        .map(|(label, _)| label.as_str())
+        .collect::<Vec<_>>()
+        .join(FLAG_SEPARATOR)
 }
 
 impl From<&Value> for PropertyValue {
@@ -45,6 +540,7 @@ impl From<&Value> for PropertyValue {
         match value {
             Value::Bool(b) => PropertyValue::Bool(*b),
             Value::Number(n) => PropertyValue::Number(*n),
+            Value::Float(n) => PropertyValue::Float(*n),
             Value::Sensor(a, b) => PropertyValue::Sensor(*a, *b),
             Value::String(s) => PropertyValue::String(s.clone()),
             Value::Duration(d) => PropertyValue::Duration(*d),
@@ -52,36 +548,111 @@ impl From<&Value> for PropertyValue {
     }
 }
 
+impl From<&PropertyValue> for Value {
+    fn from(value: &PropertyValue) -> Self {
+        match value {
+            PropertyValue::Bool(b) => Value::Bool(*b),
+            PropertyValue::Number(n) => Value::Number(*n),
+            PropertyValue::Float(n) => Value::Float(*n),
+            PropertyValue::Sensor(a, b) => Value::Sensor(*a, *b),
+            PropertyValue::String(s) => Value::String(s.clone()),
+            PropertyValue::Duration(d) => Value::Duration(*d),
+            PropertyValue::Flags(flags) => Value::String(set_flags(flags)),
+        }
+    }
+}
+
 /// Property data for display
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PropertyData {
+    pub id: PropertyId,
     pub name: String,
     pub value: PropertyValue,
     pub unit: Option<String>,
+    /// Number of decimal places to display for fractional values
+    pub decimals: u8,
+    /// Whether the property can be changed using [`WorkerCommand::SetProperty`]
+    pub writable: bool,
+    /// Whether durations are lifetime counters, not exported as it's part of the definition
+    #[serde(skip)]
+    pub cumulative: bool,
+    /// Expected range of the value, not exported as it's part of the definition
+    #[serde(skip)]
+    pub range: Option<ValueRange>,
+    /// Explanation of the property's meaning, not exported as it's part of the definition
+    #[serde(skip)]
+    pub description: Option<&'static str>,
+    /// Data the value was decoded from, if it was queried from the device, not exported
+    #[serde(skip)]
+    pub raw: Option<Vec<u8>>,
+}
+
+impl PropertyData {
+    /// Checks the value against the expected range, see [`Property::range_status`]
+    pub fn range_status(&self) -> Option<RangeStatus> {
+        let range = self.range?;
+        let val = match self.value {
+            PropertyValue::Number(num) | PropertyValue::Sensor(num, _) => f64::from(num),
+            PropertyValue::Float(num) => num,
+            _ => return None,
+        };
+
+        Some(range.status(val))
+    }
+
+    /// Data of a read-only property named like its ID, without unit or range
+    #[cfg(test)]
+    pub fn stub(id: &'static str, value: PropertyValue) -> Self {
+        Self {
+            id: PropertyId(id),
+            name: id.to_string(),
+            value,
+            unit: None,
+            decimals: 0,
+            writable: false,
+            cumulative: false,
+            range: None,
+            description: None,
+            raw: None,
+        }
+    }
 }
 
 /// Handle to communicate with the worker thread
 pub struct WorkerHandle {
     tx: Sender<WorkerCommand>,
     rx: Receiver<WorkerResponse>,
-    #[allow(dead_code)]
-    handle: JoinHandle<()>,
+    /// Set to stop waiting for the currently running action
+    abort: Arc<AtomicBool>,
+    /// Joined when the handle is dropped
+    handle: Option<JoinHandle<()>>,
 }
 
 impl WorkerHandle {
-    pub fn new(port_name: &str) -> Self {
+    /// Connect to the device at the given port, recording the session to a file if given
+    pub fn new(port_name: &str, options: ConnectOptions, recording: Option<PathBuf>) -> Self {
         let (cmd_tx, cmd_rx) = mpsc::channel();
         let (resp_tx, resp_rx) = mpsc::channel();
         let port_name = port_name.to_string();
+        let abort = Arc::new(AtomicBool::new(false));
+        let worker_abort = Arc::clone(&abort);
 
         let handle = thread::spawn(move || {
-            run_worker(&port_name, cmd_rx, resp_tx);
+            run_worker(
+                &port_name,
+                options,
+                recording,
+                &worker_abort,
+                cmd_rx,
+                resp_tx,
+            );
         });
 
         Self {
             tx: cmd_tx,
             rx: resp_rx,
-            handle,
+            abort,
+            handle: Some(handle),
         }
     }
 
@@ -89,6 +660,13 @@ impl WorkerHandle {
         let _ = self.tx.send(cmd);
     }
 
+    /// Stop waiting for the currently running action.
+    ///
+    /// This bypasses the command queue, which is blocked while the action runs.
+    pub fn abort_action(&self) {
+        self.abort.store(true, Ordering::Relaxed);
+    }
+
     pub fn try_recv(&self) -> Option<WorkerResponse> {
         match self.rx.try_recv() {
             Ok(resp) => Some(resp),
@@ -96,17 +674,48 @@ impl WorkerHandle {
             Err(TryRecvError::Disconnected) => Some(WorkerResponse::Disconnected),
         }
     }
+
+    /// Stop the worker thread, waiting briefly so that the port is closed when this returns.
+    ///
+    /// Returns `false` if the thread didn't stop in time and was detached.
+    fn shutdown(&mut self) -> bool {
+        let _ = self.tx.send(WorkerCommand::Disconnect);
+        // Running actions block the command queue
+        self.abort_action();
+
+        let Some(handle) = self.handle.take() else {
+            return true;
+        };
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+
+        while !handle.is_finished() {
+            if Instant::now() >= deadline {
+                log::warn!("Worker thread didn't stop in time, detaching it");
+                return false;
+            }
+
+            thread::sleep(SHUTDOWN_POLL_INTERVAL);
+        }
+
+        handle.join().is_ok()
+    }
 }
 
 impl Drop for WorkerHandle {
     fn drop(&mut self) {
-        let _ = self.tx.send(WorkerCommand::Disconnect);
+        self.shutdown();
     }
 }
 
 /// Run the worker thread - connects to device and handles commands
-#[allow(clippy::too_many_lines)]
-fn run_worker(port_name: &str, cmd_rx: Receiver<WorkerCommand>, resp_tx: Sender<WorkerResponse>) {
+fn run_worker(
+    port_name: &str,
+    options: ConnectOptions,
+    recording: Option<PathBuf>,
+    abort: &AtomicBool,
+    cmd_rx: Receiver<WorkerCommand>,
+    resp_tx: Sender<WorkerResponse>,
+) {
     // Create a tokio runtime for async device operations
     let rt = match tokio::runtime::Builder::new_current_thread()
         .enable_all()
@@ -114,133 +723,2473 @@ fn run_worker(port_name: &str, cmd_rx: Receiver<WorkerCommand>, resp_tx: Sender<
     {
         Ok(rt) => rt,
         Err(e) => {
-            let _ = resp_tx.send(WorkerResponse::Error(format!(
-                "Failed to create runtime: {e}"
-            )));
+            let _ = resp_tx.send(WorkerResponse::Error(ConnectError::Runtime(e.to_string())));
             return;
         }
     };
 
     rt.block_on(async move {
-        // Open serial port
-        let mut port = match freemdu::serial::open(port_name) {
-            Ok(p) => p,
-            Err(e) => {
-                let _ = resp_tx.send(WorkerResponse::Error(format!("Failed to open port: {e}")));
+        if port_name == DEMO_PORT {
+            run_demo(options, abort, &cmd_rx, &resp_tx).await;
+            return;
+        }
+
+        if let Some(path) = port_name.strip_prefix(REPLAY_PREFIX) {
+            run_replay(Path::new(path), options, abort, &cmd_rx, &resp_tx).await;
+            return;
+        }
+
+        let recording = match recording.as_deref().map(SessionRecording::create) {
+            Some(Ok(rec)) => Some(rec),
+            Some(Err(e)) => {
+                let _ = resp_tx.send(WorkerResponse::Error(e));
                 return;
             }
+            None => None,
+        };
+
+        let recording = recording.as_ref();
+        let Some((mut dev, baud_rate)) =
+            connect_initial(port_name, options, recording, &cmd_rx, &resp_tx).await
+        else {
+            return;
         };
+        let mut config = serial_config(baud_rate, options.timeouts);
+        // Recorded once connected, so that the adapter is found again if its port is renamed
+        let identity = AdapterIdentity::of_port(port_name);
+        let mut port_name = port_name.to_string();
+        // Properties rejected by the device or failing repeatedly, which are no longer polled
+        let mut unsupported = Unsupported::default();
+        // Excluded from polling by the user, as sent by the UI once connected
+        let mut unpolled = Vec::new();
+        // Kept across reconnections, so that the log continues
+        let mut logger = None;
+        // Kept when reconfiguring, so that commands sent meanwhile aren't lost
+        let mut commands = CommandQueue::new(&cmd_rx).warming_up(options.warm_up);
+
+        loop {
+            let info = device_info(dev.as_ref(), options, config.baud_rate);
+            let _ = resp_tx.send(WorkerResponse::Connected(info));
+
+            let session = run_session(
+                dev.as_mut(),
+                &mut unsupported,
+                &mut unpolled,
+                &mut logger,
+                options,
+                abort,
+                &mut commands,
+                &resp_tx,
+            );
+
+            match session.await {
+                SessionEnd::Closed => break,
+                SessionEnd::LinkLost(e) => {
+                    log::warn!("Serial link lost: {e}");
+
+                    // Stale once reconnected, like those sent while reconnecting
+                    commands.pending.clear();
+
+                    let attempt = async || {
+                        let name = relocate(&port_name, identity.as_ref());
+                        let dev = connect_at(&name, config, options, recording).await?;
+
+                        Ok((dev, name))
+                    };
 
-        // Connect to device with timeout
-        let dev =
-            match tokio::time::timeout(Duration::from_secs(5), freemdu::device::connect(&mut port))
-                .await
-            {
-                Ok(Ok(d)) => d,
-                Ok(Err(e)) => {
-                    let _ = resp_tx.send(WorkerResponse::Error(format!("Failed to connect: {e}")));
-                    return;
+                    match reconnect(attempt, &cmd_rx, &resp_tx).await {
+                        Some((new_dev, name)) => {
+                            dev = new_dev;
+                            port_name = name;
+                        }
+                        None => break,
+                    }
                 }
-                Err(_) => {
-                    let _ = resp_tx.send(WorkerResponse::Error("Connection timeout".to_string()));
-                    return;
+                SessionEnd::Reconfigure(new_config) => {
+                    let timeout = options.timeouts.query;
+                    let res = reconfigure(dev.as_mut(), &mut config, new_config, timeout, &resp_tx);
+
+                    if res.await {
+                        // Some platforms don't allow opening a port twice
+                        drop(dev);
+
+                        let res = reopen(
+                            &port_name,
+                            &mut config,
+                            new_config,
+                            options,
+                            recording,
+                            &cmd_rx,
+                            &resp_tx,
+                        );
+
+                        match res.await {
+                            Some(new_dev) => dev = new_dev,
+                            None => return,
+                        }
+                    }
                 }
-            };
+            }
+        }
 
-        // Send connected response
-        let info = DeviceInfo {
-            software_id: dev.software_id(),
-            kind: dev.kind(),
-            actions: dev.actions().iter().map(ActionInfo::from_action).collect(),
-        };
-        let _ = resp_tx.send(WorkerResponse::Connected(info));
+        // Close the port before the thread exits, which the handle waits for
+        drop(dev);
+    });
+}
 
-        // Store properties and actions for later use
-        let properties = dev.properties();
-        let actions = dev.actions();
+/// Connect to the device for the first time, detecting the baud rate if requested
+///
+/// Returns `None` if the UI disconnected meanwhile or the device can't be reached,
+/// which is reported to the UI.
+async fn connect_initial(
+    port_name: &str,
+    options: ConnectOptions,
+    recording: Option<&SessionRecording>,
+    cmd_rx: &Receiver<WorkerCommand>,
+    resp_tx: &Sender<WorkerResponse>,
+) -> Option<(Box<dyn Device<Port>>, u32)> {
+    // Only retry at other baud rates if requested,
+    // a serial-to-TCP bridge has a fixed baud rate
+    let baud_rates = if options.auto_baud && !port_name.starts_with(TCP_PREFIX) {
+        &freemdu::serial::BAUD_RATES[..]
+    } else {
+        &freemdu::serial::BAUD_RATES[..1]
+    };
+    let attempt = async |baud_rate| {
+        let config = serial_config(baud_rate, options.timeouts);
+
+        connect_at(port_name, config, options, recording).await
+    };
+    let res = tokio::select! {
+        res = detect_baud_rate(baud_rates, attempt) => res,
+        // Dropping the attempt closes the port
+        () = disconnect_requested(cmd_rx) => return None,
+    };
 
-        // Need to reborrow dev as mutable
-        let mut dev = dev;
+    match res {
+        Ok(res) => Some(res),
+        Err(e) => {
+            let _ = resp_tx.send(WorkerResponse::Error(e));
+            None
+        }
+    }
+}
 
-        // Main command loop
-        loop {
-            // Check for commands (non-blocking with small timeout)
-            match cmd_rx.recv_timeout(Duration::from_millis(50)) {
-                Ok(WorkerCommand::QueryProperties(kind)) => {
-                    let mut data = Vec::new();
-
-                    for prop in properties.iter().filter(|p| p.kind == kind) {
-                        match tokio::time::timeout(Duration::from_secs(1), dev.query_property(prop))
-                            .await
-                        {
-                            Ok(Ok(value)) => {
-                                data.push(PropertyData {
-                                    name: prop.name.to_string(),
-                                    value: PropertyValue::from(&value),
-                                    unit: prop.unit.map(String::from),
-                                });
-                            }
-                            Ok(Err(e)) => {
-                                log::warn!("Failed to query property {}: {e}", prop.name);
-                            }
-                            Err(_) => {
-                                log::warn!("Timeout querying property {}", prop.name);
-                            }
-                        }
+/// Reopen the closed port with new settings, or with the previous ones if that fails
+///
+/// Updates the settings once the device responds with the new ones.
+/// Returns `None` if the device can't be reached at all, after reporting it to the UI.
+async fn reopen(
+    port_name: &str,
+    config: &mut SerialConfig,
+    new_config: SerialConfig,
+    options: ConnectOptions,
+    recording: Option<&SessionRecording>,
+    cmd_rx: &Receiver<WorkerCommand>,
+    resp_tx: &Sender<WorkerResponse>,
+) -> Option<Box<dyn Device<Port>>> {
+    match connect_at(port_name, new_config, options, recording).await {
+        Ok(dev) => {
+            *config = new_config;
+            Some(dev)
+        }
+        Err(e) => {
+            let _ = resp_tx.send(WorkerResponse::ReconfigureFailed(e.to_string()));
+            let config = *config;
+            let attempt = async || connect_at(port_name, config, options, recording).await;
+
+            reconnect(attempt, cmd_rx, resp_tx).await
+        }
+    }
+}
+
+/// Wait until the UI disconnects while no session is running
+///
+/// Other commands are discarded, since they can't be handled without a device.
+async fn disconnect_requested(cmd_rx: &Receiver<WorkerCommand>) {
+    loop {
+        match cmd_rx.try_recv() {
+            Ok(WorkerCommand::Disconnect) | Err(TryRecvError::Disconnected) => return,
+            Ok(_) | Err(TryRecvError::Empty) => tokio::time::sleep(COMMAND_POLL_INTERVAL).await,
+        }
+    }
+}
+
+/// Connect to an emulated device with deterministic values and handle commands
+///
+/// Allows trying out the UI without an appliance.
+async fn run_demo(
+    options: ConnectOptions,
+    abort: &AtomicBool,
+    cmd_rx: &Receiver<WorkerCommand>,
+    resp_tx: &Sender<WorkerResponse>,
+) {
+    match freemdu::device::connect(MockPort::washing_machine()).await {
+        Ok(dev) => run_offline(dev, options, abort, cmd_rx, resp_tx).await,
+        Err(e) => {
+            let _ = resp_tx.send(WorkerResponse::Error(ConnectError::Device(e.to_string())));
+        }
+    }
+}
+
+/// Connect to a device replaying a recorded session and handle commands
+///
+/// Allows analyzing a session offline, without the appliance.
+async fn run_replay(
+    path: &Path,
+    options: ConnectOptions,
+    abort: &AtomicBool,
+    cmd_rx: &Receiver<WorkerCommand>,
+    resp_tx: &Sender<WorkerResponse>,
+) {
+    let connect = freemdu::device::connect_replay(path, options.replay_end);
+
+    match tokio::time::timeout(options.timeouts.connect, connect).await {
+        Ok(Ok(dev)) => run_offline(dev, options, abort, cmd_rx, resp_tx).await,
+        Ok(Err(e)) => {
+            let _ = resp_tx.send(WorkerResponse::Error(connect_error(e)));
+        }
+        Err(_) => {
+            let _ = resp_tx.send(WorkerResponse::Error(ConnectError::Timeout));
+        }
+    }
+}
+
+/// Handle commands for a device that isn't connected via a port
+async fn run_offline<P: Read + Write>(
+    mut dev: Box<dyn Device<P>>,
+    options: ConnectOptions,
+    abort: &AtomicBool,
+    cmd_rx: &Receiver<WorkerCommand>,
+    resp_tx: &Sender<WorkerResponse>,
+) {
+    dev.interface().set_read_only(options.read_only);
+
+    let info = device_info(dev.as_ref(), options, freemdu::serial::BAUD_RATES[0]);
+    let _ = resp_tx.send(WorkerResponse::Connected(info));
+
+    let mut unsupported = Unsupported::default();
+    let mut unpolled = Vec::new();
+    let mut logger = None;
+    let mut commands = CommandQueue::new(cmd_rx).warming_up(options.warm_up);
+
+    loop {
+        let session = run_session(
+            dev.as_mut(),
+            &mut unsupported,
+            &mut unpolled,
+            &mut logger,
+            options,
+            abort,
+            &mut commands,
+            resp_tx,
+        );
+
+        match session.await {
+            SessionEnd::Closed => break,
+            // There is no port that could be reopened, so there is nothing to reconnect to
+            SessionEnd::LinkLost(e) => {
+                log::info!("Offline device stopped responding: {e}");
+
+                let _ = resp_tx.send(WorkerResponse::Disconnected);
+                break;
+            }
+            SessionEnd::Reconfigure(_) => {
+                let _ = resp_tx.send(WorkerResponse::ReconfigureFailed(
+                    "Offline devices have no port settings".to_string(),
+                ));
+            }
+        }
+    }
+}
+
+fn device_info<P: Read + Write>(
+    dev: &dyn Device<P>,
+    options: ConnectOptions,
+    baud_rate: u32,
+) -> DeviceInfo {
+    DeviceInfo {
+        software_id: dev.software_id(),
+        kind: dev.kind(),
+        actions: dev.actions().iter().map(ActionInfo::from_action).collect(),
+        read_only: options.read_only,
+        baud_rate,
+    }
+}
+
+/// Handle commands until the UI disconnects or the serial link fails
+#[allow(clippy::too_many_arguments)] // State kept across sessions is passed explicitly
+async fn run_session<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    unsupported: &mut Unsupported,
+    unpolled: &mut Vec<(PropertyKind, String)>,
+    logger: &mut Option<CsvLogger<BufWriter<File>>>,
+    options: ConnectOptions,
+    abort: &AtomicBool,
+    commands: &mut CommandQueue<'_>,
+    resp_tx: &Sender<WorkerResponse>,
+) -> SessionEnd {
+    // Store properties and actions for later use
+    let properties = dev.properties();
+    let actions = dev.actions();
+    // Indexed once, as `Device::property` searches linearly on each lookup
+    let properties_by_id: HashMap<PropertyId, &'static Property> =
+        properties.iter().map(|prop| (prop.id, prop)).collect();
+    let mut latency = Latency::default();
+    // The port may have been reconfigured, starting a new session without reconnecting
+    let link = LinkHealth::new(dev.stats());
+    // Requested or preempted refresh, run once no interactive command is waiting
+    let mut refresh = None;
+
+    // Main command loop
+    loop {
+        if let Some(next) = refresh.take() {
+            match commands.refresh_interrupted() {
+                None => {
+                    let res = refresh_properties(
+                        dev,
+                        next,
+                        unsupported,
+                        options,
+                        logger,
+                        commands,
+                        &mut latency,
+                        &link,
+                        resp_tx,
+                    );
+
+                    match res.await {
+                        Ok(preempted) => refresh = preempted,
+                        Err(LinkLost(e)) => return SessionEnd::LinkLost(e),
                     }
 
-                    let _ = resp_tx.send(WorkerResponse::Properties(kind, data));
+                    continue;
                 }
+                Some(Interrupt::Preempted) => refresh = Some(next),
+                Some(Interrupt::Canceled) => {}
+            }
+        }
 
-                Ok(WorkerCommand::TriggerAction(action_id, param)) => {
-                    if let Some(action) = actions.iter().find(|a| a.id == action_id) {
-                        let value_param = param.map(freemdu::device::Value::String);
-
-                        match tokio::time::timeout(
-                            Duration::from_secs(2),
-                            dev.trigger_action(action, value_param),
-                        )
-                        .await
-                        {
-                            Ok(Ok(())) => {
-                                let _ = resp_tx.send(WorkerResponse::ActionResult(
-                                    action.name.to_string(),
-                                    true,
-                                    "Success".to_string(),
-                                ));
-                            }
-                            Ok(Err(e)) => {
-                                let _ = resp_tx.send(WorkerResponse::ActionResult(
-                                    action.name.to_string(),
-                                    false,
-                                    e.to_string(),
-                                ));
-                            }
-                            Err(_) => {
-                                let _ = resp_tx.send(WorkerResponse::ActionResult(
-                                    action.name.to_string(),
-                                    false,
-                                    "Timeout".to_string(),
-                                ));
-                            }
-                        }
+        // Check for commands (non-blocking with small timeout)
+        match commands.recv_timeout(COMMAND_POLL_INTERVAL) {
+            // Interactive commands are received first, so no refresh is pending here
+            Ok(WorkerCommand::QueryProperties(kind)) => {
+                refresh = Some(Refresh::new(kind).skipping(unpolled));
+            }
+
+            Ok(WorkerCommand::QueryFaultHistory) => {
+                match fault_history(dev, options.timeouts.query).await {
+                    Ok(res) => {
+                        let _ = resp_tx.send(WorkerResponse::FaultHistory(res));
                     }
+                    Err(LinkLost(e)) => return SessionEnd::LinkLost(e),
                 }
+            }
 
-                Ok(WorkerCommand::Disconnect) => {
-                    let _ = resp_tx.send(WorkerResponse::Disconnected);
-                    break;
+            Ok(WorkerCommand::QueryProperty(id)) => {
+                if let Some(prop) = properties_by_id.get(&id) {
+                    let res = send_property(dev, prop, unsupported, options, resp_tx);
+
+                    if let Err(LinkLost(e)) = res.await {
+                        return SessionEnd::LinkLost(e);
+                    }
                 }
+            }
+
+            Ok(WorkerCommand::SetProperty(id, value)) => {
+                if let Some(prop) = properties_by_id.get(&id) {
+                    let res = write_property(dev, prop, &value, unsupported, options, resp_tx);
 
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    // No command, continue loop
+                    if let Err(LinkLost(e)) = res.await {
+                        return SessionEnd::LinkLost(e);
+                    }
                 }
+            }
 
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    // UI disconnected
-                    break;
+            Ok(WorkerCommand::TriggerAction(action_id, param, timeout)) => {
+                if let Some(action) = actions.iter().find(|a| a.id == action_id) {
+                    let res = send_action_result(dev, action, param, timeout, abort, resp_tx);
+
+                    if let Err(LinkLost(e)) = res.await {
+                        return SessionEnd::LinkLost(e);
+                    }
                 }
             }
-        }
-    });
+
+            Ok(WorkerCommand::RawTransaction(req)) => {
+                let res = match raw_transaction(dev, &req, options.timeouts.query).await {
+                    Ok(res) => res,
+                    Err(LinkLost(e)) => return SessionEnd::LinkLost(e),
+                };
+                let _ = resp_tx.send(WorkerResponse::RawResponse(req, res));
+            }
+
+            Ok(WorkerCommand::StartLogging(path)) => {
+                if let Some(log) = start_logging(&path, properties, resp_tx) {
+                    *logger = Some(log);
+                }
+            }
+
+            Ok(WorkerCommand::StopLogging) => stop_logging(logger, resp_tx),
+
+            // Reported to the UI once the next refresh completes
+            Ok(WorkerCommand::ProbeUnsupported) => unsupported.clear(),
+
+            Ok(WorkerCommand::SetUnpolled(props)) => *unpolled = props,
+
+            Ok(WorkerCommand::Reconfigure(config)) => return SessionEnd::Reconfigure(config),
+
+            Ok(WorkerCommand::Disconnect) => {
+                let _ = resp_tx.send(WorkerResponse::Disconnected);
+                return SessionEnd::Closed;
+            }
+
+            Ok(WorkerCommand::Cancel) | Err(RecvTimeoutError::Timeout) => {
+                // Kinds not queried yet since connecting are queried while idle
+                refresh = commands
+                    .next_warm_up()
+                    .map(|kind| Refresh::new(kind).skipping(unpolled));
+            }
+
+            Err(RecvTimeoutError::Disconnected) => {
+                // UI disconnected
+                return SessionEnd::Closed;
+            }
+        }
+    }
+}
+
+/// Change the value of a property, then send the outcome and the value the device has now
+///
+/// Fails only if the serial link was lost.
+async fn write_property<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    prop: &'static Property,
+    value: &PropertyValue,
+    unsupported: &mut Unsupported,
+    options: ConnectOptions,
+    resp_tx: &Sender<WorkerResponse>,
+) -> Result<(), LinkLost> {
+    let res = set_property(dev, prop, value, options.timeouts.query).await?;
+    let _ = resp_tx.send(WorkerResponse::PropertyWritten(prop.name.to_string(), res));
+
+    send_property(dev, prop, unsupported, options, resp_tx).await
+}
+
+/// Trigger an action and send its outcome to the UI
+///
+/// Fails only if the serial link was lost, which is reported as a failed action.
+async fn send_action_result<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    action: &'static Action,
+    param: Option<String>,
+    timeout: Duration,
+    abort: &AtomicBool,
+    resp_tx: &Sender<WorkerResponse>,
+) -> Result<(), LinkLost> {
+    let outcome = match run_action(dev, action, param.clone(), timeout, abort, resp_tx).await {
+        Ok(outcome) => outcome,
+        Err(LinkLost(e)) => {
+            let _ = resp_tx.send(WorkerResponse::ActionResult(ActionOutcome {
+                id: action.id.to_string(),
+                name: action.name.to_string(),
+                param,
+                success: false,
+                message: e.clone(),
+                value: None,
+            }));
+
+            return Err(LinkLost(e));
+        }
+    };
+    let _ = resp_tx.send(WorkerResponse::ActionResult(outcome));
+
+    Ok(())
+}
+
+/// Create a CSV log, reporting failures to the UI
+fn start_logging(
+    path: &Path,
+    properties: &[Property],
+    resp_tx: &Sender<WorkerResponse>,
+) -> Option<CsvLogger<BufWriter<File>>> {
+    match CsvLogger::create(path, properties) {
+        Ok(log) => Some(log),
+        Err(e) => {
+            let _ = resp_tx.send(WorkerResponse::LoggingError(format!(
+                "Failed to create {}: {e}",
+                path.display()
+            )));
+
+            None
+        }
+    }
+}
+
+/// Close the CSV log, reporting if the remaining rows couldn't be written
+fn stop_logging(logger: &mut Option<CsvLogger<BufWriter<File>>>, resp_tx: &Sender<WorkerResponse>) {
+    if let Some(Err(e)) = logger.take().map(|mut log| log.flush()) {
+        let _ = resp_tx.send(WorkerResponse::LoggingError(e.to_string()));
+    }
+}
+
+/// Try to reconnect with exponential backoff
+///
+/// Reports each attempt to the UI and gives up after [`MAX_RECONNECT_ATTEMPTS`],
+/// or as soon as the UI disconnects.
+async fn reconnect<T>(
+    mut attempt: impl AsyncFnMut() -> Result<T, ConnectError>,
+    cmd_rx: &Receiver<WorkerCommand>,
+    resp_tx: &Sender<WorkerResponse>,
+) -> Option<T> {
+    let mut backoff = RECONNECT_INITIAL_BACKOFF;
+
+    for num in 1..=MAX_RECONNECT_ATTEMPTS {
+        let _ = resp_tx.send(WorkerResponse::Reconnecting(num));
+
+        tokio::time::sleep(backoff).await;
+
+        // Commands queued in the meantime are stale, only look for a disconnect
+        loop {
+            match cmd_rx.try_recv() {
+                Ok(WorkerCommand::Disconnect) => {
+                    let _ = resp_tx.send(WorkerResponse::Disconnected);
+                    return None;
+                }
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return None,
+            }
+        }
+
+        match attempt().await {
+            Ok(res) => return Some(res),
+            Err(e) => {
+                log::info!("Reconnection attempt {num} failed: {e}");
+            }
+        }
+
+        backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+    }
+
+    let _ = resp_tx.send(WorkerResponse::Disconnected);
+
+    None
+}
+
+/// Identity of the USB adapter behind a serial port
+///
+/// Used to find the adapter again if the OS assigns it another port name
+/// after it's re-enumerated, e.g. `COM5` instead of `COM3`.
+#[derive(Debug, Clone, PartialEq)]
+struct AdapterIdentity {
+    vid: u16,
+    pid: u16,
+    serial_number: Option<String>,
+    product: Option<String>,
+}
+
+impl AdapterIdentity {
+    /// Identity of the adapter behind the given port, `None` if it isn't a USB port
+    fn of_port(port_name: &str) -> Option<Self> {
+        let ports = serialport::available_ports().unwrap_or_default();
+
+        Self::find(&ports, port_name, canonical_port)
+    }
+
+    /// Identity of the adapter behind the given port among the listed ones
+    ///
+    /// A port can be listed under several names, of which only some have USB information.
+    fn find(
+        ports: &[serialport::SerialPortInfo],
+        port_name: &str,
+        canonicalize: impl Fn(&str) -> String,
+    ) -> Option<Self> {
+        let name = canonicalize(port_name);
+
+        ports
+            .iter()
+            .filter(|port| canonicalize(&port.port_name) == name)
+            .find_map(|port| match &port.port_type {
+                serialport::SerialPortType::UsbPort(usb) => Some(Self {
+                    vid: usb.vid,
+                    pid: usb.pid,
+                    serial_number: usb.serial_number.clone(),
+                    product: usb.product.clone(),
+                }),
+                _ => None,
+            })
+    }
+
+    /// Name of the port of the adapter among the listed ones
+    ///
+    /// Returns `None` unless exactly one port matches, which is ambiguous for identical
+    /// adapters without a serial number.
+    fn locate(
+        &self,
+        ports: &[serialport::SerialPortInfo],
+        canonicalize: impl Fn(&str) -> String,
+    ) -> Option<String> {
+        let mut names: Vec<_> = ports
+            .iter()
+            .filter(|port| {
+                matches!(&port.port_type, serialport::SerialPortType::UsbPort(usb)
+                if usb.vid == self.vid
+                    && usb.pid == self.pid
+                    && usb.serial_number == self.serial_number
+                    && usb.product == self.product)
+            })
+            .map(|port| canonicalize(&port.port_name))
+            .collect();
+
+        names.sort();
+        names.dedup();
+
+        match &names[..] {
+            [name] => Some(name.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Name of the port to reconnect to, where the adapter with the given identity is found now
+///
+/// Falls back to the previous name if the adapter isn't found or has no identity.
+fn relocate(port_name: &str, identity: Option<&AdapterIdentity>) -> String {
+    let Some(identity) = identity else {
+        return port_name.to_string();
+    };
+    let ports = serialport::available_ports().unwrap_or_default();
+
+    match identity.locate(&ports, canonical_port) {
+        Some(name) if name != canonical_port(port_name) => {
+            log::info!("Adapter moved from {port_name} to {name}");
+            name
+        }
+        _ => port_name.to_string(),
+    }
+}
+
+/// Returns whether the error indicates a failure of the serial link itself
+fn is_link_error<E>(err: &device::Error<E>) -> bool {
+    matches!(
+        err,
+        device::Error::Protocol(freemdu::Error::Io(_) | freemdu::Error::UnexpectedEof)
+    )
+}
+
+/// Settings of a serial port at the given baud rate, with the inter-byte timeout of the options
+pub fn serial_config(baud_rate: u32, timeouts: Timeouts) -> SerialConfig {
+    SerialConfig {
+        baud_rate,
+        inter_byte_timeout: timeouts.inter_byte,
+        ..SerialConfig::default()
+    }
+}
+
+/// Open the port with the given settings and connect to the device, with timeout
+///
+/// The session is recorded from the start if requested, including the initialization.
+async fn connect_at(
+    port_name: &str,
+    config: SerialConfig,
+    options: ConnectOptions,
+    recording: Option<&SessionRecording>,
+) -> Result<Box<dyn Device<Port>>, ConnectError> {
+    let port = open_port(port_name, &config, options.timeouts.connect).await?;
+    let mut intf = Interface::new(port);
+
+    intf.set_observer(recording.map(SessionRecording::observer));
+
+    let connect = async {
+        let mut dev = freemdu::device::connect_interface(intf).await?;
+
+        dev.interface().set_read_only(options.read_only);
+
+        Ok::<_, device::Error<freemdu::serial::PortError>>(dev)
+    };
+
+    match tokio::time::timeout(options.timeouts.connect, connect).await {
+        Ok(res) => res.map_err(connect_error),
+        // A bridge has a fixed baud rate, so there is nothing to hint at
+        Err(_) if port_name.starts_with(TCP_PREFIX) => Err(ConnectError::Timeout),
+        Err(_) => Err(probe_port(port_name, config.baud_rate).await),
+    }
+}
+
+/// Apply new settings to the open port and check that the device still responds
+///
+/// Updates the settings on success. Otherwise the previous settings are restored
+/// and the failure is reported to the UI. Returns whether the port can't be
+/// reconfigured while it's open, so that it has to be reopened instead.
+async fn reconfigure(
+    dev: &mut dyn Device<Port>,
+    config: &mut SerialConfig,
+    new_config: SerialConfig,
+    timeout: Duration,
+    resp_tx: &Sender<WorkerResponse>,
+) -> bool {
+    if let Err(e) = dev.interface().port_mut().reconfigure(&new_config) {
+        log::info!("Port can't be reconfigured while open: {e}");
+        return true;
+    }
+
+    let err = match tokio::time::timeout(timeout, dev.interface().query_software_id()).await {
+        Ok(Ok(_)) => {
+            *config = new_config;
+            return false;
+        }
+        Ok(Err(e)) => ConnectError::Device(e.to_string()),
+        Err(_) => ConnectError::Timeout,
+    };
+
+    // If this fails too, the next request reports the link as lost
+    if let Err(e) = dev.interface().port_mut().reconfigure(config) {
+        log::warn!("Failed to restore port settings: {e}");
+    }
+
+    let _ = resp_tx.send(WorkerResponse::ReconfigureFailed(err.to_string()));
+
+    false
+}
+
+/// Find out why the device didn't respond by sending the identify frame once more
+///
+/// Returns [`ConnectError::UnexpectedResponse`] if anything other than
+/// a response code comes back, which usually means the baud rate is wrong
+/// or another kind of device is connected, e.g. a GPS receiver.
+async fn probe_port(port_name: &str, baud_rate: u32) -> ConnectError {
+    let config = SerialConfig {
+        baud_rate,
+        ..SerialConfig::default()
+    };
+    let Ok(mut port) = open_port(port_name, &config, PROBE_WINDOW).await else {
+        return ConnectError::Timeout;
+    };
+    let received = probe_response(&mut port).await;
+
+    if is_misaligned(&received) {
+        log::info!("Unexpected response at {baud_rate} baud: {received:02x?}");
+        ConnectError::UnexpectedResponse(baud_rate)
+    } else {
+        ConnectError::Timeout
+    }
+}
+
+/// Send the identify frame and collect whatever arrives within [`PROBE_WINDOW`]
+async fn probe_response<P: Read + Write>(port: &mut P) -> Vec<u8> {
+    let mut received = Vec::new();
+    let probe = async {
+        port.write_all(&IDENTIFY_FRAME).await?;
+
+        let mut buf = [0x00; PROBE_LIMIT];
+
+        while received.len() < PROBE_LIMIT {
+            match port.read(&mut buf).await? {
+                0 => break,
+                n => received.extend_from_slice(&buf[..n]),
+            }
+        }
+
+        Ok::<_, P::Error>(())
+    };
+
+    // Errors and the end of the window only stop the collection
+    let _ = tokio::time::timeout(PROBE_WINDOW, probe).await;
+
+    received
+}
+
+/// Returns whether the received data is something other than silence or a single response code
+fn is_misaligned(received: &[u8]) -> bool {
+    !matches!(received, [] | [0x00..=0x02])
+}
+
+/// Convert a failure to connect into the error shown to the user
+fn connect_error<E: std::error::Error>(err: device::Error<E>) -> ConnectError {
+    match err {
+        device::Error::UnknownSoftwareId(id) => ConnectError::UnsupportedDevice(id),
+        device::Error::UnsupportedDevice { kind, software_id } => {
+            ConnectError::UnsupportedKind(kind, software_id)
+        }
+        e => ConnectError::Device(e.to_string()),
+    }
+}
+
+/// File that the frames of a session are recorded to
+///
+/// Shared by all connections of a session, so that reconnecting continues the recording.
+struct SessionRecording {
+    file: Arc<File>,
+    start: Instant,
+}
+
+impl SessionRecording {
+    fn create(path: &Path) -> Result<Self, ConnectError> {
+        let file = File::create(path)
+            .map_err(|e| ConnectError::Recording(format!("{}: {e}", path.display())))?;
+
+        Ok(Self {
+            file: Arc::new(file),
+            start: Instant::now(),
+        })
+    }
+
+    fn observer(&self) -> Box<dyn FrameObserver> {
+        Box::new(RecordingObserver::new(Arc::clone(&self.file), self.start))
+    }
+}
+
+/// Open a serial port, or connect to a serial-to-TCP bridge if the name starts with [`TCP_PREFIX`]
+///
+/// The settings are ignored for bridges, which are configured separately.
+async fn open_port(
+    port_name: &str,
+    config: &SerialConfig,
+    timeout: Duration,
+) -> Result<Port, ConnectError> {
+    if let Some(addr) = port_name.strip_prefix(TCP_PREFIX) {
+        return match tokio::time::timeout(timeout, freemdu::serial::open_tcp(addr)).await {
+            Ok(Ok(p)) => Ok(p),
+            Ok(Err(e)) => Err(ConnectError::Port(e.to_string())),
+            Err(_) => Err(ConnectError::Timeout),
+        };
+    }
+
+    match freemdu::serial::open_with(port_name, config) {
+        Ok(p) => Ok(p),
+        Err(freemdu::Error::PortBusy) => Err(ConnectError::PortBusy(port_name.to_string())),
+        Err(e) => Err(ConnectError::Port(e.to_string())),
+    }
+}
+
+/// Try connecting at each of the given baud rates in order
+///
+/// Returns the first successful connection along with its baud rate.
+/// Gives up immediately if the port can't be opened,
+/// otherwise returns the error of the last attempt.
+async fn detect_baud_rate<T>(
+    baud_rates: &[u32],
+    mut attempt: impl AsyncFnMut(u32) -> Result<T, ConnectError>,
+) -> Result<(T, u32), ConnectError> {
+    let mut last_err = ConnectError::Timeout;
+
+    for &baud_rate in baud_rates {
+        match attempt(baud_rate).await {
+            Ok(res) => return Ok((res, baud_rate)),
+            Err(e) if e.is_port_error() => return Err(e),
+            Err(e) => {
+                log::info!("No response at {baud_rate} baud: {e}");
+                last_err = e;
+            }
+        }
+    }
+
+    Err(last_err)
+}
+
+/// Trigger an action, reporting progress until it completes, times out or is aborted
+///
+/// The parameter is converted to the type expected by the action,
+/// e.g. a number, failing without contacting the device if it is invalid.
+async fn run_action<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    action: &'static Action,
+    param: Option<String>,
+    timeout: Duration,
+    abort: &AtomicBool,
+    resp_tx: &Sender<WorkerResponse>,
+) -> Result<ActionOutcome, LinkLost> {
+    let input = param.clone();
+    let param = match (&action.params, param) {
+        (Some(params), Some(param)) => match params.parse_value(&param) {
+            Some(value) => Some(value),
+            None => {
+                return Ok(ActionOutcome {
+                    id: action.id.to_string(),
+                    name: action.name.to_string(),
+                    param: input,
+                    success: false,
+                    message: format!("Invalid argument: {param}"),
+                    value: None,
+                });
+            }
+        },
+        (_, param) => param.map(Value::String),
+    };
+    let start = Instant::now();
+    let mut fut = dev.trigger_action(action, param);
+
+    abort.store(false, Ordering::Relaxed);
+
+    let (success, message, value) = loop {
+        let remaining = timeout.saturating_sub(start.elapsed());
+
+        match tokio::time::timeout(remaining.min(ACTION_PROGRESS_INTERVAL), &mut fut).await {
+            Ok(Ok(value)) => {
+                break (
+                    true,
+                    "Success".to_string(),
+                    value.as_ref().map(PropertyValue::from),
+                );
+            }
+            Ok(Err(e)) if is_link_error(&e) => return Err(LinkLost(e.to_string())),
+            Ok(Err(e)) => break (false, e.to_string(), None),
+            Err(_) if abort.load(Ordering::Relaxed) => break (false, "Aborted".to_string(), None),
+            Err(_) if start.elapsed() >= timeout => break (false, "Timeout".to_string(), None),
+            Err(_) => {
+                let _ = resp_tx.send(WorkerResponse::ActionProgress(ActionProgress {
+                    id: action.id.to_string(),
+                    elapsed: start.elapsed(),
+                    timeout,
+                }));
+            }
+        }
+    };
+
+    Ok(ActionOutcome {
+        id: action.id.to_string(),
+        name: action.name.to_string(),
+        param: input,
+        success,
+        message,
+        value,
+    })
+}
+
+/// Change the value of a writable property
+///
+/// Fails only if the serial link was lost, otherwise returns the result for display.
+async fn set_property<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    prop: &'static Property,
+    value: &PropertyValue,
+    timeout: Duration,
+) -> Result<Result<(), String>, LinkLost> {
+    let fut = dev.set_property(prop, Value::from(value));
+
+    // Writing takes one request, verifying the value another
+    match tokio::time::timeout(timeout * 2, fut).await {
+        Ok(Ok(())) => Ok(Ok(())),
+        Ok(Err(e)) if is_link_error(&e) => Err(LinkLost(e.to_string())),
+        Ok(Err(e)) => Ok(Err(e.to_string())),
+        Err(_) => Ok(Err("Timeout".to_string())),
+    }
+}
+
+async fn raw_transaction<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    req: &[u8],
+    timeout: Duration,
+) -> Result<Result<Vec<u8>, String>, LinkLost> {
+    // Sending the request and receiving the reply are separate transfers
+    match tokio::time::timeout(timeout * 2, dev.raw_transaction(req)).await {
+        Ok(Ok(reply)) => Ok(Ok(reply)),
+        Ok(Err(e)) if is_link_error(&e) => Err(LinkLost(e.to_string())),
+        Ok(Err(e)) => Ok(Err(e.to_string())),
+        Err(_) => Ok(Err("Timeout".to_string())),
+    }
+}
+
+/// Query all properties of a kind and send them to the UI along with the link quality
+///
+/// Memory read for one property is reused for the others of the batch, see
+/// [`freemdu::Interface::start_read_cache`]. The properties are appended to the CSV log
+/// if enabled. Returns the refresh if it was preempted, to be resumed later. Fails only
+/// if the serial link was lost.
+#[allow(clippy::too_many_arguments)] // Session state is passed explicitly, like in run_session
+async fn refresh_properties<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    refresh: Refresh,
+    unsupported: &mut Unsupported,
+    options: ConnectOptions,
+    logger: &mut Option<CsvLogger<BufWriter<File>>>,
+    commands: &mut CommandQueue<'_>,
+    latency: &mut Latency,
+    link: &LinkHealth,
+    resp_tx: &Sender<WorkerResponse>,
+) -> Result<Option<Refresh>, LinkLost> {
+    let properties = dev.properties();
+    let kind = refresh.kind;
+
+    // Properties decoded from the same memory are read once per batch
+    dev.interface().start_read_cache();
+
+    let res = query_properties(
+        dev,
+        properties,
+        refresh,
+        unsupported,
+        options,
+        commands,
+        latency,
+    );
+    let outcome = res.await;
+
+    dev.interface().stop_read_cache();
+
+    let suspended = match outcome? {
+        RefreshOutcome::Complete(data) => {
+            send_properties(kind, data, logger, resp_tx);
+            None
+        }
+        RefreshOutcome::Preempted(refresh) => Some(refresh),
+        RefreshOutcome::Aborted => None,
+    };
+
+    let _ = resp_tx.send(link.response(dev.stats()));
+
+    if let Some(resp) = latency.response() {
+        let _ = resp_tx.send(resp);
+    }
+
+    unsupported.report(resp_tx);
+
+    Ok(suspended)
+}
+
+/// Send a completed batch of properties to the UI, appending it to the CSV log if enabled
+fn send_properties(
+    kind: PropertyKind,
+    data: Vec<PropertyData>,
+    logger: &mut Option<CsvLogger<BufWriter<File>>>,
+    resp_tx: &Sender<WorkerResponse>,
+) {
+    if let Some(log) = logger {
+        if let Err(e) = log.log(jiff::Timestamp::now(), &data) {
+            let _ = resp_tx.send(WorkerResponse::LoggingError(e.to_string()));
+            *logger = None;
+        }
+    }
+
+    let _ = resp_tx.send(WorkerResponse::Properties(kind, data));
+}
+
+async fn fault_history<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    timeout: Duration,
+) -> Result<Result<Vec<FaultRecord>, String>, LinkLost> {
+    match tokio::time::timeout(timeout, dev.fault_history()).await {
+        Ok(Ok(records)) => Ok(Ok(records)),
+        Ok(Err(e)) if is_link_error(&e) => Err(LinkLost(e.to_string())),
+        Ok(Err(e)) => Ok(Err(e.to_string())),
+        Err(_) => Ok(Err("Timeout".to_string())),
+    }
+}
+
+/// Properties that are no longer polled, because the device rejected them or they failed repeatedly
+#[derive(Debug, Default)]
+struct Unsupported {
+    /// ID, kind and name of each property, in the order they were added
+    properties: Vec<(PropertyId, PropertyKind, String)>,
+    /// Consecutive failed queries of properties that are still polled
+    failures: HashMap<PropertyId, u32>,
+    /// Whether the properties changed since they were last reported to the UI
+    changed: bool,
+}
+
+impl Unsupported {
+    fn contains(&self, id: &PropertyId) -> bool {
+        self.properties.iter().any(|(prop_id, ..)| prop_id == id)
+    }
+
+    fn insert(&mut self, prop: &Property) {
+        if !self.contains(&prop.id) {
+            self.properties
+                .push((prop.id, prop.kind, prop.name.to_string()));
+            self.changed = true;
+        }
+
+        self.failures.remove(&prop.id);
+    }
+
+    /// Count a failed query, returning whether the property is no longer polled because of it
+    fn failed(&mut self, prop: &Property) -> bool {
+        let failures = self.failures.entry(prop.id).or_default();
+
+        *failures += 1;
+
+        if *failures < UNSUPPORTED_FAILURES {
+            return false;
+        }
+
+        self.insert(prop);
+        true
+    }
+
+    fn succeeded(&mut self, id: PropertyId) {
+        self.failures.remove(&id);
+    }
+
+    /// Poll all properties again
+    fn clear(&mut self) {
+        self.changed |= !self.properties.is_empty();
+        self.properties.clear();
+        self.failures.clear();
+    }
+
+    /// Send the properties to the UI if they changed
+    fn report(&mut self, resp_tx: &Sender<WorkerResponse>) {
+        if std::mem::take(&mut self.changed) {
+            let _ = resp_tx.send(WorkerResponse::Unsupported(self.properties.clone()));
+        }
+    }
+}
+
+/// Rolling average of the round-trip time of property queries
+#[derive(Debug, Default)]
+struct Latency {
+    samples: VecDeque<Duration>,
+}
+
+impl Latency {
+    /// Add a round trip, replacing the oldest one if [`LATENCY_SAMPLES`] are stored
+    fn record(&mut self, elapsed: Duration) {
+        if self.samples.len() == LATENCY_SAMPLES {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(elapsed);
+    }
+
+    /// Response reporting the current latency, or `None` if nothing was measured yet
+    fn response(&self) -> Option<WorkerResponse> {
+        let last = *self.samples.back()?;
+        let avg = self.samples.iter().sum::<Duration>() / self.samples.len() as u32;
+
+        Some(WorkerResponse::Latency {
+            avg_ms: avg.as_secs_f64() * 1000.0,
+            last_ms: last.as_secs_f64() * 1000.0,
+        })
+    }
+}
+
+/// Uptime and transfers of the current session
+#[derive(Debug)]
+struct LinkHealth {
+    connected: Instant,
+    /// Transfers before the session started, which aren't reported
+    baseline: TransportStats,
+}
+
+impl LinkHealth {
+    fn new(baseline: TransportStats) -> Self {
+        Self {
+            connected: Instant::now(),
+            baseline,
+        }
+    }
+
+    /// Response reporting the transfers since the session started
+    fn response(&self, stats: TransportStats) -> WorkerResponse {
+        WorkerResponse::LinkStats {
+            stats: TransportStats {
+                frames_ok: stats.frames_ok.saturating_sub(self.baseline.frames_ok),
+                checksum_errors: stats
+                    .checksum_errors
+                    .saturating_sub(self.baseline.checksum_errors),
+                timeouts: stats.timeouts.saturating_sub(self.baseline.timeouts),
+            },
+            connected: self.connected,
+        }
+    }
+}
+
+/// Property refresh in progress, which interactive commands can preempt
+struct Refresh {
+    kind: PropertyKind,
+    /// Values queried so far
+    data: Vec<PropertyData>,
+    /// Index of the next property in the device's property list
+    next: usize,
+    /// Names of the properties excluded from polling by the user
+    skipped: Vec<String>,
+}
+
+impl Refresh {
+    fn new(kind: PropertyKind) -> Self {
+        Self {
+            kind,
+            data: Vec::new(),
+            next: 0,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Exclude the properties of the refreshed kind that aren't polled
+    fn skipping(mut self, unpolled: &[(PropertyKind, String)]) -> Self {
+        self.skipped = unpolled
+            .iter()
+            .filter(|(kind, _)| *kind == self.kind)
+            .map(|(_, name)| name.clone())
+            .collect();
+        self
+    }
+}
+
+/// Outcome of [`query_properties`]
+enum RefreshOutcome {
+    /// All properties of the kind were queried
+    Complete(Vec<PropertyData>),
+    /// An interactive command is waiting, the refresh can be resumed afterwards
+    Preempted(Refresh),
+    /// The refresh was canceled
+    Aborted,
+}
+
+/// Query all properties of a kind one after another
+///
+/// The command queue is checked between the queries, so that interactive commands
+/// don't have to wait for the whole batch. Fails only if the serial link was lost.
+async fn query_properties<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    properties: &'static [Property],
+    mut refresh: Refresh,
+    unsupported: &mut Unsupported,
+    options: ConnectOptions,
+    commands: &mut CommandQueue<'_>,
+    latency: &mut Latency,
+) -> Result<RefreshOutcome, LinkLost> {
+    let kind = refresh.kind;
+
+    while let Some(prop) = properties.get(refresh.next) {
+        if prop.kind != kind
+            || unsupported.contains(&prop.id)
+            || refresh.skipped.iter().any(|name| name == prop.name)
+        {
+            refresh.next += 1;
+            continue;
+        }
+
+        match commands.refresh_interrupted() {
+            Some(Interrupt::Preempted) => {
+                log::debug!("Query of {kind:?} properties preempted");
+                return Ok(RefreshOutcome::Preempted(refresh));
+            }
+            Some(Interrupt::Canceled) => {
+                log::debug!("Query of {kind:?} properties canceled");
+                return Ok(RefreshOutcome::Aborted);
+            }
+            None => {}
+        }
+
+        let query = async |dev: &mut dyn Device<P>| dev.query_property_raw(prop).await;
+        let start = tokio::time::Instant::now();
+
+        // A silent property only drops its own value, the rest of the batch is kept
+        let Some(res) = with_retries(dev, options.retry, options.timeouts.query, query).await
+        else {
+            handle_timeout(prop, unsupported);
+            refresh.next += 1;
+            continue;
+        };
+
+        if res.is_ok() {
+            latency.record(start.elapsed());
+        }
+
+        if let Some(prop_data) = handle_query_result(prop, res, unsupported)? {
+            refresh.data.push(prop_data);
+        }
+
+        refresh.next += 1;
+    }
+
+    Ok(RefreshOutcome::Complete(refresh.data))
+}
+
+/// Query a single property and send it to the UI
+///
+/// Fails only if the serial link was lost.
+async fn send_property<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    prop: &'static Property,
+    unsupported: &mut Unsupported,
+    options: ConnectOptions,
+    resp_tx: &Sender<WorkerResponse>,
+) -> Result<(), LinkLost> {
+    let res = query_property(
+        dev,
+        prop,
+        unsupported,
+        options.retry,
+        options.timeouts.query,
+    );
+
+    if let Some(prop_data) = res.await? {
+        let _ = resp_tx.send(WorkerResponse::Property(prop_data));
+    }
+
+    unsupported.report(resp_tx);
+
+    Ok(())
+}
+
+/// Query a single property, logging failures
+///
+/// Properties rejected by the device or failing repeatedly are added to `unsupported`
+/// and skipped afterwards. Fails only if the serial link was lost.
+async fn query_property<P: Read + Write>(
+    dev: &mut dyn Device<P>,
+    prop: &'static Property,
+    unsupported: &mut Unsupported,
+    retry: RetryPolicy,
+    timeout: Duration,
+) -> Result<Option<PropertyData>, LinkLost> {
+    if unsupported.contains(&prop.id) {
+        return Ok(None);
+    }
+
+    let query = async |dev: &mut dyn Device<P>| dev.query_property_raw(prop).await;
+
+    let Some(res) = with_retries(dev, retry, timeout, query).await else {
+        handle_timeout(prop, unsupported);
+        return Ok(None);
+    };
+
+    handle_query_result(prop, res, unsupported)
+}
+
+/// Log a property query that timed out on every attempt, counting it as a failure
+///
+/// Properties that never answer are thereby no longer polled, like ones failing otherwise.
+fn handle_timeout(prop: &Property, unsupported: &mut Unsupported) {
+    if unsupported.failed(prop) {
+        log::info!(
+            "Property {} timed out {UNSUPPORTED_FAILURES} times in a row, no longer polling",
+            prop.name
+        );
+    } else {
+        log::warn!("Timeout querying property {}", prop.name);
+    }
+}
+
+/// Run a query with a timeout, retrying according to the policy
+///
+/// Returns `None` if all attempts timed out. Data still arriving for an abandoned
+/// attempt is discarded before retrying, so that the protocol is back in sync.
+async fn with_retries<P: Read + Write, T>(
+    dev: &mut dyn Device<P>,
+    retry: RetryPolicy,
+    timeout: Duration,
+    mut query: impl AsyncFnMut(&mut dyn Device<P>) -> T,
+) -> Option<T> {
+    for attempt in 1..=retry.attempts() {
+        if let Ok(res) = tokio::time::timeout(timeout, query(&mut *dev)).await {
+            return Some(res);
+        }
+
+        if attempt < retry.attempts() {
+            log::info!("Query timed out, retrying ({attempt}/{})", retry.retries);
+            drain_input(dev).await;
+        }
+    }
+
+    None
+}
+
+/// Discard incoming data until the port has been silent for [`DRAIN_TIMEOUT`]
+async fn drain_input<P: Read + Write>(dev: &mut dyn Device<P>) {
+    let port = dev.interface().port_mut();
+    let mut buf = [0x00; 16];
+
+    while let Ok(Ok(len)) = tokio::time::timeout(DRAIN_TIMEOUT, port.read(&mut buf)).await {
+        if len == 0 {
+            break;
+        }
+    }
+}
+
+/// Convert the result of a property query for display, logging failures
+fn handle_query_result<E: std::error::Error>(
+    prop: &Property,
+    res: device::Result<(Value, Vec<u8>), E>,
+    unsupported: &mut Unsupported,
+) -> Result<Option<PropertyData>, LinkLost> {
+    let data = match res {
+        Ok((value, raw)) => Some(PropertyData {
+            id: prop.id,
+            name: prop.name.to_string(),
+            value: PropertyValue::from_property(prop, &value),
+            unit: prop.unit.map(String::from),
+            decimals: prop.decimals,
+            writable: prop.writable,
+            cumulative: prop.cumulative,
+            range: prop.range,
+            description: prop.description,
+            raw: Some(raw),
+        }),
+        Err(device::Error::Unsupported) => {
+            log::info!(
+                "Property {} not supported by device, no longer polling",
+                prop.name
+            );
+            unsupported.insert(prop);
+            None
+        }
+        Err(e) if is_link_error(&e) => return Err(LinkLost(e.to_string())),
+        Err(e) => {
+            if unsupported.failed(prop) {
+                log::info!(
+                    "Property {} failed {UNSUPPORTED_FAILURES} times in a row, no longer polling: {e}",
+                    prop.name
+                );
+            } else {
+                log::warn!("Failed to query property {}: {e}", prop.name);
+            }
+
+            None
+        }
+    };
+
+    if data.is_some() {
+        unsupported.succeeded(prop.id);
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freemdu::embedded_io_async::ErrorType;
+    use std::collections::VecDeque;
+    use std::convert::Infallible;
+
+    /// Port that answers with the responses needed to connect to a device
+    /// and then stalls or reports end-of-file
+    struct TestPort {
+        data: VecDeque<u8>,
+        stall: bool,
+        /// Everything written to the port
+        written: Vec<u8>,
+    }
+
+    impl TestPort {
+        fn new(stall: bool) -> Self {
+            Self {
+                data: VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00]),
+                stall,
+                written: Vec::new(),
+            }
+        }
+    }
+
+    impl ErrorType for TestPort {
+        type Error = Infallible;
+    }
+
+    impl Read for TestPort {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Infallible> {
+            if self.data.is_empty() && self.stall {
+                std::future::pending().await
+            } else {
+                self.data.read(buf).await
+            }
+        }
+    }
+
+    // Everything written is recorded, but never answered
+    #[allow(clippy::unused_async_trait_impl)]
+    impl Write for TestPort {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Infallible> {
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        async fn flush(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    /// Emulated washing machine that never answers reads of one memory address
+    struct SilentAddressPort {
+        inner: MockPort,
+        addr: u16,
+        /// Whether the last request was a read of the silent address
+        silent: bool,
+    }
+
+    impl ErrorType for SilentAddressPort {
+        type Error = Infallible;
+    }
+
+    impl Read for SilentAddressPort {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Infallible> {
+            if self.silent {
+                std::future::pending().await
+            } else {
+                self.inner.read(buf).await
+            }
+        }
+    }
+
+    impl Write for SilentAddressPort {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Infallible> {
+            // Requests start with command, address and length, followed by the checksum
+            if let &[cmd, lo, hi, _] = buf {
+                self.silent = cmd == 0x30 && u16::from_le_bytes([lo, hi]) == self.addr;
+            }
+
+            if self.silent {
+                Ok(buf.len())
+            } else {
+                self.inner.write(buf).await
+            }
+        }
+
+        async fn flush(&mut self) -> Result<(), Infallible> {
+            self.inner.flush().await
+        }
+    }
+
+    async fn connect_test(port: &mut TestPort) -> Box<dyn Device<&mut TestPort> + '_> {
+        device::connect(port)
+            .await
+            .expect("connection should succeed")
+    }
+
+    #[tokio::test]
+    async fn unsupported_property_not_polled_again() {
+        // Device responds with invalid command code to the read request
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00, 0x02]);
+        let mut dev = device::connect(&mut deque)
+            .await
+            .expect("connection should succeed");
+        let prop = dev
+            .property(PropertyId("serial_number"))
+            .expect("property should exist");
+        let mut unsupported = Unsupported::default();
+
+        assert!(
+            matches!(
+                query_property(
+                    dev.as_mut(),
+                    prop,
+                    &mut unsupported,
+                    RetryPolicy::default(),
+                    DEFAULT_QUERY_TIMEOUT,
+                )
+                .await,
+                Ok(None)
+            ),
+            "unsupported property should not return data"
+        );
+        assert!(
+            unsupported.contains(&prop.id),
+            "property should be marked as unsupported"
+        );
+
+        assert!(
+            matches!(
+                query_property(
+                    dev.as_mut(),
+                    prop,
+                    &mut unsupported,
+                    RetryPolicy::default(),
+                    DEFAULT_QUERY_TIMEOUT,
+                )
+                .await,
+                Ok(None)
+            ),
+            "unsupported property should be skipped"
+        );
+
+        drop(dev);
+
+        // Read EEPROM request for the serial number
+        let request = [0x31, 0xba, 0x01, 0x0a, 0xf6];
+        let sent: Vec<_> = deque.into_iter().collect();
+
+        assert_eq!(
+            sent.windows(request.len())
+                .filter(|w| *w == request)
+                .count(),
+            1,
+            "device should only be queried once"
+        );
+    }
+
+    #[tokio::test]
+    async fn failing_property_not_polled_again() {
+        // Device responds with an unknown response code to each read request
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00]);
+
+        deque.extend([0xff; UNSUPPORTED_FAILURES as usize]);
+
+        let mut dev = device::connect(&mut deque)
+            .await
+            .expect("connection should succeed");
+        let prop = dev
+            .property(PropertyId("serial_number"))
+            .expect("property should exist");
+        let mut unsupported = Unsupported::default();
+        let (resp_tx, resp_rx) = mpsc::channel();
+
+        for _ in 0..=UNSUPPORTED_FAILURES {
+            let res = query_property(
+                dev.as_mut(),
+                prop,
+                &mut unsupported,
+                RetryPolicy::default(),
+                DEFAULT_QUERY_TIMEOUT,
+            );
+
+            assert!(
+                matches!(res.await, Ok(None)),
+                "failing property should not return data"
+            );
+        }
+
+        assert!(
+            unsupported.contains(&prop.id),
+            "property should be marked as unsupported"
+        );
+
+        unsupported.report(&resp_tx);
+        unsupported.report(&resp_tx);
+
+        let reported: Vec<_> = resp_rx.try_iter().collect();
+
+        assert!(
+            matches!(
+                reported.as_slice(),
+                [WorkerResponse::Unsupported(props)] if props[0].0 == prop.id
+            ),
+            "unsupported properties should be reported once"
+        );
+
+        unsupported.clear();
+
+        assert!(
+            !unsupported.contains(&prop.id),
+            "property should be polled again"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn query_retried_after_timeout() {
+        let mut port = TestPort::new(true);
+        let mut dev = connect_test(&mut port).await;
+        let prop = dev
+            .property(PropertyId("rom_code"))
+            .expect("property should exist");
+        let retry = RetryPolicy::default();
+
+        assert!(
+            matches!(
+                query_property(
+                    dev.as_mut(),
+                    prop,
+                    &mut Unsupported::default(),
+                    retry,
+                    DEFAULT_QUERY_TIMEOUT,
+                )
+                .await,
+                Ok(None)
+            ),
+            "timed out property should not return data"
+        );
+
+        drop(dev);
+
+        // Read memory request for the ROM code
+        let request = [0x30, 0xdb, 0xff, 0x01, 0x0b];
+
+        assert_eq!(retry.attempts(), 3, "default attempts should be correct");
+        assert_eq!(
+            port.written
+                .windows(request.len())
+                .filter(|w| *w == request)
+                .count(),
+            3,
+            "query should be attempted three times"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn query_waits_for_configured_timeout() {
+        let mut port = TestPort::new(true);
+        let mut dev = connect_test(&mut port).await;
+        let prop = dev
+            .property(PropertyId("rom_code"))
+            .expect("property should exist");
+        let retry = RetryPolicy { retries: 0 };
+        let timeout = Duration::from_secs(10);
+        let start = tokio::time::Instant::now();
+
+        assert!(
+            matches!(
+                query_property(
+                    dev.as_mut(),
+                    prop,
+                    &mut Unsupported::default(),
+                    retry,
+                    timeout
+                )
+                .await,
+                Ok(None)
+            ),
+            "timed out property should not return data"
+        );
+        assert_eq!(
+            start.elapsed(),
+            timeout,
+            "query should wait for the timeout"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn timed_out_property_keeps_batch() {
+        // ROM code in the middle of the general properties
+        let port = SilentAddressPort {
+            inner: MockPort::washing_machine(),
+            addr: 0xffdb,
+            silent: false,
+        };
+        let mut dev = device::connect(port)
+            .await
+            .expect("connection should succeed");
+        let properties = dev.properties();
+        let (_cmd_tx, cmd_rx) = mpsc::channel();
+        let mut commands = CommandQueue::new(&cmd_rx);
+        let mut unsupported = Unsupported::default();
+        let general = properties
+            .iter()
+            .filter(|prop| prop.kind == PropertyKind::General)
+            .count();
+
+        for refresh in 1..=UNSUPPORTED_FAILURES {
+            let res = query_properties(
+                dev.as_mut(),
+                properties,
+                Refresh::new(PropertyKind::General),
+                &mut unsupported,
+                ConnectOptions::default(),
+                &mut commands,
+                &mut Latency::default(),
+            )
+            .await;
+            let Ok(RefreshOutcome::Complete(data)) = res else {
+                panic!("refresh should complete");
+            };
+
+            assert_eq!(
+                data.len(),
+                general - 1,
+                "only the timed out property should be missing"
+            );
+            assert!(
+                data.iter().all(|prop| prop.id != PropertyId("rom_code")),
+                "timed out property should not return data"
+            );
+            assert_eq!(
+                unsupported.contains(&PropertyId("rom_code")),
+                refresh == UNSUPPORTED_FAILURES,
+                "property should only be unsupported after repeated timeouts"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn unpolled_property_skipped() {
+        let mut dev = device::connect(MockPort::washing_machine())
+            .await
+            .expect("connection should succeed");
+        let properties = dev.properties();
+        let (_cmd_tx, cmd_rx) = mpsc::channel();
+        let mut commands = CommandQueue::new(&cmd_rx);
+        let general: Vec<_> = properties
+            .iter()
+            .filter(|prop| prop.kind == PropertyKind::General)
+            .collect();
+        let unpolled = [
+            (PropertyKind::General, general[0].name.to_string()),
+            (PropertyKind::Io, general[1].name.to_string()),
+        ];
+
+        let res = query_properties(
+            dev.as_mut(),
+            properties,
+            Refresh::new(PropertyKind::General).skipping(&unpolled),
+            &mut Unsupported::default(),
+            ConnectOptions::default(),
+            &mut commands,
+            &mut Latency::default(),
+        )
+        .await;
+        let Ok(RefreshOutcome::Complete(data)) = res else {
+            panic!("refresh should complete");
+        };
+
+        assert_eq!(
+            data.len(),
+            general.len() - 1,
+            "only the unpolled property should be skipped"
+        );
+        assert!(
+            data.iter().all(|prop| prop.id != general[0].id),
+            "unpolled property should not be queried"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refresh_canceled() {
+        let mut port = TestPort::new(true);
+        let mut dev = connect_test(&mut port).await;
+        let properties = dev.properties();
+        let (cmd_tx, cmd_rx) = mpsc::channel();
+        let mut commands = CommandQueue::new(&cmd_rx);
+        let start = tokio::time::Instant::now();
+
+        for cmd in [
+            WorkerCommand::QueryProperties(PropertyKind::General),
+            WorkerCommand::Cancel,
+            WorkerCommand::QueryProperties(PropertyKind::Io),
+        ] {
+            cmd_tx.send(cmd).expect("command should be sent");
+        }
+
+        assert!(
+            matches!(
+                query_properties(
+                    dev.as_mut(),
+                    properties,
+                    Refresh::new(PropertyKind::Failure),
+                    &mut Unsupported::default(),
+                    ConnectOptions::default(),
+                    &mut commands,
+                    &mut Latency::default(),
+                )
+                .await,
+                Ok(RefreshOutcome::Aborted)
+            ),
+            "canceled refresh should not return data"
+        );
+        assert_eq!(
+            start.elapsed(),
+            Duration::ZERO,
+            "device should not be queried"
+        );
+        assert!(
+            matches!(
+                commands.pending.iter().collect::<Vec<_>>()[..],
+                [WorkerCommand::QueryProperties(PropertyKind::Io)]
+            ),
+            "only refreshes requested after canceling should be kept"
+        );
+        assert_eq!(
+            commands.refresh_interrupted(),
+            None,
+            "queued refresh should not interrupt another"
+        );
+
+        cmd_tx
+            .send(WorkerCommand::Disconnect)
+            .expect("command should be sent");
+
+        assert_eq!(
+            commands.refresh_interrupted(),
+            Some(Interrupt::Preempted),
+            "disconnect should interrupt refresh"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refresh_preempted() {
+        let mut port = TestPort::new(true);
+        let mut dev = connect_test(&mut port).await;
+        let properties = dev.properties();
+        let (cmd_tx, cmd_rx) = mpsc::channel();
+        let mut commands = CommandQueue::new(&cmd_rx);
+        let start = tokio::time::Instant::now();
+
+        for cmd in [
+            WorkerCommand::QueryProperties(PropertyKind::Io),
+            WorkerCommand::TriggerAction("start_program".to_string(), None, Duration::ZERO),
+        ] {
+            cmd_tx.send(cmd).expect("command should be sent");
+        }
+
+        let res = query_properties(
+            dev.as_mut(),
+            properties,
+            Refresh::new(PropertyKind::General),
+            &mut Unsupported::default(),
+            ConnectOptions::default(),
+            &mut commands,
+            &mut Latency::default(),
+        )
+        .await;
+        let Ok(RefreshOutcome::Preempted(refresh)) = res else {
+            panic!("refresh should be preempted");
+        };
+
+        assert_eq!(
+            refresh.kind,
+            PropertyKind::General,
+            "kind of preempted refresh should be correct"
+        );
+        assert!(refresh.data.is_empty(), "no property should be queried");
+        assert_eq!(
+            start.elapsed(),
+            Duration::ZERO,
+            "device should not be queried"
+        );
+        assert!(
+            matches!(
+                commands.recv_timeout(Duration::ZERO),
+                Ok(WorkerCommand::TriggerAction(..))
+            ),
+            "action should be handled before queued refresh"
+        );
+        assert!(
+            matches!(
+                commands.recv_timeout(Duration::ZERO),
+                Ok(WorkerCommand::QueryProperties(PropertyKind::Io))
+            ),
+            "queued refresh should be handled afterwards"
+        );
+    }
+
+    #[test]
+    fn warm_up_canceled() {
+        let (cmd_tx, cmd_rx) = mpsc::channel();
+        let mut commands = CommandQueue::new(&cmd_rx).warming_up(WarmUpOrder::default());
+
+        assert_eq!(
+            commands.warm_up.front(),
+            Some(&PropertyKind::Io),
+            "warm-up should follow the order"
+        );
+
+        cmd_tx
+            .send(WorkerCommand::Cancel)
+            .expect("command should be sent");
+
+        assert_eq!(
+            commands.refresh_interrupted(),
+            Some(Interrupt::Canceled),
+            "cancel should interrupt refresh"
+        );
+        assert!(commands.warm_up.is_empty(), "warm-up should be dropped");
+    }
+
+    #[test]
+    fn warm_up_order_reordered() {
+        let mut order = WarmUpOrder::default();
+
+        order.move_earlier(4);
+        order.move_earlier(0);
+
+        assert_eq!(
+            order.0[3],
+            PropertyKind::General,
+            "kind should be moved earlier"
+        );
+        assert!(order.is_valid(), "reordered kinds should be valid");
+        assert!(
+            !WarmUpOrder([PropertyKind::Io; 5]).is_valid(),
+            "repeated kinds should be invalid"
+        );
+    }
+
+    #[test]
+    fn bitfield_flags() {
+        let prop = Property {
+            kind: PropertyKind::Io,
+            id: PropertyId("outputs"),
+            name: "Outputs",
+            description: None,
+            unit: None,
+            writable: false,
+            decimals: 0,
+            decoding: Some(Decode::Bitfield(&["Pump 1", "Pump 2", "3-Way Valve"])),
+            register: None,
+            cumulative: false,
+            range: None,
+        };
+        let raw = prop
+            .decode(&[0x05, 0x00])
+            .expect("property should have a decoding");
+        let value = PropertyValue::from_property(&prop, &raw);
+
+        assert_eq!(
+            value,
+            PropertyValue::Flags(vec![
+                ("Pump 1".to_string(), true),
+                ("Pump 2".to_string(), false),
+                ("3-Way Valve".to_string(), true),
+            ]),
+            "flags should be correct"
+        );
+        assert_eq!(
+            Value::from(&value),
+            Value::String("Pump 1 | 3-Way Valve".to_string()),
+            "value of set flags should be correct"
+        );
+    }
+
+    #[test]
+    fn latency_average() {
+        let mut latency = Latency::default();
+
+        assert!(
+            latency.response().is_none(),
+            "latency should not be reported without round trips"
+        );
+
+        for ms in 1..=LATENCY_SAMPLES as u64 + 2 {
+            latency.record(Duration::from_millis(ms * 10));
+        }
+
+        let Some(WorkerResponse::Latency { avg_ms, last_ms }) = latency.response() else {
+            panic!("latency should be reported");
+        };
+
+        assert!(
+            (avg_ms - 125.0).abs() < 1e-6,
+            "average of the most recent round trips should be correct"
+        );
+        assert!(
+            (last_ms - 220.0).abs() < 1e-6,
+            "last round trip should be correct"
+        );
+    }
+
+    #[test]
+    fn link_stats_since_session() {
+        let link = LinkHealth::new(TransportStats {
+            frames_ok: 100,
+            checksum_errors: 1,
+            timeouts: 2,
+        });
+        let WorkerResponse::LinkStats { stats, connected } = link.response(TransportStats {
+            frames_ok: 150,
+            checksum_errors: 1,
+            timeouts: 3,
+        }) else {
+            panic!("link stats should be reported");
+        };
+
+        assert_eq!(
+            stats,
+            TransportStats {
+                frames_ok: 50,
+                checksum_errors: 0,
+                timeouts: 1,
+            },
+            "transfers since the session started should be correct"
+        );
+        assert_eq!(connected, link.connected, "uptime should be kept");
+    }
+
+    #[tokio::test]
+    async fn recorded_session_replayed() {
+        let path = std::env::temp_dir().join(format!("freemdu-{}.freemdu", std::process::id()));
+        let recording = SessionRecording::create(&path).expect("recording should be created");
+        let mut intf = Interface::new(MockPort::washing_machine());
+
+        intf.set_observer(Some(recording.observer()));
+
+        let mut dev = device::connect_interface(intf)
+            .await
+            .expect("connection should succeed");
+        let prop = dev
+            .property(PropertyId("operating_time"))
+            .expect("property should exist");
+        let recorded = dev.query_property(prop).await.ok();
+
+        drop(dev);
+
+        let res = device::connect_replay(&path, ReplayEnd::Loop).await;
+        let _ = std::fs::remove_file(&path);
+        let mut dev = res.expect("replayed connection should succeed");
+
+        assert_eq!(dev.software_id(), 629, "software ID should be replayed");
+        assert_eq!(
+            dev.query_property(prop).await.ok(),
+            recorded,
+            "property value should be replayed"
+        );
+    }
+
+    #[tokio::test]
+    async fn open_tcp_port() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let addr = listener.local_addr().expect("address should be known");
+        let timeout = Duration::from_secs(1);
+
+        assert!(
+            open_port(
+                &format!("{TCP_PREFIX}{addr}"),
+                &SerialConfig::default(),
+                timeout
+            )
+            .await
+            .is_ok(),
+            "bridge should be connected"
+        );
+
+        drop(listener);
+
+        assert!(
+            matches!(
+                open_port(
+                    &format!("{TCP_PREFIX}{addr}"),
+                    &SerialConfig::default(),
+                    timeout
+                )
+                .await,
+                Err(ConnectError::Port(_))
+            ),
+            "closed bridge should be port error"
+        );
+    }
+
+    #[tokio::test]
+    async fn set_property_not_writable() {
+        let mut port = TestPort::new(false);
+        let mut dev = connect_test(&mut port).await;
+        let prop = dev
+            .property(PropertyId("serial_number"))
+            .expect("property should exist");
+
+        let value = PropertyValue::String("1".to_string());
+        let res = set_property(dev.as_mut(), prop, &value, DEFAULT_QUERY_TIMEOUT)
+            .await
+            .expect("serial link should be up");
+
+        assert_eq!(
+            res,
+            Err("property is not writable".to_string()),
+            "result should be correct"
+        );
+    }
+
+    #[tokio::test]
+    async fn action_times_out_with_progress() {
+        let mut port = TestPort::new(true);
+        let mut dev = connect_test(&mut port).await;
+        let action = dev
+            .actions()
+            .iter()
+            .find(|action| action.id == "start_program")
+            .expect("action should exist");
+        let abort = AtomicBool::new(false);
+        let (resp_tx, resp_rx) = mpsc::channel();
+
+        let outcome = run_action(
+            dev.as_mut(),
+            action,
+            None,
+            Duration::from_millis(500),
+            &abort,
+            &resp_tx,
+        )
+        .await
+        .expect("serial link should be up");
+
+        assert!(!outcome.success, "action should fail");
+        assert_eq!(outcome.message, "Timeout", "message should be correct");
+        assert!(
+            resp_rx.try_iter().any(
+                |resp| matches!(resp, WorkerResponse::ActionProgress(p) if p.id == "start_program")
+            ),
+            "progress should be reported"
+        );
+    }
+
+    #[tokio::test]
+    async fn action_aborted() {
+        let mut port = TestPort::new(true);
+        let mut dev = connect_test(&mut port).await;
+        let action = dev
+            .actions()
+            .iter()
+            .find(|action| action.id == "start_program")
+            .expect("action should exist");
+        let abort = AtomicBool::new(false);
+        let (resp_tx, _resp_rx) = mpsc::channel();
+
+        let (outcome, ()) = tokio::join!(
+            run_action(
+                dev.as_mut(),
+                action,
+                None,
+                Duration::from_secs(60),
+                &abort,
+                &resp_tx,
+            ),
+            async {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                abort.store(true, Ordering::Relaxed);
+            }
+        );
+
+        let outcome = outcome.expect("serial link should be up");
+
+        assert!(!outcome.success, "action should fail");
+        assert_eq!(outcome.message, "Aborted", "message should be correct");
+    }
+
+    #[tokio::test]
+    async fn baud_rate_detected() {
+        let mut attempts = Vec::new();
+
+        // Device only answers at 9600 baud
+        let attempt = async |baud_rate| {
+            attempts.push(baud_rate);
+
+            if baud_rate == 9600 {
+                let deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00]);
+
+                device::connect(deque)
+                    .await
+                    .map_err(|e| ConnectError::Device(e.to_string()))
+            } else {
+                Err(ConnectError::Timeout)
+            }
+        };
+        let (dev, baud_rate) = detect_baud_rate(&freemdu::serial::BAUD_RATES, attempt)
+            .await
+            .expect("connection should succeed");
+
+        assert_eq!(baud_rate, 9600, "baud rate should be correct");
+        assert_eq!(dev.software_id(), 629, "software ID should be correct");
+        assert_eq!(
+            attempts,
+            [2400, 9600],
+            "default baud rate should be tried first"
+        );
+    }
+
+    #[tokio::test]
+    async fn baud_rate_detection_stops_on_port_error() {
+        let mut attempts = 0;
+        let attempt = async |_| {
+            attempts += 1;
+
+            Err::<(), _>(ConnectError::Port("No such file or directory".to_string()))
+        };
+
+        assert!(
+            matches!(
+                detect_baud_rate(&freemdu::serial::BAUD_RATES, attempt).await,
+                Err(ConnectError::Port(_))
+            ),
+            "result should be port error"
+        );
+        assert_eq!(attempts, 1, "port should only be opened once");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn misaligned_probe_response() {
+        // NMEA sentence from a GPS receiver
+        let mut port = TestPort {
+            data: VecDeque::from(b"$GPGGA,123519".to_vec()),
+            stall: true,
+            written: Vec::new(),
+        };
+        let received = probe_response(&mut port).await;
+
+        assert_eq!(
+            port.written, IDENTIFY_FRAME,
+            "identify frame should be sent"
+        );
+        assert_eq!(
+            received, b"$GPGGA,123519",
+            "received data should be correct"
+        );
+        assert!(is_misaligned(&received), "NMEA data should be misaligned");
+        assert!(!is_misaligned(&[]), "silence should not be misaligned");
+        assert!(
+            !is_misaligned(&[0x00]),
+            "response code should not be misaligned"
+        );
+        assert!(
+            is_misaligned(&[0x00, 0xf8, 0x80]),
+            "garbage should be misaligned"
+        );
+    }
+
+    #[tokio::test]
+    async fn link_lost_on_eof() {
+        let mut port = TestPort::new(false);
+        let mut dev = connect_test(&mut port).await;
+        let prop = dev
+            .property(PropertyId("serial_number"))
+            .expect("property should exist");
+
+        assert!(
+            query_property(
+                dev.as_mut(),
+                prop,
+                &mut Unsupported::default(),
+                RetryPolicy::default(),
+                DEFAULT_QUERY_TIMEOUT,
+            )
+            .await
+            .is_err(),
+            "link should be reported as lost"
+        );
+    }
+
+    #[tokio::test]
+    async fn offline_device_not_reconfigured() {
+        let (cmd_tx, cmd_rx) = mpsc::channel();
+        let (resp_tx, resp_rx) = mpsc::channel();
+
+        for cmd in [
+            WorkerCommand::Reconfigure(serial_config(9600, Timeouts::default())),
+            WorkerCommand::Disconnect,
+        ] {
+            cmd_tx.send(cmd).expect("command should be sent");
+        }
+
+        run_demo(
+            ConnectOptions::default(),
+            &AtomicBool::new(false),
+            &cmd_rx,
+            &resp_tx,
+        )
+        .await;
+
+        let responses: Vec<_> = resp_rx.try_iter().collect();
+
+        assert!(
+            matches!(
+                responses[..],
+                [
+                    WorkerResponse::Connected(_),
+                    WorkerResponse::ReconfigureFailed(_),
+                    WorkerResponse::Disconnected
+                ]
+            ),
+            "session should continue after failing to reconfigure: {responses:?}"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reconnect_with_backoff() {
+        let (_cmd_tx, cmd_rx) = mpsc::channel();
+        let (resp_tx, resp_rx) = mpsc::channel();
+        let start = tokio::time::Instant::now();
+        let mut attempts = 0;
+
+        // Port only comes back on the third attempt
+        let attempt = async || {
+            attempts += 1;
+
+            if attempts < 3 {
+                Err(ConnectError::Port("No such file or directory".to_string()))
+            } else {
+                Ok(())
+            }
+        };
+
+        assert!(
+            reconnect(attempt, &cmd_rx, &resp_tx).await.is_some(),
+            "reconnection should succeed"
+        );
+        assert_eq!(
+            start.elapsed(),
+            Duration::from_millis(500 + 1000 + 2000),
+            "backoff should be correct"
+        );
+
+        let attempts: Vec<_> = resp_rx
+            .try_iter()
+            .map(|resp| match resp {
+                WorkerResponse::Reconnecting(num) => num,
+                resp => panic!("unexpected response: {resp:?}"),
+            })
+            .collect();
+
+        assert_eq!(attempts, [1, 2, 3], "attempts should be reported");
+    }
+
+    #[test]
+    fn adapter_located_by_identity() {
+        use serialport::{SerialPortInfo, SerialPortType, UsbPortInfo};
+
+        let port = |name: &str, serial: &str| SerialPortInfo {
+            port_name: name.to_string(),
+            port_type: SerialPortType::UsbPort(UsbPortInfo {
+                vid: 0x0403,
+                pid: 0x6001,
+                serial_number: Some(serial.to_string()),
+                manufacturer: Some("FTDI".to_string()),
+                product: Some("FT232R USB UART".to_string()),
+            }),
+        };
+        let before = [port("COM3", "A50285BI"), port("COM4", "B7731CX")];
+        let after = [port("COM4", "B7731CX"), port("COM5", "A50285BI")];
+        let identity = AdapterIdentity::find(&before, "COM3", str::to_string)
+            .expect("USB port should have identity");
+
+        assert_eq!(
+            identity.locate(&after, str::to_string).as_deref(),
+            Some("COM5"),
+            "renamed port should be found"
+        );
+        assert!(
+            identity.locate(&after[..1], str::to_string).is_none(),
+            "missing adapter should not be found"
+        );
+
+        let twins = [port("COM4", "A50285BI"), port("COM5", "A50285BI")];
+
+        assert!(
+            identity.locate(&twins, str::to_string).is_none(),
+            "ambiguous identity should not be found"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reconnect_gives_up() {
+        let (_cmd_tx, cmd_rx) = mpsc::channel();
+        let (resp_tx, resp_rx) = mpsc::channel();
+        let attempt = async || Err::<(), _>(ConnectError::Timeout);
+
+        assert!(
+            reconnect(attempt, &cmd_rx, &resp_tx).await.is_none(),
+            "reconnection should fail"
+        );
+
+        let resps: Vec<_> = resp_rx.try_iter().collect();
+
+        assert_eq!(
+            resps.len(),
+            MAX_RECONNECT_ATTEMPTS as usize + 1,
+            "each attempt should be reported"
+        );
+        assert!(
+            matches!(resps.last(), Some(WorkerResponse::Disconnected)),
+            "disconnect should be reported last"
+        );
+    }
+
+    #[test]
+    fn shutdown_stops_worker() {
+        let mut worker = WorkerHandle::new(DEMO_PORT, ConnectOptions::default(), None);
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        loop {
+            match worker.try_recv() {
+                Some(WorkerResponse::Connected(_)) => break,
+                Some(resp) => panic!("unexpected response: {resp:?}"),
+                None => {
+                    assert!(Instant::now() < deadline, "demo device should connect");
+                    thread::sleep(Duration::from_millis(10));
+                }
+            }
+        }
+
+        assert!(worker.shutdown(), "worker thread should stop in time");
+        assert!(
+            matches!(worker.try_recv(), Some(WorkerResponse::Disconnected)),
+            "disconnect should be reported"
+        );
+    }
 }
@@ -131,18 +131,27 @@ async fn publish_device(port: &mut OpticalPort<'_>, hostname: &str) -> Result<()
     let mut vals = Vec::with_capacity(props.clone().count());
 
     // Query properties first, as publishing them immediately might lead to timeout
-    for prop in props.clone() {
-        let val = dev
+    for prop in props {
+        let res = dev
             .query_property(prop)
             .with_timeout(DEVICE_TIMEOUT)
             .await
-            .map_err(|err| anyhow::anyhow!("Failed to query property: {err:?}"))??;
+            .map_err(|err| anyhow::anyhow!("Failed to query property: {err:?}"))?;
 
-        info!("Queried property {prop:?} with value {val:?}");
-        vals.push(val);
+        match res {
+            Err(device::Error::Unsupported) => {
+                info!("Skipped unsupported property: {prop:?}");
+            }
+            res => {
+                let val = res?;
+
+                info!("Queried property {prop:?} with value {val:?}");
+                vals.push((prop, val));
+            }
+        }
     }
 
-    for (prop, val) in props.zip(vals) {
+    for (prop, val) in vals {
         publish_property(prop, &dev_kind, hostname).await?;
         publish_property_value(prop, &val).await?;
         info!("Published property: {prop:?}");
@@ -192,6 +201,11 @@ async fn publish_property_value(prop: &Property, val: &Value) -> Result<()> {
 
     match *val {
         Value::Number(num) => topic.with_display(num).publish().await,
+        Value::Float(num) => {
+            let prec = usize::from(prop.decimals);
+
+            topic.with_display(format!("{num:.prec$}")).publish().await
+        }
         Value::Bool(val) => {
             topic
                 .with_display(if val { "Yes" } else { "No" })
@@ -246,17 +260,26 @@ async fn trigger_action(port: &mut OpticalPort<'_>, id: &str, param: &str) -> Re
 
     info!("Triggering action {action:?} with parameter {param}");
 
-    let param = if action.params.is_some() {
-        Some(Value::String(param.to_string()))
-    } else {
-        None
+    let param = match &action.params {
+        Some(params) => Some(
+            params
+                .parse_value(param)
+                .ok_or_else(|| anyhow::anyhow!("Invalid parameter for action {id}: {param}"))?,
+        ),
+        None => None,
     };
 
-    Ok(dev
+    let res = dev
         .trigger_action(action, param)
         .with_timeout(DEVICE_TIMEOUT)
         .await
-        .map_err(|err| anyhow::anyhow!("Failed to trigger action: {err:?}"))??)
+        .map_err(|err| anyhow::anyhow!("Failed to trigger action: {err:?}"))??;
+
+    if let Some(val) = res {
+        info!("Action {} returned {val:?}", action.id);
+    }
+
+    Ok(())
 }
 
 async fn connect_to_device<'a, 'b>(
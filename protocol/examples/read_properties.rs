@@ -0,0 +1,40 @@
+use freemdu::client::DeviceClient;
+use freemdu::device::PropertyKind;
+use std::error::Error;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "/dev/ttyACM0".to_string());
+    let client = DeviceClient::open(&path).await?;
+
+    println!(
+        "Connected to {}, software ID {}",
+        client.kind(),
+        client.software_id()
+    );
+
+    // Each query runs in its own task, the client forwards them to the device one by one
+    let tasks: Vec<_> = client
+        .properties()
+        .iter()
+        .filter(|prop| prop.kind == PropertyKind::General)
+        .take(3)
+        .map(|prop| {
+            let client = client.clone();
+
+            tokio::spawn(async move { (prop, client.query_property(prop.id).await) })
+        })
+        .collect();
+
+    for task in tasks {
+        let (prop, val) = task.await?;
+
+        println!("{}: {:?}", prop.name, val?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,227 @@
+//! Blocking access to a device from synchronous applications.
+//!
+//! [`Device`] methods are asynchronous and require a runtime to drive them.
+//! A [`BlockingDevice`] owns a current-thread runtime instead and blocks on each call,
+//! so scripts and other synchronous programs can use the library without `async`.
+//!
+//! Since the runtime is owned by the device, it can't be used from within another runtime,
+//! e.g. inside `#[tokio::main]`. Asynchronous applications should use [`Device`] directly
+//! or a [`DeviceClient`](crate::client::DeviceClient) instead.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use freemdu::blocking::BlockingDevice;
+//! use freemdu::device::PropertyId;
+//!
+//! # fn main() -> freemdu::device::Result<(), freemdu::serial::PortError> {
+//! let mut dev = BlockingDevice::open("/dev/ttyACM0")?;
+//! let val = dev.query_property(PropertyId("operating_time"))?;
+//!
+//! println!("{}, operating time {val:?}", dev.kind());
+//! # Ok(())
+//! # }
+//! ```
+
+extern crate std;
+
+use crate::device::{
+    self, Action, Device, DeviceKind, Error, FaultRecord, Property, PropertyId, Result, Value,
+};
+use crate::serial::{self, SerialConfig};
+use alloc::{boxed::Box, vec::Vec};
+use embedded_io_async::{Read, Write};
+use tokio::runtime::Runtime;
+
+/// Device driven by a runtime of its own, with methods that block until they complete.
+///
+/// The runtime is only used by the device and is dropped along with it.
+///
+/// # Panics
+///
+/// All methods communicating with the device panic if called from within
+/// an asynchronous context, as the owned runtime can't be nested inside another one.
+pub struct BlockingDevice<P> {
+    // Dropped before the runtime, since ports may be bound to it
+    dev: Box<dyn Device<P>>,
+    rt: Runtime,
+}
+
+impl BlockingDevice<serial::Port> {
+    /// Opens the serial port at the specified path and connects to the device.
+    ///
+    /// See [`serial::open`] and [`device::connect`].
+    pub fn open(path: &str) -> Result<Self, serial::PortError> {
+        Self::open_with(path, &SerialConfig::default())
+    }
+
+    /// Opens the serial port at the specified path with a custom configuration
+    /// and connects to the device.
+    ///
+    /// See [`serial::open_with`] and [`device::connect`].
+    pub fn open_with(path: &str, config: &SerialConfig) -> Result<Self, serial::PortError> {
+        let rt = runtime()?;
+        // The port is bound to the runtime it's opened in
+        let port = {
+            let _guard = rt.enter();
+
+            serial::open_with(path, config)?
+        };
+
+        Self::connect_in(rt, port)
+    }
+
+    /// Connects to a device behind a serial-to-TCP bridge at the specified address.
+    ///
+    /// See [`serial::open_tcp`] and [`device::connect`].
+    pub fn open_tcp(addr: &str) -> Result<Self, serial::PortError> {
+        let rt = runtime()?;
+        let port = rt.block_on(serial::open_tcp(addr))?;
+
+        Self::connect_in(rt, port)
+    }
+}
+
+impl<P: Read + Write + 'static> BlockingDevice<P> {
+    /// Connects to the device via the given port.
+    ///
+    /// Ports bound to a runtime, like [`serial::Port`], can't be opened beforehand,
+    /// use [`BlockingDevice::open`] or [`BlockingDevice::open_tcp`] for these instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::Error::UnexpectedEof`], as if the port had been closed,
+    /// if the runtime couldn't be started.
+    ///
+    /// See [`device::connect`] for other errors.
+    pub fn connect(port: P) -> Result<Self, P::Error> {
+        Self::connect_in(runtime()?, port)
+    }
+
+    fn connect_in(rt: Runtime, port: P) -> Result<Self, P::Error> {
+        let dev = rt.block_on(device::connect(port))?;
+
+        Ok(Self { dev, rt })
+    }
+
+    /// Returns the device's software ID.
+    #[must_use]
+    pub fn software_id(&self) -> u16 {
+        self.dev.software_id()
+    }
+
+    /// Returns the device's kind.
+    #[must_use]
+    pub fn kind(&self) -> DeviceKind {
+        self.dev.kind()
+    }
+
+    /// Returns the set of queryable properties.
+    #[must_use]
+    pub fn properties(&self) -> &'static [Property] {
+        self.dev.properties()
+    }
+
+    /// Returns the property with the given identifier,
+    /// or `None` if the device doesn't support it.
+    #[must_use]
+    pub fn property(&self, id: PropertyId) -> Option<&'static Property> {
+        self.properties().iter().find(|prop| prop.id == id)
+    }
+
+    /// Returns the set of actions that can be triggered.
+    #[must_use]
+    pub fn actions(&self) -> &'static [Action] {
+        self.dev.actions()
+    }
+
+    /// Queries the property with the given identifier.
+    ///
+    /// # Errors
+    ///
+    /// See [`Device::query_property`].
+    pub fn query_property(&mut self, id: PropertyId) -> Result<Value, P::Error> {
+        let prop = self.property(id).ok_or(Error::UnknownProperty)?;
+
+        self.rt.block_on(self.dev.query_property(prop))
+    }
+
+    /// Changes the value of the writable property with the given identifier.
+    ///
+    /// # Errors
+    ///
+    /// See [`Device::set_property`].
+    pub fn set_property(&mut self, id: PropertyId, value: Value) -> Result<(), P::Error> {
+        let prop = self.property(id).ok_or(Error::UnknownProperty)?;
+
+        self.rt.block_on(self.dev.set_property(prop, value))
+    }
+
+    /// Triggers the action with the given identifier.
+    ///
+    /// # Errors
+    ///
+    /// See [`Device::trigger_action`].
+    pub fn trigger_action(
+        &mut self,
+        id: &str,
+        param: Option<Value>,
+    ) -> Result<Option<Value>, P::Error> {
+        let action = self
+            .actions()
+            .iter()
+            .find(|action| action.id == id)
+            .ok_or(Error::UnknownAction)?;
+
+        self.rt.block_on(self.dev.trigger_action(action, param))
+    }
+
+    /// Queries the faults stored by the device.
+    ///
+    /// # Errors
+    ///
+    /// See [`Device::fault_history`].
+    pub fn fault_history(&mut self) -> Result<Vec<FaultRecord>, P::Error> {
+        self.rt.block_on(self.dev.fault_history())
+    }
+}
+
+/// Start the current-thread runtime owned by a device.
+fn runtime<E>() -> Result<Runtime, E> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| {
+            log::error!("Failed to start runtime for blocking device: {e}");
+            Error::Protocol(crate::Error::UnexpectedEof)
+        })
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocking_calls() {
+        use crate::mock::MockPort;
+
+        let mut dev = BlockingDevice::connect(MockPort::washing_machine())
+            .expect("connection should succeed");
+
+        assert_eq!(dev.software_id(), 629, "software ID should be correct");
+        assert!(
+            dev.query_property(PropertyId("serial_number")).is_ok(),
+            "property should be queried"
+        );
+        assert_eq!(
+            dev.query_property(PropertyId("unknown")),
+            Err(Error::UnknownProperty),
+            "unknown property should be rejected"
+        );
+        assert_eq!(
+            dev.trigger_action("unknown", None),
+            Err(Error::UnknownAction),
+            "unknown action should be rejected"
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Shared access to a device from multi-threaded asynchronous applications.
+//!
+//! [`Device`] methods take `&mut self` and return futures that aren't [`Send`],
+//! so a device can only be used by a single task on a current-thread runtime.
+//! A [`DeviceClient`] serves the device on a dedicated thread instead and forwards
+//! requests to it. The client can be cloned and used from any task of any runtime,
+//! e.g. one started by `#[tokio::main]`.
+//!
+//! The diagnostic protocol only allows a single outstanding request,
+//! so requests from several clones are handled one after another.
+//! The thread exits and closes the port once all clones are dropped.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use freemdu::client::DeviceClient;
+//! use freemdu::device::PropertyId;
+//!
+//! # async fn example() -> freemdu::device::Result<(), freemdu::serial::PortError> {
+//! let client = DeviceClient::open("/dev/ttyACM0").await?;
+//! let val = client.query_property(PropertyId("operating_time")).await?;
+//!
+//! println!("{}, operating time {val:?}", client.kind());
+//! # Ok(())
+//! # }
+//! ```
+
+extern crate std;
+
+use crate::device::{
+    self, Action, Device, DeviceKind, Error, FaultRecord, Property, PropertyId, Result, Value,
+};
+use crate::serial;
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::future::Future;
+use embedded_io_async::{ErrorType, Read, Write};
+use tokio::sync::{mpsc, oneshot};
+
+/// Channel the result of a request is sent back on.
+type Reply<T, E> = oneshot::Sender<Result<T, E>>;
+
+/// Request forwarded to the thread serving the device.
+enum Request<E> {
+    QueryProperty(&'static Property, Reply<Value, E>),
+    SetProperty(&'static Property, Value, Reply<(), E>),
+    TriggerAction(&'static Action, Option<Value>, Reply<Option<Value>, E>),
+    FaultHistory(Reply<Vec<FaultRecord>, E>),
+}
+
+/// Properties of the device known after connecting, which don't require communication.
+struct DeviceDetails {
+    software_id: u16,
+    kind: DeviceKind,
+    properties: &'static [Property],
+    actions: &'static [Action],
+}
+
+/// Handle to a device served on a dedicated thread.
+///
+/// Cloning the client is cheap, all clones communicate with the same device.
+/// Unlike [`Device`], the methods take `&self` and return futures that are [`Send`].
+///
+/// If the thread serving the device has stopped, e.g. because it couldn't start a runtime,
+/// requests fail with [`crate::Error::UnexpectedEof`], as if the port had been closed.
+pub struct DeviceClient<E> {
+    requests: mpsc::UnboundedSender<Request<E>>,
+    software_id: u16,
+    kind: DeviceKind,
+    properties: &'static [Property],
+    actions: &'static [Action],
+}
+
+impl<E> Clone for DeviceClient<E> {
+    fn clone(&self) -> Self {
+        Self {
+            requests: self.requests.clone(),
+            software_id: self.software_id,
+            kind: self.kind,
+            properties: self.properties,
+            actions: self.actions,
+        }
+    }
+}
+
+impl DeviceClient<serial::PortError> {
+    /// Opens the serial port at the specified path and connects to the device.
+    ///
+    /// See [`serial::open`] and [`device::connect`].
+    pub async fn open(path: &str) -> Result<Self, serial::PortError> {
+        let path = String::from(path);
+
+        Self::connect(async move || Ok(serial::open(&path)?)).await
+    }
+
+    /// Connects to a device behind a serial-to-TCP bridge at the specified address.
+    ///
+    /// See [`serial::open_tcp`] and [`device::connect`].
+    pub async fn open_tcp(addr: &str) -> Result<Self, serial::PortError> {
+        let addr = String::from(addr);
+
+        Self::connect(async move || Ok(serial::open_tcp(addr.as_str()).await?)).await
+    }
+}
+
+impl<E: Send + 'static> DeviceClient<E> {
+    /// Connects to a device via the port returned by `open`.
+    ///
+    /// The port is opened on the thread serving the device, since ports
+    /// like [`serial::Port`] are bound to the runtime they were opened in.
+    ///
+    /// # Errors
+    ///
+    /// - Any error returned by `open`.
+    ///
+    /// See [`device::connect`] for other errors.
+    pub async fn connect<P, F, Fut>(open: F) -> Result<Self, E>
+    where
+        P: Read + Write + ErrorType<Error = E> + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<P, E>>,
+    {
+        let (requests, rx) = mpsc::unbounded_channel();
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let thread = std::thread::Builder::new()
+            .name("freemdu-client".into())
+            .spawn(move || serve(open, ready_tx, rx));
+
+        if let Err(e) = thread {
+            log::error!("Failed to start device thread: {e}");
+            return Err(stopped());
+        }
+
+        let details = ready_rx.await.unwrap_or_else(|_| Err(stopped()))?;
+
+        Ok(Self {
+            requests,
+            software_id: details.software_id,
+            kind: details.kind,
+            properties: details.properties,
+            actions: details.actions,
+        })
+    }
+
+    /// Returns the device's software ID.
+    #[must_use]
+    pub fn software_id(&self) -> u16 {
+        self.software_id
+    }
+
+    /// Returns the device's kind.
+    #[must_use]
+    pub fn kind(&self) -> DeviceKind {
+        self.kind
+    }
+
+    /// Returns the set of queryable properties.
+    #[must_use]
+    pub fn properties(&self) -> &'static [Property] {
+        self.properties
+    }
+
+    /// Returns the property with the given identifier,
+    /// or `None` if the device doesn't support it.
+    #[must_use]
+    pub fn property(&self, id: PropertyId) -> Option<&'static Property> {
+        self.properties.iter().find(|prop| prop.id == id)
+    }
+
+    /// Returns the set of actions that can be triggered.
+    #[must_use]
+    pub fn actions(&self) -> &'static [Action] {
+        self.actions
+    }
+
+    /// Queries the property with the given identifier.
+    ///
+    /// # Errors
+    ///
+    /// See [`Device::query_property`].
+    pub async fn query_property(&self, id: PropertyId) -> Result<Value, E> {
+        let prop = self.property(id).ok_or(Error::UnknownProperty)?;
+
+        self.request(|reply| Request::QueryProperty(prop, reply))
+            .await
+    }
+
+    /// Changes the value of the writable property with the given identifier.
+    ///
+    /// # Errors
+    ///
+    /// See [`Device::set_property`].
+    pub async fn set_property(&self, id: PropertyId, value: Value) -> Result<(), E> {
+        let prop = self.property(id).ok_or(Error::UnknownProperty)?;
+
+        self.request(|reply| Request::SetProperty(prop, value, reply))
+            .await
+    }
+
+    /// Triggers the action with the given identifier.
+    ///
+    /// # Errors
+    ///
+    /// See [`Device::trigger_action`].
+    pub async fn trigger_action(&self, id: &str, param: Option<Value>) -> Result<Option<Value>, E> {
+        let action = self
+            .actions
+            .iter()
+            .find(|action| action.id == id)
+            .ok_or(Error::UnknownAction)?;
+
+        self.request(|reply| Request::TriggerAction(action, param, reply))
+            .await
+    }
+
+    /// Queries the faults stored by the device.
+    ///
+    /// # Errors
+    ///
+    /// See [`Device::fault_history`].
+    pub async fn fault_history(&self) -> Result<Vec<FaultRecord>, E> {
+        self.request(Request::FaultHistory).await
+    }
+
+    /// Forwards a request to the thread serving the device and waits for its result.
+    async fn request<T>(&self, request: impl FnOnce(Reply<T, E>) -> Request<E>) -> Result<T, E> {
+        let (reply, rx) = oneshot::channel();
+
+        self.requests.send(request(reply)).map_err(|_| stopped())?;
+
+        rx.await.unwrap_or_else(|_| Err(stopped()))
+    }
+}
+
+/// Error returned once the thread serving the device has stopped.
+fn stopped<E>() -> Error<E> {
+    Error::Protocol(crate::Error::UnexpectedEof)
+}
+
+/// Open the port, connect to the device and handle requests until all clients are dropped.
+fn serve<P, F, Fut>(
+    open: F,
+    ready: oneshot::Sender<Result<DeviceDetails, P::Error>>,
+    mut requests: mpsc::UnboundedReceiver<Request<P::Error>>,
+) where
+    P: Read + Write + 'static,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<P, P::Error>>,
+{
+    let rt = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(e) => {
+            // Dropping the sender reports the thread as stopped
+            log::error!("Failed to start runtime for device thread: {e}");
+            return;
+        }
+    };
+
+    rt.block_on(async move {
+        let mut dev: Box<dyn Device<P>> = match open().await {
+            Ok(port) => match device::connect(port).await {
+                Ok(dev) => dev,
+                Err(e) => {
+                    let _ = ready.send(Err(e));
+                    return;
+                }
+            },
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            }
+        };
+        let details = DeviceDetails {
+            software_id: dev.software_id(),
+            kind: dev.kind(),
+            properties: dev.properties(),
+            actions: dev.actions(),
+        };
+
+        if ready.send(Ok(details)).is_err() {
+            return;
+        }
+
+        // Results are discarded if the client stopped waiting for them
+        while let Some(request) = requests.recv().await {
+            match request {
+                Request::QueryProperty(prop, reply) => {
+                    let _ = reply.send(dev.query_property(prop).await);
+                }
+                Request::SetProperty(prop, value, reply) => {
+                    let _ = reply.send(dev.set_property(prop, value).await);
+                }
+                Request::TriggerAction(action, param, reply) => {
+                    let _ = reply.send(dev.trigger_action(action, param).await);
+                }
+                Request::FaultHistory(reply) => {
+                    let _ = reply.send(dev.fault_history().await);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn shared_between_tasks() {
+        use crate::mock::MockPort;
+        use core::convert::Infallible;
+
+        let client =
+            DeviceClient::connect(async || Ok::<_, Error<Infallible>>(MockPort::washing_machine()))
+                .await
+                .expect("connection should succeed");
+
+        assert_eq!(client.software_id(), 629, "software ID should be correct");
+
+        let tasks: Vec<_> = ["serial_number", "model_number"]
+            .into_iter()
+            .map(|id| {
+                let client = client.clone();
+
+                tokio::spawn(async move { client.query_property(PropertyId(id)).await })
+            })
+            .collect();
+
+        for task in tasks {
+            let res = task.await.expect("task should not panic");
+
+            assert!(res.is_ok(), "property should be queried");
+        }
+
+        assert_eq!(
+            client.query_property(PropertyId("unknown")).await,
+            Err(Error::UnknownProperty),
+            "unknown property should be rejected"
+        );
+    }
+}
@@ -14,8 +14,21 @@ pub mod id419;
 pub mod id605;
 pub mod id629;
 
-use crate::{Error as ProtocolError, Interface, Read, Write};
-use alloc::{boxed::Box, string::String};
+#[cfg(feature = "native-serial")]
+extern crate std;
+
+#[cfg(feature = "native-serial")]
+use crate::replay::{ReplayEnd, ReplayPort};
+use crate::{
+    Error as ProtocolError, Interface, Read, TransportStats, Write, observer::FrameObserver,
+};
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::{
     fmt::{Display, Formatter},
     num::TryFromIntError,
@@ -37,6 +50,13 @@ pub type Result<T, E> = core::result::Result<T, Error<E>>;
 pub enum Error<E> {
     /// The software ID is unknown or does not match the expected value.
     UnknownSoftwareId(u16),
+    /// The device was recognized, but no properties or actions are defined for it yet.
+    UnsupportedDevice {
+        /// Kind of the recognized device.
+        kind: DeviceKind,
+        /// Software ID reported by the device.
+        software_id: u16,
+    },
     /// The provided argument is invalid.
     InvalidArgument,
     /// The device was in an invalid state for the requested operation.
@@ -45,8 +65,13 @@ pub enum Error<E> {
     UnexpectedMemoryValue,
     /// An unknown device property was queried.
     UnknownProperty,
+    /// The device rejected reading a property, e.g. because
+    /// the property doesn't exist on this device variant.
+    Unsupported,
     /// An unrecognized device action was requested.
     UnknownAction,
+    /// A property that cannot be changed was passed to [`Device::set_property`].
+    NotWritable,
     /// Generic diagnostic protocol error.
     Protocol(ProtocolError<E>),
 }
@@ -55,11 +80,17 @@ impl<E: core::error::Error> Display for Error<E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::UnknownSoftwareId(id) => write!(f, "unknown software ID: {id}"),
+            Self::UnsupportedDevice { kind, software_id } => write!(
+                f,
+                "unsupported device: no definitions for {kind} with software ID {software_id}"
+            ),
             Self::InvalidArgument => write!(f, "invalid argument"),
             Self::InvalidState => write!(f, "invalid state"),
             Self::UnexpectedMemoryValue => write!(f, "unexpected memory value"),
             Self::UnknownProperty => write!(f, "unknown property"),
+            Self::Unsupported => write!(f, "unsupported property"),
             Self::UnknownAction => write!(f, "unknown action"),
+            Self::NotWritable => write!(f, "property is not writable"),
             Self::Protocol(err) => write!(f, "protocol error: {err}"),
         }
     }
@@ -98,6 +129,7 @@ impl<E> From<strum::ParseError> for Error<E> {
 /// This enum is marked `#[non_exhaustive]` to allow for future variants.
 #[non_exhaustive]
 #[derive(strum::Display, PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[strum(serialize_all = "title_case")]
 pub enum DeviceKind {
     /// Washing machine.
@@ -114,34 +146,297 @@ pub enum DeviceKind {
 
 /// Device property kind.
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PropertyKind {
     /// General properties, e.g. model number.
     General,
     /// Failure properties, e.g. stored faults.
     Failure,
+    /// Warning properties, e.g. empty salt reservoir.
+    ///
+    /// Unlike failures, warnings are advisory and don't prevent normal operation.
+    Warning,
     /// Operation properties, e.g. program phase.
     Operation,
     /// Input/output properties, e.g. water level.
     Io,
 }
 
+/// Stable identifier of a device property, e.g. `"operating_time"`.
+///
+/// Unlike the human-readable name, the identifier is unique for each device
+/// and should be used to look up or store properties.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct PropertyId(pub &'static str);
+
+impl PropertyId {
+    /// Returns the identifier as a string slice.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        self.0
+    }
+}
+
+impl Display for PropertyId {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        f.pad(self.0)
+    }
+}
+
 /// A device property, e.g. total operating time.
 ///
-/// Properties can be queried using [`Device::query_property`].
-#[derive(PartialEq, Eq, Debug)]
+/// Properties can be queried using [`Device::query_property`]
+/// and, if writable, changed using [`Device::set_property`].
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Property {
     /// Property kind.
     pub kind: PropertyKind,
     /// Unique identifier.
-    pub id: &'static str,
+    pub id: PropertyId,
     /// Human-readable name.
     pub name: &'static str,
+    /// Optional explanation of the property's meaning for non-experts.
+    pub description: Option<&'static str>,
     /// Optional unit of the property's value.
     pub unit: Option<&'static str>,
+    /// Whether the property can be changed using [`Device::set_property`].
+    pub writable: bool,
+    /// Number of decimal places shown for the property's value.
+    ///
+    /// Only relevant for properties with [`Value::Float`] values.
+    pub decimals: u8,
+    /// Interpretation of the property's raw memory value, see [`Property::decode`].
+    ///
+    /// `None` for properties decoded by device-specific code, e.g. durations and strings.
+    pub decoding: Option<Decode>,
+    /// Location of the property's raw memory value.
+    ///
+    /// Properties with both a register and a decoding are described by data alone
+    /// and queried without device-specific code.
+    pub register: Option<Register>,
+    /// Whether the value is a counter accumulated over the device's lifetime,
+    /// such as the operating time, rather than an elapsed time.
+    ///
+    /// Only relevant for properties with [`Value::Duration`] values.
+    pub cumulative: bool,
+    /// Expected range of the property's value, if known.
+    ///
+    /// Used to flag abnormal readings, see [`Property::range_status`].
+    pub range: Option<ValueRange>,
+}
+
+impl Property {
+    /// Checks a value against the property's expected range.
+    ///
+    /// Sensor readings are checked by their current value.
+    /// Returns `None` if the property has no range or the value isn't numeric.
+    #[must_use]
+    pub fn range_status(&self, val: &Value) -> Option<RangeStatus> {
+        let range = self.range?;
+        let val = match *val {
+            Value::Number(num) | Value::Sensor(num, _) => f64::from(num),
+            Value::Float(num) => num,
+            _ => return None,
+        };
+
+        Some(range.status(val))
+    }
+
+    /// Converts a raw memory value into a property value according to [`Property::decoding`].
+    ///
+    /// The raw value is given in little-endian byte order.
+    /// Only the first four bytes are taken into account.
+    /// Returns `None` if the property has no decoding.
+    #[must_use]
+    pub fn decode(&self, raw: &[u8]) -> Option<Value> {
+        self.decode_with(raw, ByteOrder::Little)
+    }
+
+    /// Converts a raw memory value given in the specified byte order into a property value.
+    ///
+    /// See [`Property::decode`] and [`DeviceSchema::decode`].
+    #[must_use]
+    pub fn decode_with(&self, raw: &[u8], order: ByteOrder) -> Option<Value> {
+        let decoding = self.decoding?;
+        let mut bytes = [0x00; 4];
+        let len = raw.len().min(bytes.len());
+
+        bytes[..len].copy_from_slice(&raw[..len]);
+
+        if order == ByteOrder::Big {
+            bytes[..len].reverse();
+        }
+
+        let raw = &bytes[..len];
+        let num = u32::from_le_bytes(pad_le_bytes(raw, 0x00));
+
+        Some(match decoding {
+            Decode::Unsigned => Value::Number(num),
+            Decode::Signed => {
+                // Sign-extend based on the most significant byte
+                let negative = raw.last().is_some_and(|b| b & 0x80 != 0);
+                let fill = if negative { 0xff } else { 0x00 };
+
+                Value::Float(i32::from_le_bytes(pad_le_bytes(raw, fill)).into())
+            }
+            Decode::Scale(factor) => Value::Float(f64::from(num) * factor),
+            Decode::Enum(map) => Value::String(
+                map.iter()
+                    .find(|(val, _)| *val == num)
+                    .map_or_else(|| num.to_string(), |(_, name)| (*name).into()),
+            ),
+            Decode::Bitfield(labels) => Value::String(
+                labels
+                    .iter()
+                    .zip(0..)
+                    .filter(|&(_, bit)| num.checked_shr(bit).is_some_and(|n| n & 1 != 0))
+                    .map(|(label, _)| *label)
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            ),
+        })
+    }
+
+    /// Returns whether a raw value was likely given in the wrong byte order.
+    ///
+    /// This is the case if the decoded value is outside the property's expected range,
+    /// while the value decoded with the opposite byte order is within it.
+    /// Always returns `false` for properties without a range.
+    #[must_use]
+    pub fn byte_order_suspect(&self, raw: &[u8], order: ByteOrder) -> bool {
+        let in_range = |order| {
+            self.decode_with(raw, order)
+                .and_then(|val| self.range_status(&val))
+                .is_some_and(|status| status != RangeStatus::OutOfRange)
+        };
+
+        self.range.is_some() && !in_range(order) && in_range(order.swapped())
+    }
+}
+
+/// Copies up to four little-endian bytes into a buffer padded with `fill`.
+fn pad_le_bytes(raw: &[u8], fill: u8) -> [u8; 4] {
+    let mut bytes = [fill; 4];
+    let len = raw.len().min(bytes.len());
+
+    bytes[..len].copy_from_slice(&raw[..len]);
+    bytes
+}
+
+/// Location of a property's raw value in a device's memory.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Register {
+    /// Memory address of the first byte.
+    pub addr: u16,
+    /// Length of the value in bytes, either 1, 2 or 4.
+    pub len: u8,
+}
+
+/// Interpretation of a property's raw memory value.
+///
+/// Allows new properties to be described by data alone, see [`Property::decode`].
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Decode {
+    /// Unsigned integer, decoded as a [`Value::Number`].
+    Unsigned,
+    /// Two's complement signed integer, decoded as a [`Value::Float`].
+    Signed,
+    /// Unsigned integer multiplied by a factor, decoded as a [`Value::Float`].
+    ///
+    /// For example, a factor of `0.1` converts tenths of a degree into degrees.
+    Scale(f64),
+    /// Unsigned integer mapped to a name, decoded as a [`Value::String`].
+    ///
+    /// Values missing from the mapping are decoded as their number.
+    Enum(&'static [(u32, &'static str)]),
+    /// Set of flags, decoded as a [`Value::String`] of the labels of all set bits.
+    ///
+    /// The label at index `n` corresponds to bit `n`. Set bits without a label are ignored.
+    Bitfield(&'static [&'static str]),
+}
+
+/// Byte order of multi-byte values in a device's memory.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ByteOrder {
+    /// Least significant byte first, used by all supported devices.
+    #[default]
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+impl ByteOrder {
+    /// Returns the opposite byte order.
+    #[must_use]
+    pub fn swapped(self) -> Self {
+        match self {
+            Self::Little => Self::Big,
+            Self::Big => Self::Little,
+        }
+    }
+}
+
+/// Expected range of a numeric property value.
+///
+/// Bounds are given in the property's unit, i.e. after decoding by [`Property::decode`].
+#[derive(PartialEq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ValueRange {
+    /// Lowest normal value.
+    pub min: f64,
+    /// Highest normal value.
+    pub max: f64,
+    /// Value above which readings are still normal but noteworthy.
+    pub warn: Option<f64>,
+}
+
+impl ValueRange {
+    /// Classifies a value according to the range.
+    #[must_use]
+    pub fn status(self, val: f64) -> RangeStatus {
+        if val < self.min || val > self.max {
+            RangeStatus::OutOfRange
+        } else if self.warn.is_some_and(|warn| val > warn) {
+            RangeStatus::Warning
+        } else {
+            RangeStatus::Normal
+        }
+    }
+}
+
+/// Classification of a value according to a [`ValueRange`].
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum RangeStatus {
+    /// The value is within the normal range.
+    Normal,
+    /// The value is within range, but above the warning threshold.
+    Warning,
+    /// The value is outside the range, indicating a fault or misreading.
+    OutOfRange,
+}
+
+/// Fault stored by a device, returned by [`Device::fault_history`].
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FaultRecord {
+    /// Raw fault code, i.e. the fault's bit in the device's fault memory.
+    pub code: u16,
+    /// Name of the fault, or `None` if the code is unknown.
+    pub description: Option<&'static str>,
+    /// Number of occurrences, if stored by the device.
+    pub count: Option<u32>,
+    /// Operating time at which the fault last occurred, if stored by the device.
+    pub timestamp: Option<Duration>,
 }
 
 /// Device action kind.
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ActionKind {
     /// Operation action, e.g. setting the program options.
     Operation,
@@ -154,21 +449,92 @@ pub enum ActionKind {
 /// Each variant specifies which kind of [`Value`] must be supplied
 /// when invoking [`Device::trigger_action`].
 #[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ActionParameters {
     /// Action accepts a single [`Value::String`] chosen from an enumeration.
     ///
     /// The slice contains all possible options.
     Enumeration(&'static [&'static str]),
-    /// Action accepts a [`Value::String`] representing a combination of flags.
+    /// Action accepts a [`Value::Number`] representing a combination of flags.
+    ///
+    /// The slice maps the bit value of each flag to its name. The number is the bitwise OR
+    /// of the values of all selected flags.
+    Flags(&'static [(u32, &'static str)]),
+    /// Action accepts a [`Value::Number`] within an inclusive range.
+    Number {
+        /// Minimum value.
+        min: u32,
+        /// Maximum value.
+        max: u32,
+        /// Suggested increment, e.g. for input widgets.
+        step: u32,
+    },
+    /// Action accepts any [`Value::String`], e.g. the argument of an undocumented
+    /// service command.
+    ///
+    /// The input is passed on unchanged, without validation.
+    FreeText,
+}
+
+impl ActionParameters {
+    /// Converts user input into a parameter value of the expected type.
+    ///
+    /// Flags are given either as a number or as names in the format produced by
+    /// [`Decode::Bitfield`], e.g. `Prewash | Soak`, and converted into their bitmask.
+    /// An empty string selects no flags.
+    ///
+    /// Returns `None` if a number is expected, but the input
+    /// is not a valid number within the allowed range, or if a flag is unknown.
+    #[must_use]
+    pub fn parse_value(&self, input: &str) -> Option<Value> {
+        match *self {
+            Self::Enumeration(_) | Self::FreeText => Some(Value::String(input.into())),
+            Self::Flags(flags) => match input.trim().parse() {
+                Ok(mask) => Some(Value::Number(mask)),
+                Err(_) => input
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .try_fold(0, |mask, name| {
+                        flags
+                            .iter()
+                            .find(|&&(_, flag)| flag == name)
+                            .map(|&(bit, _)| mask | bit)
+                    })
+                    .map(Value::Number),
+            }
+            .filter(|value| self.accepts(value)),
+            Self::Number { min, max, .. } => input
+                .trim()
+                .parse()
+                .ok()
+                .filter(|num| (min..=max).contains(num))
+                .map(Value::Number),
+        }
+    }
+
+    /// Returns `true` if the value is of the expected type and one of the accepted options.
     ///
-    /// The slice contains all possible flag names.
-    Flags(&'static [&'static str]),
+    /// Flags are accepted if no bits other than those of the declared flags are set.
+    #[must_use]
+    pub fn accepts(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Self::Enumeration(options), Value::String(s)) => options.contains(&s.as_str()),
+            (Self::Flags(flags), Value::Number(mask)) => {
+                flags.iter().fold(*mask, |rest, &(bit, _)| rest & !bit) == 0
+            }
+            (&Self::Number { min, max, .. }, Value::Number(num)) => (min..=max).contains(num),
+            (Self::FreeText, Value::String(_)) => true,
+            _ => false,
+        }
+    }
 }
 
 /// A device action, e.g. starting the current washing program.
 ///
 /// Triggered via [`Device::trigger_action`].
 #[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Action {
     /// Action kind.
     pub kind: ActionKind,
@@ -180,11 +546,27 @@ pub struct Action {
     pub params: Option<ActionParameters>,
 }
 
+impl Action {
+    /// Returns `true` if the parameter is accepted by the action.
+    ///
+    /// Actions with parameters require an accepted value, see [`ActionParameters::accepts`].
+    /// Actions without parameters require `None`.
+    #[must_use]
+    pub fn accepts(&self, param: Option<&Value>) -> bool {
+        match (&self.params, param) {
+            (Some(params), Some(value)) => params.accepts(value),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
 /// The value of a device property or action argument.
 ///
 /// Returned by [`Device::query_property`] or passed to [`Device::trigger_action`].
 /// The type depends on the queried property or triggered action.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     /// Boolean value.
     Bool(bool),
@@ -192,12 +574,76 @@ pub enum Value {
     Number(u32),
     /// Sensor reading (current and target values).
     Sensor(u32, u32),
+    /// Fractional or signed value, e.g. a temperature of `-2.5 °C`.
+    ///
+    /// The number of significant decimal places is given by [`Property::decimals`].
+    Float(f64),
     /// String value of arbitrary length.
     String(String),
     /// Duration value.
     Duration(Duration),
 }
 
+impl Value {
+    /// Returns the value as a number, e.g. for comparing it against a threshold.
+    ///
+    /// Sensor readings return their current value and durations their number of seconds.
+    /// Returns `None` for booleans and strings.
+    #[must_use]
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::Number(num) | Self::Sensor(num, _) => Some(f64::from(num)),
+            Self::Float(num) => Some(num),
+            Self::Duration(dur) => Some(dur.as_secs_f64()),
+            Self::Bool(_) | Self::String(_) => None,
+        }
+    }
+
+    /// Returns the value if it's a boolean.
+    #[must_use]
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Self::Bool(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Returns the current and target values if the value is a sensor reading.
+    #[must_use]
+    pub fn as_sensor(&self) -> Option<(u32, u32)> {
+        match *self {
+            Self::Sensor(current, target) => Some((current, target)),
+            _ => None,
+        }
+    }
+
+    /// Formats the value for display, e.g. `42 / 60 °C` or `123h 30min`.
+    ///
+    /// Fractional values are shown with the given number of decimal places.
+    /// The unit is only appended to numeric values.
+    #[must_use]
+    pub fn format(&self, unit: Option<&str>, decimals: u8) -> String {
+        let unit = unit.map(|unit| format!(" {unit}")).unwrap_or_default();
+
+        match *self {
+            Self::Bool(val) => if val { "Yes" } else { "No" }.to_string(),
+            Self::Number(num) => format!("{num}{unit}"),
+            Self::Float(num) => {
+                let prec = usize::from(decimals);
+
+                format!("{num:.prec$}{unit}")
+            }
+            Self::Sensor(current, target) => format!("{current} / {target}{unit}"),
+            Self::String(ref string) => string.clone(),
+            Self::Duration(dur) => {
+                let total_mins = dur.as_secs() / 60;
+
+                format!("{}h {}min", total_mins / 60, total_mins % 60)
+            }
+        }
+    }
+}
+
 impl From<bool> for Value {
     fn from(val: bool) -> Self {
         Self::Bool(val)
@@ -252,6 +698,198 @@ impl From<Duration> for Value {
     }
 }
 
+/// Static description of the properties and actions supported by a device.
+///
+/// Obtained via [`schema`] without connecting to a device.
+/// If the `serde` feature is enabled, schemas can be serialized, e.g. to JSON.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DeviceSchema {
+    /// Kind of the device.
+    pub kind: DeviceKind,
+    /// Set of queryable properties, see [`Device::properties`].
+    pub properties: &'static [Property],
+    /// Set of actions that can be triggered, see [`Device::actions`].
+    pub actions: &'static [Action],
+    /// Byte order of multi-byte values in the device's memory, see [`DeviceSchema::decode`].
+    pub byte_order: ByteOrder,
+}
+
+impl DeviceSchema {
+    /// Converts a raw memory value of one of the device's properties into a property value,
+    /// using the device's byte order.
+    ///
+    /// Logs a warning if the value suggests a byte order mismatch,
+    /// see [`Property::byte_order_suspect`].
+    /// Returns `None` if the property has no decoding.
+    #[must_use]
+    pub fn decode(&self, prop: &Property, raw: &[u8]) -> Option<Value> {
+        if prop.byte_order_suspect(raw, self.byte_order) {
+            log::warn!(
+                "Value of property {} is out of range, but within it in {:?}-endian byte order. \
+                 Is the byte order of {} devices correct?",
+                prop.id,
+                self.byte_order.swapped(),
+                self.kind
+            );
+        }
+
+        prop.decode_with(raw, self.byte_order)
+    }
+}
+
+/// Summary of what a connected device supports.
+///
+/// Obtained via [`Device::capabilities`], e.g. for enumerating the available
+/// properties and actions without a user interface.
+/// If the `serde` feature is enabled, capabilities can be serialized, e.g. to JSON.
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DeviceCapabilities {
+    /// Software ID of the device.
+    pub software_id: u16,
+    /// Raw payload of the reply to the software ID query sent when connecting,
+    /// useful for debugging.
+    ///
+    /// See [`Interface::software_id_response`].
+    pub identify_response: Option<[u8; 2]>,
+    /// Kind of the device.
+    pub kind: DeviceKind,
+    /// Number of supported properties of each kind.
+    ///
+    /// Kinds without any properties are omitted.
+    pub property_kinds: Vec<(PropertyKind, usize)>,
+    /// Properties that can be changed using [`Device::set_property`].
+    pub writable_properties: Vec<PropertyId>,
+    /// Identifiers of the actions that can be triggered using [`Device::trigger_action`].
+    pub actions: Vec<&'static str>,
+}
+
+/// Current version of the [`DeviceSnapshot`] schema.
+///
+/// Incremented whenever the snapshot layout changes in an incompatible way.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A snapshot of all properties of a device at a single point in time.
+///
+/// Created using [`Device::snapshot`]. If the `serde` feature is enabled,
+/// snapshots can be serialized, e.g. to JSON for saving or comparing device state.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceSnapshot {
+    /// Schema version, see [`SNAPSHOT_VERSION`].
+    pub version: u32,
+    /// Software ID of the device.
+    pub software_id: u16,
+    /// Kind of the device.
+    pub kind: DeviceKind,
+    /// Values of all supported properties, in device order.
+    pub properties: Vec<PropertySnapshot>,
+}
+
+/// A property value with its metadata, as part of a [`DeviceSnapshot`].
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PropertySnapshot {
+    /// Property kind.
+    pub kind: PropertyKind,
+    /// Unique identifier.
+    pub id: String,
+    /// Human-readable name.
+    pub name: String,
+    /// Optional unit of the property's value.
+    pub unit: Option<String>,
+    /// Queried value.
+    pub value: Value,
+}
+
+impl DeviceSnapshot {
+    /// Returns the failure properties that currently indicate a fault.
+    pub fn active_faults(&self) -> impl Iterator<Item = &PropertySnapshot> {
+        self.properties.iter().filter(|prop| {
+            prop.kind == PropertyKind::Failure
+                && match &prop.value {
+                    Value::Bool(val) => *val,
+                    Value::Number(num) => *num != 0,
+                    Value::Float(num) => *num != 0.0,
+                    Value::String(string) => !string.is_empty(),
+                    Value::Sensor(..) | Value::Duration(_) => true,
+                }
+        })
+    }
+}
+
+/// Change notifications for a set of properties.
+///
+/// Created using [`Device::subscribe`]. Each call to [`Subscription::poll`]
+/// queries all subscribed properties, but only yields values that differ
+/// from the last one read. The first poll yields every value.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn example() -> freemdu::device::Result<(), freemdu::serial::PortError> {
+/// let mut port = freemdu::serial::open("/dev/ttyACM0")?;
+/// let mut dev = freemdu::device::connect(&mut port).await?;
+/// let props: Vec<_> = dev.properties().iter().collect();
+/// let mut sub = dev.subscribe(&props);
+///
+/// loop {
+///     for (prop, val) in sub.poll(dev.as_mut()).await {
+///         println!("{}: {:?}", prop.name, val?);
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Subscription<'a> {
+    props: Vec<&'a Property>,
+    /// Last value read of each property.
+    last: BTreeMap<PropertyId, Value>,
+}
+
+impl<'a> Subscription<'a> {
+    /// Constructs a new subscription to the specified properties.
+    #[must_use]
+    pub fn new(props: &[&'a Property]) -> Self {
+        Self {
+            props: props.to_vec(),
+            last: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the subscribed properties.
+    #[must_use]
+    pub fn properties(&self) -> &[&'a Property] {
+        &self.props
+    }
+
+    /// Queries all subscribed properties and returns those that changed, preserving their order.
+    ///
+    /// Errors are always returned. They don't affect the last value read,
+    /// so a property is only yielded again once its value actually changes.
+    pub async fn poll<P: Read + Write>(
+        &mut self,
+        dev: &mut (impl Device<P> + ?Sized),
+    ) -> Vec<(&'a Property, Result<Value, P::Error>)> {
+        let mut res = dev.query_properties(&self.props).await;
+
+        res.retain(|(prop, val)| match val {
+            Ok(val) => self.last.insert(prop.id, val.clone()).as_ref() != Some(val),
+            Err(_) => true,
+        });
+
+        res
+    }
+
+    /// Forgets all values read, so that the next poll yields every property again.
+    ///
+    /// Useful after reconnecting to a device.
+    pub fn reset(&mut self) {
+        self.last.clear();
+    }
+}
+
 /// Trait implemented by all supported devices.
 ///
 /// Provides asynchronous access to device properties and actions
@@ -279,7 +917,7 @@ impl From<Duration> for Value {
 /// # }
 /// ```
 #[async_trait::async_trait(?Send)]
-pub trait Device<P: Read + Write>: private::Sealed {
+pub trait Device<P: Read + Write>: private::Sealed + private::QueryProperty<P> {
     /// Connects to the device via the specified port.
     ///
     /// This is an associated function and does not require an existing device instance.
@@ -298,6 +936,16 @@ pub trait Device<P: Read + Write>: private::Sealed {
     /// Returns the device's software ID.
     fn software_id(&self) -> u16;
 
+    /// Returns a human-readable description of the device's software.
+    ///
+    /// Falls back to the software ID in hexadecimal, e.g. `0x0275`,
+    /// if it isn't listed in [`SOFTWARE_VERSIONS`].
+    fn software_version(&self) -> String {
+        let id = self.software_id();
+
+        software_version(id).map_or_else(|| format!("{id:#06x}"), Into::into)
+    }
+
     /// Returns the device's kind.
     fn kind(&self) -> DeviceKind;
 
@@ -306,6 +954,16 @@ pub trait Device<P: Read + Write>: private::Sealed {
     /// Only properties returned here can be queried via [`Device::query_property`].
     fn properties(&self) -> &'static [Property];
 
+    /// Returns the property with the given identifier,
+    /// or `None` if the device doesn't support it.
+    ///
+    /// The properties are searched linearly, which is cheap compared to querying one
+    /// from the device. Callers looking up many identifiers, e.g. a polling loop,
+    /// may build their own index from [`Device::properties`] instead.
+    fn property(&self, id: PropertyId) -> Option<&'static Property> {
+        self.properties().iter().find(|prop| prop.id == id)
+    }
+
     /// Returns the set of actions that can be triggered.
     ///
     /// Only actions returned here can be triggered via [`Device::trigger_action`].
@@ -318,9 +976,150 @@ pub trait Device<P: Read + Write>: private::Sealed {
     /// # Errors
     ///
     /// - [`Error::UnknownProperty`] if the device does not support the specified property.
+    /// - [`Error::Unsupported`] if the device rejected reading the property.
+    ///
+    /// See the [`Device`] documentation for other errors.
+    async fn query_property(&mut self, prop: &Property) -> Result<Value, P::Error> {
+        self.query_device_property(prop)
+            .await
+            .map_err(utils::map_unsupported)
+    }
+
+    /// Queries a specified property along with the raw data it was decoded from.
+    ///
+    /// The raw data consists of the payloads of all responses received for the query,
+    /// in order, e.g. the memory contents of several reads for properties combined from them.
+    /// Useful for cross-checking decoded values against other diagnostic software.
+    ///
+    /// # Errors
+    ///
+    /// See [`Device::query_property`].
+    async fn query_property_raw(&mut self, prop: &Property) -> Result<(Value, Vec<u8>), P::Error> {
+        self.interface().start_capture();
+
+        let res = self.query_property(prop).await;
+        let raw = self.interface().finish_capture();
+
+        Ok((res?, raw))
+    }
+
+    /// Queries multiple properties, preserving their order.
+    ///
+    /// The diagnostic protocol only allows a single outstanding request,
+    /// so the properties are queried one after another.
+    /// Errors are reported per property and don't abort the remaining queries.
+    async fn query_properties<'a>(
+        &mut self,
+        props: &[&'a Property],
+    ) -> Vec<(&'a Property, Result<Value, P::Error>)> {
+        let mut res = Vec::with_capacity(props.len());
+
+        for prop in props {
+            res.push((*prop, self.query_property(prop).await));
+        }
+
+        res
+    }
+
+    /// Subscribes to changes of the specified properties.
+    ///
+    /// The properties must be from the set returned by [`Device::properties`].
+    /// See [`Subscription`] for details.
+    fn subscribe<'a>(&self, props: &[&'a Property]) -> Subscription<'a> {
+        Subscription::new(props)
+    }
+
+    /// Queries the faults stored by the device.
+    ///
+    /// Returns a record for each stored fault, in order of the fault codes.
+    /// Unlike the faults property, codes unknown to the implementation
+    /// are included with their raw code instead of failing the query.
+    ///
+    /// None of the supported devices are known to store occurrence counts
+    /// or timestamps, so these are currently always `None`.
+    ///
+    /// # Errors
+    ///
+    /// See the [`Device`] documentation.
+    async fn fault_history(&mut self) -> Result<Vec<FaultRecord>, P::Error>;
+
+    /// Changes the value of a writable property.
+    ///
+    /// The property must be from the set returned by [`Device::properties`]
+    /// and have [`Property::writable`] set.
+    /// The value is read back after writing to verify that it was applied.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::NotWritable`] if the property cannot be changed.
+    /// - [`Error::UnknownProperty`] if the device does not support the specified property.
+    /// - [`Error::InvalidArgument`] if `value` does not match the property's type.
+    /// - [`Error::UnexpectedMemoryValue`] if the value read back doesn't match.
     ///
     /// See the [`Device`] documentation for other errors.
-    async fn query_property(&mut self, prop: &Property) -> Result<Value, P::Error>;
+    async fn set_property(&mut self, prop: &Property, value: Value) -> Result<(), P::Error> {
+        // Only devices with writable properties override this
+        let _ = (prop, value);
+
+        Err(Error::NotWritable)
+    }
+
+    /// Returns a summary of the supported properties and actions.
+    ///
+    /// Doesn't communicate with the device, the capabilities are known after connecting.
+    fn capabilities(&mut self) -> DeviceCapabilities {
+        let properties = self.properties();
+        let property_kinds = [
+            PropertyKind::General,
+            PropertyKind::Failure,
+            PropertyKind::Warning,
+            PropertyKind::Operation,
+            PropertyKind::Io,
+        ]
+        .into_iter()
+        .map(|kind| (kind, properties.iter().filter(|p| p.kind == kind).count()))
+        .filter(|&(_, count)| count > 0)
+        .collect();
+
+        DeviceCapabilities {
+            software_id: self.software_id(),
+            identify_response: self.interface().software_id_response(),
+            kind: self.kind(),
+            property_kinds,
+            writable_properties: properties
+                .iter()
+                .filter(|prop| prop.writable)
+                .map(|prop| prop.id)
+                .collect(),
+            actions: self.actions().iter().map(|action| action.id).collect(),
+        }
+    }
+
+    /// Queries all supported properties and returns them as a [`DeviceSnapshot`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Device::query_property`].
+    async fn snapshot(&mut self) -> Result<DeviceSnapshot, P::Error> {
+        let mut properties = Vec::new();
+
+        for prop in self.properties() {
+            properties.push(PropertySnapshot {
+                kind: prop.kind,
+                id: prop.id.as_str().into(),
+                name: prop.name.into(),
+                unit: prop.unit.map(Into::into),
+                value: self.query_property(prop).await?,
+            });
+        }
+
+        Ok(DeviceSnapshot {
+            version: SNAPSHOT_VERSION,
+            software_id: self.software_id(),
+            kind: self.kind(),
+            properties,
+        })
+    }
 
     /// Triggers a specified action.
     ///
@@ -329,9 +1128,14 @@ pub trait Device<P: Read + Write>: private::Sealed {
     /// Depending on the value of [`Action::params`], the `param` argument
     /// must be supplied with a corresponding [`Value`] variant.
     ///
+    /// Some actions return a result value, e.g. a setting read back from the device
+    /// after it has been changed. Actions without a result return `None`.
+    ///
     /// # Errors
     ///
-    /// - [`Error::InvalidArgument`] if `param` does not match the expected type.
+    /// - [`Error::InvalidArgument`] if `param` does not match the expected type
+    ///   or isn't one of the accepted options, see [`Action::accepts`].
+    ///   Nothing is sent to the device in this case.
     /// - [`Error::UnknownAction`] if the device does not support the specified action.
     ///
     /// See the [`Device`] documentation for other errors.
@@ -339,10 +1143,36 @@ pub trait Device<P: Read + Write>: private::Sealed {
         &mut self,
         action: &Action,
         param: Option<Value>,
-    ) -> Result<(), P::Error>;
+    ) -> Result<Option<Value>, P::Error>;
+
+    /// Sends a raw request to the device and returns its reply.
+    ///
+    /// Intended for exploring undocumented parts of the protocol.
+    /// See [`Interface::raw_transaction`] for how the reply length is determined.
+    ///
+    /// # Errors
+    ///
+    /// See [`Interface::raw_transaction`].
+    async fn raw_transaction(&mut self, req: &[u8]) -> Result<Vec<u8>, P::Error> {
+        Ok(self.interface().raw_transaction(req).await?)
+    }
 
     /// Returns a mutable reference to the underlying diagnostic interface.
     fn interface(&mut self) -> &mut Interface<P>;
+
+    /// Installs an observer that is notified of every frame exchanged with the device.
+    ///
+    /// See [`Interface::set_observer`].
+    fn set_observer(&mut self, observer: Box<dyn FrameObserver>) {
+        self.interface().set_observer(Some(observer));
+    }
+
+    /// Returns statistics about the data exchanged with the device.
+    ///
+    /// See [`Interface::stats`].
+    fn stats(&mut self) -> TransportStats {
+        self.interface().stats()
+    }
 }
 
 /// Connects to a device asynchronously, based on the detected software ID.
@@ -353,6 +1183,8 @@ pub trait Device<P: Read + Write>: private::Sealed {
 ///
 /// - [`Error::UnknownSoftwareId`] if the device's software ID is not recognized
 ///   by any supported implementation.
+/// - [`Error::UnsupportedDevice`] if the device is recognized, but its implementation
+///   defines neither properties nor actions yet.
 /// - [`Error::Protocol`] for any other errors during diagnostic communication.
 ///
 /// # Examples
@@ -369,9 +1201,26 @@ pub trait Device<P: Read + Write>: private::Sealed {
 pub async fn connect<'a, P: 'a + Read + Write>(
     port: P,
 ) -> Result<Box<dyn Device<P> + 'a>, P::Error> {
-    let mut intf = Interface::new(port);
+    connect_interface(Interface::new(port)).await
+}
+
+/// Connects to a device asynchronously using an existing interface.
+///
+/// Like [`connect`], but allows configuring the interface beforehand,
+/// e.g. installing a [`FrameObserver`] that also sees the initialization.
+///
+/// # Errors
+///
+/// See [`connect`].
+pub async fn connect_interface<'a, P: 'a + Read + Write>(
+    mut intf: Interface<P>,
+) -> Result<Box<dyn Device<P> + 'a>, P::Error> {
     let id = intf.query_software_id().await?;
 
+    if let Some(schema) = schema(id) {
+        check_definitions(schema, id)?;
+    }
+
     match id {
         id360::compatible_software_ids!() => {
             Ok(Box::new(id360::WashingMachine::initialize(intf, id).await?) as Box<dyn Device<P>>)
@@ -392,9 +1241,203 @@ pub async fn connect<'a, P: 'a + Read + Write>(
     }
 }
 
-/// Utility functions for device implementations.
-mod utils {
-    /// Decodes a BCD-encoded value into a base-10 integer.
+/// Fails with [`Error::UnsupportedDevice`] if the schema has nothing to offer,
+/// so that users get an explicit error instead of an empty device.
+fn check_definitions<E>(schema: &DeviceSchema, software_id: u16) -> Result<(), E> {
+    if schema.properties.is_empty() && schema.actions.is_empty() {
+        Err(Error::UnsupportedDevice {
+            kind: schema.kind,
+            software_id,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the schema of the device implementation for the given software ID.
+///
+/// This is a pure lookup in the static device tables and doesn't perform any communication.
+/// Returns `None` if the software ID is not supported by any implementation.
+///
+/// # Examples
+///
+/// ```
+/// let schema = freemdu::device::schema(629).expect("software ID should be supported");
+///
+/// for prop in schema.properties {
+///     println!("{}: {}", prop.id, prop.name);
+/// }
+/// ```
+#[must_use]
+pub fn schema(software_id: u16) -> Option<&'static DeviceSchema> {
+    match software_id {
+        id360::compatible_software_ids!() => Some(&id360::SCHEMA),
+        id419::compatible_software_ids!() => Some(&id419::SCHEMA),
+        id605::compatible_software_ids!() => Some(&id605::SCHEMA),
+        id629::compatible_software_ids!() => Some(&id629::SCHEMA),
+        id1998::compatible_software_ids!() => Some(&id1998::SCHEMA),
+        _ => None,
+    }
+}
+
+/// Descriptions of known software IDs, sorted by ID.
+///
+/// Each entry names the appliance series and, if known, the control board
+/// the firmware runs on. Entries can be added for software IDs
+/// that aren't supported by any device implementation yet.
+pub const SOFTWARE_VERSIONS: &[(u16, &str)] = &[
+    (360, "W 3xx series, EDPW 223-A board"),
+    (419, "W 8xx/9xx series, EDPW 206 board"),
+    (605, "G 6xx series, EGPL 542-C board"),
+    (629, "W 2xxx series, EDPL 126-B board"),
+    (1998, "W 6xx series"),
+];
+
+/// Returns the description of the given software ID from [`SOFTWARE_VERSIONS`].
+///
+/// Returns `None` if the software ID is unknown.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(
+///     freemdu::device::software_version(629),
+///     Some("W 2xxx series, EDPL 126-B board")
+/// );
+/// ```
+#[must_use]
+pub fn software_version(software_id: u16) -> Option<&'static str> {
+    SOFTWARE_VERSIONS
+        .binary_search_by_key(&software_id, |&(id, _)| id)
+        .ok()
+        .map(|idx| SOFTWARE_VERSIONS[idx].1)
+}
+
+/// Connects to a device asynchronously in read-only mode.
+///
+/// The device is initialized exactly like with [`connect`], after which the
+/// underlying [`Interface`] is switched to read-only mode.
+/// Properties can still be queried, but triggering actions or any other
+/// operation that would modify the device fails with [`Error::Protocol`]
+/// wrapping [`crate::Error::ReadOnly`].
+///
+/// This is useful for passively observing a device, e.g. alongside a logger.
+///
+/// # Errors
+///
+/// See [`connect`].
+pub async fn connect_read_only<'a, P: 'a + Read + Write>(
+    port: P,
+) -> Result<Box<dyn Device<P> + 'a>, P::Error> {
+    let mut dev = connect(port).await?;
+
+    dev.interface().set_read_only(true);
+
+    Ok(dev)
+}
+
+/// Connects to a device replaying a recorded session.
+///
+/// The recording is read from the file at the given path, see [`ReplayPort`]
+/// for how requests are answered. The end of the recording is handled as specified.
+///
+/// # Errors
+///
+/// - [`Error::Protocol`] wrapping [`crate::Error::Io`] if the recording can't be read.
+/// - See [`connect`] for other errors.
+#[cfg(feature = "native-serial")]
+#[cfg_attr(docsrs, doc(cfg(feature = "native-serial")))]
+pub async fn connect_replay(
+    path: impl AsRef<std::path::Path>,
+    end: ReplayEnd,
+) -> Result<Box<dyn Device<ReplayPort>>, std::io::Error> {
+    let port = ReplayPort::open(path, end).map_err(ProtocolError::Io)?;
+
+    connect(port).await
+}
+
+/// Utility functions for device implementations.
+mod utils {
+    use super::{
+        Action, DeviceSchema, Error, FaultRecord, Interface, Property, ProtocolError, Read, Value,
+        Write,
+    };
+    use alloc::vec::Vec;
+    use bitflags::Flags;
+
+    /// Rejects parameters that aren't accepted by the action, before anything is sent.
+    pub(super) fn check_param<E>(action: &Action, param: Option<&Value>) -> Result<(), Error<E>> {
+        if action.accepts(param) {
+            Ok(())
+        } else {
+            Err(Error::InvalidArgument)
+        }
+    }
+
+    /// Converts the bitmask of a flags parameter into the device's flags.
+    pub(super) fn flags_param<F: Flags, E>(mask: u32) -> Result<F, Error<E>>
+    where
+        F::Bits: TryFrom<u32>,
+    {
+        F::Bits::try_from(mask)
+            .ok()
+            .and_then(F::from_bits)
+            .ok_or(Error::InvalidArgument)
+    }
+
+    /// Splits stored faults into a record per fault, keeping unknown bits as raw codes.
+    pub(super) fn fault_records<F: Flags>(faults: &F) -> Vec<FaultRecord>
+    where
+        F::Bits: Into<u16>,
+    {
+        let bits: u16 = faults.bits().into();
+
+        (0..u16::BITS)
+            .map(|idx| 1 << idx)
+            .filter(|code| bits & code != 0)
+            .map(|code| FaultRecord {
+                code,
+                description: F::FLAGS
+                    .iter()
+                    .find(|flag| flag.value().bits().into() == code)
+                    .map(bitflags::Flag::name),
+                count: None,
+                timestamp: None,
+            })
+            .collect()
+    }
+
+    /// Queries a property described by data alone, i.e. one with a register and a decoding.
+    ///
+    /// The raw value is decoded using the schema's byte order, see [`DeviceSchema::decode`].
+    pub(super) async fn query_register<P: Read + Write>(
+        intf: &mut Interface<P>,
+        schema: &DeviceSchema,
+        prop: &Property,
+    ) -> Result<Value, Error<P::Error>> {
+        let reg = prop.register.ok_or(Error::UnknownProperty)?;
+        let raw = match reg.len {
+            1 => intf.read_memory::<[u8; 1], 1>(reg.addr).await?.to_vec(),
+            2 => intf.read_memory::<[u8; 2], 2>(reg.addr).await?.to_vec(),
+            4 => intf.read_memory::<[u8; 4], 4>(reg.addr).await?.to_vec(),
+            _ => return Err(Error::InvalidArgument),
+        };
+
+        schema.decode(prop, &raw).ok_or(Error::UnknownProperty)
+    }
+
+    /// Maps a rejected read command to [`Error::Unsupported`].
+    ///
+    /// Used when querying properties to distinguish unsupported
+    /// properties from other communication errors.
+    pub(super) fn map_unsupported<E>(err: Error<E>) -> Error<E> {
+        match err {
+            Error::Protocol(ProtocolError::InvalidCommand) => Error::Unsupported,
+            err => err,
+        }
+    }
+
+    /// Decodes a BCD-encoded value into a base-10 integer.
     pub(super) fn decode_bcd_value(mut val: u32) -> u32 {
         let mut mul = 1;
         let mut res = 0;
@@ -470,14 +1513,26 @@ mod utils {
 }
 
 mod private {
+    use super::{Property, Read, Result, Value, Write};
+    use alloc::boxed::Box;
+
     pub trait Sealed {}
+
+    /// Device-specific part of [`Device::query_property`](super::Device::query_property).
+    ///
+    /// Rejected reads are mapped to [`Error::Unsupported`](super::Error::Unsupported)
+    /// by the caller.
+    #[async_trait::async_trait(?Send)]
+    pub trait QueryProperty<P: Read + Write> {
+        async fn query_device_property(&mut self, prop: &Property) -> Result<Value, P::Error>;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::tests::init_logger;
-    use alloc::collections::vec_deque::VecDeque;
+    use alloc::{collections::vec_deque::VecDeque, string::ToString};
     use core::convert::Infallible;
 
     #[tokio::test]
@@ -509,6 +1564,917 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn software_version_lookup() {
+        init_logger();
+
+        assert!(
+            SOFTWARE_VERSIONS.is_sorted_by_key(|&(id, _)| id),
+            "software versions should be sorted"
+        );
+        assert_eq!(
+            software_version(605),
+            Some("G 6xx series, EGPL 542-C board"),
+            "software version should be found"
+        );
+        assert_eq!(
+            software_version(1234),
+            None,
+            "unknown software version should not be found"
+        );
+    }
+
+    #[test]
+    fn definitions_required() {
+        init_logger();
+
+        let empty = DeviceSchema {
+            kind: DeviceKind::CoffeeMachine,
+            properties: &[],
+            actions: &[],
+            byte_order: ByteOrder::Little,
+        };
+
+        assert_eq!(
+            check_definitions::<Infallible>(&empty, 1234),
+            Err(Error::UnsupportedDevice {
+                kind: DeviceKind::CoffeeMachine,
+                software_id: 1234
+            }),
+            "empty schema should be unsupported"
+        );
+
+        for &(id, _) in SOFTWARE_VERSIONS {
+            if let Some(schema) = schema(id) {
+                assert_eq!(
+                    check_definitions::<Infallible>(schema, id),
+                    Ok(()),
+                    "schema of software ID {id} should have definitions"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn property_by_id() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00]);
+        let dev = connect(&mut deque).await?;
+
+        assert_eq!(
+            dev.property(PropertyId("serial_number"))
+                .map(|prop| prop.name),
+            Some("Serial Number"),
+            "property should be found"
+        );
+        assert_eq!(
+            dev.property(PropertyId("unknown")),
+            None,
+            "unknown property should not be found"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn trigger_action_with_result() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([
+            0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x04,
+        ]);
+        let mut dev = connect(&mut deque).await?;
+        let action = dev
+            .actions()
+            .iter()
+            .find(|action| action.id == "set_program_spin_setting")
+            .expect("action should exist");
+        let res = dev
+            .trigger_action(action, Some(Value::String("SpinMed".to_string())))
+            .await?;
+
+        assert_eq!(
+            res,
+            Some(Value::String("SpinMed".to_string())),
+            "action should return read back value"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn subscription_yields_changes() -> Result<(), Infallible> {
+        use crate::mock::MockPort;
+
+        init_logger();
+
+        let mut dev = connect(MockPort::washing_machine()).await?;
+        let props = [
+            dev.property(PropertyId("operating_time"))
+                .expect("property should exist"),
+            dev.property(PropertyId("program_spin_setting"))
+                .expect("property should exist"),
+        ];
+        let mut sub = dev.subscribe(&props);
+        let ids = |res: Vec<(&Property, Result<Value, Infallible>)>| {
+            res.into_iter().map(|(prop, _)| prop.id).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(sub.poll(dev.as_mut()).await),
+            [props[0].id, props[1].id],
+            "first poll should yield all properties"
+        );
+        assert_eq!(
+            ids(sub.poll(dev.as_mut()).await),
+            [],
+            "unchanged properties should not be yielded"
+        );
+
+        dev.set_property(props[1], Value::String("SpinMed".to_string()))
+            .await?;
+
+        assert_eq!(
+            ids(sub.poll(dev.as_mut()).await),
+            [props[1].id],
+            "changed property should be yielded"
+        );
+
+        sub.reset();
+
+        assert_eq!(
+            ids(sub.poll(dev.as_mut()).await),
+            [props[0].id, props[1].id],
+            "all properties should be yielded after reset"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn fault_history_keeps_unknown_codes() -> Result<(), Infallible> {
+        use crate::mock::MockPort;
+
+        init_logger();
+
+        // Heater, EEPROM and an undocumented fault
+        let port = MockPort::washing_machine().with_memory(0x004e, &[0x04, 0x11]);
+        let mut dev = connect(port).await?;
+        let record = |code, description| FaultRecord {
+            code,
+            description,
+            count: None,
+            timestamp: None,
+        };
+
+        assert_eq!(
+            dev.fault_history().await?,
+            [
+                record(0x0004, Some("Heater")),
+                record(0x0100, Some("Eeprom")),
+                record(0x1000, None),
+            ],
+            "fault records should be correct"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn query_data_only_properties() -> Result<(), Infallible> {
+        use crate::mock::MockPort;
+
+        init_logger();
+
+        let mut dev = connect(MockPort::washing_machine()).await?;
+        let prop = dev
+            .property(PropertyId("rom_code"))
+            .expect("property should exist");
+
+        assert!(
+            prop.decoding.is_some() && prop.register.is_some(),
+            "property should be described by data alone"
+        );
+        assert_eq!(
+            dev.query_property(prop).await?,
+            Value::Number(4),
+            "ROM code should be correct"
+        );
+
+        // Target water amount of 3 liters, stored in centiliters
+        let port = MockPort::new(605).with_memory(0x00d6, &[0x2c, 0x01]);
+        let mut dev = connect(port).await?;
+        let prop = dev
+            .property(PropertyId("target_water_amount"))
+            .expect("property should exist");
+
+        assert_eq!(
+            dev.query_property(prop).await?,
+            Value::Float(3000.0),
+            "target water amount should be correct"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn query_scaled_property() -> Result<(), Infallible> {
+        use crate::mock::MockPort;
+
+        init_logger();
+
+        // Duty cycle of 0x80 out of 0xff
+        let mut dev = connect(MockPort::washing_machine()).await?;
+        let prop = dev
+            .property(PropertyId("motor_pwm_duty_cycle"))
+            .expect("property should exist");
+        let val = dev.query_property(prop).await?;
+        let Value::Float(duty) = val else {
+            panic!("duty cycle should be fractional");
+        };
+
+        assert!(
+            (duty - 50.196).abs() < 0.001,
+            "duty cycle should be correct"
+        );
+        assert_eq!(
+            format!("{duty:.*}", usize::from(prop.decimals)),
+            "50.2",
+            "duty cycle should be shown with one decimal place"
+        );
+        assert_eq!(
+            prop.range_status(&val),
+            Some(RangeStatus::Normal),
+            "duty cycle should be within range"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn swapped_temperature_register_logged() -> Result<(), Infallible> {
+        use crate::mock::MockPort;
+        use crate::tests::warning_logged;
+
+        const PROP: Property = Property {
+            kind: PropertyKind::Io,
+            id: PropertyId("swapped_temperature"),
+            name: "Temperature",
+            description: None,
+            unit: Some("°C"),
+            writable: false,
+            decimals: 0,
+            decoding: Some(Decode::Unsigned),
+            register: Some(Register {
+                addr: 0x0200,
+                len: 2,
+            }),
+            cumulative: false,
+            range: Some(ValueRange {
+                min: 5.0,
+                max: 100.0,
+                warn: None,
+            }),
+        };
+        const SCHEMA: DeviceSchema = DeviceSchema {
+            kind: DeviceKind::WashingMachine,
+            properties: &[PROP],
+            actions: &[],
+            byte_order: ByteOrder::Little,
+        };
+
+        init_logger();
+
+        // Temperature of 42 °C, stored most significant byte first
+        let port = MockPort::washing_machine().with_memory(0x0200, &[0x00, 0x2a]);
+        let mut dev = connect(port).await?;
+        let val = utils::query_register(dev.interface(), &SCHEMA, &PROP).await?;
+
+        assert_eq!(
+            PROP.range_status(&val),
+            Some(RangeStatus::OutOfRange),
+            "value should be out of range"
+        );
+        assert!(
+            warning_logged("Value of property swapped_temperature is out of range"),
+            "byte order mismatch should be logged"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn snapshot_serde_round_trip() {
+        init_logger();
+
+        let snapshot = DeviceSnapshot {
+            version: SNAPSHOT_VERSION,
+            software_id: 629,
+            kind: DeviceKind::WashingMachine,
+            properties: alloc::vec![
+                PropertySnapshot {
+                    kind: PropertyKind::General,
+                    id: "operating_time".to_string(),
+                    name: "Operating Time".to_string(),
+                    unit: None,
+                    value: Value::Duration(Duration::from_secs(3600)),
+                },
+                PropertySnapshot {
+                    kind: PropertyKind::Failure,
+                    id: "faults".to_string(),
+                    name: "Faults".to_string(),
+                    unit: None,
+                    value: Value::String("DrainPump".to_string()),
+                },
+                PropertySnapshot {
+                    kind: PropertyKind::Io,
+                    id: "water_level".to_string(),
+                    name: "Water Level".to_string(),
+                    unit: Some("mmWC".to_string()),
+                    value: Value::Sensor(20, 40),
+                },
+            ],
+        };
+        let json = serde_json::to_string(&snapshot).expect("snapshot should serialize");
+        let res: DeviceSnapshot = serde_json::from_str(&json).expect("snapshot should deserialize");
+
+        assert_eq!(res, snapshot, "snapshot should be unchanged");
+        assert_eq!(
+            res.active_faults()
+                .map(|prop| prop.id.as_str())
+                .collect::<Vec<_>>(),
+            ["faults"],
+            "active faults should be correct"
+        );
+    }
+
+    #[tokio::test]
+    async fn error_unsupported() -> Result<(), Infallible> {
+        init_logger();
+
+        // Device responds with invalid command code to the read request
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00, 0x02]);
+        let mut dev = connect(&mut deque).await?;
+        let prop = dev
+            .property(PropertyId("serial_number"))
+            .expect("property should exist");
+        let res = dev.query_property(prop).await;
+
+        assert!(
+            matches!(res, Err(Error::Unsupported)),
+            "result should be unsupported error"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_properties() -> Result<(), Infallible> {
+        init_logger();
+
+        // Serial number index is read successfully, serial number is rejected
+        let mut deque = VecDeque::from([
+            0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x31, 0x00, 0x61,
+            0x02,
+        ]);
+        let mut dev = connect(&mut deque).await?;
+        let props = [
+            dev.property(PropertyId("serial_number_index"))
+                .expect("property should exist"),
+            dev.property(PropertyId("serial_number"))
+                .expect("property should exist"),
+        ];
+        let res = dev.query_properties(&props).await;
+
+        assert_eq!(res.len(), 2, "all properties should be queried");
+        assert!(
+            matches!(&res[0], (prop, Ok(Value::String(idx))) if prop.id == props[0].id && idx == "01"),
+            "first result should be correct"
+        );
+        assert!(
+            matches!(&res[1], (prop, Err(Error::Unsupported)) if prop.id == props[1].id),
+            "second result should be unsupported error"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_property_raw() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([
+            0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x31, 0x00, 0x61,
+        ]);
+        let mut dev = connect(&mut deque).await?;
+        let prop = dev
+            .property(PropertyId("serial_number_index"))
+            .expect("property should exist");
+        let (val, raw) = dev.query_property_raw(prop).await?;
+
+        assert_eq!(
+            val,
+            Value::String("01".to_string()),
+            "value should be correct"
+        );
+        assert_eq!(raw, [0x00, 0x30, 0x31, 0x00], "raw data should be correct");
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_action_value() {
+        let number = ActionParameters::Number {
+            min: 1,
+            max: 60,
+            step: 1,
+        };
+        let flags = ActionParameters::Flags(&[(0x10, "Soak"), (0x20, "Prewash")]);
+
+        assert_eq!(
+            number.parse_value(" 30 "),
+            Some(Value::Number(30)),
+            "number should be parsed"
+        );
+        assert_eq!(number.parse_value("61"), None, "number should be in range");
+        assert_eq!(number.parse_value("abc"), None, "input should be a number");
+        assert_eq!(
+            flags.parse_value("Prewash | Soak"),
+            Some(Value::Number(0x30)),
+            "flags should be converted into bitmask"
+        );
+        assert_eq!(
+            flags.parse_value("16"),
+            Some(Value::Number(0x10)),
+            "bitmask should be parsed"
+        );
+        assert_eq!(
+            flags.parse_value(""),
+            Some(Value::Number(0)),
+            "no flags should be selected"
+        );
+        assert_eq!(flags.parse_value("Starch"), None, "flag should be known");
+        assert_eq!(flags.parse_value("64"), None, "bits should be known");
+        assert_eq!(
+            ActionParameters::FreeText.parse_value(" 0x12 raw "),
+            Some(Value::String(" 0x12 raw ".to_string())),
+            "free text should be passed on unchanged"
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_unknown_option() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00]);
+        let mut dev = connect(&mut deque).await?;
+        let action = dev
+            .actions()
+            .iter()
+            .find(|action| action.id == "set_program_options")
+            .expect("action should exist");
+
+        assert!(
+            action.accepts(Some(&Value::Number(0))),
+            "no flags should be accepted"
+        );
+
+        let res = dev.trigger_action(action, Some(Value::Number(0x21))).await;
+
+        assert!(
+            action.accepts(Some(&Value::Number(0x30))),
+            "known flags should be accepted"
+        );
+        assert!(
+            !action.accepts(Some(&Value::String("Soak".to_string()))),
+            "flag names should be rejected"
+        );
+        assert!(
+            matches!(res, Err(Error::InvalidArgument)),
+            "result should be invalid argument error"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn error_not_writable() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00]);
+        let mut dev = connect(&mut deque).await?;
+        let prop = dev
+            .property(PropertyId("serial_number"))
+            .expect("property should exist");
+        let res = dev
+            .set_property(prop, Value::String("123".to_string()))
+            .await;
+
+        assert!(!prop.writable, "property should not be writable");
+        assert!(
+            matches!(res, Err(Error::NotWritable)),
+            "result should be not writable error"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn error_set_property_read_only() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00]);
+        let mut dev = connect_read_only(&mut deque).await?;
+        let prop = dev
+            .property(PropertyId("program_spin_setting"))
+            .expect("property should exist");
+        let invalid = dev.set_property(prop, Value::Bool(true)).await;
+        let res = dev
+            .set_property(prop, Value::String("SpinMed".to_string()))
+            .await;
+
+        assert!(prop.writable, "property should be writable");
+        assert!(
+            matches!(invalid, Err(Error::InvalidArgument)),
+            "result should be invalid argument error"
+        );
+        assert!(
+            matches!(res, Err(Error::Protocol(crate::Error::ReadOnly))),
+            "result should be read-only error"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode() {
+        let prop = |decoding| Property {
+            kind: PropertyKind::Io,
+            id: PropertyId("outdoor_temperature"),
+            name: "Outdoor Temperature",
+            description: None,
+            unit: Some("°C"),
+            writable: false,
+            decimals: 1,
+            decoding: Some(decoding),
+            register: None,
+            cumulative: false,
+            range: None,
+        };
+
+        assert_eq!(
+            prop(Decode::Unsigned).decode(&[0xa9, 0x01]),
+            Some(Value::Number(425)),
+            "unsigned value should be correct"
+        );
+        assert_eq!(
+            prop(Decode::Signed).decode(&[0xfb, 0xff]),
+            Some(Value::Float(-5.0)),
+            "signed value should be correct"
+        );
+        assert_eq!(
+            prop(Decode::Signed).decode(&[0xfb]),
+            Some(Value::Float(-5.0)),
+            "signed byte value should be correct"
+        );
+        assert_eq!(
+            prop(Decode::Scale(0.5)).decode(&[0x55]),
+            Some(Value::Float(42.5)),
+            "scaled value should be correct"
+        );
+        assert_eq!(
+            prop(Decode::Enum(&[(0, "Off"), (1, "On")])).decode(&[0x01]),
+            Some(Value::String("On".into())),
+            "enumeration value should be correct"
+        );
+        assert_eq!(
+            prop(Decode::Enum(&[(0, "Off"), (1, "On")])).decode(&[0x02]),
+            Some(Value::String("2".into())),
+            "unknown enumeration value should be correct"
+        );
+        assert_eq!(
+            prop(Decode::Bitfield(&["Heater", "Pump", "Drain"])).decode(&[0x0d]),
+            Some(Value::String("Heater | Drain".into())),
+            "bitfield value should be correct"
+        );
+        assert_eq!(
+            prop(Decode::Unsigned).decode_with(&[0x01, 0xa9], ByteOrder::Big),
+            Some(Value::Number(425)),
+            "big-endian value should be correct"
+        );
+        assert_eq!(
+            prop(Decode::Signed).decode_with(&[0xff, 0xfb], ByteOrder::Big),
+            Some(Value::Float(-5.0)),
+            "big-endian signed value should be correct"
+        );
+    }
+
+    #[test]
+    fn byte_order_suspect() {
+        let prop = Property {
+            kind: PropertyKind::Io,
+            id: PropertyId("temperature"),
+            name: "Temperature",
+            description: None,
+            unit: Some("°C"),
+            writable: false,
+            decimals: 0,
+            decoding: Some(Decode::Unsigned),
+            register: None,
+            cumulative: false,
+            range: Some(ValueRange {
+                min: 5.0,
+                max: 100.0,
+                warn: None,
+            }),
+        };
+
+        assert!(
+            prop.byte_order_suspect(&[0x00, 0x2a], ByteOrder::Little),
+            "value in range when swapped should be suspect"
+        );
+        assert!(
+            !prop.byte_order_suspect(&[0x2a, 0x00], ByteOrder::Little),
+            "value in range should not be suspect"
+        );
+        assert!(
+            !prop.byte_order_suspect(&[0xff, 0xff], ByteOrder::Little),
+            "value out of range in both byte orders should not be suspect"
+        );
+
+        let prop = Property {
+            decoding: Some(Decode::Signed),
+            range: Some(ValueRange {
+                min: -20.0,
+                max: 50.0,
+                warn: None,
+            }),
+            ..prop
+        };
+
+        assert!(
+            prop.byte_order_suspect(&[0xff, 0xfb], ByteOrder::Little),
+            "negative value in range when swapped should be suspect"
+        );
+        assert!(
+            !prop.byte_order_suspect(&[0xfb, 0xff], ByteOrder::Little),
+            "negative value in range should not be suspect"
+        );
+    }
+
+    #[test]
+    fn value_accessors() {
+        assert_eq!(
+            Value::Number(42).as_f64(),
+            Some(42.0),
+            "number should be converted"
+        );
+        assert_eq!(
+            Value::Sensor(30, 40).as_f64(),
+            Some(30.0),
+            "sensor should be converted into current value"
+        );
+        assert_eq!(
+            Value::Duration(Duration::from_millis(90_500)).as_f64(),
+            Some(90.5),
+            "duration should be converted into seconds"
+        );
+        assert_eq!(
+            Value::Bool(true).as_f64(),
+            None,
+            "boolean should not be converted"
+        );
+        assert_eq!(
+            Value::Bool(true).as_bool(),
+            Some(true),
+            "boolean should be returned"
+        );
+        assert_eq!(
+            Value::Number(1).as_bool(),
+            None,
+            "number should not be boolean"
+        );
+        assert_eq!(
+            Value::Sensor(30, 40).as_sensor(),
+            Some((30, 40)),
+            "sensor reading should be returned"
+        );
+        assert_eq!(
+            Value::Float(30.0).as_sensor(),
+            None,
+            "float should not be sensor reading"
+        );
+    }
+
+    #[test]
+    fn format_value() {
+        for (val, unit, decimals, text) in [
+            (Value::Bool(true), None, 0, "Yes"),
+            (Value::Number(42), Some("rpm"), 0, "42 rpm"),
+            (Value::Float(-2.54), Some("°C"), 1, "-2.5 °C"),
+            (Value::Sensor(42, 60), Some("°C"), 0, "42 / 60 °C"),
+            (
+                Value::String("SpinMed".to_string()),
+                Some("rpm"),
+                0,
+                "SpinMed",
+            ),
+            (
+                Value::Duration(Duration::from_secs(123 * 3600 + 30 * 60)),
+                None,
+                0,
+                "123h 30min",
+            ),
+        ] {
+            assert_eq!(
+                val.format(unit, decimals),
+                text,
+                "formatted {val:?} should be correct"
+            );
+        }
+    }
+
+    #[test]
+    fn range_status() {
+        let prop = Property {
+            kind: PropertyKind::Io,
+            id: PropertyId("temperature"),
+            name: "Temperature",
+            description: None,
+            unit: Some("°C"),
+            writable: false,
+            decimals: 0,
+            decoding: Some(Decode::Unsigned),
+            register: None,
+            cumulative: false,
+            range: Some(ValueRange {
+                min: 5.0,
+                max: 100.0,
+                warn: Some(90.0),
+            }),
+        };
+
+        for (val, status) in [
+            (Value::Number(4), RangeStatus::OutOfRange),
+            (Value::Number(42), RangeStatus::Normal),
+            (Value::Sensor(95, 40), RangeStatus::Warning),
+            (Value::Float(100.5), RangeStatus::OutOfRange),
+        ] {
+            assert_eq!(
+                prop.range_status(&val),
+                Some(status),
+                "status of {val:?} should be correct"
+            );
+        }
+
+        assert_eq!(
+            prop.range_status(&Value::Bool(true)),
+            None,
+            "non-numeric value shouldn't be checked"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn schema_json() {
+        init_logger();
+
+        let schema = schema(1998).expect("software ID should be supported");
+        let json = serde_json::to_string_pretty(schema).expect("schema should serialize");
+
+        assert_eq!(
+            json,
+            r#"{
+  "kind": "WashingMachine",
+  "properties": [
+    {
+      "kind": "General",
+      "id": "rom_code",
+      "name": "ROM Code",
+      "description": "Version of the firmware stored in the control board's ROM.",
+      "unit": null,
+      "writable": false,
+      "decimals": 0,
+      "decoding": "Unsigned",
+      "register": {
+        "addr": 65503,
+        "len": 1
+      },
+      "cumulative": false,
+      "range": null
+    },
+    {
+      "kind": "General",
+      "id": "operating_time",
+      "name": "Operating Time",
+      "description": "Total operating time counted by the control board.",
+      "unit": null,
+      "writable": false,
+      "decimals": 0,
+      "decoding": null,
+      "register": null,
+      "cumulative": true,
+      "range": null
+    },
+    {
+      "kind": "Failure",
+      "id": "faults",
+      "name": "Faults",
+      "description": "Faults currently detected by the appliance.",
+      "unit": null,
+      "writable": false,
+      "decimals": 0,
+      "decoding": null,
+      "register": null,
+      "cumulative": false,
+      "range": null
+    },
+    {
+      "kind": "Operation",
+      "id": "operating_mode",
+      "name": "Operating Mode",
+      "description": "Whether a program is idle, running or finished, or which programming or service mode is active.",
+      "unit": null,
+      "writable": false,
+      "decimals": 0,
+      "decoding": null,
+      "register": null,
+      "cumulative": false,
+      "range": null
+    }
+  ],
+  "actions": [],
+  "byte_order": "Little"
+}"#,
+            "schema JSON should be correct"
+        );
+    }
+
+    #[tokio::test]
+    async fn capabilities() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00]);
+        let mut dev = connect(&mut deque).await?;
+
+        assert_eq!(
+            dev.capabilities(),
+            DeviceCapabilities {
+                software_id: 629,
+                identify_response: Some([0x75, 0x02]),
+                kind: DeviceKind::WashingMachine,
+                property_kinds: alloc::vec![
+                    (PropertyKind::General, 6),
+                    (PropertyKind::Failure, 1),
+                    (PropertyKind::Operation, 10),
+                    (PropertyKind::Io, 7),
+                ],
+                writable_properties: alloc::vec![
+                    PropertyId("program_options"),
+                    PropertyId("program_spin_setting"),
+                ],
+                actions: alloc::vec![
+                    "set_program_options",
+                    "set_program_spin_setting",
+                    "start_program",
+                ],
+            },
+            "capabilities should be correct"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn error_read_only() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x00, 0x00, 0x00]);
+        let mut dev = connect_read_only(&mut deque).await?;
+        let action = dev
+            .actions()
+            .iter()
+            .find(|action| action.id == "set_program_spin_setting")
+            .expect("action should exist");
+        let res = dev
+            .trigger_action(action, Some(Value::String("SpinMed".to_string())))
+            .await;
+
+        assert!(
+            matches!(res, Err(Error::Protocol(crate::Error::ReadOnly))),
+            "result should be read-only error"
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn error_unknown_software_id() -> Result<(), Infallible> {
         init_logger();
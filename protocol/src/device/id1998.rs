@@ -14,10 +14,10 @@
 //! discovered by dumping and analyzing the device's memory and EEPROM.
 
 use crate::device::{
-    Action, Device, DeviceKind, Error, Interface, Property, PropertyKind, Result, Value, private,
-    utils,
+    Action, ByteOrder, Decode, Device, DeviceKind, DeviceSchema, Error, FaultRecord, Interface,
+    Property, PropertyId, PropertyKind, Register, Result, Value, private, utils,
 };
-use alloc::{boxed::Box, string::ToString};
+use alloc::{boxed::Box, string::ToString, vec::Vec};
 use bitflags_derive::{FlagsDebug, FlagsDisplay};
 use core::time::Duration;
 use embedded_io_async::{Read, Write};
@@ -32,27 +32,60 @@ pub(super) use compatible_software_ids;
 
 const PROP_ROM_CODE: Property = Property {
     kind: PropertyKind::General,
-    id: "rom_code",
+    id: PropertyId("rom_code"),
     name: "ROM Code",
+    description: Some("Version of the firmware stored in the control board's ROM."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Unsigned),
+    register: Some(Register {
+        addr: 0xffdf,
+        len: 1,
+    }),
+    cumulative: false,
+    range: None,
 };
 const PROP_OPERATING_TIME: Property = Property {
     kind: PropertyKind::General,
-    id: "operating_time",
+    id: PropertyId("operating_time"),
     name: "Operating Time",
+    description: Some("Total operating time counted by the control board."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: true,
+    range: None,
 };
 const PROP_FAULTS: Property = Property {
     kind: PropertyKind::Failure,
-    id: "faults",
+    id: PropertyId("faults"),
     name: "Faults",
+    description: Some("Faults currently detected by the appliance."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_OPERATING_MODE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "operating_mode",
+    id: PropertyId("operating_mode"),
     name: "Operating Mode",
+    description: Some(
+        "Whether a program is idle, running or finished, or which programming or service mode is active.",
+    ),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 
 bitflags::bitflags! {
@@ -191,6 +224,19 @@ impl<P: Read + Write> WashingMachine<P> {
     }
 }
 
+pub(super) const SCHEMA: DeviceSchema = DeviceSchema {
+    kind: DeviceKind::WashingMachine,
+    properties: &[
+        PROP_ROM_CODE,
+        PROP_OPERATING_TIME,
+        PROP_FAULTS,
+        PROP_OPERATING_MODE,
+    ],
+    // No actions implemented yet - need to discover memory addresses first
+    actions: &[],
+    byte_order: ByteOrder::Little,
+};
+
 #[async_trait::async_trait(?Send)]
 impl<P: Read + Write> Device<P> for WashingMachine<P> {
     async fn connect(port: P) -> Result<Self, P::Error> {
@@ -212,40 +258,45 @@ impl<P: Read + Write> Device<P> for WashingMachine<P> {
     }
 
     fn kind(&self) -> DeviceKind {
-        DeviceKind::WashingMachine
+        SCHEMA.kind
     }
 
     fn properties(&self) -> &'static [Property] {
-        &[
-            PROP_ROM_CODE,
-            PROP_OPERATING_TIME,
-            PROP_FAULTS,
-            PROP_OPERATING_MODE,
-        ]
+        SCHEMA.properties
     }
 
     fn actions(&self) -> &'static [Action] {
-        // No actions implemented yet - need to discover memory addresses first
-        &[]
+        SCHEMA.actions
     }
 
-    async fn query_property(&mut self, prop: &Property) -> Result<Value, P::Error> {
-        match *prop {
-            PROP_ROM_CODE => Ok(self.query_rom_code().await?.into()),
-            PROP_OPERATING_TIME => Ok(self.query_operating_time().await?.into()),
-            PROP_FAULTS => Ok(self.query_faults().await?.to_string().into()),
-            PROP_OPERATING_MODE => Ok(self.query_operating_mode().await?.to_string().into()),
-            _ => Err(Error::UnknownProperty),
-        }
+    async fn fault_history(&mut self) -> Result<Vec<FaultRecord>, P::Error> {
+        let faults = Fault::from_bits_retain(self.intf.read_memory(0x000e).await?);
+
+        Ok(utils::fault_records(&faults))
     }
 
     async fn trigger_action(
         &mut self,
         _action: &Action,
         _param: Option<Value>,
-    ) -> Result<(), P::Error> {
+    ) -> Result<Option<Value>, P::Error> {
         Err(Error::UnknownAction)
     }
 }
 
 impl<P> private::Sealed for WashingMachine<P> {}
+
+#[async_trait::async_trait(?Send)]
+impl<P: Read + Write> private::QueryProperty<P> for WashingMachine<P> {
+    async fn query_device_property(&mut self, prop: &Property) -> Result<Value, P::Error> {
+        match *prop {
+            PROP_OPERATING_TIME => Ok(self.query_operating_time().await?.into()),
+            PROP_FAULTS => Ok(self.query_faults().await?.to_string().into()),
+            PROP_OPERATING_MODE => Ok(self.query_operating_mode().await?.to_string().into()),
+            _ if SCHEMA.properties.contains(prop) => {
+                utils::query_register(&mut self.intf, &SCHEMA, prop).await
+            }
+            _ => Err(Error::UnknownProperty),
+        }
+    }
+}
@@ -9,12 +9,14 @@
 //! the device's software ID and return an appropriate device instance.
 
 use crate::device::{
-    Action, ActionKind, ActionParameters, Device, DeviceKind, Error, Interface, Property,
-    PropertyKind, Result, Value, private, utils,
+    Action, ActionKind, ActionParameters, ByteOrder, Decode, Device, DeviceKind, DeviceSchema,
+    Error, FaultRecord, Interface, Property, PropertyId, PropertyKind, Register, Result, Value,
+    ValueRange, private, utils,
 };
 use alloc::{
     boxed::Box,
     string::{String, ToString},
+    vec::Vec,
 };
 use bitflags_derive::{FlagsDebug, FlagsDisplay, FlagsFromStr};
 use core::{str, time::Duration};
@@ -30,129 +32,309 @@ pub(super) use compatible_software_ids;
 
 const PROP_ROM_CODE: Property = Property {
     kind: PropertyKind::General,
-    id: "rom_code",
+    id: PropertyId("rom_code"),
     name: "ROM Code",
+    description: Some("Version of the firmware stored in the control board's ROM."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Unsigned),
+    register: Some(Register {
+        addr: 0xffdb,
+        len: 1,
+    }),
+    cumulative: false,
+    range: None,
 };
 const PROP_OPERATING_TIME: Property = Property {
     kind: PropertyKind::General,
-    id: "operating_time",
+    id: PropertyId("operating_time"),
     name: "Operating Time",
+    description: Some("Total operating time counted by the control board."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: true,
+    range: None,
 };
 const PROP_FAULTS: Property = Property {
     kind: PropertyKind::Failure,
-    id: "faults",
+    id: PropertyId("faults"),
     name: "Faults",
+    description: Some("Faults currently detected by the appliance."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_OPERATING_MODE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "operating_mode",
+    id: PropertyId("operating_mode"),
     name: "Operating Mode",
+    description: Some(
+        "Whether a program is idle, running or finished, or which programming or service mode is active.",
+    ),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_LOAD_LEVEL: Property = Property {
     kind: PropertyKind::Operation,
-    id: "load_level",
+    id: PropertyId("load_level"),
     name: "Load Level",
+    description: Some("Amount of laundry detected in the drum."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Unsigned),
+    register: Some(Register {
+        addr: 0x004a,
+        len: 1,
+    }),
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_SELECTOR: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_selector",
+    id: PropertyId("program_selector"),
     name: "Program Selector",
+    description: Some("Position of the program selector knob."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_TYPE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_type",
+    id: PropertyId("program_type"),
     name: "Program Type",
+    description: Some("Type of the program chosen with the program selector."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_TEMPERATURE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_temperature",
+    id: PropertyId("program_temperature"),
     name: "Program Temperature",
+    description: Some("Wash temperature selected for the program."),
     unit: Some("°C"),
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Unsigned),
+    register: Some(Register {
+        addr: 0x00de,
+        len: 1,
+    }),
+    cumulative: false,
+    range: Some(ValueRange {
+        min: 0.0,
+        max: 95.0,
+        warn: None,
+    }),
 };
 const PROP_PROGRAM_OPTIONS: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_options",
+    id: PropertyId("program_options"),
     name: "Program Options",
+    description: Some("Options selected in addition to the program."),
     unit: None,
+    writable: true,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_BUZZER_ENABLED: Property = Property {
     kind: PropertyKind::Operation,
-    id: "buzzer_enabled",
+    id: PropertyId("buzzer_enabled"),
     name: "Buzzer Enabled",
+    description: Some("Whether the buzzer sounds at the end of a program."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_SPIN_SETTING: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_spin_setting",
+    id: PropertyId("program_spin_setting"),
     name: "Program Spin Setting",
+    description: Some("Final spin speed selected for the program."),
     unit: None,
+    writable: true,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_PHASE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_phase",
+    id: PropertyId("program_phase"),
     name: "Program Phase",
+    description: Some("Current phase of the running program."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_LOCKED: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_locked",
+    id: PropertyId("program_locked"),
     name: "Program Locked",
+    description: Some("Whether the selected program is locked against changes."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_DISPLAY_CONTENTS: Property = Property {
     kind: PropertyKind::Operation,
-    id: "display_contents",
+    id: PropertyId("display_contents"),
     name: "Display Contents",
+    description: Some("Text currently shown on the appliance's display."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_ACTIVE_ACTUATORS: Property = Property {
     kind: PropertyKind::Io,
-    id: "active_actuators",
+    id: PropertyId("active_actuators"),
     name: "Active Actuators",
+    description: Some("Actuators that are currently switched on, e.g. heater, pumps and valves."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_NTC_RESISTANCE: Property = Property {
     kind: PropertyKind::Io,
-    id: "ntc_resistance",
+    id: PropertyId("ntc_resistance"),
     name: "NTC Resistance",
+    description: Some(
+        "Resistance of the NTC temperature sensor, which decreases as the temperature rises.",
+    ),
     unit: Some("Ω"),
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_TEMPERATURE: Property = Property {
     kind: PropertyKind::Io,
-    id: "temperature",
+    id: PropertyId("temperature"),
     name: "Temperature",
+    description: Some("Water temperature measured by the NTC sensor."),
     unit: Some("°C"),
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: Some(ValueRange {
+        min: 0.0,
+        max: 100.0,
+        warn: Some(90.0),
+    }),
 };
 const PROP_PRESSURE_SENSOR_VALUE: Property = Property {
     kind: PropertyKind::Io,
-    id: "pressure_sensor_value",
+    id: PropertyId("pressure_sensor_value"),
     name: "Pressure Sensor Value",
+    description: Some(
+        "Raw reading of the analog pressure sensor, used for calibrating it with an empty drum.",
+    ),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Unsigned),
+    register: Some(Register {
+        addr: 0x0285,
+        len: 1,
+    }),
+    cumulative: false,
+    range: None,
 };
 const PROP_WATER_LEVEL: Property = Property {
     kind: PropertyKind::Io,
-    id: "water_level",
+    id: PropertyId("water_level"),
     name: "Water Level",
+    description: Some("Water level in the drum, sensed by the analog pressure sensor."),
     unit: Some("mmH₂O"),
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_MOTOR_PWM_DUTY_CYCLE: Property = Property {
     kind: PropertyKind::Io,
-    id: "motor_pwm_duty_cycle",
+    id: PropertyId("motor_pwm_duty_cycle"),
     name: "Motor PWM Duty Cycle",
+    description: Some("Share of time the motor is powered, which controls its speed."),
     unit: Some("%"),
+    writable: false,
+    decimals: 1,
+    decoding: Some(Decode::Scale(100.0 / 255.0)),
+    register: Some(Register {
+        addr: 0x0280,
+        len: 1,
+    }),
+    cumulative: false,
+    range: Some(ValueRange {
+        min: 0.0,
+        max: 100.0,
+        warn: None,
+    }),
 };
 const PROP_TACHOMETER_SPEED: Property = Property {
     kind: PropertyKind::Io,
-    id: "tachometer_speed",
+    id: PropertyId("tachometer_speed"),
     name: "Tachometer Speed",
+    description: Some("Motor speed measured by the tachometer."),
     unit: Some("rpm"),
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 
 const ACTION_SET_PROGRAM_OPTIONS: Action = Action {
@@ -160,10 +342,10 @@ const ACTION_SET_PROGRAM_OPTIONS: Action = Action {
     id: "set_program_options",
     name: "Set Program Options",
     params: Some(ActionParameters::Flags(&[
-        "Soak",
-        "PreWash",
-        "WaterPlus",
-        "Short",
+        (ProgramOption::Soak.bits() as u32, "Soak"),
+        (ProgramOption::PreWash.bits() as u32, "PreWash"),
+        (ProgramOption::WaterPlus.bits() as u32, "WaterPlus"),
+        (ProgramOption::Short.bits() as u32, "Short"),
     ])),
 };
 const ACTION_SET_PROGRAM_SPIN_SETTING: Action = Action {
@@ -721,6 +903,39 @@ impl<P: Read + Write> WashingMachine<P> {
     }
 }
 
+pub(super) const SCHEMA: DeviceSchema = DeviceSchema {
+    kind: DeviceKind::WashingMachine,
+    properties: &[
+        PROP_ROM_CODE,
+        PROP_OPERATING_TIME,
+        PROP_FAULTS,
+        PROP_OPERATING_MODE,
+        PROP_PROGRAM_SELECTOR,
+        PROP_PROGRAM_TYPE,
+        PROP_PROGRAM_TEMPERATURE,
+        PROP_PROGRAM_OPTIONS,
+        PROP_BUZZER_ENABLED,
+        PROP_PROGRAM_SPIN_SETTING,
+        PROP_PROGRAM_PHASE,
+        PROP_PROGRAM_LOCKED,
+        PROP_LOAD_LEVEL,
+        PROP_DISPLAY_CONTENTS,
+        PROP_ACTIVE_ACTUATORS,
+        PROP_NTC_RESISTANCE,
+        PROP_TEMPERATURE,
+        PROP_PRESSURE_SENSOR_VALUE,
+        PROP_WATER_LEVEL,
+        PROP_MOTOR_PWM_DUTY_CYCLE,
+        PROP_TACHOMETER_SPEED,
+    ],
+    actions: &[
+        ACTION_SET_PROGRAM_OPTIONS,
+        ACTION_SET_PROGRAM_SPIN_SETTING,
+        ACTION_START_PROGRAM,
+    ],
+    byte_order: ByteOrder::Little,
+};
+
 #[async_trait::async_trait(?Send)]
 impl<P: Read + Write> Device<P> for WashingMachine<P> {
     async fn connect(port: P) -> Result<Self, P::Error> {
@@ -742,47 +957,105 @@ impl<P: Read + Write> Device<P> for WashingMachine<P> {
     }
 
     fn kind(&self) -> DeviceKind {
-        DeviceKind::WashingMachine
+        SCHEMA.kind
     }
 
     fn properties(&self) -> &'static [Property] {
-        &[
-            PROP_ROM_CODE,
-            PROP_OPERATING_TIME,
-            PROP_FAULTS,
-            PROP_OPERATING_MODE,
-            PROP_PROGRAM_SELECTOR,
-            PROP_PROGRAM_TYPE,
-            PROP_PROGRAM_TEMPERATURE,
-            PROP_PROGRAM_OPTIONS,
-            PROP_BUZZER_ENABLED,
-            PROP_PROGRAM_SPIN_SETTING,
-            PROP_PROGRAM_PHASE,
-            PROP_PROGRAM_LOCKED,
-            PROP_LOAD_LEVEL,
-            PROP_DISPLAY_CONTENTS,
-            PROP_ACTIVE_ACTUATORS,
-            PROP_NTC_RESISTANCE,
-            PROP_TEMPERATURE,
-            PROP_PRESSURE_SENSOR_VALUE,
-            PROP_WATER_LEVEL,
-            PROP_MOTOR_PWM_DUTY_CYCLE,
-            PROP_TACHOMETER_SPEED,
-        ]
+        SCHEMA.properties
     }
 
     fn actions(&self) -> &'static [Action] {
-        &[
-            ACTION_SET_PROGRAM_OPTIONS,
-            ACTION_SET_PROGRAM_SPIN_SETTING,
-            ACTION_START_PROGRAM,
-        ]
+        SCHEMA.actions
     }
 
-    async fn query_property(&mut self, prop: &Property) -> Result<Value, P::Error> {
+    async fn fault_history(&mut self) -> Result<Vec<FaultRecord>, P::Error> {
+        let faults = Fault::from_bits_retain(self.intf.read_memory(0x004e).await?);
+
+        Ok(utils::fault_records(&faults))
+    }
+
+    async fn set_property(&mut self, prop: &Property, value: Value) -> Result<(), P::Error> {
+        let Value::String(s) = value else {
+            return Err(if prop.writable {
+                Error::InvalidArgument
+            } else {
+                Error::NotWritable
+            });
+        };
+
+        // Read back the value to verify it was applied
+        let applied = match *prop {
+            PROP_PROGRAM_OPTIONS => {
+                let opts = s.parse()?;
+
+                self.set_program_options(opts).await?;
+                self.query_program_options().await? == opts
+            }
+            PROP_PROGRAM_SPIN_SETTING => {
+                let setting = s.parse()?;
+
+                self.set_program_spin_setting(setting).await?;
+                self.query_program_spin_setting().await? == setting
+            }
+            _ if SCHEMA.properties.contains(prop) => return Err(Error::NotWritable),
+            _ => return Err(Error::UnknownProperty),
+        };
+
+        if applied {
+            Ok(())
+        } else {
+            Err(Error::UnexpectedMemoryValue)
+        }
+    }
+
+    async fn trigger_action(
+        &mut self,
+        action: &Action,
+        param: Option<Value>,
+    ) -> Result<Option<Value>, P::Error> {
+        utils::check_param(action, param.as_ref())?;
+
+        match *action {
+            ACTION_SET_PROGRAM_OPTIONS => match param {
+                Some(Value::Number(mask)) => {
+                    self.set_program_options(utils::flags_param(mask)?).await?;
+
+                    // Read back the options to confirm they were applied
+                    Ok(Some(self.query_program_options().await?.to_string().into()))
+                }
+                _ => Err(Error::InvalidArgument),
+            },
+            ACTION_SET_PROGRAM_SPIN_SETTING => match param {
+                Some(Value::String(s)) => {
+                    self.set_program_spin_setting(s.parse()?).await?;
+
+                    // Read back the spin setting to confirm it was applied
+                    Ok(Some(
+                        self.query_program_spin_setting().await?.to_string().into(),
+                    ))
+                }
+                _ => Err(Error::InvalidArgument),
+            },
+            ACTION_START_PROGRAM => match param {
+                None => {
+                    self.start_program().await?;
+
+                    Ok(None)
+                }
+                _ => Err(Error::InvalidArgument),
+            },
+            _ => Err(Error::UnknownAction),
+        }
+    }
+}
+
+impl<P> private::Sealed for WashingMachine<P> {}
+
+#[async_trait::async_trait(?Send)]
+impl<P: Read + Write> private::QueryProperty<P> for WashingMachine<P> {
+    async fn query_device_property(&mut self, prop: &Property) -> Result<Value, P::Error> {
         match *prop {
             // General
-            PROP_ROM_CODE => Ok(self.query_rom_code().await?.into()),
             PROP_OPERATING_TIME => Ok(self.query_operating_time().await?.into()),
             // Failure
             PROP_FAULTS => Ok(self.query_faults().await?.to_string().into()),
@@ -790,7 +1063,6 @@ impl<P: Read + Write> Device<P> for WashingMachine<P> {
             PROP_OPERATING_MODE => Ok(self.query_operating_mode().await?.to_string().into()),
             PROP_PROGRAM_SELECTOR => Ok(self.query_program_selector().await?.to_string().into()),
             PROP_PROGRAM_TYPE => Ok(self.query_program_type().await?.to_string().into()),
-            PROP_PROGRAM_TEMPERATURE => Ok(self.query_program_temperature().await?.into()),
             PROP_PROGRAM_OPTIONS => Ok(self.query_program_options().await?.to_string().into()),
             PROP_BUZZER_ENABLED => Ok(self.query_buzzer_enabled().await?.into()),
             PROP_PROGRAM_SPIN_SETTING => {
@@ -798,41 +1070,17 @@ impl<P: Read + Write> Device<P> for WashingMachine<P> {
             }
             PROP_PROGRAM_PHASE => Ok(self.query_program_phase().await?.to_string().into()),
             PROP_PROGRAM_LOCKED => Ok(self.query_program_locked().await?.into()),
-            PROP_LOAD_LEVEL => Ok(self.query_load_level().await?.into()),
             PROP_DISPLAY_CONTENTS => Ok(self.query_display_contents().await?.into()),
             // Input/output
             PROP_ACTIVE_ACTUATORS => Ok(self.query_active_actuators().await?.to_string().into()),
             PROP_NTC_RESISTANCE => Ok(self.query_ntc_resistance().await?.into()),
             PROP_TEMPERATURE => Ok(self.query_temperature().await?.into()),
-            PROP_PRESSURE_SENSOR_VALUE => Ok(self.query_pressure_sensor_value().await?.into()),
             PROP_WATER_LEVEL => Ok(self.query_water_level().await?.into()),
-            PROP_MOTOR_PWM_DUTY_CYCLE => Ok(self.query_motor_pwm_duty_cycle().await?.into()),
             PROP_TACHOMETER_SPEED => Ok(self.query_tachometer_speed().await?.into()),
+            _ if SCHEMA.properties.contains(prop) => {
+                utils::query_register(&mut self.intf, &SCHEMA, prop).await
+            }
             _ => Err(Error::UnknownProperty),
         }
     }
-
-    async fn trigger_action(
-        &mut self,
-        action: &Action,
-        param: Option<Value>,
-    ) -> Result<(), P::Error> {
-        match *action {
-            ACTION_SET_PROGRAM_OPTIONS => match param {
-                Some(Value::String(s)) => self.set_program_options(s.parse()?).await,
-                _ => Err(Error::InvalidArgument),
-            },
-            ACTION_SET_PROGRAM_SPIN_SETTING => match param {
-                Some(Value::String(s)) => self.set_program_spin_setting(s.parse()?).await,
-                _ => Err(Error::InvalidArgument),
-            },
-            ACTION_START_PROGRAM => match param {
-                None => self.start_program().await,
-                _ => Err(Error::InvalidArgument),
-            },
-            _ => Err(Error::UnknownAction),
-        }
-    }
 }
-
-impl<P> private::Sealed for WashingMachine<P> {}
@@ -9,10 +9,11 @@
 //! the device's software ID and return an appropriate device instance.
 
 use crate::device::{
-    Action, ActionKind, ActionParameters, Device, DeviceKind, Error, Interface, Property,
-    PropertyKind, Result, Value, private, utils,
+    Action, ActionKind, ActionParameters, ByteOrder, Decode, Device, DeviceKind, DeviceSchema,
+    Error, FaultRecord, Interface, Property, PropertyId, PropertyKind, Register, Result, Value,
+    ValueRange, private, utils,
 };
-use alloc::{boxed::Box, string::ToString};
+use alloc::{boxed::Box, string::ToString, vec::Vec};
 use bitflags_derive::{FlagsDebug, FlagsDisplay, FlagsFromStr};
 use core::{str, time::Duration};
 use embedded_io_async::{Read, Write};
@@ -27,99 +28,232 @@ pub(super) use compatible_software_ids;
 
 const PROP_ROM_CODE: Property = Property {
     kind: PropertyKind::General,
-    id: "rom_code",
+    id: PropertyId("rom_code"),
     name: "ROM Code",
+    description: Some("Version of the firmware stored in the control board's ROM."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Unsigned),
+    register: Some(Register {
+        addr: 0xffdf,
+        len: 1,
+    }),
+    cumulative: false,
+    range: None,
 };
 const PROP_OPERATING_TIME: Property = Property {
     kind: PropertyKind::General,
-    id: "operating_time",
+    id: PropertyId("operating_time"),
     name: "Operating Time",
+    description: Some("Total operating time counted by the control board."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: true,
+    range: None,
 };
 const PROP_FAULTS: Property = Property {
     kind: PropertyKind::Failure,
-    id: "faults",
+    id: PropertyId("faults"),
     name: "Faults",
+    description: Some("Faults currently detected by the appliance."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_OPERATING_MODE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "operating_mode",
+    id: PropertyId("operating_mode"),
     name: "Operating Mode",
+    description: Some(
+        "Whether a program is idle, running or finished, or which programming or service mode is active.",
+    ),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_LOAD_LEVEL: Property = Property {
     kind: PropertyKind::Operation,
-    id: "load_level",
+    id: PropertyId("load_level"),
     name: "Load Level",
+    description: Some("Amount of laundry detected in the drum."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Unsigned),
+    register: Some(Register {
+        addr: 0x000a,
+        len: 1,
+    }),
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_SELECTOR: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_selector",
+    id: PropertyId("program_selector"),
     name: "Program Selector",
+    description: Some("Position of the program selector knob."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_TYPE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_type",
+    id: PropertyId("program_type"),
     name: "Program Type",
+    description: Some("Type of the program chosen with the program selector."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_TEMPERATURE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_temperature",
+    id: PropertyId("program_temperature"),
     name: "Program Temperature",
+    description: Some("Wash temperature selected for the program."),
     unit: Some("°C"),
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Unsigned),
+    register: Some(Register {
+        addr: 0x009f,
+        len: 1,
+    }),
+    cumulative: false,
+    range: Some(ValueRange {
+        min: 0.0,
+        max: 95.0,
+        warn: None,
+    }),
 };
 const PROP_PROGRAM_OPTIONS: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_options",
+    id: PropertyId("program_options"),
     name: "Program Options",
+    description: Some("Options selected in addition to the program."),
     unit: None,
+    writable: true,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_SPIN_SETTING: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_spin_setting",
+    id: PropertyId("program_spin_setting"),
     name: "Program Spin Setting",
+    description: Some("Final spin speed selected for the program."),
     unit: None,
+    writable: true,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_PHASE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_phase",
+    id: PropertyId("program_phase"),
     name: "Program Phase",
+    description: Some("Current phase of the running program."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_LOCKED: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_locked",
+    id: PropertyId("program_locked"),
     name: "Program Locked",
+    description: Some("Whether the selected program is locked against changes."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_ACTIVE_ACTUATORS: Property = Property {
     kind: PropertyKind::Io,
-    id: "active_actuators",
+    id: PropertyId("active_actuators"),
     name: "Active Actuators",
+    description: Some("Actuators that are currently switched on, e.g. heater, pumps and valves."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_NTC_RESISTANCE: Property = Property {
     kind: PropertyKind::Io,
-    id: "ntc_resistance",
+    id: PropertyId("ntc_resistance"),
     name: "NTC Resistance",
+    description: Some(
+        "Resistance of the NTC temperature sensor, which decreases as the temperature rises.",
+    ),
     unit: Some("Ω"),
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_TEMPERATURE: Property = Property {
     kind: PropertyKind::Io,
-    id: "temperature",
+    id: PropertyId("temperature"),
     name: "Temperature",
+    description: Some("Water temperature measured by the NTC sensor."),
     unit: Some("°C"),
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: Some(ValueRange {
+        min: 0.0,
+        max: 100.0,
+        warn: Some(90.0),
+    }),
 };
 const PROP_WATER_LEVEL: Property = Property {
     kind: PropertyKind::Io,
-    id: "water_level",
+    id: PropertyId("water_level"),
     name: "Water Level",
+    description: Some("Water level in the drum, sensed by the analog pressure sensor."),
     unit: Some("mmH₂O"),
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 
 const ACTION_SET_PROGRAM_OPTIONS: Action = Action {
@@ -127,10 +261,10 @@ const ACTION_SET_PROGRAM_OPTIONS: Action = Action {
     id: "set_program_options",
     name: "Set Program Options",
     params: Some(ActionParameters::Flags(&[
-        "Soak",
-        "PreWash",
-        "WaterPlus",
-        "Short",
+        (ProgramOption::Soak.bits() as u32, "Soak"),
+        (ProgramOption::PreWash.bits() as u32, "PreWash"),
+        (ProgramOption::WaterPlus.bits() as u32, "WaterPlus"),
+        (ProgramOption::Short.bits() as u32, "Short"),
     ])),
 };
 const ACTION_SET_PROGRAM_SPIN_SETTING: Action = Action {
@@ -614,6 +748,34 @@ impl<P: Read + Write> WashingMachine<P> {
     }
 }
 
+pub(super) const SCHEMA: DeviceSchema = DeviceSchema {
+    kind: DeviceKind::WashingMachine,
+    properties: &[
+        PROP_ROM_CODE,
+        PROP_OPERATING_TIME,
+        PROP_FAULTS,
+        PROP_OPERATING_MODE,
+        PROP_PROGRAM_SELECTOR,
+        PROP_PROGRAM_TYPE,
+        PROP_PROGRAM_TEMPERATURE,
+        PROP_PROGRAM_OPTIONS,
+        PROP_PROGRAM_SPIN_SETTING,
+        PROP_PROGRAM_PHASE,
+        PROP_PROGRAM_LOCKED,
+        PROP_LOAD_LEVEL,
+        PROP_ACTIVE_ACTUATORS,
+        PROP_NTC_RESISTANCE,
+        PROP_TEMPERATURE,
+        PROP_WATER_LEVEL,
+    ],
+    actions: &[
+        ACTION_SET_PROGRAM_OPTIONS,
+        ACTION_SET_PROGRAM_SPIN_SETTING,
+        ACTION_START_PROGRAM,
+    ],
+    byte_order: ByteOrder::Little,
+};
+
 #[async_trait::async_trait(?Send)]
 impl<P: Read + Write> Device<P> for WashingMachine<P> {
     async fn connect(port: P) -> Result<Self, P::Error> {
@@ -635,63 +797,54 @@ impl<P: Read + Write> Device<P> for WashingMachine<P> {
     }
 
     fn kind(&self) -> DeviceKind {
-        DeviceKind::WashingMachine
+        SCHEMA.kind
     }
 
     fn properties(&self) -> &'static [Property] {
-        &[
-            PROP_ROM_CODE,
-            PROP_OPERATING_TIME,
-            PROP_FAULTS,
-            PROP_OPERATING_MODE,
-            PROP_PROGRAM_SELECTOR,
-            PROP_PROGRAM_TYPE,
-            PROP_PROGRAM_TEMPERATURE,
-            PROP_PROGRAM_OPTIONS,
-            PROP_PROGRAM_SPIN_SETTING,
-            PROP_PROGRAM_PHASE,
-            PROP_PROGRAM_LOCKED,
-            PROP_LOAD_LEVEL,
-            PROP_ACTIVE_ACTUATORS,
-            PROP_NTC_RESISTANCE,
-            PROP_TEMPERATURE,
-            PROP_WATER_LEVEL,
-        ]
+        SCHEMA.properties
     }
 
     fn actions(&self) -> &'static [Action] {
-        &[
-            ACTION_SET_PROGRAM_OPTIONS,
-            ACTION_SET_PROGRAM_SPIN_SETTING,
-            ACTION_START_PROGRAM,
-        ]
+        SCHEMA.actions
     }
 
-    async fn query_property(&mut self, prop: &Property) -> Result<Value, P::Error> {
-        match *prop {
-            // General
-            PROP_ROM_CODE => Ok(self.query_rom_code().await?.into()),
-            PROP_OPERATING_TIME => Ok(self.query_operating_time().await?.into()),
-            // Failure
-            PROP_FAULTS => Ok(self.query_faults().await?.to_string().into()),
-            // Operation
-            PROP_OPERATING_MODE => Ok(self.query_operating_mode().await?.to_string().into()),
-            PROP_PROGRAM_SELECTOR => Ok(self.query_program_selector().await?.to_string().into()),
-            PROP_PROGRAM_TYPE => Ok(self.query_program_type().await?.to_string().into()),
-            PROP_PROGRAM_TEMPERATURE => Ok(self.query_program_temperature().await?.into()),
-            PROP_PROGRAM_OPTIONS => Ok(self.query_program_options().await?.to_string().into()),
+    async fn fault_history(&mut self) -> Result<Vec<FaultRecord>, P::Error> {
+        let faults = Fault::from_bits_retain(self.intf.read_memory(0x000e).await?);
+
+        Ok(utils::fault_records(&faults))
+    }
+
+    async fn set_property(&mut self, prop: &Property, value: Value) -> Result<(), P::Error> {
+        let Value::String(s) = value else {
+            return Err(if prop.writable {
+                Error::InvalidArgument
+            } else {
+                Error::NotWritable
+            });
+        };
+
+        // Read back the value to verify it was applied
+        let applied = match *prop {
+            PROP_PROGRAM_OPTIONS => {
+                let opts = s.parse()?;
+
+                self.set_program_options(opts).await?;
+                self.query_program_options().await? == opts
+            }
             PROP_PROGRAM_SPIN_SETTING => {
-                Ok(self.query_program_spin_setting().await?.to_string().into())
+                let setting = s.parse()?;
+
+                self.set_program_spin_setting(setting).await?;
+                self.query_program_spin_setting().await? == setting
             }
-            PROP_PROGRAM_PHASE => Ok(self.query_program_phase().await?.to_string().into()),
-            PROP_PROGRAM_LOCKED => Ok(self.query_program_locked().await?.into()),
-            PROP_LOAD_LEVEL => Ok(self.query_load_level().await?.into()),
-            // Input/output
-            PROP_ACTIVE_ACTUATORS => Ok(self.query_active_actuators().await?.to_string().into()),
-            PROP_NTC_RESISTANCE => Ok(self.query_ntc_resistance().await?.into()),
-            PROP_TEMPERATURE => Ok(self.query_temperature().await?.into()),
-            PROP_WATER_LEVEL => Ok(self.query_water_level().await?.into()),
-            _ => Err(Error::UnknownProperty),
+            _ if SCHEMA.properties.contains(prop) => return Err(Error::NotWritable),
+            _ => return Err(Error::UnknownProperty),
+        };
+
+        if applied {
+            Ok(())
+        } else {
+            Err(Error::UnexpectedMemoryValue)
         }
     }
 
@@ -699,18 +852,36 @@ impl<P: Read + Write> Device<P> for WashingMachine<P> {
         &mut self,
         action: &Action,
         param: Option<Value>,
-    ) -> Result<(), P::Error> {
+    ) -> Result<Option<Value>, P::Error> {
+        utils::check_param(action, param.as_ref())?;
+
         match *action {
             ACTION_SET_PROGRAM_OPTIONS => match param {
-                Some(Value::String(s)) => self.set_program_options(s.parse()?).await,
+                Some(Value::Number(mask)) => {
+                    self.set_program_options(utils::flags_param(mask)?).await?;
+
+                    // Read back the options to confirm they were applied
+                    Ok(Some(self.query_program_options().await?.to_string().into()))
+                }
                 _ => Err(Error::InvalidArgument),
             },
             ACTION_SET_PROGRAM_SPIN_SETTING => match param {
-                Some(Value::String(s)) => self.set_program_spin_setting(s.parse()?).await,
+                Some(Value::String(s)) => {
+                    self.set_program_spin_setting(s.parse()?).await?;
+
+                    // Read back the spin setting to confirm it was applied
+                    Ok(Some(
+                        self.query_program_spin_setting().await?.to_string().into(),
+                    ))
+                }
                 _ => Err(Error::InvalidArgument),
             },
             ACTION_START_PROGRAM => match param {
-                None => self.start_program().await,
+                None => {
+                    self.start_program().await?;
+
+                    Ok(None)
+                }
                 _ => Err(Error::InvalidArgument),
             },
             _ => Err(Error::UnknownAction),
@@ -719,3 +890,34 @@ impl<P: Read + Write> Device<P> for WashingMachine<P> {
 }
 
 impl<P> private::Sealed for WashingMachine<P> {}
+
+#[async_trait::async_trait(?Send)]
+impl<P: Read + Write> private::QueryProperty<P> for WashingMachine<P> {
+    async fn query_device_property(&mut self, prop: &Property) -> Result<Value, P::Error> {
+        match *prop {
+            // General
+            PROP_OPERATING_TIME => Ok(self.query_operating_time().await?.into()),
+            // Failure
+            PROP_FAULTS => Ok(self.query_faults().await?.to_string().into()),
+            // Operation
+            PROP_OPERATING_MODE => Ok(self.query_operating_mode().await?.to_string().into()),
+            PROP_PROGRAM_SELECTOR => Ok(self.query_program_selector().await?.to_string().into()),
+            PROP_PROGRAM_TYPE => Ok(self.query_program_type().await?.to_string().into()),
+            PROP_PROGRAM_OPTIONS => Ok(self.query_program_options().await?.to_string().into()),
+            PROP_PROGRAM_SPIN_SETTING => {
+                Ok(self.query_program_spin_setting().await?.to_string().into())
+            }
+            PROP_PROGRAM_PHASE => Ok(self.query_program_phase().await?.to_string().into()),
+            PROP_PROGRAM_LOCKED => Ok(self.query_program_locked().await?.into()),
+            // Input/output
+            PROP_ACTIVE_ACTUATORS => Ok(self.query_active_actuators().await?.to_string().into()),
+            PROP_NTC_RESISTANCE => Ok(self.query_ntc_resistance().await?.into()),
+            PROP_TEMPERATURE => Ok(self.query_temperature().await?.into()),
+            PROP_WATER_LEVEL => Ok(self.query_water_level().await?.into()),
+            _ if SCHEMA.properties.contains(prop) => {
+                utils::query_register(&mut self.intf, &SCHEMA, prop).await
+            }
+            _ => Err(Error::UnknownProperty),
+        }
+    }
+}
@@ -9,12 +9,13 @@
 //! the device's software ID and return an appropriate device instance.
 
 use crate::device::{
-    Action, ActionKind, Device, DeviceKind, Error, Interface, Property, PropertyKind, Result,
-    Value, private, utils,
+    Action, ActionKind, ByteOrder, Decode, Device, DeviceKind, DeviceSchema, Error, FaultRecord,
+    Interface, Property, PropertyId, PropertyKind, Register, Result, Value, private, utils,
 };
 use alloc::{
     boxed::Box,
     string::{String, ToString},
+    vec::Vec,
 };
 use bitflags_derive::{FlagsDebug, FlagsDisplay};
 use core::str;
@@ -30,75 +31,196 @@ pub(super) use compatible_software_ids;
 
 const PROP_BOARD_NUMBER: Property = Property {
     kind: PropertyKind::General,
-    id: "board_number",
+    id: PropertyId("board_number"),
     name: "Board Number",
+    description: Some("Identifier of the electronic control board."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_FAULTS: Property = Property {
     kind: PropertyKind::Failure,
-    id: "faults",
+    id: PropertyId("faults"),
     name: "Faults",
+    description: Some("Faults currently detected by the appliance."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
+};
+const PROP_SALT_RESERVOIR_EMPTY: Property = Property {
+    kind: PropertyKind::Warning,
+    id: PropertyId("salt_reservoir_empty"),
+    name: "Salt Reservoir Empty",
+    description: Some("Whether the salt reservoir of the water softener needs refilling."),
+    unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
+};
+const PROP_RINSE_AID_RESERVOIR_EMPTY: Property = Property {
+    kind: PropertyKind::Warning,
+    id: PropertyId("rinse_aid_reservoir_empty"),
+    name: "Rinse Aid Reservoir Empty",
+    description: Some("Whether the rinse aid reservoir needs refilling."),
+    unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_SELECTOR: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_selector",
+    id: PropertyId("program_selector"),
     name: "Program Selector",
+    description: Some("Position of the program selector knob."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Unsigned),
+    register: Some(Register {
+        addr: 0x00af,
+        len: 1,
+    }),
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_TYPE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_type",
+    id: PropertyId("program_type"),
     name: "Program Type",
+    description: Some("Type of the program chosen with the program selector."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_TOP_SOLO_ENABLED: Property = Property {
     kind: PropertyKind::Operation,
-    id: "top_solo_enabled",
+    id: PropertyId("top_solo_enabled"),
     name: "Top Solo Enabled",
+    description: Some("Whether only the upper basket is washed."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_PHASE: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_phase",
+    id: PropertyId("program_phase"),
     name: "Program Phase",
+    description: Some("Current phase of the running program."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_PROGRAM_STEP: Property = Property {
     kind: PropertyKind::Operation,
-    id: "program_step",
+    id: PropertyId("program_step"),
     name: "Program Step",
+    description: Some("Current step within the program phase."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Unsigned),
+    register: Some(Register {
+        addr: 0x020d,
+        len: 1,
+    }),
+    cumulative: false,
+    range: None,
 };
 const PROP_ACTIVE_ACTUATORS: Property = Property {
     kind: PropertyKind::Io,
-    id: "active_actuators",
+    id: PropertyId("active_actuators"),
     name: "Active Actuators",
+    description: Some("Actuators that are currently switched on, e.g. heater, pumps and valves."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_CLOSED_SWITCHES: Property = Property {
     kind: PropertyKind::Io,
-    id: "closed_switches",
+    id: PropertyId("closed_switches"),
     name: "Closed Switches",
+    description: Some("Pressure and reed switches that are currently closed."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_NTC_RESISTANCE: Property = Property {
     kind: PropertyKind::Io,
-    id: "ntc_resistance",
+    id: PropertyId("ntc_resistance"),
     name: "NTC Resistance",
+    description: Some(
+        "Resistance of the NTC temperature sensor, which decreases as the temperature rises.",
+    ),
     unit: Some("Ω"),
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_FLOW_METER_PULSES: Property = Property {
     kind: PropertyKind::Io,
-    id: "flow_meter_pulses",
+    id: PropertyId("flow_meter_pulses"),
     name: "Flow Meter Pulses",
+    description: Some("Pulses counted by the flow meter, measuring the amount of water taken in."),
     unit: None,
+    writable: false,
+    decimals: 0,
+    decoding: None,
+    register: None,
+    cumulative: false,
+    range: None,
 };
 const PROP_TARGET_WATER_AMOUNT: Property = Property {
     kind: PropertyKind::Io,
-    id: "target_water_amount",
+    id: PropertyId("target_water_amount"),
     name: "Target Water Amount",
+    description: Some("Amount of water the program is supposed to take in."),
     unit: Some("ml"),
+    writable: false,
+    decimals: 0,
+    decoding: Some(Decode::Scale(10.0)),
+    register: Some(Register {
+        addr: 0x00d6,
+        len: 2,
+    }),
+    cumulative: false,
+    range: None,
 };
 
 const ACTION_START_PROGRAM: Action = Action {
@@ -375,6 +497,25 @@ impl<P: Read + Write> Dishwasher<P> {
         Switch::from_bits(self.intf.read_memory(0x006f).await?).ok_or(Error::UnexpectedMemoryValue)
     }
 
+    /// Queries whether the salt reservoir is empty.
+    ///
+    /// The dishwasher remains operational, but the water softener
+    /// cannot be regenerated until the reservoir is refilled.
+    pub async fn query_salt_reservoir_empty(&mut self) -> Result<bool, P::Error> {
+        Ok(self
+            .query_closed_switches()
+            .await?
+            .contains(Switch::SaltReservoirEmpty))
+    }
+
+    /// Queries whether the rinse aid reservoir is empty.
+    pub async fn query_rinse_aid_reservoir_empty(&mut self) -> Result<bool, P::Error> {
+        Ok(self
+            .query_closed_switches()
+            .await?
+            .contains(Switch::RinseAidReservoirEmpty))
+    }
+
     /// Queries the current NTC thermistor resistance and target resistance.
     ///
     /// The resistance in `Ω` (ohms) is calculated from the ADC voltage.
@@ -440,6 +581,28 @@ impl<P: Read + Write> Dishwasher<P> {
     }
 }
 
+pub(super) const SCHEMA: DeviceSchema = DeviceSchema {
+    kind: DeviceKind::Dishwasher,
+    properties: &[
+        PROP_BOARD_NUMBER,
+        PROP_FAULTS,
+        PROP_SALT_RESERVOIR_EMPTY,
+        PROP_RINSE_AID_RESERVOIR_EMPTY,
+        PROP_PROGRAM_SELECTOR,
+        PROP_PROGRAM_TYPE,
+        PROP_TOP_SOLO_ENABLED,
+        PROP_PROGRAM_PHASE,
+        PROP_PROGRAM_STEP,
+        PROP_ACTIVE_ACTUATORS,
+        PROP_CLOSED_SWITCHES,
+        PROP_NTC_RESISTANCE,
+        PROP_FLOW_METER_PULSES,
+        PROP_TARGET_WATER_AMOUNT,
+    ],
+    actions: &[ACTION_START_PROGRAM],
+    byte_order: ByteOrder::Little,
+};
+
 #[async_trait::async_trait(?Send)]
 impl<P: Read + Write> Device<P> for Dishwasher<P> {
     async fn connect(port: P) -> Result<Self, P::Error> {
@@ -461,65 +624,72 @@ impl<P: Read + Write> Device<P> for Dishwasher<P> {
     }
 
     fn kind(&self) -> DeviceKind {
-        DeviceKind::Dishwasher
+        SCHEMA.kind
     }
 
     fn properties(&self) -> &'static [Property] {
-        &[
-            PROP_BOARD_NUMBER,
-            PROP_FAULTS,
-            PROP_PROGRAM_SELECTOR,
-            PROP_PROGRAM_TYPE,
-            PROP_TOP_SOLO_ENABLED,
-            PROP_PROGRAM_PHASE,
-            PROP_PROGRAM_STEP,
-            PROP_ACTIVE_ACTUATORS,
-            PROP_CLOSED_SWITCHES,
-            PROP_NTC_RESISTANCE,
-            PROP_FLOW_METER_PULSES,
-            PROP_TARGET_WATER_AMOUNT,
-        ]
+        SCHEMA.properties
     }
 
     fn actions(&self) -> &'static [Action] {
-        &[ACTION_START_PROGRAM]
+        SCHEMA.actions
+    }
+
+    async fn fault_history(&mut self) -> Result<Vec<FaultRecord>, P::Error> {
+        let faults = Fault::from_bits_retain(self.intf.read_memory(0x0082).await?);
+
+        Ok(utils::fault_records(&faults))
+    }
+
+    async fn trigger_action(
+        &mut self,
+        action: &Action,
+        param: Option<Value>,
+    ) -> Result<Option<Value>, P::Error> {
+        utils::check_param(action, param.as_ref())?;
+
+        match *action {
+            ACTION_START_PROGRAM => match param {
+                None => {
+                    self.start_program().await?;
+
+                    Ok(None)
+                }
+                _ => Err(Error::InvalidArgument),
+            },
+            _ => Err(Error::UnknownAction),
+        }
     }
+}
 
-    async fn query_property(&mut self, prop: &Property) -> Result<Value, P::Error> {
+impl<P> private::Sealed for Dishwasher<P> {}
+
+#[async_trait::async_trait(?Send)]
+impl<P: Read + Write> private::QueryProperty<P> for Dishwasher<P> {
+    async fn query_device_property(&mut self, prop: &Property) -> Result<Value, P::Error> {
         match *prop {
             // General
             PROP_BOARD_NUMBER => Ok(self.query_board_number().await?.into()),
             // Failure
             PROP_FAULTS => Ok(self.query_faults().await?.to_string().into()),
+            // Warning
+            PROP_SALT_RESERVOIR_EMPTY => Ok(self.query_salt_reservoir_empty().await?.into()),
+            PROP_RINSE_AID_RESERVOIR_EMPTY => {
+                Ok(self.query_rinse_aid_reservoir_empty().await?.into())
+            }
             // Operation
-            PROP_PROGRAM_SELECTOR => Ok(self.query_program_selector().await?.into()),
             PROP_PROGRAM_TYPE => Ok(self.query_program_type().await?.to_string().into()),
             PROP_TOP_SOLO_ENABLED => Ok(self.query_top_solo_enabled().await?.into()),
             PROP_PROGRAM_PHASE => Ok(self.query_program_phase().await?.to_string().into()),
-            PROP_PROGRAM_STEP => Ok(self.query_program_step().await?.into()),
             // Input/output
             PROP_ACTIVE_ACTUATORS => Ok(self.query_active_actuators().await?.to_string().into()),
             PROP_CLOSED_SWITCHES => Ok(self.query_closed_switches().await?.to_string().into()),
             PROP_NTC_RESISTANCE => Ok(self.query_ntc_resistance().await?.into()),
             PROP_FLOW_METER_PULSES => Ok(self.query_flow_meter_pulses().await?.into()),
-            PROP_TARGET_WATER_AMOUNT => Ok(self.query_target_water_amount().await?.into()),
+            _ if SCHEMA.properties.contains(prop) => {
+                utils::query_register(&mut self.intf, &SCHEMA, prop).await
+            }
             _ => Err(Error::UnknownProperty),
         }
     }
-
-    async fn trigger_action(
-        &mut self,
-        action: &Action,
-        param: Option<Value>,
-    ) -> Result<(), P::Error> {
-        match *action {
-            ACTION_START_PROGRAM => match param {
-                None => self.start_program().await,
-                _ => Err(Error::InvalidArgument),
-            },
-            _ => Err(Error::UnknownAction),
-        }
-    }
 }
-
-impl<P> private::Sealed for Dishwasher<P> {}
@@ -12,6 +12,11 @@
 //! - Instantiate device implementations (e.g. [`device::id629`]) to access model-specific methods.
 //! - Work directly with the low-level diagnostic [`Interface`].
 //!
+//! Multi-threaded applications can share a device between tasks
+//! using a [`client::DeviceClient`], which requires the `native-serial` feature.
+//! Synchronous applications can use a [`blocking::BlockingDevice`] instead,
+//! which drives the device on a runtime of its own.
+//!
 //! # Getting started
 //!
 //! Most Miele appliances expose a diagnostic UART on their control board.
@@ -36,6 +41,9 @@
 //! The UART connection can be provided by a USB–UART adapter.
 //! In that case, the adapter's RX, TX and GND lines must be connected to
 //! the corresponding pins on the appliance's control board.
+//! Adapters that require different settings can be configured
+//! using [`serial::open_with`] and a custom [`serial::SerialConfig`].
+//! Appliances behind a serial-to-TCP bridge can be reached using [`serial::open_tcp`].
 //!
 //! <div class="warning">
 //! Because the control board is typically not galvanically isolated,
@@ -48,6 +56,16 @@
 //! Instructions for building a simple adapter are available on the
 //! [FreeMDU project page](https://github.com/medusalix/FreeMDU).
 //!
+//! The diagnostic interface is a point-to-point link. Requests don't carry
+//! a device address, so appliances can't share a bus and each one needs
+//! its own port. To communicate with several appliances, connect to each
+//! of them separately.
+//!
+//! For development without an appliance, the `mock` feature provides
+//! an emulated port in the [`mock`] module.
+//! Sessions recorded with the `native-serial` feature can be replayed
+//! using the [`replay`] module.
+//!
 //! # Examples
 //!
 //! The following examples demonstrate the primary ways to communicate with devices:
@@ -138,19 +156,38 @@
 extern crate alloc;
 
 pub mod device;
+pub mod observer;
 
 #[cfg(feature = "native-serial")]
 #[cfg_attr(docsrs, doc(cfg(feature = "native-serial")))]
 pub mod serial;
 
+#[cfg(feature = "mock")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+pub mod mock;
+
+#[cfg(feature = "native-serial")]
+#[cfg_attr(docsrs, doc(cfg(feature = "native-serial")))]
+pub mod replay;
+
+#[cfg(feature = "native-serial")]
+#[cfg_attr(docsrs, doc(cfg(feature = "native-serial")))]
+pub mod client;
+
+#[cfg(feature = "native-serial")]
+#[cfg_attr(docsrs, doc(cfg(feature = "native-serial")))]
+pub mod blocking;
+
 pub use embedded_io_async;
 
+use alloc::{boxed::Box, vec, vec::Vec};
 use core::{
     fmt::{Debug, Display, Formatter},
     num::Wrapping,
 };
 use embedded_io_async::{Read, ReadExactError, Write};
 use log::debug;
+use observer::FrameObserver;
 use strum::FromRepr;
 
 /// A specialized [`Result`] type for [`Interface`] operations.
@@ -168,14 +205,25 @@ pub type Result<T, E> = core::result::Result<T, Error<E>>;
 pub enum Error<E> {
     /// The provided argument is invalid.
     InvalidArgument,
-    /// Data received by or from the device has an incorrect checksum.
+    /// The device reported that data sent to it has an incorrect checksum.
     IncorrectChecksum,
+    /// Data received from the device has an incorrect checksum.
+    ChecksumMismatch {
+        /// Checksum computed over the received data.
+        expected: u8,
+        /// Checksum sent by the device.
+        actual: u8,
+    },
     /// The device received an invalid command.
     InvalidCommand,
     /// The device responded with an unknown response code.
     UnknownResponseCode,
     /// The port encountered an unexpected end-of-file.
     UnexpectedEof,
+    /// The operation would modify the device, but the interface is read-only.
+    ReadOnly,
+    /// The port is already in use by another program.
+    PortBusy,
     /// A port-specific input/output error.
     Io(E),
 }
@@ -185,9 +233,15 @@ impl<E: core::error::Error> Display for Error<E> {
         match self {
             Self::InvalidArgument => write!(f, "invalid argument"),
             Self::IncorrectChecksum => write!(f, "incorrect checksum"),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch (expected {expected:#04x}, got {actual:#04x})"
+            ),
             Self::InvalidCommand => write!(f, "invalid command"),
             Self::UnknownResponseCode => write!(f, "unknown response code"),
             Self::UnexpectedEof => write!(f, "unexpected end-of-file"),
+            Self::ReadOnly => write!(f, "interface is read-only"),
+            Self::PortBusy => write!(f, "port in use by another program"),
             Self::Io(err) => write!(f, "input/output error: {err}"),
         }
     }
@@ -211,7 +265,7 @@ impl<E> From<ReadExactError<E>> for Error<E> {
 }
 
 /// Command code used by the diagnostic interface.
-#[derive(Debug)]
+#[derive(FromRepr, Debug)]
 #[repr(u8)]
 enum Command {
     Lock = 0x10,
@@ -335,6 +389,61 @@ fn compute_checksum(data: &[u8]) -> u8 {
     data.iter().map(|&x| Wrapping(x)).sum::<Wrapping<_>>().0
 }
 
+/// Framing variant of the diagnostic protocol.
+///
+/// All variants split payloads into chunks of up to four bytes followed by a checksum,
+/// but differ in the additional bytes exchanged around each chunk.
+///
+/// This enum is marked `#[non_exhaustive]` to allow for future variants.
+#[non_exhaustive]
+#[derive(PartialEq, Eq, Copy, Clone, Default, Debug)]
+pub enum Protocol {
+    /// Standard framing used by most devices.
+    #[default]
+    V1,
+    /// Framing with additional dummy bytes after every chunk.
+    ///
+    /// Required by some older devices (e.g. devices with software ID 419).
+    V2,
+}
+
+impl Protocol {
+    /// Returns the number of dummy bytes to send after a chunk has been sent.
+    fn dummy_bytes_after_send(self) -> usize {
+        match self {
+            Self::V1 => 0,
+            Self::V2 => 1,
+        }
+    }
+
+    /// Returns the number of dummy bytes to send after a chunk
+    /// of the given length has been received.
+    fn dummy_bytes_after_receive(self, len: usize) -> usize {
+        match self {
+            Self::V1 => 0,
+            Self::V2 => len + 1,
+        }
+    }
+}
+
+/// Statistics about the data exchanged through an [`Interface`].
+///
+/// Can be used to judge the quality of the link to the device,
+/// e.g. to detect a marginal cable that corrupts data.
+#[derive(PartialEq, Eq, Copy, Clone, Default, Debug)]
+pub struct TransportStats {
+    /// Number of chunks sent or received without errors.
+    pub frames_ok: u64,
+    /// Number of chunks with an incorrect checksum,
+    /// detected either by the interface or by the device.
+    pub checksum_errors: u64,
+    /// Number of transfers that were abandoned before completing.
+    ///
+    /// This happens when the caller stops waiting for a transfer,
+    /// typically because a timeout elapsed.
+    pub timeouts: u64,
+}
+
 /// Asynchronous diagnostic protocol interface.
 ///
 /// Requires a port that implements [`Read`] and [`Write`] for communication.
@@ -367,18 +476,143 @@ fn compute_checksum(data: &[u8]) -> u8 {
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug)]
 pub struct Interface<P> {
     port: P,
-    send_dummy_bytes: bool,
+    protocol: Protocol,
+    read_only: bool,
+    observer: Option<Box<dyn FrameObserver>>,
+    stats: TransportStats,
+    /// Set while a transfer is in progress, remains set if it's abandoned
+    transfer_pending: bool,
+    /// Payloads received since [`Interface::start_capture`]
+    capture: Option<Vec<u8>>,
+    /// Memory contents read since [`Interface::start_read_cache`], by start address
+    read_cache: Option<Vec<(u16, Vec<u8>)>>,
+    /// Payload of the last reply to [`Interface::query_software_id`]
+    software_id_response: Option<[u8; 2]>,
+}
+
+impl<P: Debug> Debug for Interface<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Interface")
+            .field("port", &self.port)
+            .field("protocol", &self.protocol)
+            .field("read_only", &self.read_only)
+            .field("observer", &self.observer.is_some())
+            .field("stats", &self.stats)
+            .finish_non_exhaustive()
+    }
 }
 
 impl<P: Read + Write> Interface<P> {
-    /// Constructs a new diagnostic interface.
+    /// Constructs a new diagnostic interface using the default [`Protocol`].
     pub fn new(port: P) -> Self {
+        Self::with_protocol(port, Protocol::default())
+    }
+
+    /// Constructs a new diagnostic interface using the specified [`Protocol`].
+    pub fn with_protocol(port: P, protocol: Protocol) -> Self {
         Self {
             port,
-            send_dummy_bytes: false,
+            protocol,
+            read_only: false,
+            observer: None,
+            stats: TransportStats::default(),
+            transfer_pending: false,
+            capture: None,
+            read_cache: None,
+            software_id_response: None,
+        }
+    }
+
+    /// Returns the framing variant used by the interface.
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    /// Enables or disables read-only mode.
+    ///
+    /// In read-only mode, all operations that could modify the device's memory
+    /// or state fail with [`Error::ReadOnly`] without sending anything to the device.
+    /// Locking, unlocking and reading remain available.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Returns `true` if the interface is in read-only mode.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Installs an observer that is notified of every frame exchanged with the device.
+    ///
+    /// Replaces any previously installed observer. Passing `None` removes it.
+    pub fn set_observer(&mut self, observer: Option<Box<dyn FrameObserver>>) {
+        self.observer = observer;
+    }
+
+    /// Returns a mutable reference to the underlying port.
+    ///
+    /// Allows port-specific operations, e.g. discarding data that arrives late
+    /// after a transfer was abandoned. Reading or writing data directly
+    /// may leave the device in an unexpected state.
+    pub fn port_mut(&mut self) -> &mut P {
+        &mut self.port
+    }
+
+    /// Returns statistics about the data exchanged so far.
+    pub fn stats(&self) -> TransportStats {
+        self.stats
+    }
+
+    /// Starts collecting the payloads of all responses received from now on.
+    ///
+    /// Discards payloads collected by a capture that was never finished,
+    /// e.g. because its query was abandoned.
+    pub fn start_capture(&mut self) {
+        self.capture = Some(Vec::new());
+    }
+
+    /// Stops collecting payloads and returns those received since [`Interface::start_capture`].
+    ///
+    /// Checksums and response codes aren't included.
+    /// Returns an empty vector if no capture was started.
+    pub fn finish_capture(&mut self) -> Vec<u8> {
+        self.capture.take().unwrap_or_default()
+    }
+
+    /// Starts caching the contents of memory reads.
+    ///
+    /// Until [`Interface::stop_read_cache`] is called, reads of memory that was already read
+    /// are answered from the cache without a round trip, e.g. for several properties decoded
+    /// from the same word within a batch of queries. Cached payloads are still captured, see
+    /// [`Interface::start_capture`]. Writes and subroutine calls clear the cache, as they might
+    /// change the memory contents.
+    pub fn start_read_cache(&mut self) {
+        self.read_cache = Some(Vec::new());
+    }
+
+    /// Stops caching memory reads and discards the cached contents.
+    pub fn stop_read_cache(&mut self) {
+        self.read_cache = None;
+    }
+
+    /// Returns cached memory contents at the specified address, if fully covered by a read.
+    fn cached_read<const N: usize>(&self, addr: u16) -> Option<Payload<N>> {
+        let addr = usize::from(addr);
+
+        self.read_cache.as_ref()?.iter().find_map(|(start, data)| {
+            let offset = addr.checked_sub(usize::from(*start))?;
+            let bytes = data.get(offset..offset + N)?;
+
+            bytes.try_into().ok().map(Payload)
+        })
+    }
+
+    /// Clears the cached memory contents, as they might have changed.
+    fn invalidate_read_cache(&mut self) {
+        if let Some(cache) = &mut self.read_cache {
+            cache.clear();
         }
     }
 
@@ -390,8 +624,9 @@ impl<P: Read + Write> Interface<P> {
     ///
     /// The first dummy bytes are sent immediately as a response
     /// to the query software ID command.
+    /// Afterwards, the interface uses [`Protocol::V2`].
     pub async fn enable_dummy_bytes(&mut self) -> Result<(), P::Error> {
-        self.send_dummy_bytes = true;
+        self.protocol = Protocol::V2;
         self.write(&[0x00, 0x00, 0x00, 0x00]).await
     }
 
@@ -416,7 +651,19 @@ impl<P: Read + Write> Interface<P> {
         self.send(Request::new(Command::QuerySoftwareId, 0x0000, 0x02).into())
             .await?;
 
-        Ok(self.receive().await?.into())
+        let payload: [u8; 2] = self.receive().await?.into();
+
+        self.software_id_response = Some(payload);
+
+        Ok(u16::from_le_bytes(payload))
+    }
+
+    /// Returns the raw payload of the last reply to [`Interface::query_software_id`],
+    /// or `None` if the software ID hasn't been queried yet.
+    ///
+    /// The software ID is transmitted in little-endian byte order.
+    pub fn software_id_response(&self) -> Option<[u8; 2]> {
+        self.software_id_response
     }
 
     /// Unlocks read-only diagnostic access.
@@ -437,6 +684,8 @@ impl<P: Read + Write> Interface<P> {
     /// Reads data from the device's memory.
     ///
     /// The requested payload length cannot exceed 255 bytes.
+    /// Memory that was already read is answered from the cache if enabled,
+    /// see [`Interface::start_read_cache`].
     ///
     /// # Errors
     ///
@@ -449,10 +698,26 @@ impl<P: Read + Write> Interface<P> {
             return Err(Error::InvalidArgument);
         };
 
+        if let Some(payload) = self.cached_read(addr) {
+            debug!("Read of {N} bytes at {addr:#06x} answered from cache");
+
+            if let Some(capture) = &mut self.capture {
+                capture.extend_from_slice(&payload.0);
+            }
+
+            return Ok(payload.into());
+        }
+
         self.send(Request::new(Command::ReadMemory, addr, len).into())
             .await?;
 
-        Ok(self.receive().await?.into())
+        let payload: Payload<N> = self.receive().await?;
+
+        if let Some(cache) = &mut self.read_cache {
+            cache.push((addr, payload.0.to_vec()));
+        }
+
+        Ok(payload.into())
     }
 
     /// Reads data from the device's EEPROM.
@@ -505,15 +770,19 @@ impl<P: Read + Write> Interface<P> {
     /// # Errors
     ///
     /// - [`Error::InvalidArgument`] if the payload length is greater than 255 bytes.
+    /// - [`Error::ReadOnly`] if the interface is in read-only mode.
     pub async fn write_memory<L: Into<Payload<N>>, const N: usize>(
         &mut self,
         addr: u16,
         payload: L,
     ) -> Result<(), P::Error> {
+        self.ensure_writable()?;
+
         let Ok(len) = N.try_into() else {
             return Err(Error::InvalidArgument);
         };
 
+        self.invalidate_read_cache();
         self.send(Request::new(Command::WriteMemory, addr, len).into())
             .await?;
         self.send(payload.into()).await
@@ -528,16 +797,20 @@ impl<P: Read + Write> Interface<P> {
     /// # Errors
     ///
     /// - [`Error::InvalidArgument`] if the payload length is not a multiple of two or exceeds 255 bytes.
+    /// - [`Error::ReadOnly`] if the interface is in read-only mode.
     pub async fn write_eeprom<L: Into<Payload<N>>, const N: usize>(
         &mut self,
         addr: u16,
         payload: L,
     ) -> Result<(), P::Error> {
+        self.ensure_writable()?;
+
         let len = match N.try_into() {
             Ok(n) if n % 2 == 0 => n,
             _ => return Err(Error::InvalidArgument),
         };
 
+        self.invalidate_read_cache();
         self.send(Request::new(Command::WriteEeprom, addr, len).into())
             .await?;
         self.send(payload.into()).await
@@ -548,7 +821,14 @@ impl<P: Read + Write> Interface<P> {
     /// This resets the device's diagnostic access level.
     /// The interface must be unlocked again after this operation
     /// to perform further diagnostic commands.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::ReadOnly`] if the interface is in read-only mode.
     pub async fn jump_to_subroutine(&mut self, addr: u16) -> Result<(), P::Error> {
+        self.ensure_writable()?;
+        self.invalidate_read_cache();
+
         // Response is sent once subroutine returns
         self.send(Request::new(Command::JumpToSubroutine, addr, 0x00).into())
             .await?;
@@ -558,7 +838,13 @@ impl<P: Read + Write> Interface<P> {
     /// Halts the device's normal operation.
     ///
     /// Causes the device to enter an infinite loop.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::ReadOnly`] if the interface is in read-only mode.
     pub async fn halt(&mut self) -> Result<(), P::Error> {
+        self.ensure_writable()?;
+
         self.send(Request::new(Command::Halt, 0x0000, 0x00).into())
             .await
     }
@@ -571,7 +857,13 @@ impl<P: Read + Write> Interface<P> {
     ///
     /// Note that this does not change the baud rate of the current port instance.
     /// A new [`Interface`] must be created with a port configured for 2400 baud.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::ReadOnly`] if the interface is in read-only mode.
     pub async fn set_baud_rate_2400(&mut self) -> Result<(), P::Error> {
+        self.ensure_writable()?;
+
         self.send(Request::new(Command::SetBaudRate2400, 0x0000, 0x00).into())
             .await
     }
@@ -584,17 +876,132 @@ impl<P: Read + Write> Interface<P> {
     ///
     /// Note that this does not change the baud rate of the current port instance.
     /// A new [`Interface`] must be created with a port configured for 9600 baud.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::ReadOnly`] if the interface is in read-only mode.
     pub async fn set_baud_rate_9600(&mut self) -> Result<(), P::Error> {
+        self.ensure_writable()?;
+
         self.send(Request::new(Command::SetBaudRate9600, 0x0000, 0x00).into())
             .await
     }
 
+    /// Sends a raw request and receives the reply.
+    ///
+    /// Intended for experimenting with the protocol, e.g. to explore undocumented commands.
+    /// The request is split into chunks with an appended checksum like any other payload.
+    /// Four-byte requests consist of a command, a parameter and a length. Afterwards,
+    /// the number of bytes given by the length is received, unless the command writes data.
+    /// Other requests, such as the data following a write request, don't receive a reply.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidArgument`] if the request is empty.
+    /// - [`Error::ReadOnly`] if the interface is in read-only mode
+    ///   and the request isn't one of the commands allowed in that mode.
+    pub async fn raw_transaction(&mut self, req: &[u8]) -> Result<Vec<u8>, P::Error> {
+        let Some(&code) = req.first() else {
+            return Err(Error::InvalidArgument);
+        };
+        let cmd = Command::from_repr(code);
+        let reads = matches!(
+            cmd,
+            Some(
+                Command::Lock
+                    | Command::QuerySoftwareId
+                    | Command::UnlockReadAccess
+                    | Command::ReadMemory
+                    | Command::ReadEeprom
+            )
+        );
+
+        if !(reads && req.len() == 4) {
+            self.ensure_writable()?;
+            self.invalidate_read_cache();
+        }
+
+        let reply_len = match *req {
+            [_, _, _, len] if !matches!(cmd, Some(Command::WriteMemory | Command::WriteEeprom)) => {
+                usize::from(len)
+            }
+            _ => 0,
+        };
+        let mut reply = vec![0x00; reply_len];
+
+        self.send_bytes(req).await?;
+
+        if reply_len > 0 {
+            self.receive_bytes(&mut reply).await?;
+        }
+
+        Ok(reply)
+    }
+
+    /// Returns [`Error::ReadOnly`] if the interface is in read-only mode.
+    fn ensure_writable(&self) -> Result<(), P::Error> {
+        if self.read_only {
+            Err(Error::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Sends a payload to the port.
     ///
     /// The payload is split into chunks with an appended checksum.
     /// Chunks are sent sequentially, verifying the response code for every transmission.
     async fn send<const N: usize>(&mut self, payload: Payload<N>) -> Result<(), P::Error> {
-        for chunk in payload.0.chunks(4) {
+        self.send_bytes(&payload.0).await
+    }
+
+    async fn receive<const N: usize>(&mut self) -> Result<Payload<N>, P::Error> {
+        let mut payload = Payload([0x00; N]);
+
+        self.receive_bytes(&mut payload.0).await?;
+
+        Ok(payload)
+    }
+
+    async fn send_bytes(&mut self, data: &[u8]) -> Result<(), P::Error> {
+        self.begin_transfer();
+
+        let res = self.send_chunks(data).await;
+
+        self.transfer_pending = false;
+
+        res
+    }
+
+    async fn receive_bytes(&mut self, buf: &mut [u8]) -> Result<(), P::Error> {
+        self.begin_transfer();
+
+        let res = self.receive_chunks(buf).await;
+
+        self.transfer_pending = false;
+
+        if let (Ok(()), Some(capture)) = (&res, &mut self.capture) {
+            capture.extend_from_slice(buf);
+        }
+
+        res
+    }
+
+    /// Marks the start of a transfer, counting the previous one as timed out
+    /// if it never completed.
+    ///
+    /// Futures of abandoned transfers are simply dropped, e.g. by a timeout,
+    /// so this is the only place where they can be detected.
+    fn begin_transfer(&mut self) {
+        if self.transfer_pending {
+            self.stats.timeouts += 1;
+        }
+
+        self.transfer_pending = true;
+    }
+
+    async fn send_chunks(&mut self, data: &[u8]) -> Result<(), P::Error> {
+        for chunk in data.chunks(4) {
             let checksum = compute_checksum(chunk);
             let mut resp = [0xff];
 
@@ -604,48 +1011,59 @@ impl<P: Read + Write> Interface<P> {
 
             match ResponseCode::from_repr(resp[0]) {
                 Some(ResponseCode::Success) => Ok(()),
-                Some(ResponseCode::IncorrectChecksum) => Err(Error::IncorrectChecksum),
+                Some(ResponseCode::IncorrectChecksum) => {
+                    self.stats.checksum_errors += 1;
+
+                    Err(Error::IncorrectChecksum)
+                }
                 Some(ResponseCode::InvalidCommand) => Err(Error::InvalidCommand),
                 None => Err(Error::UnknownResponseCode),
             }?;
 
-            if self.send_dummy_bytes {
-                self.write(&[0x00]).await?;
-            }
+            self.stats.frames_ok += 1;
+            self.write_dummy_bytes(self.protocol.dummy_bytes_after_send())
+                .await?;
         }
 
         Ok(())
     }
 
-    /// Receives a payload from the port.
-    ///
-    /// Chunks of the payload are read and their checksums verified.
-    /// A response code is sent for every received chunk.
-    async fn receive<const N: usize>(&mut self) -> Result<Payload<N>, P::Error> {
-        let mut payload = Payload([0x00; N]);
-
-        for chunk in payload.0.chunks_mut(4) {
+    async fn receive_chunks(&mut self, buf: &mut [u8]) -> Result<(), P::Error> {
+        for chunk in buf.chunks_mut(4) {
             let mut checksum = [0x00];
 
             self.read(chunk).await?;
             self.read(&mut checksum).await?;
 
-            if checksum[0] != compute_checksum(chunk) {
-                return Err(Error::IncorrectChecksum);
-            }
+            let expected = compute_checksum(chunk);
 
-            if self.send_dummy_bytes {
-                for _ in 0..=chunk.len() {
-                    self.write(&[0x00]).await?;
-                }
+            if checksum[0] != expected {
+                self.stats.checksum_errors += 1;
+
+                return Err(Error::ChecksumMismatch {
+                    expected,
+                    actual: checksum[0],
+                });
             }
 
+            self.stats.frames_ok += 1;
+            self.write_dummy_bytes(self.protocol.dummy_bytes_after_receive(chunk.len()))
+                .await?;
+
             // Acknowledge reception of chunk
             // Sending other response codes here aborts the transfer
             self.write(&[ResponseCode::Success as u8]).await?;
         }
 
-        Ok(payload)
+        Ok(())
+    }
+
+    async fn write_dummy_bytes(&mut self, count: usize) -> Result<(), P::Error> {
+        for _ in 0..count {
+            self.write(&[0x00]).await?;
+        }
+
+        Ok(())
     }
 
     /// Reads data from the port into the provided buffer.
@@ -653,12 +1071,21 @@ impl<P: Read + Write> Interface<P> {
         self.port.read_exact(buf).await?;
         debug!("Read from port: {buf:02x?}");
 
+        if let Some(observer) = &mut self.observer {
+            observer.on_rx(buf);
+        }
+
         Ok(())
     }
 
     /// Writes the provided buffer to the port.
     async fn write(&mut self, buf: &[u8]) -> Result<(), P::Error> {
         debug!("Write to port: {buf:02x?}");
+
+        if let Some(observer) = &mut self.observer {
+            observer.on_tx(buf);
+        }
+
         self.port.write_all(buf).await?;
 
         Ok(())
@@ -667,15 +1094,70 @@ impl<P: Read + Write> Interface<P> {
 
 #[cfg(test)]
 mod tests {
+    extern crate std;
+
     use super::*;
-    use alloc::collections::vec_deque::VecDeque;
+    use alloc::{
+        collections::vec_deque::VecDeque,
+        string::{String, ToString},
+    };
     use core::convert::Infallible;
+    use std::sync::{Mutex, OnceLock};
+
+    /// Logger forwarding to `env_logger`, keeping warnings for inspection by tests.
+    struct TestLogger {
+        inner: env_logger::Logger,
+        warnings: Mutex<Vec<String>>,
+    }
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            self.inner.enabled(metadata)
+        }
+
+        fn log(&self, record: &log::Record) {
+            if record.level() <= log::Level::Warn {
+                self.warnings
+                    .lock()
+                    .expect("warnings should be accessible")
+                    .push(record.args().to_string());
+            }
+
+            self.inner.log(record);
+        }
+
+        fn flush(&self) {
+            self.inner.flush();
+        }
+    }
+
+    static LOGGER: OnceLock<TestLogger> = OnceLock::new();
 
     pub fn init_logger() {
-        let _ = env_logger::builder()
-            .filter_level(log::LevelFilter::max())
-            .is_test(true)
-            .try_init();
+        let logger = LOGGER.get_or_init(|| TestLogger {
+            inner: env_logger::builder()
+                .filter_level(log::LevelFilter::max())
+                .is_test(true)
+                .build(),
+            warnings: Mutex::default(),
+        });
+
+        if log::set_logger(logger).is_ok() {
+            log::set_max_level(log::LevelFilter::max());
+        }
+    }
+
+    /// Returns whether a warning containing the given text has been logged by any test.
+    #[cfg(feature = "mock")]
+    pub fn warning_logged(text: &str) -> bool {
+        LOGGER.get().is_some_and(|logger| {
+            logger
+                .warnings
+                .lock()
+                .expect("warnings should be accessible")
+                .iter()
+                .any(|warning| warning.contains(text))
+        })
     }
 
     #[tokio::test]
@@ -691,6 +1173,7 @@ mod tests {
         let data: [u8; 2] = intf.read_memory(0xabcd).await?;
 
         assert_eq!(id, 419, "software ID should be correct");
+        assert_eq!(intf.protocol(), Protocol::V2, "protocol should be correct");
         assert_eq!(
             deque,
             [
@@ -705,6 +1188,25 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn protocol_v2() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77]);
+        let mut intf = Interface::with_protocol(&mut deque, Protocol::V2);
+        let id = intf.query_software_id().await?;
+
+        assert_eq!(intf.protocol(), Protocol::V2, "protocol should be correct");
+        assert_eq!(id, 629, "software ID should be correct");
+        assert_eq!(
+            deque,
+            [0x11, 0x00, 0x00, 0x02, 0x13, 0x00, 0x00, 0x00, 0x00, 0x00],
+            "deque contents should be correct"
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn lock() -> Result<(), Infallible> {
         init_logger();
@@ -731,6 +1233,7 @@ mod tests {
         let mut intf = Interface::new(&mut deque);
         let id = intf.query_software_id().await?;
 
+        assert_eq!(intf.protocol(), Protocol::V1, "protocol should be correct");
         assert_eq!(id, 629, "software ID should be correct");
         assert_eq!(
             deque,
@@ -741,6 +1244,53 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn observer() -> Result<(), Infallible> {
+        use alloc::{rc::Rc, vec::Vec};
+        use core::cell::RefCell;
+
+        /// Frames with their direction, `true` for transmitted ones
+        type Frames = Vec<(bool, Vec<u8>)>;
+
+        #[derive(Default)]
+        struct Recorder(Rc<RefCell<Frames>>);
+
+        impl FrameObserver for Recorder {
+            fn on_tx(&mut self, frame: &[u8]) {
+                self.0.borrow_mut().push((true, frame.to_vec()));
+            }
+
+            fn on_rx(&mut self, frame: &[u8]) {
+                self.0.borrow_mut().push((false, frame.to_vec()));
+            }
+        }
+
+        init_logger();
+
+        let recorder = Recorder::default();
+        let frames = recorder.0.clone();
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77]);
+        let mut intf = Interface::new(&mut deque);
+
+        intf.set_observer(Some(Box::new(recorder)));
+        intf.query_software_id().await?;
+
+        assert_eq!(
+            *frames.borrow(),
+            [
+                (true, alloc::vec![0x11, 0x00, 0x00, 0x02]),
+                (true, alloc::vec![0x13]),
+                (false, alloc::vec![0x00]),
+                (false, alloc::vec![0x75, 0x02]),
+                (false, alloc::vec![0x77]),
+                (true, alloc::vec![0x00]),
+            ],
+            "observed frames should be correct"
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn unlock_read_access() -> Result<(), Infallible> {
         init_logger();
@@ -784,6 +1334,49 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn cached_read_memory() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([
+            0x00, 0x11, 0x22, 0x33, 0x44, 0xaa, 0xab, 0xcd, 0xef, 0x99, 0x00, 0xde, 0xad, 0x8b,
+        ]);
+        let mut intf = Interface::new(&mut deque);
+
+        intf.start_read_cache();
+        intf.start_capture();
+
+        let _: [u8; 10] = intf.read_memory(0xabcd).await?;
+        let word: u16 = intf.read_memory(0xabd0).await?;
+        let byte: u8 = intf.read_memory(0xabcd).await?;
+
+        assert_eq!(word, 0xab44, "word should be read from cache");
+        assert_eq!(byte, 0x11, "byte should be read from cache");
+        assert_eq!(
+            intf.finish_capture()[10..],
+            [0x44, 0xab, 0x11],
+            "cached payloads should be captured"
+        );
+        assert!(
+            intf.cached_read::<4>(0xabd5).is_none(),
+            "memory beyond read should not be cached"
+        );
+
+        intf.stop_read_cache();
+
+        assert!(
+            intf.cached_read::<1>(0xabcd).is_none(),
+            "cache should be discarded"
+        );
+        assert_eq!(
+            deque,
+            [0x30, 0xcd, 0xab, 0x0a, 0xb2, 0x00, 0x00, 0x00],
+            "memory should be read once"
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn read_eeprom() -> Result<(), Infallible> {
         init_logger();
@@ -949,6 +1542,64 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn raw_transaction() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00]);
+        let mut intf = Interface::new(&mut deque);
+        let reply = intf.raw_transaction(&[0x11, 0x00, 0x00, 0x02]).await?;
+
+        assert_eq!(reply, [0x75, 0x02], "reply should be correct");
+
+        // Data following a write request isn't answered with a payload
+        let reply = intf.raw_transaction(&[0x11]).await?;
+
+        assert!(reply.is_empty(), "reply should be empty");
+        assert_eq!(
+            deque,
+            [0x11, 0x00, 0x00, 0x02, 0x13, 0x00, 0x11, 0x11],
+            "deque contents should be correct"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn raw_transaction_read_only() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([0x00, 0x11, 0x11]);
+        let mut intf = Interface::new(&mut deque);
+
+        intf.set_read_only(true);
+
+        for req in [&[0x40, 0xcd, 0xab, 0x01][..], &[0x11]] {
+            assert_eq!(
+                intf.raw_transaction(req).await.unwrap_err(),
+                Error::ReadOnly,
+                "result should be read-only error"
+            );
+        }
+
+        assert_eq!(
+            intf.raw_transaction(&[]).await.unwrap_err(),
+            Error::InvalidArgument,
+            "result should be invalid argument error"
+        );
+
+        let reply = intf.raw_transaction(&[0x30, 0xcd, 0xab, 0x01]).await?;
+
+        assert_eq!(reply, [0x11], "reply should be correct");
+        assert_eq!(
+            deque,
+            [0x30, 0xcd, 0xab, 0x01, 0xa9, 0x00],
+            "deque contents should be correct"
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn error_invalid_argument() -> Result<(), Infallible> {
         init_logger();
@@ -990,6 +1641,44 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn error_read_only() -> Result<(), Infallible> {
+        init_logger();
+
+        let mut deque = VecDeque::from([0x00, 0x11, 0x11]);
+        let mut intf = Interface::new(&mut deque);
+
+        intf.set_read_only(true);
+
+        let res = intf.write_memory(0xabcd, 0x11u8).await;
+
+        assert_eq!(
+            res.unwrap_err(),
+            Error::ReadOnly,
+            "result should be read-only error"
+        );
+
+        let res = intf.halt().await;
+
+        assert_eq!(
+            res.unwrap_err(),
+            Error::ReadOnly,
+            "result should be read-only error"
+        );
+
+        // Reads are still allowed, nothing has been written before
+        let data: u8 = intf.read_memory(0xabcd).await?;
+
+        assert_eq!(data, 0x11, "memory contents should be correct");
+        assert_eq!(
+            deque,
+            [0x30, 0xcd, 0xab, 0x01, 0xa9, 0x00],
+            "deque contents should be correct"
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn error_incorrect_checksum() -> Result<(), Infallible> {
         init_logger();
@@ -1008,8 +1697,78 @@ mod tests {
 
         assert_eq!(
             res.unwrap_err(),
-            Error::IncorrectChecksum,
-            "result should be incorrect checksum error"
+            Error::ChecksumMismatch {
+                expected: 0x11,
+                actual: 0xff
+            },
+            "result should be checksum mismatch error"
+        );
+        assert_eq!(
+            intf.stats(),
+            TransportStats {
+                frames_ok: 1,
+                checksum_errors: 2,
+                timeouts: 0,
+            },
+            "stats should be correct"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stats_count_abandoned_transfers() -> Result<(), Infallible> {
+        /// Port that never responds while it has no data to return
+        struct StallPort(VecDeque<u8>);
+
+        impl embedded_io_async::ErrorType for StallPort {
+            type Error = Infallible;
+        }
+
+        impl Read for StallPort {
+            async fn read(&mut self, buf: &mut [u8]) -> core::result::Result<usize, Infallible> {
+                if self.0.is_empty() {
+                    core::future::pending::<()>().await;
+                }
+
+                self.0.read(buf).await
+            }
+        }
+
+        // Written data is discarded
+        #[allow(clippy::unused_async_trait_impl)]
+        impl Write for StallPort {
+            async fn write(&mut self, buf: &[u8]) -> core::result::Result<usize, Infallible> {
+                Ok(buf.len())
+            }
+
+            async fn flush(&mut self) -> core::result::Result<(), Infallible> {
+                Ok(())
+            }
+        }
+
+        init_logger();
+
+        let mut intf = Interface::new(StallPort(VecDeque::new()));
+        let res = tokio::time::timeout(
+            core::time::Duration::from_millis(10),
+            intf.query_software_id(),
+        )
+        .await;
+
+        assert!(res.is_err(), "query should time out");
+
+        intf.port.0.push_back(0x00);
+        intf.lock().await?;
+
+        assert_eq!(
+            intf.stats(),
+            TransportStats {
+                frames_ok: 1,
+                checksum_errors: 0,
+                timeouts: 1,
+            },
+            "stats should be correct"
         );
 
         Ok(())
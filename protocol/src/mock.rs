@@ -0,0 +1,461 @@
+//! Emulated diagnostic port for development without an appliance.
+//!
+//! A [`MockPort`] implements [`Read`] and [`Write`] and answers requests
+//! like the diagnostic interface of a real device, serving memory and EEPROM
+//! contents from a table. The regular device implementations are used to
+//! decode these contents, so queried values are deterministic and match what
+//! a real device with the same contents would report.
+//!
+//! Access keys are not checked and only the default [`Protocol::V1`](crate::Protocol::V1)
+//! framing is supported.
+//!
+//! # Examples
+//!
+//! ```
+//! use freemdu::mock::MockPort;
+//!
+//! # async fn example() -> freemdu::device::Result<(), core::convert::Infallible> {
+//! let mut dev = freemdu::device::connect(MockPort::washing_machine()).await?;
+//!
+//! for prop in dev.properties() {
+//!     let val = dev.query_property(prop).await?;
+//!
+//!     println!("{}: {val:?}", prop.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Command, ResponseCode, compute_checksum};
+use alloc::{
+    collections::{btree_map::BTreeMap, vec_deque::VecDeque},
+    vec::Vec,
+};
+use core::convert::Infallible;
+use embedded_io_async::{ErrorType, Read, Write};
+
+/// Size of the chunks that payloads are split into.
+const CHUNK_LEN: usize = 4;
+
+/// State of a transfer between the host and the emulated device.
+#[derive(Debug)]
+enum State {
+    /// Waiting for a request.
+    Idle,
+    /// Waiting for the host to acknowledge the given number of sent chunks.
+    Acknowledge(usize),
+    /// Receiving the payload of a write request.
+    Payload {
+        addr: u16,
+        len: usize,
+        eeprom: bool,
+        data: Vec<u8>,
+    },
+}
+
+/// Port emulating the diagnostic interface of a device.
+///
+/// Memory and EEPROM locations that haven't been set read as `0x00`.
+/// Write requests from the host update the stored contents.
+#[derive(Debug)]
+pub struct MockPort {
+    software_id: u16,
+    memory: BTreeMap<u16, u8>,
+    /// EEPROM contents by byte address, the device addresses 16-bit words
+    eeprom: BTreeMap<u32, u8>,
+    state: State,
+    /// Data written by the host that hasn't been processed yet
+    input: Vec<u8>,
+    /// Data waiting to be read by the host
+    output: VecDeque<u8>,
+}
+
+impl MockPort {
+    /// Constructs a new port emulating a device with the specified software ID.
+    #[must_use]
+    pub fn new(software_id: u16) -> Self {
+        Self {
+            software_id,
+            memory: BTreeMap::new(),
+            eeprom: BTreeMap::new(),
+            state: State::Idle,
+            input: Vec::new(),
+            output: VecDeque::new(),
+        }
+    }
+
+    /// Constructs a port emulating a washing machine with software ID 629.
+    ///
+    /// The machine is running a _Cottons 60 °C_ program in the main wash phase.
+    #[must_use]
+    pub fn washing_machine() -> Self {
+        Self::new(629)
+            // Serial number, serial number index and model number
+            .with_eeprom(0x01ba, b"\x009314023903W2446          ")
+            // Board number
+            .with_eeprom(0x01ca, b"56554705")
+            // ROM code
+            .with_memory(0xffdb, &[0x04])
+            // Operating time of 123 hours and 30 minutes
+            .with_memory(0x0052, &[0x1e, 0x23, 0x01, 0x00])
+            // Program locked state
+            .with_memory(0x0045, &[0x00])
+            // Load level
+            .with_memory(0x004a, &[0x03])
+            // Faults
+            .with_memory(0x004e, &[0x00, 0x00])
+            // Program spin setting and options
+            .with_memory(0x0057, &[0x05, 0x20])
+            // Active actuators and water level
+            .with_memory(0x007d, &[0x20, 0x04, 0x5f, 0x6e])
+            // Display contents
+            .with_memory(0x009e, &[0x21, 0x05, 0x10, 0x00])
+            // Program phase
+            .with_memory(0x00a2, &[0x04])
+            // Program selector
+            .with_memory(0x00b5, &[0x03])
+            // Operating mode
+            .with_memory(0x00cd, &[0x02])
+            // Program type and temperature
+            .with_memory(0x00de, &[0x01, 0x3c])
+            // Program state
+            .with_memory(0x00e7, &[0x05])
+            // Target and current temperature
+            .with_memory(0x0136, &[0x3c, 0x2a])
+            // Target and current tachometer speed
+            .with_memory(0x01a4, &[0x37, 0x00, 0x34, 0x00])
+            // NTC thermistor ADC value
+            .with_memory(0x01bf, &[0x80])
+            // Motor PWM duty cycle
+            .with_memory(0x02b9, &[0x80])
+            // Pressure sensor value
+            .with_memory(0x02be, &[0x5a])
+    }
+
+    /// Sets the memory contents starting at the specified address.
+    #[must_use]
+    pub fn with_memory(mut self, addr: u16, data: &[u8]) -> Self {
+        self.set_memory(addr, data);
+        self
+    }
+
+    /// Sets the EEPROM contents starting at the specified word address.
+    #[must_use]
+    pub fn with_eeprom(mut self, addr: u16, data: &[u8]) -> Self {
+        self.set_eeprom(addr, data);
+        self
+    }
+
+    /// Returns the memory contents at the specified address.
+    #[must_use]
+    pub fn memory(&self, addr: u16) -> u8 {
+        self.memory.get(&addr).copied().unwrap_or_default()
+    }
+
+    /// Returns the EEPROM contents at the specified word address.
+    #[must_use]
+    pub fn eeprom(&self, addr: u16) -> u16 {
+        let byte = |addr| self.eeprom.get(&addr).copied().unwrap_or_default();
+        let addr = u32::from(addr) * 2;
+
+        u16::from_le_bytes([byte(addr), byte(addr + 1)])
+    }
+
+    fn set_memory(&mut self, addr: u16, data: &[u8]) {
+        for (addr, &val) in (addr..=u16::MAX).zip(data) {
+            self.memory.insert(addr, val);
+        }
+    }
+
+    fn set_eeprom(&mut self, addr: u16, data: &[u8]) {
+        for (addr, &val) in (u32::from(addr) * 2..).zip(data) {
+            self.eeprom.insert(addr, val);
+        }
+    }
+
+    /// Processes the data written by the host, as far as possible.
+    fn process(&mut self) {
+        loop {
+            let consumed = match self.state {
+                State::Idle => self.process_request(),
+                State::Acknowledge(_) => self.process_acknowledgement(),
+                State::Payload { .. } => self.process_payload(),
+            };
+
+            if consumed == 0 {
+                return;
+            }
+
+            self.input.drain(..consumed);
+        }
+    }
+
+    /// Handles a request, returning the number of bytes it used.
+    fn process_request(&mut self) -> usize {
+        let Some((&[cmd, lo, hi, len], &[checksum, ..])) =
+            self.input.split_first_chunk::<CHUNK_LEN>()
+        else {
+            return 0;
+        };
+
+        if checksum != compute_checksum(&[cmd, lo, hi, len]) {
+            self.output.push_back(ResponseCode::IncorrectChecksum as u8);
+
+            return CHUNK_LEN + 1;
+        }
+
+        let Some(cmd) = Command::from_repr(cmd) else {
+            self.output.push_back(ResponseCode::InvalidCommand as u8);
+
+            return CHUNK_LEN + 1;
+        };
+        let param = u16::from_le_bytes([lo, hi]);
+
+        self.output.push_back(ResponseCode::Success as u8);
+
+        match cmd {
+            Command::QuerySoftwareId => self.send(&self.software_id.to_le_bytes()),
+            Command::ReadMemory => {
+                let data: Vec<_> = (0..u16::from(len))
+                    .map(|i| self.memory(param.wrapping_add(i)))
+                    .collect();
+
+                self.send(&data);
+            }
+            Command::ReadEeprom => {
+                let data: Vec<_> = (0..u16::from(len) / 2)
+                    .flat_map(|i| self.eeprom(param.wrapping_add(i)).to_le_bytes())
+                    .collect();
+
+                self.send(&data);
+            }
+            Command::WriteMemory | Command::WriteEeprom if len > 0 => {
+                self.state = State::Payload {
+                    addr: param,
+                    len: len.into(),
+                    eeprom: matches!(cmd, Command::WriteEeprom),
+                    data: Vec::new(),
+                };
+            }
+            // Sent once the subroutine returns
+            Command::JumpToSubroutine => self.output.push_back(ResponseCode::Success as u8),
+            _ => {}
+        }
+
+        CHUNK_LEN + 1
+    }
+
+    /// Handles the acknowledgement of a sent chunk, returning the number of bytes it used.
+    fn process_acknowledgement(&mut self) -> usize {
+        let (Some(&code), State::Acknowledge(count)) = (self.input.first(), &mut self.state) else {
+            return 0;
+        };
+
+        *count -= 1;
+
+        // Other response codes abort the transfer
+        if *count == 0 || code != ResponseCode::Success as u8 {
+            self.state = State::Idle;
+        }
+
+        1
+    }
+
+    /// Handles a chunk of a write payload, returning the number of bytes it used.
+    fn process_payload(&mut self) -> usize {
+        let State::Payload {
+            addr,
+            len,
+            eeprom,
+            data,
+        } = &mut self.state
+        else {
+            return 0;
+        };
+
+        let chunk_len = (*len - data.len()).min(CHUNK_LEN);
+        let Some((chunk, &[checksum, ..])) = self.input.split_at_checked(chunk_len) else {
+            return 0;
+        };
+
+        if checksum != compute_checksum(chunk) {
+            self.output.push_back(ResponseCode::IncorrectChecksum as u8);
+
+            return chunk_len + 1;
+        }
+
+        data.extend_from_slice(chunk);
+        self.output.push_back(ResponseCode::Success as u8);
+
+        if data.len() == *len {
+            let (addr, eeprom, data) = (*addr, *eeprom, core::mem::take(data));
+
+            self.state = State::Idle;
+
+            if eeprom {
+                self.set_eeprom(addr, &data);
+            } else {
+                self.set_memory(addr, &data);
+            }
+        }
+
+        chunk_len + 1
+    }
+
+    /// Queues the response payload, split into chunks followed by their checksum.
+    fn send(&mut self, data: &[u8]) {
+        let chunks = data.chunks(CHUNK_LEN);
+
+        if chunks.len() > 0 {
+            self.state = State::Acknowledge(chunks.len());
+        }
+
+        for chunk in chunks {
+            self.output.extend(chunk);
+            self.output.push_back(compute_checksum(chunk));
+        }
+    }
+}
+
+impl ErrorType for MockPort {
+    type Error = Infallible;
+}
+
+// The emulated device responds immediately
+#[allow(clippy::unused_async_trait_impl)]
+impl Read for MockPort {
+    /// Reads the pending response data.
+    ///
+    /// Returns `0` if the emulated device has nothing to send,
+    /// which the host interprets as an unexpected end-of-file.
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let len = buf.len().min(self.output.len());
+
+        for (dst, src) in buf.iter_mut().zip(self.output.drain(..len)) {
+            *dst = src;
+        }
+
+        Ok(len)
+    }
+}
+
+#[allow(clippy::unused_async_trait_impl)]
+impl Write for MockPort {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.input.extend_from_slice(buf);
+        self.process();
+
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{self, PropertyId, Value};
+    use crate::tests::init_logger;
+    use alloc::string::ToString;
+    use core::time::Duration;
+
+    #[tokio::test]
+    async fn washing_machine() -> device::Result<(), Infallible> {
+        init_logger();
+
+        let mut dev = device::connect(MockPort::washing_machine()).await?;
+        let snapshot = dev.snapshot().await?;
+        let value = |id: &str| {
+            snapshot
+                .properties
+                .iter()
+                .find(|prop| prop.id == id)
+                .map(|prop| &prop.value)
+        };
+
+        assert_eq!(snapshot.software_id, 629, "software ID should be correct");
+        assert_eq!(
+            value("serial_number"),
+            Some(&Value::String("93140239".to_string())),
+            "serial number should be correct"
+        );
+        assert_eq!(
+            value("serial_number_index"),
+            Some(&Value::String("03".to_string())),
+            "serial number index should be correct"
+        );
+        assert_eq!(
+            value("model_number"),
+            Some(&Value::String("W2446".to_string())),
+            "model number should be correct"
+        );
+        assert_eq!(
+            value("operating_time"),
+            Some(&Value::Duration(Duration::from_secs(123 * 3600 + 30 * 60))),
+            "operating time should be correct"
+        );
+        assert_eq!(
+            value("display_contents"),
+            Some(&Value::String("1.25".to_string())),
+            "display contents should be correct"
+        );
+        assert_eq!(
+            value("temperature"),
+            Some(&Value::Sensor(42, 60)),
+            "temperature should be correct"
+        );
+        assert_eq!(
+            snapshot.active_faults().count(),
+            0,
+            "no faults should be active"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_memory() -> device::Result<(), Infallible> {
+        init_logger();
+
+        let mut dev = device::connect(MockPort::washing_machine()).await?;
+        let prop = dev
+            .property(PropertyId("program_spin_setting"))
+            .expect("property should exist");
+        let start = &dev.actions()[2];
+
+        dev.set_property(prop, Value::String("SpinMed".to_string()))
+            .await?;
+
+        assert_eq!(
+            dev.query_property(prop).await?,
+            Value::String("SpinMed".to_string()),
+            "spin setting should be correct"
+        );
+        assert!(
+            matches!(
+                dev.trigger_action(start, None).await,
+                Err(device::Error::InvalidState)
+            ),
+            "result should be invalid state error"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn invalid_command() -> crate::Result<(), Infallible> {
+        init_logger();
+
+        let mut port = MockPort::new(629);
+        let mut resp = [0xff];
+
+        port.write_all(&[0x99, 0x00, 0x00, 0x00, 0x99]).await?;
+        port.read_exact(&mut resp).await?;
+
+        assert_eq!(resp, [0x02], "response should be invalid command");
+
+        Ok(())
+    }
+}
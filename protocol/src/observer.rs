@@ -0,0 +1,64 @@
+//! Observation of the raw data exchanged with a device.
+//!
+//! A [`FrameObserver`] can be installed on an [`Interface`](crate::Interface)
+//! using [`Interface::set_observer`](crate::Interface::set_observer)
+//! or on a device using [`Device::set_observer`](crate::device::Device::set_observer).
+//! It is notified of every frame written to or read from the port,
+//! which helps with debugging devices that respond unexpectedly.
+
+use core::time::Duration;
+use log::trace;
+
+/// Callbacks invoked for every frame exchanged with the device.
+///
+/// A frame is the data of a single write or read operation on the port,
+/// e.g. a chunk of a payload, its checksum or a response code.
+pub trait FrameObserver {
+    /// Called before a frame is written to the port.
+    fn on_tx(&mut self, frame: &[u8]);
+
+    /// Called after a frame has been read from the port.
+    fn on_rx(&mut self, frame: &[u8]);
+}
+
+/// Frame observer that logs each frame as hex at trace level.
+///
+/// Every log line is prefixed with a timestamp obtained from the provided clock,
+/// since the crate itself has no notion of time.
+///
+/// # Examples
+///
+/// ```no_run
+/// use freemdu::observer::HexLogObserver;
+/// use std::time::Instant;
+///
+/// # async fn example() -> freemdu::device::Result<(), freemdu::serial::PortError> {
+/// let mut port = freemdu::serial::open("/dev/ttyACM0")?;
+/// let mut dev = freemdu::device::connect(&mut port).await?;
+/// let start = Instant::now();
+///
+/// dev.set_observer(Box::new(HexLogObserver::new(move || start.elapsed())));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct HexLogObserver<C> {
+    clock: C,
+}
+
+impl<C: FnMut() -> Duration> HexLogObserver<C> {
+    /// Constructs a new observer using the specified clock for timestamps.
+    pub fn new(clock: C) -> Self {
+        Self { clock }
+    }
+}
+
+impl<C: FnMut() -> Duration> FrameObserver for HexLogObserver<C> {
+    fn on_tx(&mut self, frame: &[u8]) {
+        trace!("[{:>12.3?}] TX {frame:02x?}", (self.clock)());
+    }
+
+    fn on_rx(&mut self, frame: &[u8]) {
+        trace!("[{:>12.3?}] RX {frame:02x?}", (self.clock)());
+    }
+}
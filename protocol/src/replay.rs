@@ -0,0 +1,570 @@
+//! Recording and replaying sessions for offline analysis.
+//!
+//! A [`RecordingObserver`] writes every frame exchanged with a device to a file,
+//! one line per frame with a timestamp relative to the start of the recording.
+//! The recording can later be replayed using a [`ReplayPort`],
+//! which answers requests with the responses recorded for them,
+//! so that the regular device implementations can be used without the appliance.
+//!
+//! Recordings are plain text, e.g. `1520 TX 11 00 00 02` for a frame sent
+//! 1.52 ms after the recording started. Empty lines and lines starting with `#` are ignored.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use freemdu::replay::{RecordingObserver, ReplayEnd};
+//! use std::{fs::File, time::Instant};
+//!
+//! # async fn example() -> freemdu::device::Result<(), freemdu::serial::PortError> {
+//! let port = freemdu::serial::open("/dev/ttyACM0")?;
+//! let mut intf = freemdu::Interface::new(port);
+//! let file = File::create("session.freemdu").map_err(freemdu::Error::Io)?;
+//!
+//! intf.set_observer(Some(Box::new(RecordingObserver::new(file, Instant::now()))));
+//!
+//! let mut dev = freemdu::device::connect_interface(intf).await?;
+//!
+//! // Later, without the appliance
+//! let mut dev = freemdu::device::connect_replay("session.freemdu", ReplayEnd::Loop).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+extern crate std;
+
+use crate::{Command, observer::FrameObserver};
+use alloc::{
+    collections::{btree_map::BTreeMap, vec_deque::VecDeque},
+    vec::Vec,
+};
+use core::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+    time::Duration,
+};
+use embedded_io_async::{ErrorType, Read, Write};
+use std::{
+    io::{self, ErrorKind},
+    path::Path,
+    time::Instant,
+};
+
+/// Direction of a recorded frame.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    /// Sent to the device.
+    Tx,
+    /// Received from the device.
+    Rx,
+}
+
+/// A frame exchanged with the device, see [`FrameObserver`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Frame {
+    /// Time since the start of the recording.
+    pub time: Duration,
+    /// Whether the frame was sent or received.
+    pub direction: Direction,
+    /// Data of the frame.
+    pub data: Vec<u8>,
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let direction = match self.direction {
+            Direction::Tx => "TX",
+            Direction::Rx => "RX",
+        };
+
+        write!(f, "{} {direction}", self.time.as_micros())?;
+
+        for byte in &self.data {
+            write!(f, " {byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Error returned when parsing an invalid [`Frame`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParseFrameError;
+
+impl Display for ParseFrameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid frame")
+    }
+}
+
+impl core::error::Error for ParseFrameError {}
+
+impl FromStr for Frame {
+    type Err = ParseFrameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let time = parts
+            .next()
+            .and_then(|time| time.parse().ok())
+            .map(Duration::from_micros)
+            .ok_or(ParseFrameError)?;
+        let direction = match parts.next() {
+            Some("TX") => Direction::Tx,
+            Some("RX") => Direction::Rx,
+            _ => return Err(ParseFrameError),
+        };
+        let data = parts
+            .map(|byte| u8::from_str_radix(byte, 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ParseFrameError)?;
+
+        if data.is_empty() {
+            return Err(ParseFrameError);
+        }
+
+        Ok(Self {
+            time,
+            direction,
+            data,
+        })
+    }
+}
+
+/// Frame observer that writes each frame to a recording.
+///
+/// Timestamps are relative to the given start, so that recordings of
+/// multiple connections, e.g. after reconnecting, can share a timeline.
+/// If writing fails, a warning is logged and recording stops.
+#[derive(Debug)]
+pub struct RecordingObserver<W> {
+    writer: Option<W>,
+    start: Instant,
+}
+
+impl<W: io::Write> RecordingObserver<W> {
+    /// Constructs a new observer writing to the specified writer.
+    pub fn new(writer: W, start: Instant) -> Self {
+        Self {
+            writer: Some(writer),
+            start,
+        }
+    }
+
+    fn record(&mut self, direction: Direction, data: &[u8]) {
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        let frame = Frame {
+            time: self.start.elapsed(),
+            direction,
+            data: data.to_vec(),
+        };
+
+        if let Err(e) = writeln!(writer, "{frame}") {
+            log::warn!("Recording stopped: {e}");
+
+            self.writer = None;
+        }
+    }
+}
+
+impl<W: io::Write> FrameObserver for RecordingObserver<W> {
+    fn on_tx(&mut self, frame: &[u8]) {
+        self.record(Direction::Tx, frame);
+    }
+
+    fn on_rx(&mut self, frame: &[u8]) {
+        self.record(Direction::Rx, frame);
+    }
+}
+
+/// Behavior of a [`ReplayPort`] once the end of the recording is reached.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ReplayEnd {
+    /// Start over from the beginning.
+    #[default]
+    Loop,
+    /// Fail all further requests with [`ErrorKind::UnexpectedEof`].
+    Stop,
+}
+
+/// Port replaying a recorded session, implementing [`Read`] and [`Write`].
+///
+/// Requests are answered with the responses recorded for them, following
+/// the recorded cadence: each request is matched to its most recent recording
+/// before the time elapsed since the port was created, so values change
+/// just like they did during the recorded session.
+///
+/// Requests that were never recorded are not answered, like by a device
+/// that doesn't respond, so reading stalls until the caller times out.
+#[derive(Debug)]
+pub struct ReplayPort {
+    frames: Vec<Frame>,
+    /// Positions of the recorded requests by their data
+    requests: BTreeMap<Vec<u8>, Vec<usize>>,
+    end: ReplayEnd,
+    start: Instant,
+    /// Position of the next frame expected to be exchanged
+    cursor: usize,
+    /// Set if the host sent something that wasn't recorded at this point
+    diverged: bool,
+    /// Data of the current frame waiting to be read by the host
+    output: VecDeque<u8>,
+}
+
+impl ReplayPort {
+    /// Constructs a new port replaying the specified frames, ordered by time.
+    #[must_use]
+    pub fn new(frames: Vec<Frame>, end: ReplayEnd) -> Self {
+        let mut requests: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        let mut unanswered = None;
+
+        // Requests that timed out, e.g. at the wrong baud rate, are never replayed
+        for (pos, frame) in frames.iter().enumerate() {
+            match frame.direction {
+                Direction::Tx if is_request(&frame.data) => unanswered = Some(pos),
+                Direction::Tx => {}
+                Direction::Rx => {
+                    if let Some(req) = unanswered.take() {
+                        requests
+                            .entry(frames[req].data.clone())
+                            .or_default()
+                            .push(req);
+                    }
+                }
+            }
+        }
+
+        Self {
+            frames,
+            requests,
+            end,
+            start: Instant::now(),
+            cursor: 0,
+            diverged: false,
+            output: VecDeque::new(),
+        }
+    }
+
+    /// Parses a recording and constructs a new port replaying it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorKind::InvalidData`] if a line doesn't contain a valid frame.
+    pub fn from_recording(recording: &str, end: ReplayEnd) -> io::Result<Self> {
+        let frames = recording
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|(idx, line)| {
+                line.parse().map_err(|e: ParseFrameError| {
+                    io::Error::new(
+                        ErrorKind::InvalidData,
+                        std::format!("line {}: {e}", idx + 1),
+                    )
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self::new(frames, end))
+    }
+
+    /// Reads a recording from a file and constructs a new port replaying it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, see also [`ReplayPort::from_recording`].
+    pub fn open(path: impl AsRef<Path>, end: ReplayEnd) -> io::Result<Self> {
+        Self::from_recording(&std::fs::read_to_string(path)?, end)
+    }
+
+    /// Returns the duration of the recording.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.frames
+            .last()
+            .map_or(Duration::ZERO, |frame| frame.time)
+    }
+
+    /// Returns the position in the recording that is currently replayed.
+    fn replay_time(&self) -> io::Result<Duration> {
+        let elapsed = self.start.elapsed();
+        let duration = self.duration();
+
+        match self.end {
+            ReplayEnd::Stop if elapsed > duration => {
+                Err(io::Error::new(ErrorKind::UnexpectedEof, "end of recording"))
+            }
+            ReplayEnd::Loop if !duration.is_zero() => {
+                let pos = elapsed.as_micros() % duration.as_micros();
+
+                Ok(Duration::from_micros(u64::try_from(pos).unwrap_or(0)))
+            }
+            _ => Ok(elapsed),
+        }
+    }
+
+    /// Continues the replay after the most recent recording of the request.
+    ///
+    /// Falls back to the next recording if the request wasn't recorded yet at this time.
+    fn seek_request(&mut self, req: &[u8]) -> io::Result<()> {
+        let now = self.replay_time()?;
+
+        self.output.clear();
+
+        let Some(positions) = self.requests.get(req) else {
+            self.diverged = true;
+            return Ok(());
+        };
+        let idx = positions.partition_point(|&pos| self.frames[pos].time <= now);
+
+        self.cursor = positions[idx.saturating_sub(1)] + 1;
+        self.diverged = false;
+
+        Ok(())
+    }
+}
+
+/// Returns whether the frame looks like the start of a request.
+///
+/// Payload chunks of write requests may look the same, in which case
+/// the replay continues after a recording of the same data.
+fn is_request(frame: &[u8]) -> bool {
+    frame.len() == 4 && Command::from_repr(frame[0]).is_some()
+}
+
+impl ErrorType for ReplayPort {
+    type Error = io::Error;
+}
+
+impl Read for ReplayPort {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.output.is_empty() {
+            match self.frames.get(self.cursor) {
+                Some(frame) if !self.diverged && frame.direction == Direction::Rx => {
+                    self.output.extend(&frame.data);
+                    self.cursor += 1;
+                }
+                // The recorded device didn't answer here
+                _ => core::future::pending().await,
+            }
+        }
+
+        let len = buf.len().min(self.output.len());
+
+        for (dst, src) in buf.iter_mut().zip(self.output.drain(..len)) {
+            *dst = src;
+        }
+
+        Ok(len)
+    }
+}
+
+// Writes are only matched against the recording
+#[allow(clippy::unused_async_trait_impl)]
+impl Write for ReplayPort {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let expected = self
+            .frames
+            .get(self.cursor)
+            .filter(|frame| frame.direction == Direction::Tx);
+
+        if is_request(buf) {
+            self.seek_request(buf)?;
+        } else if !self.diverged && expected.is_some_and(|frame| frame.data == buf) {
+            self.cursor += 1;
+        } else {
+            self.diverged = true;
+        }
+
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, Interface, tests::init_logger};
+    use alloc::{
+        boxed::Box,
+        rc::Rc,
+        string::{String, ToString},
+        vec,
+    };
+    use core::cell::RefCell;
+
+    /// Writer whose data remains accessible after it was moved into an observer
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Records two software ID queries, answered with 629 and 630
+    async fn record_queries() -> Vec<Frame> {
+        let buf = SharedBuffer::default();
+        let mut deque = VecDeque::from([0x00, 0x75, 0x02, 0x77, 0x00, 0x76, 0x02, 0x78]);
+        let mut intf = Interface::new(&mut deque);
+
+        intf.set_observer(Some(Box::new(RecordingObserver::new(
+            buf.clone(),
+            Instant::now(),
+        ))));
+
+        for _ in 0..2 {
+            intf.query_software_id()
+                .await
+                .expect("query should succeed");
+        }
+
+        String::from_utf8(buf.0.take())
+            .expect("recording should be text")
+            .lines()
+            .map(|line| line.parse().expect("frame should be valid"))
+            .collect()
+    }
+
+    #[test]
+    fn frame_format() {
+        let frame = Frame {
+            time: Duration::from_micros(1520),
+            direction: Direction::Tx,
+            data: vec![0x11, 0x00, 0x00, 0x02],
+        };
+
+        assert_eq!(
+            frame.to_string(),
+            "1520 TX 11 00 00 02",
+            "formatted frame should be correct"
+        );
+        assert_eq!(
+            "1520 TX 11 00 00 02".parse(),
+            Ok(frame),
+            "parsed frame should be correct"
+        );
+        assert_eq!(
+            "1520 TX".parse::<Frame>(),
+            Err(ParseFrameError),
+            "frame without data should be invalid"
+        );
+        assert!(
+            ReplayPort::from_recording("# Comment\n\n0 RX zz", ReplayEnd::Stop)
+                .is_err_and(
+                    |e| e.kind() == ErrorKind::InvalidData && e.to_string().starts_with("line 3")
+                ),
+            "invalid line should be reported"
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_follows_recorded_cadence() {
+        init_logger();
+
+        let mut frames = record_queries().await;
+
+        assert_eq!(frames.len(), 12, "all frames should be recorded");
+
+        // Move the second query to a later time and end the recording after it
+        for frame in &mut frames[6..] {
+            frame.time += Duration::from_secs(10);
+        }
+
+        if let Some(frame) = frames.last_mut() {
+            frame.time += Duration::from_secs(10);
+        }
+
+        let mut intf = Interface::new(ReplayPort::new(frames, ReplayEnd::Stop));
+
+        assert_eq!(
+            intf.query_software_id().await.ok(),
+            Some(629),
+            "first recorded response should be replayed"
+        );
+
+        intf.port_mut().start -= Duration::from_secs(11);
+
+        assert_eq!(
+            intf.query_software_id().await.ok(),
+            Some(630),
+            "later recorded response should be replayed"
+        );
+
+        intf.port_mut().start -= Duration::from_secs(60);
+
+        assert!(
+            matches!(
+                intf.query_software_id().await,
+                Err(Error::Io(e)) if e.kind() == ErrorKind::UnexpectedEof
+            ),
+            "replay should stop at end"
+        );
+    }
+
+    #[tokio::test]
+    async fn unanswered_request_skipped() {
+        init_logger();
+
+        let mut frames = vec![
+            // Timed out request
+            Frame {
+                time: Duration::ZERO,
+                direction: Direction::Tx,
+                data: vec![0x11, 0x00, 0x00, 0x02],
+            },
+        ];
+
+        frames.extend(record_queries().await.into_iter().map(|frame| Frame {
+            time: frame.time + Duration::from_secs(10),
+            ..frame
+        }));
+
+        let mut intf = Interface::new(ReplayPort::new(frames, ReplayEnd::Stop));
+
+        intf.port_mut().start -= Duration::from_secs(5);
+
+        let res = tokio::time::timeout(Duration::from_secs(1), intf.query_software_id()).await;
+
+        assert!(
+            matches!(res, Ok(Ok(629))),
+            "answered request should be replayed"
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_loops() {
+        init_logger();
+
+        let mut frames = record_queries().await;
+
+        for frame in &mut frames[6..] {
+            frame.time += Duration::from_secs(10);
+        }
+
+        let mut intf = Interface::new(ReplayPort::new(frames, ReplayEnd::Loop));
+        let duration = intf.port_mut().duration();
+
+        intf.port_mut().start -= duration + Duration::from_millis(5);
+
+        assert_eq!(
+            intf.query_software_id().await.ok(),
+            Some(629),
+            "replay should start over after end"
+        );
+    }
+}
@@ -1,34 +1,506 @@
 //! Native asynchronous serial port support for [`Interface`](crate::Interface).
 //!
 //! Uses the [`serial2-tokio`](https://crates.io/crates/serial2-tokio) crate.
+//! Devices behind a serial-to-TCP bridge can be reached using [`open_tcp`].
 
 extern crate std;
 
 use crate::Error;
+use core::time::Duration;
 use embedded_io_adapters::tokio_1::FromTokio;
-use embedded_io_async::ErrorType;
-use serial2_tokio::{Parity, SerialPort, Settings};
+use embedded_io_async::{ErrorType, Read, ReadExactError, Write};
+use serial2_tokio::{SerialPort, Settings};
+use tokio::net::{TcpStream, ToSocketAddrs};
 
-/// Serial port type implementing [`Read`](embedded_io_async::Read)
-/// and [`Write`](embedded_io_async::Write).
-pub type Port = FromTokio<SerialPort>;
+pub use serial2_tokio::{CharSize, Parity, StopBits};
 
 /// Port-specific error type to be used as `E` for the generic [`Error<E>`] type.
-pub type PortError = <Port as ErrorType>::Error;
+pub type PortError = std::io::Error;
+
+/// Baud rates supported by the diagnostic interface, starting with the default.
+///
+/// Devices always start at the default baud rate, but keep a baud rate
+/// configured via [`Interface::set_baud_rate_9600`](crate::Interface::set_baud_rate_9600)
+/// until they are power-cycled.
+pub const BAUD_RATES: [u32; 2] = [2400, 9600];
+
+/// Serial port configuration used by [`open_with`].
+///
+/// The default matches the settings expected by the diagnostic interface.
+/// Other values may be required for adapters with a non-standard clock.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SerialConfig {
+    /// Baud rate, see [`BAUD_RATES`].
+    pub baud_rate: u32,
+    /// Number of data bits per character.
+    pub char_size: CharSize,
+    /// Parity checking mode.
+    pub parity: Parity,
+    /// Number of stop bits.
+    pub stop_bits: StopBits,
+    /// Maximum time to wait for incoming data, or `None` to wait indefinitely.
+    ///
+    /// Reads that time out fail with [`std::io::ErrorKind::TimedOut`].
+    pub read_timeout: Option<Duration>,
+    /// Maximum gap between the bytes of a frame once its first byte has arrived,
+    /// or `None` to use [`read_timeout`](Self::read_timeout) for the whole frame.
+    ///
+    /// Some USB-serial adapters deliver a frame in several fragments.
+    /// The timeout has to cover the delay between them, which can be much longer
+    /// than the time it takes to transmit a byte. Too short values make frames
+    /// fail with [`std::io::ErrorKind::TimedOut`] although they would have arrived.
+    pub inter_byte_timeout: Option<Duration>,
+    /// Direction control of a half-duplex RS-485 transceiver via the RTS line,
+    /// or `None` if the adapter switches direction by itself.
+    ///
+    /// Most USB adapters switch automatically. Others have to be told when to transmit,
+    /// otherwise the reply of the device collides with the end of the request.
+    pub rs485: Option<Rs485Config>,
+}
+
+impl Default for SerialConfig {
+    fn default() -> Self {
+        Self {
+            baud_rate: BAUD_RATES[0],
+            char_size: CharSize::Bits8,
+            parity: Parity::Even,
+            stop_bits: StopBits::One,
+            read_timeout: None,
+            inter_byte_timeout: None,
+            rs485: None,
+        }
+    }
+}
+
+impl SerialConfig {
+    /// Returns the time it takes to transmit a byte with these line settings,
+    /// including the start, parity and stop bits.
+    #[must_use]
+    pub fn byte_time(&self) -> Duration {
+        let parity_bits = u64::from(self.parity != Parity::None);
+        let bits =
+            1 + u64::from(self.char_size as u8) + parity_bits + u64::from(self.stop_bits as u8);
+
+        Duration::from_nanos((bits * 1_000_000_000).div_ceil(u64::from(self.baud_rate.max(1))))
+    }
+}
+
+/// RTS direction control of a half-duplex RS-485 transceiver, see [`SerialConfig::rs485`].
+///
+/// RTS is switched to the transmit state before each write and back to the receive state
+/// once all bytes were transmitted, which is calculated from the baud rate.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rs485Config {
+    /// State of RTS while transmitting, the opposite state enables the receiver.
+    ///
+    /// Depends on how the transceiver's driver enable input is wired.
+    pub rts_on_send: bool,
+    /// Time for the transceiver to switch direction,
+    /// waited after enabling the driver and after the last byte was transmitted.
+    pub settle_delay: Duration,
+}
+
+impl Default for Rs485Config {
+    fn default() -> Self {
+        Self {
+            rts_on_send: true,
+            settle_delay: Duration::from_millis(1),
+        }
+    }
+}
+
+/// Serial port type implementing [`Read`] and [`Write`].
+///
+/// Returned by [`open`], [`open_with`] and [`open_tcp`].
+pub struct Port {
+    inner: Transport,
+    read_timeout: Option<Duration>,
+    inter_byte_timeout: Option<Duration>,
+    direction: Option<Direction>,
+}
+
+/// RTS direction control of a serial [`Port`].
+struct Direction {
+    config: Rs485Config,
+    /// Time it takes to transmit a byte at the configured baud rate.
+    byte_time: Duration,
+}
+
+impl Direction {
+    /// Sets up direction control if configured, enabling the receiver.
+    fn new(port: &SerialPort, config: &SerialConfig) -> Result<Option<Self>, PortError> {
+        let Some(rs485) = config.rs485 else {
+            return Ok(None);
+        };
+
+        port.set_rts(!rs485.rts_on_send)?;
+
+        Ok(Some(Self {
+            config: rs485,
+            byte_time: config.byte_time(),
+        }))
+    }
+
+    /// Writes all bytes with the driver enabled, switching back to receiving afterwards.
+    async fn transmit(
+        &self,
+        port: &mut FromTokio<SerialPort>,
+        buf: &[u8],
+    ) -> Result<usize, PortError> {
+        port.inner().set_rts(self.config.rts_on_send)?;
+        tokio::time::sleep(self.config.settle_delay).await;
+
+        let res = port.write_all(buf).await;
+
+        // Writes complete once the data is queued, not once it was transmitted
+        if res.is_ok() {
+            let len = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+
+            tokio::time::sleep(self.byte_time.saturating_mul(len) + self.config.settle_delay).await;
+        }
+
+        port.inner().set_rts(!self.config.rts_on_send)?;
+
+        res.map(|()| buf.len())
+    }
+}
+
+/// Connection the data of a [`Port`] is transferred over.
+enum Transport {
+    Serial(FromTokio<SerialPort>),
+    Tcp(FromTokio<TcpStream>),
+}
+
+impl Port {
+    /// Returns a reference to the underlying serial port,
+    /// or `None` if the port was opened using [`open_tcp`].
+    #[must_use]
+    pub fn inner(&self) -> Option<&SerialPort> {
+        match &self.inner {
+            Transport::Serial(port) => Some(port.inner()),
+            Transport::Tcp(_) => None,
+        }
+    }
+
+    /// Applies a new configuration to the open port, without closing it.
+    ///
+    /// Data received with the previous settings is discarded.
+    /// This includes the RS-485 direction control, which is enabled or disabled as configured.
+    /// Allows changing the baud rate after the device was switched to another one,
+    /// e.g. using [`Interface::set_baud_rate_9600`](crate::Interface::set_baud_rate_9600).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind [`std::io::ErrorKind::Unsupported`] if the port was opened
+    /// using [`open_tcp`], since the settings of a bridge can't be changed remotely.
+    /// Returns other errors if the serial port rejects the settings, which are then left unchanged.
+    pub fn reconfigure(&mut self, config: &SerialConfig) -> Result<(), PortError> {
+        let Transport::Serial(port) = &mut self.inner else {
+            return Err(std::io::Error::from(std::io::ErrorKind::Unsupported));
+        };
+        let port = port.inner_mut();
+        let settings = apply_config(port.get_configuration()?, config)?;
+
+        port.set_configuration(&settings)?;
+        port.discard_buffers()?;
+        self.read_timeout = config.read_timeout;
+        self.inter_byte_timeout = config.inter_byte_timeout;
+        self.direction = Direction::new(port, config)?;
+
+        Ok(())
+    }
+
+    async fn read_inner(&mut self, buf: &mut [u8]) -> Result<usize, PortError> {
+        match &mut self.inner {
+            Transport::Serial(port) => port.read(buf).await,
+            Transport::Tcp(stream) => stream.read(buf).await,
+        }
+    }
+
+    async fn read_within(
+        &mut self,
+        buf: &mut [u8],
+        timeout: Option<Duration>,
+    ) -> Result<usize, PortError> {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.read_inner(buf))
+                .await
+                .map_err(|_| std::io::Error::from(std::io::ErrorKind::TimedOut))?,
+            None => self.read_inner(buf).await,
+        }
+    }
+}
+
+impl ErrorType for Port {
+    type Error = PortError;
+}
+
+impl Read for Port {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.read_within(buf, self.read_timeout).await
+    }
+
+    /// Reads a complete frame into the buffer.
+    ///
+    /// Waiting for the first byte is limited by the read timeout,
+    /// waiting for the remaining fragments by the inter-byte timeout.
+    async fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<(), ReadExactError<Self::Error>> {
+        let mut timeout = self.read_timeout;
+
+        while !buf.is_empty() {
+            match self.read_within(buf, timeout).await {
+                Ok(0) => return Err(ReadExactError::UnexpectedEof),
+                Ok(n) => buf = &mut buf[n..],
+                Err(e) => return Err(ReadExactError::Other(e)),
+            }
+
+            timeout = self.inter_byte_timeout.or(self.read_timeout);
+        }
+
+        Ok(())
+    }
+}
+
+impl Write for Port {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        match &mut self.inner {
+            Transport::Serial(port) => match &self.direction {
+                Some(direction) => direction.transmit(port, buf).await,
+                None => port.write(buf).await,
+            },
+            Transport::Tcp(stream) => stream.write(buf).await,
+        }
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        match &mut self.inner {
+            Transport::Serial(port) => port.flush().await,
+            Transport::Tcp(stream) => stream.flush().await,
+        }
+    }
+}
 
 /// Opens a native serial port at the given path.
 ///
+/// The port is configured using [`SerialConfig::default`].
 /// Returns a [`Port`] that can be passed to [`Interface::new`](crate::Interface::new).
+///
+/// # Errors
+///
+/// Returns [`Error::PortBusy`] if the port is in use by another program
+/// or [`Error::Io`] if it cannot be opened for any other reason.
 pub fn open(path: &str) -> Result<Port, Error<std::io::Error>> {
-    let port = SerialPort::open(path, |mut settings: Settings| {
-        settings.set_raw();
-        settings.set_baud_rate(2400)?;
-        settings.set_parity(Parity::Even);
+    open_with(path, &SerialConfig::default())
+}
 
-        Ok(settings)
-    })?;
+/// Opens a native serial port at the given path with a custom configuration.
+///
+/// # Errors
+///
+/// See [`open`].
+pub fn open_with(path: &str, config: &SerialConfig) -> Result<Port, Error<std::io::Error>> {
+    let port = SerialPort::open(path, |settings| apply_config(settings, config))
+        .map_err(map_open_error)?;
 
     port.discard_buffers()?;
 
-    Ok(FromTokio::new(port))
+    let direction = Direction::new(&port, config)?;
+
+    Ok(Port {
+        inner: Transport::Serial(FromTokio::new(port)),
+        read_timeout: config.read_timeout,
+        inter_byte_timeout: config.inter_byte_timeout,
+        direction,
+    })
+}
+
+/// Connects to a serial-to-TCP bridge at the given address, e.g. `"192.168.1.50:23"`.
+///
+/// The bridge has to forward the data unchanged and use the settings
+/// expected by the diagnostic interface, see [`SerialConfig::default`].
+/// It also has to control the direction of an RS-485 transceiver, if any.
+/// Changing the baud rate of the device is not forwarded to the bridge.
+///
+/// # Errors
+///
+/// Returns [`Error::Io`] if the connection cannot be established.
+pub async fn open_tcp(addr: impl ToSocketAddrs) -> Result<Port, Error<std::io::Error>> {
+    let stream = TcpStream::connect(addr).await?;
+
+    // Requests are short and have to be sent immediately
+    stream.set_nodelay(true)?;
+
+    Ok(Port {
+        inner: Transport::Tcp(FromTokio::new(stream)),
+        read_timeout: None,
+        inter_byte_timeout: None,
+        direction: None,
+    })
+}
+
+/// Applies the line settings of the configuration to the settings of a serial port.
+fn apply_config(mut settings: Settings, config: &SerialConfig) -> std::io::Result<Settings> {
+    settings.set_raw();
+    settings.set_baud_rate(config.baud_rate)?;
+    settings.set_char_size(config.char_size);
+    settings.set_parity(config.parity);
+    settings.set_stop_bits(config.stop_bits);
+
+    Ok(settings)
+}
+
+/// Maps an error returned when opening a port to [`Error::PortBusy`]
+/// if the port is already in use by another program.
+///
+/// On Unix systems, serial ports can usually be opened by multiple programs
+/// at the same time, unless one of them requested exclusive access.
+/// Windows never allows shared access and reports the port as access denied.
+fn map_open_error(err: std::io::Error) -> Error<std::io::Error> {
+    // ERROR_ACCESS_DENIED and ERROR_SHARING_VIOLATION
+    const WINDOWS_BUSY_ERRORS: [i32; 2] = [5, 32];
+
+    let busy = err.kind() == std::io::ErrorKind::ResourceBusy
+        || (cfg!(windows)
+            && err
+                .raw_os_error()
+                .is_some_and(|code| WINDOWS_BUSY_ERRORS.contains(&code)));
+
+    if busy {
+        Error::PortBusy
+    } else {
+        Error::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+
+    #[tokio::test]
+    async fn tcp_port() -> Result<(), Error<std::io::Error>> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let mut port = open_tcp(listener.local_addr()?).await?;
+        let (mut stream, _) = listener.accept().await?;
+
+        port.write_all(&[0x11, 0x00, 0x00, 0x02, 0x13]).await?;
+
+        let mut req = [0x00; 5];
+
+        tokio::io::AsyncReadExt::read_exact(&mut stream, &mut req).await?;
+        tokio::io::AsyncWriteExt::write_all(&mut stream, &[0x00, 0x01, 0x01]).await?;
+
+        let mut resp = [0x00; 3];
+
+        port.read_exact(&mut resp)
+            .await
+            .map_err(|_| Error::UnexpectedEof)?;
+
+        assert_eq!(
+            req,
+            [0x11, 0x00, 0x00, 0x02, 0x13],
+            "request should be correct"
+        );
+        assert_eq!(resp, [0x00, 0x01, 0x01], "response should be correct");
+        assert!(port.inner().is_none(), "port should not be serial port");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tcp_port_inter_byte_timeout() -> Result<(), Error<std::io::Error>> {
+        use tokio::io::AsyncWriteExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let mut port = open_tcp(listener.local_addr()?).await?;
+        let (mut stream, _) = listener.accept().await?;
+
+        port.read_timeout = Some(Duration::from_secs(5));
+        port.inter_byte_timeout = Some(Duration::from_millis(200));
+
+        let mut frame = [0x00; 4];
+
+        stream.write_all(&[0x01, 0x02]).await?;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        stream.write_all(&[0x03, 0x04]).await?;
+
+        port.read_exact(&mut frame)
+            .await
+            .map_err(|_| Error::UnexpectedEof)?;
+
+        assert_eq!(frame, [0x01, 0x02, 0x03, 0x04], "frame should be correct");
+
+        // The remainder of the frame never arrives
+        stream.write_all(&[0x05, 0x06]).await?;
+
+        let res = port.read_exact(&mut frame).await;
+
+        assert!(
+            matches!(res, Err(ReadExactError::Other(ref err)) if err.kind() == ErrorKind::TimedOut),
+            "fragmented frame should time out"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tcp_port_reconfigure() -> Result<(), Error<std::io::Error>> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let mut port = open_tcp(listener.local_addr()?).await?;
+        let config = SerialConfig {
+            baud_rate: BAUD_RATES[1],
+            read_timeout: Some(Duration::from_secs(1)),
+            ..SerialConfig::default()
+        };
+
+        assert!(
+            matches!(port.reconfigure(&config), Err(ref err) if err.kind() == ErrorKind::Unsupported),
+            "bridge should not be reconfigured"
+        );
+        assert_eq!(port.read_timeout, None, "timeout should be unchanged");
+
+        Ok(())
+    }
+
+    #[test]
+    fn byte_time() {
+        let config = SerialConfig::default();
+        let fast = SerialConfig {
+            baud_rate: BAUD_RATES[1],
+            parity: Parity::None,
+            ..SerialConfig::default()
+        };
+
+        // Start bit, 8 data bits, parity bit and stop bit at 2400 baud
+        assert_eq!(
+            config.byte_time(),
+            Duration::from_nanos(4_583_334),
+            "byte time should be correct"
+        );
+        assert_eq!(
+            fast.byte_time(),
+            Duration::from_nanos(1_041_667),
+            "byte time without parity should be correct"
+        );
+    }
+
+    #[test]
+    fn map_open_error_busy() {
+        let err = std::io::Error::from(ErrorKind::ResourceBusy);
+
+        assert!(
+            matches!(map_open_error(err), Error::PortBusy),
+            "result should be port busy error"
+        );
+    }
+
+    #[test]
+    fn map_open_error_other() {
+        let err = std::io::Error::from(ErrorKind::NotFound);
+
+        assert!(
+            matches!(map_open_error(err), Error::Io(err) if err.kind() == ErrorKind::NotFound),
+            "result should be input/output error"
+        );
+    }
 }
@@ -0,0 +1,41 @@
+//! Tests of the native serial port configuration using a pseudo-terminal loopback.
+
+#![cfg(all(target_os = "linux", feature = "native-serial"))]
+
+use freemdu::serial::{self, CharSize, Parity, SerialConfig};
+use std::{io, os::fd::AsRawFd};
+
+#[tokio::test]
+async fn open_applies_baud_rate() -> io::Result<()> {
+    // Both ends must be kept alive for the duration of the test
+    let pair = serial2::SerialPort::pair()?;
+    let path = std::fs::read_link(format!("/proc/self/fd/{}", pair.1.as_raw_fd()))?;
+    let path = path.to_str().expect("path should be valid UTF-8");
+
+    for baud_rate in [9600, 115_200] {
+        // Pseudo-terminals don't support parity checking
+        let config = SerialConfig {
+            baud_rate,
+            parity: Parity::None,
+            ..SerialConfig::default()
+        };
+        let port = serial::open_with(path, &config).expect("port should open");
+        let settings = port
+            .inner()
+            .expect("port should be serial port")
+            .get_configuration()?;
+
+        assert_eq!(
+            settings.get_baud_rate()?,
+            baud_rate,
+            "baud rate should be correct"
+        );
+        assert_eq!(
+            settings.get_char_size()?,
+            CharSize::Bits8,
+            "character size should be correct"
+        );
+    }
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 mod bar;
 mod popup;
+mod schema;
 mod session;
 mod table;
 mod worker;
@@ -9,7 +10,7 @@ use crate::{
     worker::{Response, Worker},
 };
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use freemdu::serial::{self, Port};
 use futures::{StreamExt, future::FutureExt};
 use ratatui::{
@@ -24,10 +25,27 @@ use ratatui::{
 use tokio::task::LocalSet;
 
 #[derive(Parser, Debug)]
-#[command(version, about)]
+#[command(version, about, subcommand_negates_reqs = true)]
 struct Args {
     /// Serial port path (e.g. /dev/ttyACM0)
-    serial_port: String,
+    #[arg(required = true)]
+    serial_port: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the properties and actions supported by a device, without connecting to it
+    Schema {
+        /// Software ID of the device (e.g. 629)
+        software_id: u16,
+
+        /// Print the schema as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Default, Debug)]
@@ -156,7 +174,13 @@ async fn main() -> Result<()> {
     env_logger::init();
 
     let args = Args::parse();
-    let port = serial::open(&args.serial_port).context("Failed to open serial port")?;
+
+    if let Some(Command::Schema { software_id, json }) = args.command {
+        return schema::print(software_id, json);
+    }
+
+    let path = args.serial_port.context("Missing serial port path")?;
+    let port = serial::open(&path).context("Failed to open serial port")?;
     let mut term = ratatui::init();
     let res = LocalSet::new()
         .run_until(async move { App::default().run(port, &mut term).await })
@@ -1,4 +1,5 @@
-use freemdu::device::{Action, ActionParameters};
+use crate::table::format_value;
+use freemdu::device::{Action, ActionParameters, Value};
 use ratatui::{
     buffer::Buffer,
     crossterm::event::{Event, KeyCode, KeyEvent},
@@ -19,6 +20,7 @@ pub enum State {
 #[derive(Debug)]
 pub enum Popup {
     TriggerAction(&'static Action, Input),
+    ActionResult(&'static Action, Value),
     InvalidActionArgument(&'static Action),
     InvalidActionState(&'static Action),
 }
@@ -53,7 +55,13 @@ impl Popup {
     ) -> Position {
         let hint = match params {
             ActionParameters::Enumeration(vals) => vals.join(", "),
-            ActionParameters::Flags(vals) => vals.join(" | "),
+            ActionParameters::Flags(flags) => flags
+                .iter()
+                .map(|&(_, name)| name)
+                .collect::<Vec<_>>()
+                .join(" | "),
+            ActionParameters::Number { min, max, .. } => format!("{min} to {max}"),
+            ActionParameters::FreeText => "any text".to_string(),
         };
         let msg = Text::from(vec![
             Line::from(vec![
@@ -85,6 +93,20 @@ impl Popup {
         msg.render(inner, buf);
     }
 
+    fn render_action_result(area: Rect, buf: &mut Buffer, action: &str, val: &Value) {
+        let (text, _) = format_value(val, None, 0);
+        let msg = Line::from(vec![
+            "The ".into(),
+            action.bold(),
+            " action returned ".into(),
+            text.bold(),
+            ".".into(),
+        ]);
+        let inner = Self::render_popup(area, buf, "Action result", msg.width(), 1);
+
+        msg.render(inner, buf);
+    }
+
     fn render_invalid_action_arg(area: Rect, buf: &mut Buffer, action: &str) {
         let msg = Line::from(vec![
             "The specified argument for the ".into(),
@@ -152,6 +174,9 @@ impl StatefulWidget for &Popup {
                     Popup::render_trigger_action(area, buf, action.name);
                 }
             }
+            Popup::ActionResult(action, val) => {
+                Popup::render_action_result(area, buf, action.name, val);
+            }
             Popup::InvalidActionArgument(action) => {
                 Popup::render_invalid_action_arg(area, buf, action.name);
             }
@@ -0,0 +1,57 @@
+use anyhow::{Context, Result, anyhow};
+use freemdu::device::{self, ActionParameters};
+
+/// Prints the schema of the device with the given software ID to standard output.
+pub fn print(software_id: u16, json: bool) -> Result<()> {
+    let schema =
+        device::schema(software_id).ok_or_else(|| anyhow!("Unknown software ID: {software_id}"))?;
+
+    if json {
+        let json = serde_json::to_string_pretty(schema).context("Failed to serialize schema")?;
+
+        println!("{json}");
+
+        return Ok(());
+    }
+
+    println!("{} (software ID {software_id})", schema.kind);
+    println!();
+    println!("Properties:");
+
+    for prop in schema.properties {
+        let unit = prop
+            .unit
+            .map(|unit| format!(" [{unit}]"))
+            .unwrap_or_default();
+
+        println!("  {:<28} {:?}, {}{unit}", prop.id, prop.kind, prop.name);
+    }
+
+    println!();
+    println!("Actions:");
+
+    if schema.actions.is_empty() {
+        println!("  (none)");
+    }
+
+    for action in schema.actions {
+        let params = match &action.params {
+            Some(ActionParameters::Enumeration(vals)) => format!(" <{}>", vals.join(", ")),
+            Some(ActionParameters::Flags(flags)) => {
+                let names: Vec<_> = flags.iter().map(|&(_, name)| name).collect();
+
+                format!(" <{}>", names.join(" | "))
+            }
+            Some(ActionParameters::Number { min, max, .. }) => format!(" <{min}..={max}>"),
+            Some(ActionParameters::FreeText) => " <text>".to_string(),
+            None => String::new(),
+        };
+
+        println!(
+            "  {:<28} {:?}, {}{params}",
+            action.id, action.kind, action.name
+        );
+    }
+
+    Ok(())
+}
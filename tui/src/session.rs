@@ -47,6 +47,10 @@ impl Session {
                     PropertyKind::Failure,
                     PropertyTable::new("Failure Information", Color::Red),
                 ),
+                (
+                    PropertyKind::Warning,
+                    PropertyTable::new("Warning Information", Color::Yellow),
+                ),
                 (
                     PropertyKind::Operation,
                     PropertyTable::new("Operating State", Color::Blue),
@@ -76,12 +80,14 @@ impl Session {
                 State::Confirmed => {
                     if let Popup::TriggerAction(action, input) = popup {
                         // Use input value if action has parameters
-                        // Only string parameters are currently supported
-                        let param = if action.params.is_some() {
-                            Some(Value::String(input.value().trim().to_string()))
-                        } else {
-                            None
-                        };
+                        // Invalid numbers are passed as is for the device to reject
+                        let param = action.params.as_ref().map(|params| {
+                            let input = input.value().trim();
+
+                            params
+                                .parse_value(input)
+                                .unwrap_or_else(|| Value::String(input.to_string()))
+                        });
 
                         self.tx.send(Request::TriggerAction(action, param))?;
                     }
@@ -112,6 +118,9 @@ impl Session {
 
                 self.schedule_prop_update()?;
             }
+            Response::ActionResult(action, val) => {
+                self.popup = Some(Popup::ActionResult(action, val));
+            }
             Response::InvalidActionArgument(action) => {
                 self.popup = Some(Popup::InvalidActionArgument(action));
             }
@@ -128,10 +137,12 @@ impl Session {
         let kind = match self.update_counter {
             0 => PropertyKind::General,
             1 => PropertyKind::Failure,
-            2 => PropertyKind::Operation,
-            3 => PropertyKind::Io,
+            2 => PropertyKind::Warning,
+            3 => PropertyKind::Operation,
+            4 => PropertyKind::Io,
             cnt if cnt % 90 == 0 => PropertyKind::General,
             cnt if cnt % 30 == 0 => PropertyKind::Failure,
+            cnt if cnt % 30 == 15 => PropertyKind::Warning,
             cnt if cnt % 3 == 0 => PropertyKind::Operation,
             _ => PropertyKind::Io,
         };
@@ -152,7 +163,11 @@ impl Session {
         let [bottom_left, bottom_right] = Layout::horizontal(vec![Constraint::Fill(1); 2])
             .spacing(2)
             .areas(bottom);
-        let areas = [top_left, bottom_left, top_right, bottom_right];
+        // Failure and warning tables share the bottom left area
+        let [failure, warning] = Layout::vertical(vec![Constraint::Fill(1); 2])
+            .spacing(1)
+            .areas(bottom_left);
+        let areas = [top_left, failure, warning, top_right, bottom_right];
 
         for ((_, table), inner) in self.tables.iter().zip(areas) {
             table.render(inner, buf);
@@ -42,7 +42,7 @@ impl PropertyTable {
                 break;
             }
 
-            match PropertyTable::format_prop(prop, val) {
+            match format_value(val, prop.unit, prop.decimals) {
                 (text, None) => {
                     let par = Paragraph::new(text).wrap(Wrap { trim: false });
 
@@ -68,53 +68,26 @@ impl PropertyTable {
         }
     }
 
-    fn format_prop(prop: &Property, val: &Value) -> (String, Option<f64>) {
-        match *val {
-            Value::Bool(val) => {
-                if val {
-                    ("Yes".to_string(), None)
-                } else {
-                    ("No".to_string(), None)
-                }
-            }
-            Value::Number(num) => {
-                if let Some(unit) = prop.unit {
-                    (format!("{num} {unit}"), None)
-                } else {
-                    (num.to_string(), None)
-                }
-            }
-            Value::Sensor(current, target) => {
-                let txt = if let Some(unit) = prop.unit {
-                    format!("{current} / {target} {unit}")
-                } else {
-                    format!("{current} / {target}")
-                };
-
-                let ratio = if target > 0 {
-                    (f64::from(current) / f64::from(target)).clamp(0.0, 1.0)
-                } else {
-                    0.0
-                };
-
-                (txt, Some(ratio))
-            }
-            Value::String(ref string) => (string.clone(), None),
-            Value::Duration(dur) => {
-                let total_mins = dur.as_secs() / 60;
-                let hours = total_mins / 60;
-                let mins = total_mins % 60;
-
-                (format!("{hours}h {mins}min"), None)
-            }
-        }
-    }
-
     fn row_height_out_of_bounds(row: Rect, area: Rect) -> bool {
         row.y + row.height > area.y + area.height
     }
 }
 
+/// Formats a value for display, returning an optional gauge ratio for sensor values.
+///
+/// See [`Value::format`].
+pub fn format_value(val: &Value, unit: Option<&str>, decimals: u8) -> (String, Option<f64>) {
+    let ratio = match *val {
+        Value::Sensor(current, target) if target > 0 => {
+            Some((f64::from(current) / f64::from(target)).clamp(0.0, 1.0))
+        }
+        Value::Sensor(..) => Some(0.0),
+        _ => None,
+    };
+
+    (val.format(unit, decimals), ratio)
+}
+
 impl Widget for &PropertyTable {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let block = Block::bordered()
@@ -1,8 +1,9 @@
 use anyhow::{Context, Result};
 use freemdu::{
-    device::{self, Action, DeviceKind, Error, Property, PropertyKind, Value},
+    device::{self, Action, DeviceKind, Error, Property, PropertyId, PropertyKind, Value},
     serial::Port,
 };
+use std::collections::HashSet;
 use tokio::{
     sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
     task::{self, JoinHandle},
@@ -32,6 +33,7 @@ pub enum Response {
         tx: UnboundedSender<Request>,
     },
     PropertiesQueried(PropertyKind, Vec<(&'static Property, Value)>),
+    ActionResult(&'static Action, Value),
     InvalidActionArgument(&'static Action),
     InvalidActionState(&'static Action),
 }
@@ -39,6 +41,8 @@ pub enum Response {
 pub struct Worker<'a> {
     dev: Device<'a>,
     tx: UnboundedSender<Response>,
+    // Properties rejected by the device, which are no longer queried
+    unsupported: HashSet<PropertyId>,
 }
 
 impl Worker<'_> {
@@ -48,7 +52,15 @@ impl Worker<'_> {
             loop {
                 // Connect to device (retry on timeout)
                 match time::timeout(DEVICE_TIMEOUT, device::connect(&mut port)).await {
-                    Ok(dev) => return Worker { dev: dev?, tx }.run().await,
+                    Ok(dev) => {
+                        return Worker {
+                            dev: dev?,
+                            tx,
+                            unsupported: HashSet::new(),
+                        }
+                        .run()
+                        .await;
+                    }
                     Err(_) => time::sleep(DEVICE_CONNECT_INTERVAL).await,
                 }
             }
@@ -93,9 +105,16 @@ impl Worker<'_> {
             .iter()
             .filter(|prop| prop.kind == kind)
         {
-            let val = time::timeout(DEVICE_TIMEOUT, self.dev.query_property(prop)).await??;
+            if self.unsupported.contains(&prop.id) {
+                continue;
+            }
 
-            data.push((prop, val));
+            match time::timeout(DEVICE_TIMEOUT, self.dev.query_property(prop)).await? {
+                Err(Error::Unsupported) => {
+                    self.unsupported.insert(prop.id);
+                }
+                res => data.push((prop, res?)),
+            }
         }
 
         self.tx.send(Response::PropertiesQueried(kind, data))?;
@@ -111,7 +130,10 @@ impl Worker<'_> {
         match time::timeout(DEVICE_TIMEOUT, self.dev.trigger_action(action, param)).await? {
             Err(Error::InvalidArgument) => self.tx.send(Response::InvalidActionArgument(action))?,
             Err(Error::InvalidState) => self.tx.send(Response::InvalidActionState(action))?,
-            res => res?,
+            Ok(Some(val)) => self.tx.send(Response::ActionResult(action, val))?,
+            res => {
+                res?;
+            }
         }
 
         Ok(())